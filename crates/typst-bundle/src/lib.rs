@@ -19,7 +19,7 @@ use rustc_hash::{FxBuildHasher, FxHashMap};
 use typst_html::HtmlDocument;
 use typst_layout::PagedDocument;
 use typst_library::diag::{At, CollectCombinedResult, SourceResult, bail, error};
-use typst_library::engine::{Engine, Route, Sink, Traced};
+use typst_library::engine::{Cancellation, Engine, Fuel, Route, Sink, Traced};
 use typst_library::foundations::{
     Bytes, Content, Output, Packed, StyleChain, Target, TargetElem,
 };
@@ -128,6 +128,8 @@ pub fn bundle(
         engine.library,
         engine.introspector.into_raw(),
         engine.traced,
+        engine.cancellation,
+        engine.fuel,
         TrackedMut::reborrow_mut(&mut engine.sink),
         engine.route.track(),
         content,
@@ -143,6 +145,8 @@ fn bundle_impl(
     library: &LazyHash<Library>,
     introspector: Tracked<dyn Introspector + '_>,
     traced: Tracked<Traced>,
+    cancellation: Tracked<Cancellation>,
+    fuel: Tracked<Fuel>,
     sink: TrackedMut<Sink>,
     route: Tracked<Route>,
     content: &Content,
@@ -155,6 +159,8 @@ fn bundle_impl(
         world,
         introspector,
         traced,
+        cancellation,
+        fuel,
         sink,
         route: Route::extend(route).unnested(),
     };
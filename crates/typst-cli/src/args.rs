@@ -97,6 +97,13 @@ pub enum Command {
     /// Evaluates a piece of Typst code, optionally in the context of a document.
     Eval(EvalCommand),
 
+    /// Runs a Debug Adapter Protocol server over stdio to debug the evaluation
+    /// of an input file.
+    Debug(DebugCommand),
+
+    /// Runs the tests of a package or project.
+    Test(TestCommand),
+
     /// Lists all discovered fonts in system and custom font paths.
     Fonts(FontsCommand),
 
@@ -226,6 +233,80 @@ pub struct EvalCommand {
     pub process: ProcessArgs,
 }
 
+/// Runs a Debug Adapter Protocol server over stdio to debug the evaluation of
+/// an input file.
+#[derive(Debug, Clone, Parser)]
+pub struct DebugCommand {
+    /// Path to input Typst file.
+    #[clap(value_parser = input_value_parser(), value_hint = ValueHint::FilePath)]
+    pub input: Input,
+
+    /// The target to compile for.
+    #[clap(long, default_value_t)]
+    pub target: Target,
+
+    /// World arguments.
+    #[clap(flatten)]
+    pub world: WorldArgs,
+
+    /// Processing arguments.
+    #[clap(flatten)]
+    pub process: ProcessArgs,
+}
+
+/// Runs the tests of a package or project.
+///
+/// Every Typst file in the `tests` directory (except for those whose name
+/// starts with an underscore) is a test that passes if the file compiles.
+/// Functions defined in a test file whose name starts with `test-` are
+/// additionally called one by one. If a reference image exists at the same
+/// relative path in `tests/ref`, but with a `.png` extension, the rendered
+/// document is compared against it.
+#[derive(Debug, Clone, Parser)]
+pub struct TestCommand {
+    /// The directory of the package or project, defaults to the current
+    /// directory. Also serves as the project root unless `--root` is given.
+    #[clap(value_hint = ValueHint::DirPath)]
+    pub dir: Option<PathBuf>,
+
+    /// Only runs test files whose path contains this string.
+    #[clap(long)]
+    pub filter: Option<String>,
+
+    /// Writes the rendered documents as new reference images instead of
+    /// comparing against the existing ones.
+    #[clap(long)]
+    pub update: bool,
+
+    /// The maximum difference of a color channel, from 0 to 255, for which
+    /// a rendered pixel still matches its reference.
+    #[clap(long, default_value_t = 1)]
+    pub tolerance: u8,
+
+    /// The number of pixels that may exceed the tolerance before a rendered
+    /// document no longer matches its reference.
+    #[clap(long, default_value_t = 0)]
+    pub max_differing_pixels: usize,
+
+    /// The PPI (pixels per inch) at which documents are rendered for comparison
+    /// with reference images.
+    #[arg(long = "ppi", default_value_t = 144.0)]
+    pub ppi: f32,
+
+    /// File path to which a JUnit XML report of the test results will be
+    /// written.
+    #[clap(long, value_name = "PATH", value_hint = ValueHint::FilePath)]
+    pub junit: Option<PathBuf>,
+
+    /// World arguments.
+    #[clap(flatten)]
+    pub world: WorldArgs,
+
+    /// Processing arguments.
+    #[clap(flatten)]
+    pub process: ProcessArgs,
+}
+
 /// Lists all discovered fonts in system and custom font paths.
 #[derive(Debug, Clone, Parser)]
 pub struct FontsCommand {
@@ -348,6 +429,38 @@ pub struct CompileArgs {
     #[arg(long = "ppi", default_value_t = 144.0)]
     pub ppi: f32,
 
+    /// Arranges the pages on larger printing sheets before exporting.
+    ///
+    /// The exported document then contains one page per sheet side, and
+    /// `--pages` refers to these sheet sides. Implies `--no-pdf-tags`.
+    #[arg(long = "impose", value_name = "SCHEME")]
+    pub impose: Option<ImposeScheme>,
+
+    /// The paper size of the sheets used for imposition, for example `a3` or
+    /// `us-letter`.
+    #[arg(
+        long = "sheet",
+        value_name = "PAPER",
+        default_value = "a4",
+        requires = "impose"
+    )]
+    pub sheet: String,
+
+    /// The number of columns and rows of pages per sheet side for the `n-up`
+    /// and `cut-and-stack` schemes, for example `2x2`.
+    #[arg(
+        long = "grid",
+        value_name = "COLSxROWS",
+        default_value = "2x1",
+        requires = "impose"
+    )]
+    pub grid: ImposeGrid,
+
+    /// How far, in points, the pages of the innermost sheet of a booklet are
+    /// shifted towards the spine to compensate for the paper's thickness.
+    #[arg(long = "creep", value_name = "PT", default_value_t = 0.0, requires = "impose")]
+    pub creep: f64,
+
     /// File path to which a Makefile with the current compilation's
     /// dependencies will be written.
     #[clap(long = "make-deps", value_name = "PATH", hide = true)]
@@ -702,6 +815,21 @@ pub enum PdfStandard {
 
 display_possible_values!(PdfStandard);
 
+/// How pages are arranged on printing sheets.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, ValueEnum)]
+pub enum ImposeScheme {
+    /// Two pages per sheet side, ordered such that the folded sheets form a
+    /// booklet.
+    Booklet,
+    /// A grid of pages per sheet side in reading order.
+    NUp,
+    /// A grid of pages per sheet side, ordered such that the cut stacks can
+    /// be placed on top of each other.
+    CutAndStack,
+}
+
+display_possible_values!(ImposeScheme);
+
 /// Output file format for query and info commands
 #[derive(Debug, Default, Copy, Clone, Eq, PartialEq, ValueEnum)]
 pub enum SerializationFormat {
@@ -747,6 +875,28 @@ impl FromStr for Pages {
     }
 }
 
+/// The number of columns and rows of pages on one sheet side, parsed from
+/// `COLSxROWS`.
+#[derive(Debug, Copy, Clone)]
+pub struct ImposeGrid {
+    pub cols: NonZeroUsize,
+    pub rows: NonZeroUsize,
+}
+
+impl FromStr for ImposeGrid {
+    type Err = &'static str;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let (cols, rows) = value
+            .split_once(['x', 'X'])
+            .ok_or("grid must be columns and rows separated by an `x`")?;
+        let parse = |v: &str| {
+            NonZeroUsize::from_str(v.trim()).map_err(|_| "not a valid grid dimension")
+        };
+        Ok(Self { cols: parse(cols)?, rows: parse(rows)? })
+    }
+}
+
 /// Parses a single page number.
 fn parse_page_number(value: &str) -> Result<NonZeroUsize, &'static str> {
     if value == "0" {
@@ -2,7 +2,7 @@ use std::ffi::OsStr;
 use std::path::Path;
 
 use chrono::{DateTime, Datelike, Timelike, Utc};
-use ecow::eco_format;
+use ecow::{EcoString, eco_format};
 use parking_lot::RwLock;
 use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
 use typst::diag::{
@@ -10,17 +10,17 @@ use typst::diag::{
     bail,
 };
 use typst::foundations::{Datetime, Smart};
-use typst::layout::PageRanges;
+use typst::layout::{Abs, Axes, PageRanges, Paper, Size};
 use typst::syntax::Span;
 use typst_bundle::{Bundle, BundleOptions, VirtualFs};
 use typst_html::HtmlDocument;
 use typst_kit::timer::Timer;
-use typst_layout::{Page, PagedDocument};
+use typst_layout::{Imposition, ImpositionScheme, Page, PagedDocument};
 use typst_pdf::{PdfOptions, PdfStandards, Timestamp};
 
 use crate::args::{
-    CompileArgs, CompileCommand, DepsFormat, DiagnosticFormat, Input, Output,
-    OutputFormat, PdfStandard, WatchCommand,
+    CompileArgs, CompileCommand, DepsFormat, DiagnosticFormat, ImposeScheme, Input,
+    Output, OutputFormat, PdfStandard, WatchCommand,
 };
 use crate::deps::write_deps;
 use crate::watch::Status;
@@ -57,6 +57,8 @@ pub struct CompileConfig {
     pub output_format: OutputFormat,
     /// Which pages to export.
     pub pages: Option<PageRanges>,
+    /// How to arrange the pages on printing sheets before exporting.
+    pub imposition: Option<Imposition>,
     /// The document's creation date formatted as a UNIX timestamp, with UTC suffix.
     pub creation_timestamp: Option<DateTime<Utc>>,
     /// The format to emit diagnostics in.
@@ -139,13 +141,45 @@ impl CompileConfig {
             PageRanges::new(export_ranges.iter().map(|r| r.0.clone()).collect())
         });
 
-        let tagged = !args.no_pdf_tags && pages.is_none();
-        if output_format == OutputFormat::Pdf && pages.is_some() && !args.no_pdf_tags {
+        let imposition = args.impose.map(|scheme| {
+            let paper = args
+                .sheet
+                .parse::<Paper>()
+                .map_err(|_| eco_format!("unknown sheet size `{}`", args.sheet))?;
+            let grid = Axes::new(args.grid.cols, args.grid.rows);
+            Ok::<_, EcoString>(Imposition {
+                scheme: match scheme {
+                    ImposeScheme::Booklet => ImpositionScheme::Booklet,
+                    ImposeScheme::NUp => ImpositionScheme::NUp(grid),
+                    ImposeScheme::CutAndStack => ImpositionScheme::CutAndStack(grid),
+                },
+                sheet: Size::new(paper.width(), paper.height()),
+                creep: Abs::pt(args.creep),
+            })
+        });
+        let imposition = imposition.transpose()?;
+
+        // Tags cannot be written for a subset of pages or for pages that were
+        // rearranged.
+        let untaggable = if pages.is_some() {
+            Some("--pages")
+        } else if imposition.is_some() {
+            Some("--impose")
+        } else {
+            None
+        };
+
+        let tagged = !args.no_pdf_tags && untaggable.is_none();
+        if let Some(flag) = untaggable
+            && output_format == OutputFormat::Pdf
+            && !args.no_pdf_tags
+        {
             warnings.push(
-                HintedString::from("using --pages implies --no-pdf-tags").with_hints([
-                    "the resulting PDF will be inaccessible".into(),
-                    "add --no-pdf-tags to silence this warning".into(),
-                ]),
+                HintedString::from(eco_format!("using {flag} implies --no-pdf-tags"))
+                    .with_hints([
+                        "the resulting PDF will be inaccessible".into(),
+                        "add --no-pdf-tags to silence this warning".into(),
+                    ]),
             );
         }
 
@@ -159,13 +193,14 @@ impl CompileConfig {
 
             for (standard, name) in ACCESSIBLE {
                 if args.pdf_standard.contains(standard) {
-                    if args.no_pdf_tags {
-                        bail!("cannot disable PDF tags when exporting a {name} document");
-                    } else {
-                        bail!(
+                    match untaggable {
+                        Some(flag) if !args.no_pdf_tags => bail!(
                             "cannot disable PDF tags when exporting a {name} document";
-                            hint: "using --pages implies --no-pdf-tags";
-                        );
+                            hint: "using {flag} implies --no-pdf-tags";
+                        ),
+                        _ => bail!(
+                            "cannot disable PDF tags when exporting a {name} document"
+                        ),
                     }
                 }
             }
@@ -222,6 +257,7 @@ impl CompileConfig {
             output,
             output_format,
             pages,
+            imposition,
             pdf_standards,
             tagged,
             creation_timestamp: args
@@ -314,7 +350,12 @@ fn compile_and_export(
     match config.output_format {
         OutputFormat::Pdf | OutputFormat::Png | OutputFormat::Svg => {
             let Warned { output, warnings } = typst::compile::<PagedDocument>(world);
-            let result = output.and_then(|document| export_paged(&document, config));
+            let result = output.and_then(|document| match &config.imposition {
+                Some(imposition) => {
+                    export_paged(&typst_layout::impose(&document, imposition), config)
+                }
+                None => export_paged(&document, config),
+            });
             Warned { output: result, warnings }
         }
         OutputFormat::Html => {
@@ -0,0 +1,663 @@
+use std::collections::{HashMap, HashSet};
+use std::io::{self, BufRead, Read, Write};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::thread::{self, ThreadId};
+
+use codespan_reporting::term::termcolor::NoColor;
+use ecow::{EcoString, eco_format};
+use serde_json::{Value as Json, json};
+use typst::World;
+use typst::debug::{Debugger, Stop};
+use typst::diag::{HintedStrResult, SourceDiagnostic, Warned, bail};
+use typst::foundations::{Repr, Value};
+use typst::syntax::Span;
+use typst_html::HtmlDocument;
+use typst_layout::PagedDocument;
+
+use crate::args::{DebugCommand, Input, Target};
+use crate::world::SystemWorld;
+
+/// The variables reference of the local variables scope.
+const LOCALS: u64 = 1;
+/// The variables reference of the styles scope.
+const STYLES: u64 = 2;
+
+/// Execute a debug command.
+///
+/// Runs a debug adapter that communicates over stdin and stdout using the
+/// Debug Adapter Protocol. Compilation starts once the client has finished its
+/// configuration and the adapter exits when the client disconnects.
+pub fn debug(command: &'static DebugCommand) -> HintedStrResult<()> {
+    if matches!(command.input, Input::Stdin) {
+        bail!("cannot debug input from stdin because it is used by the debug adapter");
+    }
+
+    let mut world =
+        SystemWorld::new(Some(&command.input), &command.world, &command.process)?;
+    world.reset();
+    world.source(world.main()).map_err(|err| err.to_string())?;
+
+    let adapter = Arc::new(Adapter::new(world));
+    let mut stdin = io::stdin().lock();
+    while let Some(request) = read_message(&mut stdin)
+        .map_err(|err| eco_format!("failed to read request ({err})"))?
+    {
+        if !adapter.handle(&request, command.target) {
+            break;
+        }
+    }
+
+    adapter.detach();
+    Ok(())
+}
+
+/// A debugger that is controlled by a client over the Debug Adapter Protocol.
+struct Adapter {
+    /// The world in which the document is compiled.
+    world: SystemWorld,
+    /// The sequence number of the next outgoing message.
+    seq: AtomicU64,
+    /// The state shared between the request handler and compilation.
+    state: Mutex<State>,
+    /// Notified when evaluation may resume.
+    resumed: Condvar,
+}
+
+/// The state of a debugging session.
+#[derive(Default)]
+struct State {
+    /// The lines with breakpoints, by source file.
+    breakpoints: HashMap<PathBuf, HashSet<usize>>,
+    /// When evaluation should pause next.
+    mode: Mode,
+    /// The call stack of each thread that evaluates code.
+    stacks: HashMap<ThreadId, Vec<Frame>>,
+    /// The threads that evaluated code, in the order in which they reached
+    /// their first statement. A thread's id for the client is its index plus
+    /// one.
+    threads: Vec<ThreadId>,
+    /// Details about the point at which evaluation is paused, if it is.
+    paused: Option<Snapshot>,
+    /// Whether the client disconnected. Evaluation never pauses afterwards.
+    detached: bool,
+}
+
+/// When evaluation should pause next.
+#[derive(Default, Copy, Clone)]
+enum Mode {
+    /// Only at breakpoints.
+    #[default]
+    Run,
+    /// At the next statement.
+    Pause,
+    /// At the next statement on another line, including in called closures.
+    StepIn(Origin),
+    /// At the next statement on another line of the same or an outer frame.
+    StepOver(Origin),
+    /// At the next statement of an outer frame.
+    StepOut(Origin),
+}
+
+/// Where a step started.
+#[derive(Copy, Clone)]
+struct Origin {
+    /// The thread that was paused.
+    thread: ThreadId,
+    /// The number of frames on the paused thread's stack.
+    depth: usize,
+}
+
+/// A closure call or show rule transformation that is being evaluated.
+#[derive(Clone)]
+struct Frame {
+    /// The name of the called closure or the applied show rule.
+    name: EcoString,
+    /// The statement that is currently evaluated in the frame or the frame's
+    /// body if none was reached yet.
+    span: Span,
+    /// The position of the last statement that was reached in the frame.
+    position: Option<Position>,
+}
+
+/// A resolved position in a source file.
+#[derive(Clone, PartialEq)]
+struct Position {
+    /// The path of the file.
+    path: PathBuf,
+    /// The byte offset in the file.
+    offset: usize,
+    /// The 1-based line.
+    line: usize,
+    /// The 1-based column.
+    column: usize,
+}
+
+impl Position {
+    /// Whether evaluation moved to another line or looped back when it went
+    /// from `prev` to `self`. Statements that are nested in a previous one on
+    /// the same line don't count as a move.
+    fn moved_from(&self, prev: Option<&Self>) -> bool {
+        prev.is_none_or(|prev| {
+            self.path != prev.path || self.line != prev.line || self.offset <= prev.offset
+        })
+    }
+}
+
+/// Details about the point at which evaluation is paused.
+struct Snapshot {
+    /// Where the step started from.
+    origin: Origin,
+    /// The frames of the paused thread, innermost first.
+    frames: Vec<Frame>,
+    /// The variables that are in scope in the innermost frame.
+    locals: Vec<(EcoString, Value)>,
+    /// The active styles in the innermost frame, innermost first.
+    styles: Vec<EcoString>,
+    /// Values whose children were handed out with a variables reference. The
+    /// value at index `i` has the reference `i + 3`.
+    expandable: Vec<Value>,
+}
+
+impl Adapter {
+    /// Create a new adapter for the given world.
+    fn new(world: SystemWorld) -> Self {
+        Self {
+            world,
+            seq: AtomicU64::new(1),
+            state: Mutex::new(State::default()),
+            resumed: Condvar::new(),
+        }
+    }
+
+    /// Handle a request from the client. Returns `false` if the session ended.
+    fn handle(self: &Arc<Self>, request: &Json, target: Target) -> bool {
+        if request["type"] != "request" {
+            return true;
+        }
+
+        let command = request["command"].as_str().unwrap_or_default();
+        let args = &request["arguments"];
+        let mut running = true;
+        let result = match command {
+            "initialize" => Ok(json!({
+                "supportsConfigurationDoneRequest": true,
+                "supportsTerminateRequest": true,
+            })),
+            "launch" | "attach" => {
+                if args["stopOnEntry"] == true {
+                    self.lock().mode = Mode::Pause;
+                }
+                Ok(json!({}))
+            }
+            "setBreakpoints" => Ok(self.set_breakpoints(args)),
+            "setExceptionBreakpoints" => Ok(json!({ "breakpoints": [] })),
+            "configurationDone" => {
+                let adapter = Arc::clone(self);
+                thread::spawn(move || adapter.compile(target));
+                Ok(json!({}))
+            }
+            "threads" => Ok(self.threads()),
+            "stackTrace" => Ok(self.stack_trace(args)),
+            "scopes" => Ok(self.scopes(args)),
+            "variables" => Ok(self.variables(args)),
+            "continue" => {
+                self.resume(|_| Mode::Run);
+                Ok(json!({ "allThreadsContinued": true }))
+            }
+            "next" => {
+                self.resume(Mode::StepOver);
+                Ok(json!({}))
+            }
+            "stepIn" => {
+                self.resume(Mode::StepIn);
+                Ok(json!({}))
+            }
+            "stepOut" => {
+                self.resume(Mode::StepOut);
+                Ok(json!({}))
+            }
+            "pause" => {
+                self.lock().mode = Mode::Pause;
+                Ok(json!({}))
+            }
+            "disconnect" | "terminate" => {
+                self.detach();
+                running = false;
+                Ok(json!({}))
+            }
+            _ => Err(eco_format!("unsupported request: {command}")),
+        };
+
+        let mut response = json!({
+            "type": "response",
+            "request_seq": request["seq"],
+            "command": command,
+            "success": result.is_ok(),
+        });
+
+        match result {
+            Ok(body) => response["body"] = body,
+            Err(message) => response["message"] = json!(message),
+        }
+
+        self.send(response);
+        if command == "initialize" {
+            self.event("initialized", json!({}));
+        }
+
+        running
+    }
+
+    /// Compile the document with the debugger attached.
+    fn compile(self: Arc<Self>, target: Target) {
+        let debugger: Arc<dyn Debugger> = self.clone();
+        let Warned { output, warnings } = match target {
+            Target::Paged => {
+                typst::compile_debugged::<PagedDocument>(&self.world, debugger)
+                    .map(|result| result.map(drop))
+            }
+            Target::Html => {
+                typst::compile_debugged::<HtmlDocument>(&self.world, debugger)
+                    .map(|result| result.map(drop))
+            }
+        };
+
+        let errors = output.as_ref().err().map(|errors| errors.as_slice());
+        self.report(errors.unwrap_or_default(), &warnings);
+        self.event("exited", json!({ "exitCode": i32::from(errors.is_some()) }));
+        self.event("terminated", json!({}));
+    }
+
+    /// Send diagnostics to the client's console.
+    fn report(&self, errors: &[SourceDiagnostic], warnings: &[SourceDiagnostic]) {
+        let mut buf = NoColor::new(vec![]);
+        let emitted = typst_kit::diagnostics::emit(
+            &mut buf,
+            &self.world,
+            errors.iter().chain(warnings),
+            typst_kit::diagnostics::DiagnosticFormat::Short,
+        );
+
+        let output = match emitted {
+            Ok(()) => String::from_utf8_lossy(&buf.into_inner()).into_owned(),
+            Err(err) => format!("failed to print diagnostics ({err})\n"),
+        };
+
+        if !output.is_empty() {
+            self.event("output", json!({ "category": "stderr", "output": output }));
+        }
+    }
+
+    /// Replace the breakpoints of a source file.
+    fn set_breakpoints(&self, args: &Json) -> Json {
+        let path = args["source"]["path"].as_str().map(PathBuf::from).unwrap_or_default();
+        let lines: Vec<usize> = args["breakpoints"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|bp| bp["line"].as_u64())
+            .map(|line| line as usize)
+            .collect();
+
+        let breakpoints: Vec<_> = lines
+            .iter()
+            .map(|line| json!({ "verified": true, "line": line }))
+            .collect();
+
+        let path = path.canonicalize().unwrap_or(path);
+        self.lock().breakpoints.insert(path, lines.into_iter().collect());
+        json!({ "breakpoints": breakpoints })
+    }
+
+    /// The threads that evaluated code.
+    fn threads(&self) -> Json {
+        let state = self.lock();
+        let threads: Vec<_> = (1..=state.threads.len())
+            .map(|id| json!({ "id": id, "name": eco_format!("thread {id}") }))
+            .collect();
+        json!({ "threads": threads })
+    }
+
+    /// The frames of a thread, innermost first.
+    ///
+    /// For the paused thread, these are the frames at the point where it
+    /// paused. Other threads wait at their latest statement.
+    fn stack_trace(&self, args: &Json) -> Json {
+        let state = self.lock();
+        let id = args["threadId"].as_u64().unwrap_or_default() as usize;
+        let Some(&thread) = id.checked_sub(1).and_then(|i| state.threads.get(i)) else {
+            return json!({ "stackFrames": [], "totalFrames": 0 });
+        };
+
+        let stack: Vec<&Frame> = match &state.paused {
+            Some(snapshot) if snapshot.origin.thread == thread => {
+                snapshot.frames.iter().collect()
+            }
+            _ => state.stacks.get(&thread).into_iter().flatten().rev().collect(),
+        };
+
+        let frames: Vec<_> = stack
+            .into_iter()
+            .enumerate()
+            .map(|(i, frame)| {
+                let mut json = json!({
+                    "id": frame_id(id, i),
+                    "name": frame.name,
+                    "line": 0,
+                    "column": 0,
+                });
+                if let Some(position) = self.locate(frame.span) {
+                    json["source"] = json!({ "path": position.path });
+                    json["line"] = json!(position.line);
+                    json["column"] = json!(position.column);
+                }
+                json
+            })
+            .collect();
+
+        json!({ "stackFrames": frames, "totalFrames": frames.len() })
+    }
+
+    /// The scopes of a frame. Only the innermost frame of the paused thread
+    /// has variables.
+    fn scopes(&self, args: &Json) -> Json {
+        let state = self.lock();
+        let innermost = state.paused.as_ref().and_then(|snapshot| {
+            let index =
+                state.threads.iter().position(|&t| t == snapshot.origin.thread)?;
+            Some(frame_id(index + 1, 0))
+        });
+
+        if innermost.is_none() || args["frameId"].as_u64() != innermost {
+            return json!({ "scopes": [] });
+        }
+
+        json!({
+            "scopes": [
+                { "name": "Locals", "variablesReference": LOCALS, "expensive": false },
+                { "name": "Styles", "variablesReference": STYLES, "expensive": false },
+            ]
+        })
+    }
+
+    /// The variables of a scope or the children of a structured value.
+    fn variables(&self, args: &Json) -> Json {
+        let reference = args["variablesReference"].as_u64().unwrap_or_default();
+        let mut state = self.lock();
+        let Some(snapshot) = &mut state.paused else {
+            return json!({ "variables": [] });
+        };
+
+        let variables: Vec<_> = match reference {
+            LOCALS => snapshot
+                .locals
+                .clone()
+                .into_iter()
+                .map(|(name, value)| snapshot.variable(name, value))
+                .collect(),
+            STYLES => snapshot
+                .styles
+                .iter()
+                .enumerate()
+                .map(|(i, style)| {
+                    json!({ "name": i.to_string(), "value": style, "variablesReference": 0 })
+                })
+                .collect(),
+            _ => {
+                let index = reference.checked_sub(3).map(|i| i as usize);
+                let value = index.and_then(|i| snapshot.expandable.get(i)).cloned();
+                children(value.as_ref())
+                    .into_iter()
+                    .map(|(name, value)| snapshot.variable(name, value))
+                    .collect()
+            }
+        };
+
+        json!({ "variables": variables })
+    }
+
+    /// Resume evaluation, pausing next as determined by `mode`.
+    fn resume(&self, mode: impl FnOnce(Origin) -> Mode) {
+        let mut state = self.lock();
+        if let Some(snapshot) = state.paused.take() {
+            state.mode = mode(snapshot.origin);
+        }
+        self.resumed.notify_all();
+    }
+
+    /// Stop pausing evaluation for the rest of the session.
+    fn detach(&self) {
+        let mut state = self.lock();
+        state.detached = true;
+        state.paused = None;
+        self.resumed.notify_all();
+    }
+
+    /// Pause evaluation at the given stop if the mode or a breakpoint says so.
+    fn check(&self, mut state: MutexGuard<State>, stop: &Stop) {
+        let thread = thread::current().id();
+        let position = self.locate(stop.span);
+        let breakpoint = position.as_ref().is_some_and(|position| {
+            state
+                .breakpoints
+                .get(&position.path)
+                .is_some_and(|lines| lines.contains(&position.line))
+        });
+
+        if !state.threads.contains(&thread) {
+            state.threads.push(thread);
+        }
+
+        let stack = state.stacks.entry(thread).or_default();
+        if stack.is_empty() {
+            stack.push(Frame {
+                name: "main".into(),
+                span: stop.span,
+                position: None,
+            });
+        }
+
+        let depth = stack.len();
+        let frame = stack.last_mut().unwrap();
+        let moved = position
+            .as_ref()
+            .is_some_and(|position| position.moved_from(frame.position.as_ref()));
+        frame.span = stop.span;
+        frame.position = position;
+
+        let reason = match state.mode {
+            Mode::Pause => Some("pause"),
+            Mode::StepIn(origin)
+                if origin.thread != thread || origin.depth != depth || moved =>
+            {
+                Some("step")
+            }
+            Mode::StepOver(origin)
+                if origin.thread != thread
+                    || depth < origin.depth
+                    || (depth == origin.depth && moved) =>
+            {
+                Some("step")
+            }
+            Mode::StepOut(origin) if origin.thread != thread || depth < origin.depth => {
+                Some("step")
+            }
+            _ if breakpoint && moved => Some("breakpoint"),
+            _ => None,
+        };
+
+        let Some(reason) = reason else { return };
+        let frames = state.stacks[&thread].iter().rev().cloned().collect();
+        let id = state.threads.iter().position(|&t| t == thread).unwrap() + 1;
+        state.mode = Mode::Run;
+        state.paused = Some(Snapshot::new(stop, Origin { thread, depth }, frames));
+        self.event(
+            "stopped",
+            json!({ "reason": reason, "threadId": id, "allThreadsStopped": true }),
+        );
+
+        while state.paused.is_some() && !state.detached {
+            state = self.resumed.wait(state).unwrap();
+        }
+    }
+
+    /// Resolve a span to a position in a source file.
+    fn locate(&self, span: Span) -> Option<Position> {
+        let id = span.id()?;
+        let source = self.world.source(id).ok()?;
+        let offset = source.range(span)?.start;
+        let (line, column) = source.lines().byte_to_line_column(offset)?;
+        let path = self.world.path(id).ok()?;
+        Some(Position { path, offset, line: line + 1, column: column + 1 })
+    }
+
+    /// Lock the session state.
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap()
+    }
+
+    /// Send an event to the client.
+    fn event(&self, event: &str, body: Json) {
+        self.send(json!({ "type": "event", "event": event, "body": body }));
+    }
+
+    /// Send a message to the client.
+    fn send(&self, mut message: Json) {
+        message["seq"] = json!(self.seq.fetch_add(1, Ordering::Relaxed));
+        let body = message.to_string();
+        let mut stdout = io::stdout().lock();
+        write!(stdout, "Content-Length: {}\r\n\r\n{body}", body.len())
+            .and_then(|_| stdout.flush())
+            .ok();
+    }
+}
+
+impl Debugger for Adapter {
+    fn statement(&self, stop: &Stop) {
+        let mut state = self.lock();
+        if state.detached {
+            return;
+        }
+
+        // Other threads wait while one of them is paused.
+        while state.paused.is_some() && !state.detached {
+            state = self.resumed.wait(state).unwrap();
+        }
+
+        self.check(state, stop);
+    }
+
+    fn enter(&self, name: &str, span: Span) {
+        let mut state = self.lock();
+        let stack = state.stacks.entry(thread::current().id()).or_default();
+        stack.push(Frame { name: name.into(), span, position: None });
+    }
+
+    fn exit(&self) {
+        let mut state = self.lock();
+        if let Some(stack) = state.stacks.get_mut(&thread::current().id()) {
+            stack.pop();
+        }
+    }
+}
+
+impl Snapshot {
+    /// Capture the variables and styles at a stop.
+    fn new(stop: &Stop, origin: Origin, frames: Vec<Frame>) -> Self {
+        // Inner scopes shadow outer ones.
+        let mut seen = HashSet::new();
+        let locals = std::iter::once(&stop.scopes.top)
+            .chain(stop.scopes.scopes.iter().rev())
+            .flat_map(|scope| scope.iter())
+            .filter(|(name, _)| seen.insert(name.as_str()))
+            .map(|(name, binding)| (name.clone(), binding.read().clone()))
+            .collect();
+
+        let styles = stop
+            .styles
+            .into_iter()
+            .flat_map(|styles| styles.entries())
+            .map(|style| eco_format!("{style:?}"))
+            .collect();
+
+        Self { origin, frames, locals, styles, expandable: vec![] }
+    }
+
+    /// Describe a variable, handing out a reference to its children if it has
+    /// any.
+    fn variable(&mut self, name: EcoString, value: Value) -> Json {
+        let repr = value.repr();
+        let ty = value.ty().to_string();
+        let reference = if children(Some(&value)).is_empty() {
+            0
+        } else {
+            self.expandable.push(value);
+            self.expandable.len() as u64 + 2
+        };
+
+        json!({ "name": name, "value": repr, "type": ty, "variablesReference": reference })
+    }
+}
+
+/// The id of the frame at `index` (counting from the innermost frame) of the
+/// thread with the given client id. Ids are unique across threads.
+fn frame_id(thread: usize, index: usize) -> u64 {
+    ((thread as u64) << 32) | index as u64
+}
+
+/// The children of a structured value that the client can expand.
+fn children(value: Option<&Value>) -> Vec<(EcoString, Value)> {
+    match value {
+        Some(Value::Array(array)) => array
+            .iter()
+            .enumerate()
+            .map(|(i, item)| (eco_format!("{i}"), item.clone()))
+            .collect(),
+        Some(Value::Dict(dict)) => dict
+            .iter()
+            .map(|(key, value)| (key.as_str().into(), value.clone()))
+            .collect(),
+        Some(Value::Content(content)) => content
+            .fields()
+            .into_iter()
+            .map(|(key, value)| (key.as_str().into(), value))
+            .collect(),
+        Some(Value::Module(module)) => module
+            .scope()
+            .iter()
+            .map(|(name, binding)| (name.clone(), binding.read().clone()))
+            .collect(),
+        _ => vec![],
+    }
+}
+
+/// Read a message with a `Content-Length` header. Returns `None` at the end of
+/// the input.
+fn read_message(reader: &mut impl BufRead) -> io::Result<Option<Json>> {
+    let mut length = None;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+
+        if let Some(value) = line.strip_prefix("Content-Length:") {
+            length = value.trim().parse::<usize>().ok();
+        }
+    }
+
+    let Some(length) = length else {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "missing content length"));
+    };
+
+    let mut buf = vec![0; length];
+    reader.read_exact(&mut buf)?;
+    serde_json::from_slice(&buf).map(Some).map_err(io::Error::from)
+}
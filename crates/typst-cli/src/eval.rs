@@ -1,10 +1,11 @@
 use comemo::Track;
 use ecow::eco_format;
 use typst::diag::{HintedStrResult, SourceResult, Warned};
+use typst::engine::{Cancellation, Fuel, Sink};
 use typst::foundations::{Context, Output, Scope, StyleChain, Value};
 use typst::routines::SpanMode;
 use typst::syntax::{Span, SyntaxMode};
-use typst::{World, engine::Sink, introspection::Introspector};
+use typst::{World, introspection::Introspector};
 use typst_eval::eval_string;
 use typst_html::HtmlDocument;
 use typst_layout::PagedDocument;
@@ -89,6 +90,8 @@ fn evaluate_expression(
     eval_string(
         world.track(),
         library,
+        Cancellation::new().track(),
+        Fuel::new(library.limits.fuel).track(),
         sink.track_mut(),
         introspector.track(),
         Context::new(None, Some(StyleChain::new(&library.styles))).track(),
@@ -1,6 +1,7 @@
 mod args;
 mod compile;
 mod completions;
+mod debug;
 mod deps;
 mod download;
 mod eval;
@@ -11,6 +12,7 @@ mod init;
 mod packages;
 mod query;
 mod terminal;
+mod test;
 #[cfg(feature = "self-update")]
 mod update;
 mod watch;
@@ -73,6 +75,8 @@ fn dispatch() -> HintedStrResult<()> {
         Command::Init(command) => crate::init::init(command)?,
         Command::Query(command) => crate::query::query(command)?,
         Command::Eval(command) => crate::eval::eval(command)?,
+        Command::Debug(command) => crate::debug::debug(command)?,
+        Command::Test(command) => crate::test::test(command)?,
         Command::Fonts(command) => crate::fonts::fonts(command),
         Command::Update(command) => crate::update::update(command)?,
         Command::Completions(command) => crate::completions::completions(command),
@@ -4,7 +4,7 @@ use comemo::Track;
 use ecow::{EcoString, eco_format};
 use typst::World;
 use typst::diag::{HintedStrResult, SourceDiagnostic, StrResult, Warned, bail, warning};
-use typst::engine::Sink;
+use typst::engine::{Cancellation, Fuel, Sink};
 use typst::foundations::{
     Content, Context, IntoValue, LocatableSelector, Output, Repr, Scope,
 };
@@ -74,6 +74,8 @@ fn retrieve(
     let selector = eval_string(
         world.track(),
         world.library(),
+        Cancellation::new().track(),
+        Fuel::new(world.library().limits.fuel).track(),
         // TODO: propagate warnings
         Sink::new().track_mut(),
         EmptyIntrospector.track(),
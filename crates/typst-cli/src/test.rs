@@ -0,0 +1,463 @@
+use std::fmt::Write as _;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use codespan_reporting::term::termcolor::{Color, ColorSpec, WriteColor};
+use comemo::Track;
+use ecow::{EcoString, EcoVec, eco_format};
+use typst::diag::{
+    At, HintedStrResult, SourceDiagnostic, SourceResult, StrResult, Warned, bail,
+};
+use typst::engine::{Cancellation, Fuel, Route, Sink, Traced};
+use typst::foundations::{Context, Module, Output, StyleChain, Value};
+use typst::layout::Abs;
+use typst::routines::SpanMode;
+use typst::syntax::{Span, SyntaxMode};
+use typst::visualize;
+use typst::{World, WorldExt};
+use typst_eval::eval_string;
+use typst_kit::diagnostics::DiagnosticWorld;
+use typst_layout::PagedDocument;
+
+use crate::args::{TestCommand, WorldArgs};
+use crate::compile::print_diagnostics;
+use crate::world::SystemWorld;
+use crate::{set_failed, terminal};
+
+/// The prefix of functions that are run as unit tests.
+const PREFIX: &str = "test-";
+
+/// The gap between pages in rendered documents.
+const GAP: Abs = Abs::pt(1.0);
+
+/// Execute a test command.
+pub fn test(command: &'static TestCommand) -> HintedStrResult<()> {
+    let dir = command.dir.as_deref().unwrap_or(Path::new("."));
+
+    // The package directory serves as the project root unless another one
+    // was explicitly configured.
+    let world_args = WorldArgs {
+        root: Some(command.world.root.clone().unwrap_or_else(|| dir.to_path_buf())),
+        ..command.world.clone()
+    };
+    let mut world = SystemWorld::new(None, &world_args, &command.process)?;
+
+    let tests = dir
+        .canonicalize()
+        .map_err(|err| eco_format!("failed to resolve {} ({err})", dir.display()))?
+        .join("tests");
+
+    let mut paths = vec![];
+    discover(&tests, &tests, &mut paths)?;
+    paths.sort();
+    paths.retain(|path| {
+        command
+            .filter
+            .as_deref()
+            .is_none_or(|filter| name(&tests, path).contains(filter))
+    });
+
+    if paths.is_empty() {
+        bail!("no tests found in {}", tests.display());
+    }
+
+    let mut suites = vec![];
+    for path in &paths {
+        suites.push(run(&mut world, command, &tests, path)?);
+    }
+
+    let cases = suites.iter().flat_map(|suite| &suite.cases);
+    let failed = cases.clone().filter(|case| case.failure.is_some()).count();
+    let passed = cases.count() - failed;
+    print_summary(passed, failed)
+        .map_err(|err| eco_format!("failed to print summary ({err})"))?;
+
+    if let Some(path) = &command.junit {
+        fs::write(path, junit(&suites)).map_err(|err| {
+            eco_format!("failed to write JUnit report to {} ({err})", path.display())
+        })?;
+    }
+
+    if failed > 0 {
+        set_failed();
+    }
+
+    Ok(())
+}
+
+/// The results of the tests in one file.
+struct Suite {
+    /// The path of the file relative to the tests directory.
+    name: String,
+    /// The results of the individual tests.
+    cases: Vec<Case>,
+}
+
+/// The result of a single test.
+struct Case {
+    /// The name of the test.
+    name: EcoString,
+    /// How long the test took.
+    time: Duration,
+    /// Why the test failed, if it did.
+    failure: Option<Failure>,
+}
+
+/// Why a test failed.
+struct Failure {
+    /// A one-line summary.
+    message: EcoString,
+    /// All diagnostics that caused the failure.
+    details: String,
+}
+
+impl Failure {
+    /// Creates a failure from the errors that a test produced.
+    fn errors(world: &SystemWorld, errors: &[SourceDiagnostic]) -> Self {
+        let message = errors
+            .first()
+            .map(|error| error.message.clone())
+            .unwrap_or_else(|| "test failed".into());
+        let mut details = String::new();
+        for error in errors {
+            match locate(world, error) {
+                Some(location) => writeln!(details, "{location}: {}", error.message),
+                None => writeln!(details, "{}", error.message),
+            }
+            .unwrap();
+            for hint in &error.hints {
+                writeln!(details, "  hint: {}", hint.v).unwrap();
+            }
+        }
+        Self { message, details }
+    }
+}
+
+/// Collects all test files in `dir` and its subdirectories, skipping the
+/// reference images and files whose names start with an underscore.
+fn discover(tests: &Path, dir: &Path, paths: &mut Vec<PathBuf>) -> StrResult<()> {
+    let entries = fs::read_dir(dir).map_err(|err| {
+        eco_format!("failed to read tests directory {} ({err})", dir.display())
+    })?;
+
+    for entry in entries {
+        let path = entry
+            .map_err(|err| eco_format!("failed to read tests directory ({err})"))?
+            .path();
+        if path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_none_or(|name| name.starts_with('_') || (dir == tests && name == "ref"))
+        {
+            continue;
+        }
+
+        if path.is_dir() {
+            discover(tests, &path, paths)?;
+        } else if path.extension().is_some_and(|ext| ext == "typ") {
+            paths.push(path);
+        }
+    }
+
+    Ok(())
+}
+
+/// Runs the tests in the file at the given path.
+fn run(
+    world: &mut SystemWorld,
+    command: &TestCommand,
+    tests: &Path,
+    path: &Path,
+) -> HintedStrResult<Suite> {
+    world.set_main(path)?;
+    world.reset();
+    let world = &*world;
+
+    let mut suite = Suite { name: name(tests, path), cases: vec![] };
+
+    // The file itself is a test that passes if the document compiles and
+    // matches its reference image.
+    let start = Instant::now();
+    let Warned { output, warnings } = typst::compile::<PagedDocument>(world);
+    let (document, errors) = match output {
+        Ok(document) => (Some(document), EcoVec::new()),
+        Err(errors) => (None, errors),
+    };
+    let failure = match &document {
+        Some(document) => check_reference(command, tests, path, document)?,
+        None => Some(Failure::errors(world, &errors)),
+    };
+
+    let case = Case {
+        name: "document".into(),
+        time: start.elapsed(),
+        failure,
+    };
+    print_case(&suite, &case)?;
+    print_diagnostics(world, &errors, &warnings, command.process.diagnostic_format)
+        .map_err(|err| eco_format!("failed to print diagnostics ({err})"))?;
+    suite.cases.push(case);
+
+    let Some(document) = document else { return Ok(suite) };
+    let Ok(module) = module(world) else { return Ok(suite) };
+
+    // Then, all test functions defined in the file are called individually.
+    for (name, binding) in module.scope().iter() {
+        let Value::Func(func) = binding.read() else { continue };
+        if !name.starts_with(PREFIX) || func.span().id() != Some(world.main()) {
+            continue;
+        }
+
+        let start = Instant::now();
+        let mut sink = Sink::new();
+        let library = world.library();
+        let result = eval_string(
+            (world as &dyn World).track(),
+            library,
+            Cancellation::new().track(),
+            Fuel::new(library.limits.fuel).track(),
+            sink.track_mut(),
+            Output::introspector(&document).track(),
+            Context::new(None, Some(StyleChain::new(&library.styles))).track(),
+            &eco_format!("{name}()"),
+            SpanMode::Uniform(func.span()),
+            SyntaxMode::Code,
+            module.scope().clone(),
+        );
+
+        let failure = result.as_ref().err().map(|errors| Failure::errors(world, errors));
+        let case = Case { name: name.clone(), time: start.elapsed(), failure };
+        print_case(&suite, &case)?;
+
+        let errors = result.err().unwrap_or_default();
+        let warnings = sink.warnings();
+        print_diagnostics(world, &errors, &warnings, command.process.diagnostic_format)
+            .map_err(|err| eco_format!("failed to print diagnostics ({err})"))?;
+
+        suite.cases.push(case);
+    }
+
+    Ok(suite)
+}
+
+/// Evaluates the main file into a module to find its test functions.
+fn module(world: &SystemWorld) -> SourceResult<Module> {
+    let source = world.source(world.main()).at(Span::detached())?;
+    typst_eval::eval(
+        (world as &dyn World).track(),
+        world.library(),
+        Traced::default().track(),
+        Cancellation::new().track(),
+        Fuel::new(world.library().limits.fuel).track(),
+        Sink::new().track_mut(),
+        Route::default().track(),
+        &source,
+    )
+}
+
+/// Compares the rendered document with its reference image, if there is one,
+/// or writes a new reference image if requested.
+fn check_reference(
+    command: &TestCommand,
+    tests: &Path,
+    path: &Path,
+    document: &PagedDocument,
+) -> StrResult<Option<Failure>> {
+    let relative = path.strip_prefix(tests).unwrap_or(path);
+    let ref_path = tests.join("ref").join(relative).with_extension("png");
+    if !command.update && !ref_path.exists() {
+        return Ok(None);
+    }
+
+    let pixmap = typst_render::render_merged(
+        document,
+        command.ppi / 72.0,
+        GAP,
+        Some(visualize::Color::BLACK),
+    );
+
+    if command.update {
+        let buf = pixmap
+            .encode_png()
+            .map_err(|err| eco_format!("failed to encode PNG file ({err})"))?;
+        if let Some(parent) = ref_path.parent() {
+            fs::create_dir_all(parent).map_err(|err| {
+                eco_format!("failed to create directory {} ({err})", parent.display())
+            })?;
+        }
+        fs::write(&ref_path, buf).map_err(|err| {
+            eco_format!("failed to write reference image {} ({err})", ref_path.display())
+        })?;
+        return Ok(None);
+    }
+
+    let reference = fs::read(&ref_path)
+        .map_err(|err| eco_format!("failed to read {} ({err})", ref_path.display()))
+        .and_then(|data| {
+            tiny_skia::Pixmap::decode_png(&data).map_err(|err| {
+                eco_format!("failed to decode {} ({err})", ref_path.display())
+            })
+        })?;
+
+    let message = if (pixmap.width(), pixmap.height())
+        != (reference.width(), reference.height())
+    {
+        eco_format!(
+            "rendered document has size {}x{}, but the reference image has size {}x{}",
+            pixmap.width(),
+            pixmap.height(),
+            reference.width(),
+            reference.height(),
+        )
+    } else {
+        let differing = pixmap
+            .data()
+            .chunks(4)
+            .zip(reference.data().chunks(4))
+            .filter(|(a, b)| {
+                a.iter().zip(*b).any(|(x, y)| x.abs_diff(*y) > command.tolerance)
+            })
+            .count();
+        if differing <= command.max_differing_pixels {
+            return Ok(None);
+        }
+        eco_format!(
+            "{differing} pixels differ from the reference image, but at most {} may",
+            command.max_differing_pixels,
+        )
+    };
+
+    let details = format!(
+        "{message} ({})\n  hint: run with `--update` to accept the rendered document",
+        ref_path.display(),
+    );
+    Ok(Some(Failure { message, details }))
+}
+
+/// The name of a test file, i.e. its path relative to the tests directory.
+fn name(tests: &Path, path: &Path) -> String {
+    let relative = path.strip_prefix(tests).unwrap_or(path);
+    relative
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Formats the location of a diagnostic as `file:line:column`.
+fn locate(world: &SystemWorld, diagnostic: &SourceDiagnostic) -> Option<String> {
+    let id = diagnostic.span.id()?;
+    let source = world.source(id).ok()?;
+    let range = world.range(diagnostic.span)?;
+    let (line, column) = source.lines().byte_to_line_column(range.start)?;
+    Some(format!("{}:{}:{}", world.name(id), line + 1, column + 1))
+}
+
+/// Prints the outcome of a test.
+fn print_case(suite: &Suite, case: &Case) -> StrResult<()> {
+    let (status, color) = match case.failure {
+        Some(_) => ("FAIL", Color::Red),
+        None => ("PASS", Color::Green),
+    };
+
+    let print = || -> io::Result<()> {
+        let mut out = terminal::out();
+        out.set_color(ColorSpec::new().set_bold(true).set_fg(Some(color)))?;
+        write!(out, "{status}")?;
+        out.reset()?;
+        write!(out, " {}", suite.name)?;
+        if case.name != "document" {
+            write!(out, " › {}", case.name)?;
+        }
+        writeln!(out, " ({:.2?})", case.time)
+    };
+
+    print().map_err(|err| eco_format!("failed to print test result ({err})"))
+}
+
+/// Prints how many tests passed and failed.
+fn print_summary(passed: usize, failed: usize) -> io::Result<()> {
+    let mut out = terminal::out();
+    writeln!(out)?;
+    let color = if failed > 0 { Color::Red } else { Color::Green };
+    out.set_color(ColorSpec::new().set_bold(true).set_fg(Some(color)))?;
+    write!(out, "{}", if failed > 0 { "failed" } else { "ok" })?;
+    out.reset()?;
+    writeln!(out, ": {passed} passed, {failed} failed")
+}
+
+/// Renders the results as a JUnit XML report.
+fn junit(suites: &[Suite]) -> String {
+    let count = |suite: &Suite| {
+        let failures = suite.cases.iter().filter(|case| case.failure.is_some());
+        (suite.cases.len(), failures.count())
+    };
+
+    let (tests, failures) =
+        suites.iter().map(count).fold((0, 0), |(a, b), (c, d)| (a + c, b + d));
+
+    let mut xml = String::new();
+    writeln!(xml, r#"<?xml version="1.0" encoding="UTF-8"?>"#).unwrap();
+    writeln!(xml, r#"<testsuites name="typst" tests="{tests}" failures="{failures}">"#)
+        .unwrap();
+
+    for suite in suites {
+        let (tests, failures) = count(suite);
+        let time: Duration = suite.cases.iter().map(|case| case.time).sum();
+        writeln!(
+            xml,
+            r#"  <testsuite name="{}" tests="{tests}" failures="{failures}" time="{:.3}">"#,
+            escape(&suite.name),
+            time.as_secs_f64(),
+        )
+        .unwrap();
+
+        for case in &suite.cases {
+            write!(
+                xml,
+                r#"    <testcase name="{}" classname="{}" time="{:.3}""#,
+                escape(&case.name),
+                escape(&suite.name),
+                case.time.as_secs_f64(),
+            )
+            .unwrap();
+            match &case.failure {
+                Some(failure) => {
+                    writeln!(xml, ">").unwrap();
+                    writeln!(
+                        xml,
+                        r#"      <failure message="{}">{}</failure>"#,
+                        escape(&failure.message),
+                        escape(&failure.details),
+                    )
+                    .unwrap();
+                    writeln!(xml, "    </testcase>").unwrap();
+                }
+                None => writeln!(xml, "/>").unwrap(),
+            }
+        }
+
+        writeln!(xml, "  </testsuite>").unwrap();
+    }
+
+    writeln!(xml, "</testsuites>").unwrap();
+    xml
+}
+
+/// Escapes text for use in XML attributes and elements.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
@@ -40,7 +40,7 @@ impl SystemWorld {
     /// Creates a new system world.
     pub fn new(
         input: Option<&Input>,
-        world_args: &'static WorldArgs,
+        world_args: &WorldArgs,
         process_args: &ProcessArgs,
     ) -> Result<Self, WorldCreationError> {
         // Set up the thread pool.
@@ -72,11 +72,12 @@ impl SystemWorld {
             None => Time::system(),
         };
 
+        let font_args = world_args.font.clone();
         Ok(Self {
             workdir: std::env::current_dir().ok(),
             library: LazyHash::new(library),
-            fonts: LazyLock::new(Box::new(|| {
-                crate::fonts::discover_fonts(&world_args.font)
+            fonts: LazyLock::new(Box::new(move || {
+                crate::fonts::discover_fonts(&font_args)
             })),
             files: FileStore::new(SystemFiles::new(input, world_args)?),
             now,
@@ -93,6 +94,20 @@ impl SystemWorld {
         self.workdir.as_deref().unwrap_or(Path::new("."))
     }
 
+    /// Resolves the file system path of the file with the given `id`.
+    pub fn path(&self, id: FileId) -> FileResult<PathBuf> {
+        self.files.loader().resolve(id)
+    }
+
+    /// Makes the file at the given path, which must reside in the project root,
+    /// the new main file.
+    pub fn set_main(&mut self, path: &Path) -> Result<(), WorldCreationError> {
+        let loader = self.files.loader_mut();
+        let vpath = VirtualPath::virtualize(loader.project.path(), path)?;
+        loader.main = RootedPath::new(VirtualRoot::Project, vpath).intern();
+        Ok(())
+    }
+
     /// Return all paths the last compilation depended on.
     pub fn dependencies(&mut self) -> impl Iterator<Item = PathBuf> + '_ {
         let (loader, deps) = self.files.dependencies();
@@ -191,7 +206,7 @@ impl SystemFiles {
     /// Creates a new loader given the configuration.
     pub fn new(
         input: Option<&Input>,
-        world_args: &'static WorldArgs,
+        world_args: &WorldArgs,
     ) -> Result<Self, WorldCreationError> {
         // Resolve the system-global input path.
         let input_path = match input {
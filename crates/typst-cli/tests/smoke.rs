@@ -1,8 +1,10 @@
 use std::collections::HashSet;
 use std::fmt::{self, Debug, Display, Formatter};
+use std::io::{BufRead, BufReader, Read, Write};
 use std::path::{Path, PathBuf};
-use std::process::{Command, Output};
+use std::process::{ChildStdin, ChildStdout, Command, Output, Stdio};
 
+use serde_json::{Value as Json, json};
 use tempfile::TempDir;
 use typst::foundations::Bytes;
 
@@ -238,11 +240,234 @@ fn test_target_available() {
     exec().arg("compile").arg(&main).must_succeed();
 }
 
+#[test]
+fn test_debug_breakpoint() {
+    let project = tempfs();
+    let main = project.write("main.typ", "#let x = 1\n#let y = x + 1\n");
+    let mut child = exec()
+        .arg("debug")
+        .arg(&main)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    let mut session = DebugSession {
+        stdin: child.stdin.take().unwrap(),
+        stdout: BufReader::new(child.stdout.take().unwrap()),
+        seq: 0,
+        events: vec![],
+    };
+
+    session.request("initialize", json!({}));
+    session.request("launch", json!({}));
+    session.request(
+        "setBreakpoints",
+        json!({ "source": { "path": main }, "breakpoints": [{ "line": 2 }] }),
+    );
+    session.request("configurationDone", json!({}));
+
+    let stopped = session.event("stopped");
+    assert_eq!(stopped["body"]["reason"], "breakpoint");
+    let thread = stopped["body"]["threadId"].clone();
+
+    let threads = session.request("threads", json!({}));
+    let threads = threads["body"]["threads"].as_array().unwrap();
+    assert!(threads.iter().any(|t| t["id"] == thread));
+
+    let trace = session.request("stackTrace", json!({ "threadId": thread }));
+    assert_eq!(trace["body"]["stackFrames"][0]["line"], 2);
+
+    let locals = session.request("variables", json!({ "variablesReference": 1 }));
+    assert_eq!(locals["body"]["variables"][0]["name"], "x");
+    assert_eq!(locals["body"]["variables"][0]["value"], "1");
+
+    session.request("continue", json!({ "threadId": thread }));
+    session.event("terminated");
+    session.request("disconnect", json!({}));
+    assert!(child.wait().unwrap().success());
+}
+
+#[test]
+fn test_debug_breakpoint_in_memoized_call() {
+    let project = tempfs();
+    let main = project.write("main.typ", "#let f(x) = {\n  x + 1\n}\n#f(1)\n#f(1)\n");
+    let mut child = exec()
+        .arg("debug")
+        .arg(&main)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    let mut session = DebugSession {
+        stdin: child.stdin.take().unwrap(),
+        stdout: BufReader::new(child.stdout.take().unwrap()),
+        seq: 0,
+        events: vec![],
+    };
+
+    session.request("initialize", json!({}));
+    session.request("launch", json!({}));
+    session.request(
+        "setBreakpoints",
+        json!({ "source": { "path": main }, "breakpoints": [{ "line": 2 }] }),
+    );
+    session.request("configurationDone", json!({}));
+
+    // The second call is identical to the first one, but must not be
+    // memoized away while debugging.
+    for _ in 0..2 {
+        let stopped = session.event("stopped");
+        assert_eq!(stopped["body"]["reason"], "breakpoint");
+        let thread = stopped["body"]["threadId"].clone();
+        let trace = session.request("stackTrace", json!({ "threadId": thread }));
+        assert_eq!(trace["body"]["stackFrames"][0]["name"], "f");
+        session.request("continue", json!({ "threadId": thread }));
+    }
+
+    session.event("terminated");
+    session.request("disconnect", json!({}));
+    assert!(child.wait().unwrap().success());
+}
+
+#[test]
+fn test_test_runner() {
+    let project = tempfs();
+    project.write("lib.typ", "#let add(a, b) = a + b");
+    project.write("tests/_utils.typ", "#panic()");
+    project.write(
+        "tests/math.typ",
+        "#import \"/lib.typ\": add\n\
+         #let test-add() = assert.eq(add(1, 2), 3)\n\
+         #let test-broken() = assert.eq(add(1, 1), 3)\n",
+    );
+    let report = project.resolve("report.xml");
+    let output = exec()
+        .arg("test")
+        .arg(project.path())
+        .arg("--junit")
+        .arg(&report)
+        .must_fail();
+    output
+        .stderr
+        .must_contain("PASS math.typ (")
+        .must_contain("PASS math.typ › test-add")
+        .must_contain("FAIL math.typ › test-broken")
+        .must_contain("error: equality assertion failed")
+        .must_contain("2 passed, 1 failed");
+    project
+        .read("report.xml")
+        .must_contain(r#"<testsuites name="typst" tests="3" failures="1">"#)
+        .must_contain(r#"<testcase name="test-broken" classname="math.typ""#);
+}
+
+#[test]
+fn test_test_runner_reference() {
+    let project = tempfs();
+    project.write("tests/page.typ", "#set page(width: 10pt, height: 10pt)");
+    exec().arg("test").arg(project.path()).arg("--update").must_succeed();
+    assert!(project.resolve("tests/ref/page.png").exists());
+
+    project.write(
+        "tests/page.typ",
+        "#set page(width: 10pt, height: 10pt, margin: 0pt)\n\
+         #rect(width: 1pt, height: 1pt, fill: black)",
+    );
+    exec()
+        .arg("test")
+        .arg(project.path())
+        .must_fail()
+        .stderr
+        .must_contain("FAIL page.typ (")
+        .must_contain("pixels differ from the reference image, but at most 0 may");
+    exec()
+        .arg("test")
+        .arg(project.path())
+        .arg("--max-differing-pixels")
+        .arg("100")
+        .must_succeed()
+        .stderr
+        .must_contain("PASS page.typ (");
+}
+
 /// Executes a command with the Typst CLI.
 fn exec() -> Command {
     Command::new(env!("CARGO_BIN_EXE_typst"))
 }
 
+/// A client for the debug adapter of the Typst CLI.
+struct DebugSession {
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+    seq: u64,
+    events: Vec<Json>,
+}
+
+impl DebugSession {
+    /// Sends a request and waits for its response.
+    #[track_caller]
+    fn request(&mut self, command: &str, arguments: Json) -> Json {
+        self.seq += 1;
+        let body = json!({
+            "seq": self.seq,
+            "type": "request",
+            "command": command,
+            "arguments": arguments,
+        })
+        .to_string();
+        write!(self.stdin, "Content-Length: {}\r\n\r\n{body}", body.len()).unwrap();
+        self.stdin.flush().unwrap();
+
+        loop {
+            let message = self.read();
+            if message["type"] == "event" {
+                self.events.push(message);
+            } else if message["request_seq"] == self.seq {
+                assert_eq!(message["success"], true, "{command} failed: {message}");
+                return message;
+            }
+        }
+    }
+
+    /// Waits for an event with the given name.
+    #[track_caller]
+    fn event(&mut self, event: &str) -> Json {
+        if let Some(i) = self.events.iter().position(|message| message["event"] == event)
+        {
+            return self.events.remove(i);
+        }
+
+        loop {
+            let message = self.read();
+            if message["event"] == event {
+                return message;
+            }
+        }
+    }
+
+    /// Reads the next message.
+    #[track_caller]
+    fn read(&mut self) -> Json {
+        let mut length = 0;
+        loop {
+            let mut line = String::new();
+            assert_ne!(self.stdout.read_line(&mut line).unwrap(), 0, "unexpected end");
+            let line = line.trim_end();
+            if line.is_empty() {
+                break;
+            }
+            if let Some(value) = line.strip_prefix("Content-Length:") {
+                length = value.trim().parse().unwrap();
+            }
+        }
+
+        let mut buf = vec![0; length];
+        self.stdout.read_exact(&mut buf).unwrap();
+        serde_json::from_slice(&buf).unwrap()
+    }
+}
+
 trait CommandExt {
     fn must_succeed(&mut self) -> TestOutput;
     fn must_fail(&mut self) -> TestOutput;
@@ -1,6 +1,6 @@
 use ecow::eco_format;
 use typst_library::diag::{At, Hint, SourceResult, Trace, Tracepoint, bail};
-use typst_library::foundations::{Dict, Value};
+use typst_library::foundations::{Dict, Record, Value};
 use typst_syntax::ast::{self, AstNode};
 
 use crate::{Eval, Vm, call_method_access, is_accessor_method};
@@ -49,7 +49,11 @@ impl Access for ast::Parenthesized<'_> {
 
 impl Access for ast::FieldAccess<'_> {
     fn access<'a>(self, vm: &'a mut Vm) -> SourceResult<&'a mut Value> {
-        access_dict(vm, self)?.at_mut(self.field().get()).at(self.span())
+        let field = self.field();
+        match access_fields(vm, self)? {
+            FieldsMut::Dict(dict) => dict.at_mut(field.get()).at(self.span()),
+            FieldsMut::Record(record) => record.field_mut(field.get()).at(self.span()),
+        }
     }
 }
 
@@ -73,12 +77,22 @@ impl Access for ast::FuncCall<'_> {
     }
 }
 
-pub(crate) fn access_dict<'a>(
+/// A value whose fields can be mutated.
+pub(crate) enum FieldsMut<'a> {
+    /// A dictionary, whose fields can also be created.
+    Dict(&'a mut Dict),
+    /// A value of a custom type, whose fields are fixed.
+    Record(&'a mut Record),
+}
+
+/// Access the target of a field access mutably.
+pub(crate) fn access_fields<'a>(
     vm: &'a mut Vm,
     access: ast::FieldAccess,
-) -> SourceResult<&'a mut Dict> {
+) -> SourceResult<FieldsMut<'a>> {
     match access.target().access(vm)? {
-        Value::Dict(dict) => Ok(dict),
+        Value::Dict(dict) => Ok(FieldsMut::Dict(dict)),
+        Value::Record(record) => Ok(FieldsMut::Record(record)),
         value => {
             let ty = value.ty();
             let span = access.target().span();
@@ -87,7 +101,7 @@ pub(crate) fn access_dict<'a>(
                 Value::Symbol(_) | Value::Content(_) | Value::Module(_) | Value::Func(_)
             ) {
                 bail!(span, "cannot mutate fields on {ty}");
-            } else if typst_library::foundations::fields_on(ty).is_empty() {
+            } else if typst_library::foundations::fields_on(&ty).is_empty() {
                 bail!(span, "{ty} does not have accessible fields");
             } else {
                 // type supports static fields, which don't yet have
@@ -56,6 +56,62 @@ pub(crate) fn destructure(
     })
 }
 
+/// Whether a value has the shape required to be destructured into a pattern.
+///
+/// This holds exactly if [`destructure`] would not fail due to the value, but
+/// is checked without defining any bindings.
+pub(crate) fn fits_pattern(pattern: ast::Pattern, value: &Value) -> bool {
+    match pattern {
+        ast::Pattern::Normal(_) | ast::Pattern::Placeholder(_) => true,
+        ast::Pattern::Parenthesized(parenthesized) => {
+            fits_pattern(parenthesized.pattern(), value)
+        }
+        ast::Pattern::Destructuring(destruct) => match value {
+            Value::Array(array) => fits_array(destruct, array.as_slice()),
+            Value::Dict(dict) => destruct.items().all(|p| match p {
+                ast::DestructuringItem::Pattern(ast::Pattern::Normal(
+                    ast::Expr::Ident(ident),
+                )) => dict.contains(&ident),
+                ast::DestructuringItem::Named(named) => dict
+                    .get(&named.name())
+                    .is_ok_and(|v| fits_pattern(named.pattern(), v)),
+                ast::DestructuringItem::Spread(_) => true,
+                ast::DestructuringItem::Pattern(_) => false,
+            }),
+            _ => false,
+        },
+    }
+}
+
+/// Whether the array's elements fit the destructuring pattern.
+fn fits_array(destruct: ast::Destructuring, values: &[Value]) -> bool {
+    let mut count = 0;
+    let mut spread = false;
+    for p in destruct.items() {
+        match p {
+            ast::DestructuringItem::Pattern(_) => count += 1,
+            ast::DestructuringItem::Spread(_) => spread = true,
+            ast::DestructuringItem::Named(_) => return false,
+        }
+    }
+
+    if values.len() < count || (!spread && values.len() > count) {
+        return false;
+    }
+
+    let mut i = 0;
+    destruct.items().all(|p| match p {
+        ast::DestructuringItem::Pattern(pattern) => {
+            i += 1;
+            fits_pattern(pattern, &values[i - 1])
+        }
+        _ => {
+            i += values.len() - count;
+            true
+        }
+    })
+}
+
 /// Destruct the given value into the pattern and apply the function to each binding.
 fn destructure_impl<F>(
     vm: &mut Vm,
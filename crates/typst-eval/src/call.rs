@@ -1,12 +1,13 @@
 use comemo::{Tracked, TrackedMut};
 use ecow::{EcoString, EcoVec, eco_format};
 use typst_library::diag::{
-    At, HintedStrResult, HintedString, SourceResult, Trace, Tracepoint, bail, error,
+    At, HintedStrResult, HintedString, SourceResult, StrResult, Trace, Tracepoint,
+    Uncatchable, bail, error,
 };
-use typst_library::engine::{Engine, Sink, Traced};
+use typst_library::engine::{Cancellation, Engine, Fuel, Sink, Traced};
 use typst_library::foundations::{
     Arg, Args, Binding, Capturer, Closure, ClosureNode, Content, Context, Func,
-    NativeElement, Scope, Scopes, SequenceElem, SymbolElem, Value,
+    NativeElement, Scope, Scopes, SequenceElem, SymbolElem, Type, Value,
 };
 use typst_library::introspection::Introspector;
 use typst_library::math::LrElem;
@@ -24,62 +25,121 @@ impl Eval for ast::FuncCall<'_> {
     type Output = Value;
 
     fn eval(self, vm: &mut Vm) -> SourceResult<Self::Output> {
-        let span = self.span();
-        let callee = self.callee();
+        eval_func_call(vm, self, None)
+    }
+}
 
-        vm.engine.route.check_call_depth().at(span)?;
+/// Evaluate a function call, optionally passing a piped value as the first
+/// positional argument (as in `x |> f(y)`).
+pub(crate) fn eval_func_call(
+    vm: &mut Vm,
+    call: ast::FuncCall,
+    mut piped: Option<Spanned<Value>>,
+) -> SourceResult<Value> {
+    let span = call.span();
+    let callee = call.callee();
 
-        // Try to evaluate as a call to an associated function or field.
-        if let ast::Expr::FieldAccess(access) = callee {
-            let target_expr = access.target();
-            let field = access.field();
-            let (target, maybe_args) = if is_mutating_method(field.as_str()) {
-                match maybe_resolve_mutating(vm, target_expr, field, self.args(), span)? {
-                    Ok(value) => return Ok(value),
-                    Err((target, args)) => (target, Some(args)),
-                }
-            } else {
-                (target_expr.eval(vm)?, None)
-            };
-            match eval_field_callee(
+    let max = vm.engine.library.limits.call_depth;
+    vm.engine.route.check_call_depth(max).at(span).uncatchable()?;
+
+    // Evaluates the call's arguments, with the piped value in front.
+    let eval_args = |vm: &mut Vm, piped: Option<Spanned<Value>>| {
+        let mut args = call.args().eval(vm)?.spanned(span);
+        if let Some(Spanned { v, span }) = piped {
+            args.insert(0, span, v);
+        }
+        SourceResult::Ok(args)
+    };
+
+    // Try to evaluate as a call to an associated function or field.
+    if let ast::Expr::FieldAccess(access) = callee {
+        let target_expr = access.target();
+        let field = access.field();
+        let (target, maybe_args) = if is_mutating_method(field.as_str()) {
+            match maybe_resolve_mutating(
                 vm,
-                access.to_untyped(),
-                field.as_str(),
-                field.span(),
-                target,
-                false,
+                target_expr,
+                field,
+                call.args(),
+                piped.take(),
+                span,
             )? {
-                FieldCallee::Func(func) => {
-                    let args = match maybe_args {
-                        Some(args) => args,
-                        None => self.args().eval(vm)?.spanned(span),
-                    };
-                    call_func(vm, func, args, span)
-                }
-                FieldCallee::Method(func, target) => {
-                    let mut args = match maybe_args {
-                        Some(args) => args,
-                        None => self.args().eval(vm)?.spanned(span),
-                    };
-                    // Method calls pass the target as the first argument.
-                    args.insert(0, target_expr.span(), target);
-                    call_func(vm, func, args, span)
-                }
-                FieldCallee::NonFunc(_, err) => Err(err).at(callee.span()),
+                Ok(value) => return Ok(value),
+                Err((target, args)) => (target, Some(args)),
             }
         } else {
-            // Function call order: we evaluate the callee before the arguments.
-            let func = callee
-                .eval(vm)?
-                .cast::<Func>()
-                .map_err(|err| hint_if_shadowed_std(vm, &callee, err))
-                .at(callee.span())?;
-            let args = self.args().eval(vm)?.spanned(span);
-            call_func(vm, func, args, span)
+            (target_expr.eval(vm)?, None)
+        };
+        match eval_field_callee(
+            vm,
+            access.to_untyped(),
+            field.as_str(),
+            field.span(),
+            target,
+            false,
+        )? {
+            FieldCallee::Func(func) => {
+                let args = match maybe_args {
+                    Some(args) => args,
+                    None => eval_args(vm, piped)?,
+                };
+                call_func(vm, func, args, span)
+            }
+            FieldCallee::Method(func, target) => {
+                let mut args = match maybe_args {
+                    Some(args) => args,
+                    None => eval_args(vm, piped)?,
+                };
+                // Method calls pass the target as the first argument.
+                args.insert(0, target_expr.span(), target);
+                call_func(vm, func, args, span)
+            }
+            FieldCallee::NonFunc(_, err) => Err(err).at(callee.span()),
         }
+    } else {
+        // Function call order: we evaluate the callee before the arguments.
+        let func = callee
+            .eval(vm)?
+            .cast::<Func>()
+            .map_err(|err| hint_if_shadowed_std(vm, &callee, err))
+            .at(callee.span())?;
+        let args = eval_args(vm, piped)?;
+        call_func(vm, func, args, span)
     }
 }
 
+/// Evaluate the right-hand side of a pipe `lhs |> rhs`.
+///
+/// If the right-hand side is a function call, the piped value is passed as
+/// its first positional argument. Otherwise, the right-hand side must
+/// evaluate to a function, which is then called with just the piped value.
+pub(crate) fn eval_pipe(
+    vm: &mut Vm,
+    piped: Spanned<Value>,
+    rhs: ast::Expr,
+    span: Span,
+) -> SourceResult<Value> {
+    if let ast::Expr::FuncCall(call) = rhs {
+        return eval_func_call(vm, call, Some(piped));
+    }
+
+    let max = vm.engine.library.limits.call_depth;
+    vm.engine.route.check_call_depth(max).at(span).uncatchable()?;
+
+    let func = rhs
+        .eval(vm)?
+        .cast::<Func>()
+        .map_err(|err| {
+            err.with_hint(
+                "the right-hand side of `|>` must be a function or a function call",
+            )
+        })
+        .at(rhs.span())?;
+    let mut args = Args::new::<Value>(span, []);
+    args.insert(0, piped.span, piped.v);
+    call_func(vm, func, args, span)
+}
+
 impl Eval for ast::MathCall<'_> {
     type Output = Value;
 
@@ -94,7 +154,8 @@ fn eval_math_call(vm: &mut Vm, math_call: ast::MathCall) -> SourceResult<Value>
     let callee = math_call.callee();
     let mut target_span = Span::detached();
 
-    vm.engine.route.check_call_depth().at(span)?;
+    let max = vm.engine.library.limits.call_depth;
+    vm.engine.route.check_call_depth(max).at(span).uncatchable()?;
 
     let math_call_result = match callee {
         ast::MathAccess::MathIdent(ident) => {
@@ -191,11 +252,16 @@ fn maybe_resolve_mutating(
     target: ast::Expr,
     field: ast::Ident,
     args: ast::Args,
+    piped: Option<Spanned<Value>>,
     span: Span,
 ) -> SourceResult<Result<Value, (Value, Args)>> {
     // We evaluate the arguments first because `target_expr.access(vm)` mutably
     // borrows `vm`, so we won't be able to call `args.eval(vm)` afterwards.
-    let args = args.eval(vm)?.spanned(span);
+    let mut args = args.eval(vm)?.spanned(span);
+    if let Some(Spanned { v, span }) = piped {
+        args.insert(0, span, v);
+    }
+    let library = vm.engine.library;
     match target.access(vm)? {
         // Skip methods that aren't actually mutating for dictionaries.
         target @ Value::Dict(_) if !is_dict_mutating_method(field.as_str()) => {
@@ -203,7 +269,7 @@ fn maybe_resolve_mutating(
         }
         // Only arrays and dictionaries have mutable methods.
         target @ (Value::Array(_) | Value::Dict(_)) => {
-            let value = call_method_mut(target, &field, args, span);
+            let value = call_method_mut(target, &field, args, span, &library.limits);
             let point = || Tracepoint::Call(Some(field.get().clone()));
             Ok(Ok(value.trace(vm.world(), point, span)?))
         }
@@ -247,7 +313,14 @@ fn eval_field_callee<'a, 'b>(
     let sink = (&mut vm.engine, field_span);
 
     let mut is_method_call = false;
-    let callee_value = if let Some(method) = target.ty().scope().get(field) {
+    let ty = target.ty();
+    let callee_value = if let Value::Type(target) = &target
+        && let Some(func) = target.scope().get(field)
+    {
+        // A type's own definitions take precedence over the methods that all
+        // types share, such that `type.define` is not called on `type`.
+        func.read_checked(sink).clone()
+    } else if let Some(method) = ty.scope().get(field) {
         is_method_call = true;
         method.read_checked(sink).clone()
     } else if let Value::Content(content) = &target
@@ -339,11 +412,11 @@ fn eval_field_callee<'a, 'b>(
 
 /// If the value is content, the string "element" and the name of its element
 /// function, or the string "type" and the name of the value's type.
-fn element_or_type_with_name(value: &Value) -> (&'static str, &'static str) {
+fn element_or_type_with_name(value: &Value) -> (&'static str, EcoString) {
     if let Value::Content(content) = value {
-        ("element", content.elem().name())
+        ("element", content.elem().name().into())
     } else {
-        ("type", value.ty().long_name())
+        ("type", value.ty().long_name().into())
     }
 }
 
@@ -548,14 +621,37 @@ impl Eval for ast::Closure<'_> {
     type Output = Value;
 
     fn eval(self, vm: &mut Vm) -> SourceResult<Self::Output> {
-        // Evaluate default values of named parameters.
+        // Evaluate type annotations and default values of named parameters.
         let mut defaults = Vec::new();
+        let mut types = Vec::new();
         for param in self.params().children() {
+            let ty = match param {
+                ast::Param::Typed(typed) => Some(typed.ty()),
+                ast::Param::Named(named) => named.ty(),
+                _ => None,
+            };
+
+            let ty = match ty {
+                Some(expr) => Some(expr.eval(vm)?.cast::<Type>().at(expr.span())?),
+                None => None,
+            };
+
             if let ast::Param::Named(named) = param {
-                defaults.push(named.expr().eval(vm)?);
+                let default = named.expr().eval(vm)?;
+                let default =
+                    cast_param(ty.as_ref(), Some(named.name().as_str()), default)
+                        .at(named.expr().span())?;
+                defaults.push(default);
             }
+
+            types.push(ty);
         }
 
+        let returns = match self.returns() {
+            Some(expr) => Some(expr.eval(vm)?.cast::<Type>().at(expr.span())?),
+            None => None,
+        };
+
         // Collect captured variables.
         let captured = {
             let mut visitor = CapturesVisitor::new(Some(&vm.scopes), Capturer::Function);
@@ -571,8 +667,10 @@ impl Eval for ast::Closure<'_> {
             num_pos_params: self
                 .params()
                 .children()
-                .filter(|p| matches!(p, ast::Param::Pos(_)))
+                .filter(|p| matches!(p, ast::Param::Pos(_) | ast::Param::Typed(_)))
                 .count(),
+            types,
+            returns,
         };
 
         Ok(Value::Func(Func::from(closure).spanned(self.params().span())))
@@ -589,6 +687,8 @@ pub fn eval_closure(
     library: &LazyHash<Library>,
     introspector: Tracked<dyn Introspector + '_>,
     traced: Tracked<Traced>,
+    cancellation: Tracked<Cancellation>,
+    fuel: Tracked<Fuel>,
     sink: TrackedMut<Sink>,
     route: Tracked<Route>,
     context: Tracked<Context>,
@@ -617,13 +717,26 @@ pub fn eval_closure(
         world,
         introspector,
         traced,
+        cancellation,
+        fuel,
         sink,
         route: Route::extend(route),
     };
+    engine.check_cancelled()?;
 
     // Prepare VM.
     let mut vm = Vm::new(engine, context, scopes, body.span());
 
+    // Report the call to the attached debugger, if any.
+    let _frame = vm.debugger.as_ref().map(|debugger| {
+        let name = match (&closure.node, name) {
+            (ClosureNode::Context(_), _) => "context",
+            (_, Some(name)) => name.get().as_str(),
+            (_, None) => "(anonymous)",
+        };
+        debugger.enter(name, body.span())
+    });
+
     // Provide the closure itself for recursive calls.
     if let Some(name) = name {
         vm.define(name, func.clone());
@@ -635,7 +748,9 @@ pub fn eval_closure(
     let mut sink = None;
     let mut sink_pos_values = None;
     let mut defaults = closure.defaults.iter();
+    let mut types = closure.types.iter();
     for p in params.children() {
+        let ty = types.next().and_then(Option::as_ref);
         match p {
             ast::Param::Pos(pattern) => match pattern {
                 ast::Pattern::Normal(ast::Expr::Ident(ident)) => {
@@ -649,6 +764,22 @@ pub fn eval_closure(
                     )?;
                 }
             },
+            ast::Param::Typed(typed) => {
+                let pattern = typed.pattern();
+                let name = match pattern {
+                    ast::Pattern::Normal(ast::Expr::Ident(ident)) => Some(ident),
+                    _ => None,
+                };
+
+                let what = name.map_or("pattern parameter", |ident| ident.as_str());
+                let Spanned { v, span } = args.expect::<Spanned<Value>>(what)?;
+                let v = cast_param(ty, name.map(|ident| ident.as_str()), v).at(span)?;
+
+                match name {
+                    Some(ident) => vm.define(ident, v),
+                    None => crate::destructure(&mut vm, pattern, v)?,
+                }
+            }
             ast::Param::Spread(spread) => {
                 sink = Some(spread.sink_ident());
                 if let Some(sink_size) = sink_size {
@@ -658,8 +789,12 @@ pub fn eval_closure(
             ast::Param::Named(named) => {
                 let name = named.name();
                 let default = defaults.next().unwrap();
-                let value =
-                    args.named::<Value>(&name)?.unwrap_or_else(|| default.clone());
+                let value = match args.named::<Spanned<Value>>(&name)? {
+                    Some(Spanned { v, span }) => {
+                        cast_param(ty, Some(name.as_str()), v).at(span)?
+                    }
+                    None => default.clone(),
+                };
                 vm.define(name, value);
             }
         }
@@ -680,17 +815,43 @@ pub fn eval_closure(
     args.finish()?;
 
     // Handle control flow.
-    let output = body.eval(&mut vm)?;
+    let mut output = body.eval(&mut vm)?;
+    let mut span = body.span();
     match vm.flow {
-        Some(FlowEvent::Return(_, Some(explicit), _)) => return Ok(explicit),
+        Some(FlowEvent::Return(return_span, Some(explicit), _)) => {
+            output = explicit;
+            span = return_span;
+        }
         Some(FlowEvent::Return(_, None, _)) => {}
         Some(flow) => bail!(flow.forbidden()),
         None => {}
     }
 
+    // Cast the return value to the annotated return type.
+    if let Some(ty) = &closure.returns {
+        let found = output.ty();
+        let Some(cast) = ty.cast(output) else {
+            bail!(span, "expected {ty} as return value, found {found}");
+        };
+        output = cast;
+    }
+
     Ok(output)
 }
 
+/// Casts an argument to a parameter's type annotation, if any.
+fn cast_param(ty: Option<&Type>, name: Option<&str>, value: Value) -> StrResult<Value> {
+    let Some(ty) = ty else { return Ok(value) };
+    let found = value.ty();
+    match (ty.cast(value), name) {
+        (Some(value), _) => Ok(value),
+        (None, Some(name)) => {
+            bail!("expected {ty} for parameter `{name}`, found {found}")
+        }
+        (None, None) => bail!("expected {ty} for pattern parameter, found {found}"),
+    }
+}
+
 /// A visitor that determines which variables to capture for a closure.
 pub struct CapturesVisitor<'a> {
     external: Option<&'a Scopes<'a>>,
@@ -749,11 +910,22 @@ impl<'a> CapturesVisitor<'a> {
             // of named parameters cannot access previous parameter bindings.
             Some(ast::Expr::Closure(expr)) => {
                 for param in expr.params().children() {
-                    if let ast::Param::Named(named) = param {
-                        self.visit(named.expr().to_untyped());
+                    match param {
+                        ast::Param::Typed(typed) => self.visit(typed.ty().to_untyped()),
+                        ast::Param::Named(named) => {
+                            if let Some(ty) = named.ty() {
+                                self.visit(ty.to_untyped());
+                            }
+                            self.visit(named.expr().to_untyped());
+                        }
+                        _ => {}
                     }
                 }
 
+                if let Some(returns) = expr.returns() {
+                    self.visit(returns.to_untyped());
+                }
+
                 self.internal.enter();
                 if let Some(name) = expr.name() {
                     self.bind(name);
@@ -766,6 +938,11 @@ impl<'a> CapturesVisitor<'a> {
                                 self.bind(ident);
                             }
                         }
+                        ast::Param::Typed(typed) => {
+                            for ident in typed.pattern().bindings() {
+                                self.bind(ident);
+                            }
+                        }
                         ast::Param::Named(named) => self.bind(named.name()),
                         ast::Param::Spread(spread) => {
                             if let Some(ident) = spread.sink_ident() {
@@ -807,6 +984,43 @@ impl<'a> CapturesVisitor<'a> {
                 self.internal.exit();
             }
 
+            // A try-catch expression binds the caught error, which is only
+            // active in the handler.
+            Some(ast::Expr::TryCatch(expr)) => {
+                self.visit(expr.body().to_untyped());
+                self.internal.enter();
+                if let Some(binding) = expr.binding() {
+                    self.bind(binding);
+                }
+                self.visit(expr.handler().to_untyped());
+                self.internal.exit();
+            }
+
+            // A match arm binds the names in its pattern, which are active in
+            // its guard and body.
+            Some(ast::Expr::MatchExpr(expr)) => {
+                self.visit(expr.value().to_untyped());
+                for arm in expr.arms() {
+                    self.internal.enter();
+
+                    let pattern = arm.pattern();
+                    if let ast::MatchPattern::Type(typed) = pattern {
+                        self.visit(typed.ty().to_untyped());
+                    }
+
+                    for ident in pattern.bindings() {
+                        self.bind(ident);
+                    }
+
+                    if let Some(guard) = arm.guard() {
+                        self.visit(guard.to_untyped());
+                    }
+
+                    self.visit(arm.body().to_untyped());
+                    self.internal.exit();
+                }
+            }
+
             // An import contains items, but these are active only after the
             // path is evaluated.
             Some(ast::Expr::ModuleImport(expr)) => {
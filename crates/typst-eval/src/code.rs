@@ -1,9 +1,9 @@
 use ecow::{EcoVec, eco_vec};
-use typst_library::diag::{At, SourceResult, bail, error, warning};
+use typst_library::diag::{At, SourceResult, Uncatchable, bail, error, warning};
 use typst_library::engine::Engine;
 use typst_library::foundations::{
-    Array, Capturer, Closure, ClosureNode, Content, ContextElem, Dict, Func,
-    NativeElement, Selector, Str, Value, ops,
+    Array, Capturer, Closure, ClosureNode, Content, ContextElem, CustomElemDef, Dict,
+    Func, NativeElement, Selector, Str, Value,
 };
 use typst_library::introspection::{Counter, State};
 use typst_syntax::Span;
@@ -30,6 +30,7 @@ fn eval_code<'a>(
 
     while let Some(expr) = exprs.next() {
         let span = expr.span();
+        vm.debug(span);
         let value = match expr {
             ast::Expr::SetRule(set) => {
                 let styles = set.eval(vm)?;
@@ -56,7 +57,7 @@ fn eval_code<'a>(
             _ => expr.eval(vm)?,
         };
 
-        output = ops::join(output, value).at(span)?;
+        output = vm.join(output, value, span)?;
 
         if let Some(event) = &vm.flow {
             warn_for_discarded_content(&mut vm.engine, event, &output);
@@ -80,6 +81,8 @@ impl Eval for ast::Expr<'_> {
             error!(span, "{} is only allowed directly in code and content blocks", name)
         };
 
+        vm.consume_fuel(span)?;
+
         let value = match self {
             Self::Text(v) => v.eval(vm).map(Value::Content),
             Self::Space(v) => v.eval(vm).map(Value::Content),
@@ -137,6 +140,8 @@ impl Eval for ast::Expr<'_> {
             Self::Conditional(v) => v.eval(vm),
             Self::WhileLoop(v) => v.eval(vm),
             Self::ForLoop(v) => v.eval(vm),
+            Self::TryCatch(v) => v.eval(vm),
+            Self::MatchExpr(v) => v.eval(vm),
             Self::ModuleImport(v) => v.eval(vm),
             Self::ModuleInclude(v) => v.eval(vm).map(Value::Content),
             Self::LoopBreak(v) => v.eval(vm),
@@ -145,6 +150,8 @@ impl Eval for ast::Expr<'_> {
         }?
         .spanned(span);
 
+        vm.engine.library.limits.check_size(&value).at(span).uncatchable()?;
+
         // This satisfies the obligation to call `Vm::trace` for almost all
         // value-producing expressions!
         vm.trace_at(span, &value);
@@ -381,6 +388,21 @@ pub(crate) fn access_field(
         return Ok(value);
     }
 
+    // The same for user-defined elements.
+    if let Value::Func(func) = &target
+        && let Some(def) = func.to_custom_elem()
+        && let Some(id) = def.field_id(field)
+        && let styles = vm.context.styles().at(field_span)
+        && let Some(value) = CustomElemDef::field_from_styles(
+            def,
+            id,
+            styles.as_ref().map(|&s| s).unwrap_or_default(),
+        )
+    {
+        let _ = styles?;
+        return Ok(value);
+    }
+
     Err(err)
 }
 
@@ -403,6 +425,8 @@ impl Eval for ast::Contextual<'_> {
             defaults: vec![],
             captured,
             num_pos_params: 0,
+            types: vec![],
+            returns: None,
         };
 
         let func = Func::from(closure).spanned(body.span());
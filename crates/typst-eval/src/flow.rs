@@ -1,13 +1,10 @@
-use typst_library::diag::{At, SourceDiagnostic, SourceResult, bail, error};
-use typst_library::foundations::{IntoValue, Value, ops};
+use typst_library::diag::{At, SourceDiagnostic, SourceResult, bail, error, warning};
+use typst_library::foundations::{Array, Dict, IntoValue, Value, dict, ops, repr};
 use typst_syntax::ast::{self, AstNode};
 use typst_syntax::{Span, SyntaxKind, SyntaxNode};
 use unicode_segmentation::UnicodeSegmentation;
 
-use crate::{Eval, Vm, destructure};
-
-/// The maximum number of loop iterations.
-const MAX_ITERATIONS: usize = 10_000;
+use crate::{Eval, Vm, destructure, fits_pattern};
 
 /// A control flow event that occurred during evaluation.
 #[derive(Debug, Clone, PartialEq)]
@@ -78,12 +75,14 @@ impl Eval for ast::WhileLoop<'_> {
                 && !can_diverge(body.to_untyped())
             {
                 bail!(condition.span(), "condition is always true");
-            } else if i >= MAX_ITERATIONS {
+            } else if i >= vm.engine.library.limits.loop_iterations {
                 bail!(self.span(), "loop seems to be infinite");
             }
 
+            vm.engine.check_cancelled()?;
+
             let value = body.eval(vm)?;
-            output = ops::join(output, value).at(body.span())?;
+            output = vm.join(output, value, body.span())?;
 
             match vm.flow {
                 Some(FlowEvent::Break(_)) => {
@@ -125,11 +124,12 @@ impl Eval for ast::ForLoop<'_> {
 
                 #[allow(unused_parens)]
                 for value in $iterable {
+                    vm.engine.check_cancelled()?;
                     destructure(vm, $pat, value.into_value())?;
 
                     let body = self.body();
                     let value = body.eval(vm)?;
-                    output = ops::join(output, value).at(body.span())?;
+                    output = vm.join(output, value, body.span())?;
 
                     match vm.flow {
                         Some(FlowEvent::Break(_)) => {
@@ -189,6 +189,182 @@ impl Eval for ast::ForLoop<'_> {
     }
 }
 
+impl Eval for ast::MatchExpr<'_> {
+    type Output = Value;
+
+    fn eval(self, vm: &mut Vm) -> SourceResult<Self::Output> {
+        let value = self.value().eval(vm)?;
+        check_exhaustiveness(vm, self);
+
+        for arm in self.arms() {
+            vm.scopes.enter();
+            let output = take_arm(vm, arm, &value);
+            vm.scopes.exit();
+
+            if let Some(output) = output? {
+                // Mark the return as conditional.
+                if let Some(FlowEvent::Return(_, _, conditional)) = &mut vm.flow {
+                    *conditional = true;
+                }
+
+                return Ok(output);
+            }
+        }
+
+        bail!(
+            self.value().span(), "no arm matched value of type {}", value.ty();
+            hint: "add a catch-all arm like `_ => none`";
+        )
+    }
+}
+
+/// Evaluates the arm's body if the value matches its pattern and guard.
+fn take_arm(
+    vm: &mut Vm,
+    arm: ast::MatchArm,
+    value: &Value,
+) -> SourceResult<Option<Value>> {
+    if !pattern_matches(vm, arm.pattern(), value)? {
+        return Ok(None);
+    }
+
+    if let Some(guard) = arm.guard()
+        && !guard.eval(vm)?.cast::<bool>().at(guard.span())?
+    {
+        return Ok(None);
+    }
+
+    arm.body().eval(vm).map(Some)
+}
+
+/// Whether the value matches the pattern. Defines the pattern's bindings if
+/// it does.
+fn pattern_matches(
+    vm: &mut Vm,
+    pattern: ast::MatchPattern,
+    value: &Value,
+) -> SourceResult<bool> {
+    Ok(match pattern {
+        ast::MatchPattern::Literal(expr) => ops::equal(value, &expr.eval(vm)?),
+        ast::MatchPattern::Type(pattern) => {
+            let expected = pattern.ty();
+            let matching = match expected.eval(vm)? {
+                Value::Type(ty) => value.ty() == ty,
+                Value::Func(func) => match (func.to_element(), value) {
+                    (Some(elem), Value::Content(content)) => content.elem() == elem,
+                    (Some(_), _) => false,
+                    (None, _) if func.to_custom_elem().is_some() => {
+                        matches!(value, Value::Content(content) if content.elem_func() == func)
+                    }
+                    (None, _) => {
+                        bail!(expected.span(), "expected type or element, found function")
+                    }
+                },
+                v => bail!(expected.span(), "expected type or element, found {}", v.ty()),
+            };
+
+            if matching && let Some(binding) = pattern.binding() {
+                vm.define(binding, value.clone());
+            }
+
+            matching
+        }
+        // The shape is checked upfront such that only a mismatch makes the arm
+        // fail and errors during destructuring are not swallowed.
+        ast::MatchPattern::Binding(pattern) => {
+            let matching = fits_pattern(pattern, value);
+            if matching {
+                destructure(vm, pattern, value.clone())?;
+            }
+            matching
+        }
+    })
+}
+
+/// Warns if a match over `bool`, `none`, and `auto` values misses one of them.
+///
+/// Exhaustiveness can only be checked statically if all arms are literals of
+/// these types. Then, a match that mentions one boolean should handle the
+/// other one, too, and the same holds for `none` and `auto`, which commonly
+/// appear together.
+fn check_exhaustiveness(vm: &mut Vm, expr: ast::MatchExpr) {
+    let mut mentioned = [false; 4];
+    let mut covered = [false; 4];
+    for arm in expr.arms() {
+        let index = match arm.pattern() {
+            ast::MatchPattern::Literal(ast::Expr::Bool(v)) => usize::from(!v.get()),
+            ast::MatchPattern::Literal(ast::Expr::None(_)) => 2,
+            ast::MatchPattern::Literal(ast::Expr::Auto(_)) => 3,
+            _ => return,
+        };
+
+        mentioned[index] = true;
+        covered[index] |= arm.guard().is_none();
+    }
+
+    const NAMES: [&str; 4] = ["`true`", "`false`", "`none`", "`auto`"];
+    let bool = mentioned[0] || mentioned[1];
+    let smart = mentioned[2] || mentioned[3];
+    let missing: Vec<_> = (0..4)
+        .filter(|&i| {
+            let relevant = if i < 2 { bool } else { smart };
+            relevant && !covered[i]
+        })
+        .map(|i| NAMES[i])
+        .collect();
+
+    if !missing.is_empty() {
+        vm.engine.sink.warn(warning!(
+            expr.value().span(),
+            "match does not cover {}",
+            repr::separated_list(&missing, "or");
+            hint: "add an arm for the missing case or a catch-all arm like `_ => none`";
+        ));
+    }
+}
+
+impl Eval for ast::TryCatch<'_> {
+    type Output = Value;
+
+    fn eval(self, vm: &mut Vm) -> SourceResult<Self::Output> {
+        let depth = vm.scopes.scopes.len();
+        let errors = match self.body().eval(vm) {
+            Ok(value) => return Ok(value),
+            Err(errors) => errors,
+        };
+
+        // Errors that signal compiler limits must reach the user.
+        if errors.iter().any(|error| !error.catchable) {
+            return Err(errors);
+        }
+
+        // The failed body may have left scopes behind.
+        while vm.scopes.scopes.len() > depth {
+            vm.scopes.exit();
+        }
+
+        vm.scopes.enter();
+        if let Some(binding) = self.binding()
+            && let Some(error) = errors.first()
+        {
+            vm.define(binding, caught(error));
+        }
+        let output = self.handler().eval(vm);
+        vm.scopes.exit();
+        output
+    }
+}
+
+/// Turns a caught error into a dictionary with its message and hints.
+fn caught(error: &SourceDiagnostic) -> Dict {
+    let hints: Array =
+        error.hints.iter().map(|hint| hint.v.clone().into_value()).collect();
+    dict! {
+        "message" => error.message.clone(),
+        "hints" => hints,
+    }
+}
+
 impl Eval for ast::LoopBreak<'_> {
     type Output = Value;
 
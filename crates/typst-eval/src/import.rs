@@ -238,6 +238,8 @@ fn import_file(engine: &mut Engine, id: FileId, span: Span) -> SourceResult<Modu
         engine.world,
         engine.library,
         engine.traced,
+        engine.cancellation,
+        engine.fuel,
         TrackedMut::reborrow_mut(&mut engine.sink),
         engine.route.track(),
         &source,
@@ -25,7 +25,7 @@ use self::methods::*;
 
 use comemo::{Track, Tracked, TrackedMut};
 use typst_library::diag::{SourceResult, bail};
-use typst_library::engine::{Engine, Route, Sink, Traced};
+use typst_library::engine::{Cancellation, Engine, Fuel, Route, Sink, Traced};
 use typst_library::foundations::{Context, Module, NativeElement, Scope, Scopes, Value};
 use typst_library::introspection::{EmptyIntrospector, Introspector};
 use typst_library::math::EquationElem;
@@ -36,11 +36,14 @@ use typst_utils::{LazyHash, Protected};
 
 /// Evaluate a source file and return the resulting module.
 #[comemo::memoize]
+#[allow(clippy::too_many_arguments)]
 #[typst_macros::time(name = "eval", span = source.root().span())]
 pub fn eval(
     world: Tracked<dyn World + '_>,
     library: &LazyHash<Library>,
     traced: Tracked<Traced>,
+    cancellation: Tracked<Cancellation>,
+    fuel: Tracked<Fuel>,
     sink: TrackedMut<Sink>,
     route: Tracked<Route>,
     source: &Source,
@@ -58,6 +61,8 @@ pub fn eval(
         world,
         introspector: Protected::new(introspector.track()),
         traced,
+        cancellation,
+        fuel,
         sink,
         route: Route::extend(route).with_id(id),
     };
@@ -96,6 +101,8 @@ pub fn eval(
 pub fn eval_string(
     world: Tracked<dyn World + '_>,
     library: &LazyHash<Library>,
+    cancellation: Tracked<Cancellation>,
+    fuel: Tracked<Fuel>,
     sink: TrackedMut<Sink>,
     introspector: Tracked<dyn Introspector + '_>,
     context: Tracked<Context>,
@@ -129,6 +136,8 @@ pub fn eval_string(
         world,
         introspector: Protected::new(introspector),
         traced: traced.track(),
+        cancellation,
+        fuel,
         sink,
         route: Route::default(),
     };
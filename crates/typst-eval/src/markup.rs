@@ -31,6 +31,10 @@ fn eval_markup<'a>(
     let mut seq = Vec::with_capacity(exprs.size_hint().1.unwrap_or_default());
 
     while let Some(expr) = exprs.next() {
+        if expr.hash() {
+            vm.debug(expr.span());
+        }
+
         match expr {
             ast::Expr::SetRule(set) => {
                 let styles = set.eval(vm)?;
@@ -1,7 +1,8 @@
 //! Handles special built-in methods on values.
 
 use ecow::{EcoString, eco_format};
-use typst_library::diag::{At, SourceResult};
+use typst_library::Limits;
+use typst_library::diag::{At, SourceResult, Uncatchable};
 use typst_library::foundations::{Args, Str, Type, Value};
 use typst_syntax::Span;
 
@@ -26,16 +27,21 @@ pub(crate) fn call_method_mut(
     method: &str,
     mut args: Args,
     span: Span,
+    limits: &Limits,
 ) -> SourceResult<Value> {
     let ty = value.ty();
-    let missing = || Err(missing_method(ty, method)).at(span);
+    let missing = || Err(missing_method(&ty, method)).at(span);
     let mut output = Value::None;
 
     match value {
         Value::Array(array) => match method {
-            "push" => array.push(args.expect("value")?),
+            "push" => {
+                limits.check_array_len(array.len() + 1).at(span).uncatchable()?;
+                array.push(args.expect("value")?)
+            }
             "pop" => output = array.pop().at(span)?,
             "insert" => {
+                limits.check_array_len(array.len() + 1).at(span).uncatchable()?;
                 array.insert(args.expect("index")?, args.expect("value")?).at(span)?
             }
             "remove" => {
@@ -74,7 +80,7 @@ pub(crate) fn call_method_access<'a>(
         Err(if ty.scope().get(method).is_some() {
             "cannot mutate a temporary value".into()
         } else {
-            missing_method(ty, method)
+            missing_method(&ty, method)
         })
         .at(span)
     };
@@ -99,6 +105,6 @@ pub(crate) fn call_method_access<'a>(
 
 /// The missing method error message.
 #[cold]
-fn missing_method(ty: Type, method: &str) -> EcoString {
+fn missing_method(ty: &Type, method: &str) -> EcoString {
     eco_format!("type {ty} has no method `{method}`")
 }
@@ -1,8 +1,11 @@
-use typst_library::diag::{At, HintedStrResult, SourceResult};
+use typst_library::Limits;
+use typst_library::diag::{At, HintedStrResult, SourceResult, StrResult, Uncatchable};
 use typst_library::foundations::{IntoValue, Value, ops};
+use typst_syntax::Spanned;
 use typst_syntax::ast::{self, AstNode};
 
-use crate::{Access, Eval, Vm, access_dict};
+use crate::call::eval_pipe;
+use crate::{Access, Eval, FieldsMut, Vm, access_fields};
 
 impl Eval for ast::Unary<'_> {
     type Output = Value;
@@ -42,6 +45,7 @@ impl Eval for ast::Binary<'_> {
             ast::BinOp::SubAssign => apply_assignment(self, vm, ops::sub),
             ast::BinOp::MulAssign => apply_assignment(self, vm, ops::mul),
             ast::BinOp::DivAssign => apply_assignment(self, vm, ops::div),
+            ast::BinOp::Pipe => apply_pipe(self, vm),
         }
     }
 }
@@ -62,6 +66,9 @@ fn apply_binary(
     }
 
     let rhs = binary.rhs().eval(vm)?;
+    check_size(&vm.engine.library.limits, binary.op(), &lhs, &rhs)
+        .at(binary.span())
+        .uncatchable()?;
     op(lhs, rhs).at(binary.span())
 }
 
@@ -75,17 +82,53 @@ fn apply_assignment(
     let lhs = binary.lhs();
 
     // An assignment to a dictionary field is different from a normal access
-    // since it can create the field instead of just modifying it.
+    // since it can create the field instead of just modifying it. An
+    // assignment to a field of a custom type checks the field's type.
     if binary.op() == ast::BinOp::Assign
         && let ast::Expr::FieldAccess(access) = lhs
     {
-        let dict = access_dict(vm, access)?;
-        dict.insert(access.field().get().clone().into(), rhs);
+        let field = access.field();
+        match access_fields(vm, access)? {
+            FieldsMut::Dict(dict) => dict.insert(field.get().clone().into(), rhs),
+            FieldsMut::Record(record) => record.set(field.get(), rhs).at(binary.span())?,
+        }
         return Ok(Value::None);
     }
 
+    let library = vm.engine.library;
     let location = binary.lhs().access(vm)?;
+    check_size(&library.limits, binary.op(), location, &rhs)
+        .at(binary.span())
+        .uncatchable()?;
     let lhs = std::mem::take(&mut *location);
     *location = op(lhs, rhs).at(binary.span())?;
     Ok(Value::None)
 }
+
+/// Ensures that the result of an operation that joins or repeats values stays
+/// within the maximum size before it is allocated.
+fn check_size(
+    limits: &Limits,
+    op: ast::BinOp,
+    lhs: &Value,
+    rhs: &Value,
+) -> StrResult<()> {
+    match op {
+        ast::BinOp::Add | ast::BinOp::AddAssign => limits.check_join(lhs, rhs),
+        ast::BinOp::Mul | ast::BinOp::MulAssign => match (lhs, rhs) {
+            (value, Value::Int(n)) | (Value::Int(n), value) => {
+                limits.check_repeat(value, usize::try_from(*n).unwrap_or(0))
+            }
+            _ => Ok(()),
+        },
+        _ => Ok(()),
+    }
+}
+
+/// Apply a pipe operation, passing the left-hand side as the first positional
+/// argument to the right-hand side.
+fn apply_pipe(binary: ast::Binary, vm: &mut Vm) -> SourceResult<Value> {
+    let lhs = binary.lhs();
+    let piped = Spanned::new(lhs.eval(vm)?, lhs.span());
+    eval_pipe(vm, piped, binary.rhs(), binary.span())
+}
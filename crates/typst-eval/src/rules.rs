@@ -1,6 +1,7 @@
 use typst_library::diag::{At, SourceResult, warning};
 use typst_library::foundations::{
-    Element, Func, Recipe, Selector, ShowableSelector, StyleChain, Styles, Transformation,
+    CustomElemDef, Element, Func, Recipe, Selector, ShowableSelector, StyleChain, Styles,
+    Transformation,
 };
 use typst_library::layout::{BlockElem, PageElem};
 use typst_library::model::ParElem;
@@ -19,15 +20,20 @@ impl Eval for ast::SetRule<'_> {
         }
 
         let target_expr = self.target();
-        let target = target_expr
+        let func = target_expr
             .eval(vm)?
             .cast::<Func>()
             .map_err(|err| hint_if_shadowed_std(vm, &target_expr, err))
-            .and_then(|func| {
-                func.to_element().ok_or_else(|| {
-                    "only element functions can be used in set rules".into()
-                })
-            })
+            .at(target_expr.span())?;
+
+        if let Some(def) = func.to_custom_elem() {
+            let args = self.args().eval(vm)?.spanned(self.span());
+            return Ok(CustomElemDef::set(def, args)?.spanned(self.span()).liftable());
+        }
+
+        let target = func
+            .to_element()
+            .ok_or("only element functions can be used in set rules")
             .at(target_expr.span())?;
         let args = self.args().eval(vm)?.spanned(self.span());
         Ok(target.set(&mut vm.engine, args)?.spanned(self.span()).liftable())
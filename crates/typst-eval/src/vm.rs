@@ -1,9 +1,10 @@
 use comemo::Tracked;
 use ecow::eco_format;
 use typst_library::World;
-use typst_library::diag::{HintedString, warning};
-use typst_library::engine::Engine;
-use typst_library::foundations::{Binding, Context, IntoValue, Scopes, Value};
+use typst_library::debug::{Attached, Stop};
+use typst_library::diag::{At, HintedString, SourceResult, Uncatchable, warning};
+use typst_library::engine::{Engine, Meter};
+use typst_library::foundations::{Binding, Context, IntoValue, Scopes, Value, ops};
 use typst_syntax::Span;
 use typst_syntax::ast::{self, AstNode};
 
@@ -23,6 +24,10 @@ pub struct Vm<'a> {
     /// A span that is currently under inspection. If this is `Some`, we're in
     /// tracing mode, and will record every value the given span sees.
     pub inspected: Option<Span>,
+    /// The debugger that observes evaluation, if one is attached.
+    pub debugger: Option<Attached>,
+    /// The handle through which evaluation steps consume fuel.
+    meter: Meter,
     /// Data that is contextually made accessible to code behind the scenes.
     pub context: Tracked<'a, Context<'a>>,
 }
@@ -36,7 +41,17 @@ impl<'a> Vm<'a> {
         target: Span,
     ) -> Self {
         let inspected = target.id().and_then(|id| engine.traced.get(id));
-        Self { engine, context, flow: None, scopes, inspected }
+        let debugger = engine.traced.debugger();
+        let meter = engine.fuel.meter();
+        Self {
+            engine,
+            context,
+            flow: None,
+            scopes,
+            inspected,
+            debugger,
+            meter,
+        }
     }
 
     /// Access the underlying world.
@@ -89,6 +104,41 @@ impl<'a> Vm<'a> {
             .sink
             .value(value, self.context.styles().ok().map(|s| s.to_map()));
     }
+
+    /// Consumes fuel for one evaluation step, failing with an uncatchable
+    /// error once the compilation has run out of it.
+    pub fn consume_fuel(&self, span: Span) -> SourceResult<()> {
+        if !self.meter.consume() && self.engine.fuel.confirm_empty() {
+            let limit = self.meter.limit().unwrap_or_default();
+            return Err(eco_format!(
+                "maximum number of evaluation steps exceeded (limit is {limit})"
+            ))
+            .at(span)
+            .uncatchable();
+        }
+        Ok(())
+    }
+
+    /// Joins the results of two evaluation steps, ensuring that the joined
+    /// value stays within the maximum size before it is allocated.
+    pub fn join(&self, lhs: Value, rhs: Value, span: Span) -> SourceResult<Value> {
+        self.engine
+            .library
+            .limits
+            .check_join(&lhs, &rhs)
+            .at(span)
+            .uncatchable()?;
+        ops::join(lhs, rhs).at(span)
+    }
+
+    /// Reports a statement that is about to be evaluated to the attached
+    /// debugger, if any. The debugger may pause evaluation here.
+    pub fn debug(&self, span: Span) {
+        if let Some(debugger) = &self.debugger {
+            let styles = self.context.styles().ok();
+            debugger.statement(&Stop { span, scopes: &self.scopes, styles });
+        }
+    }
 }
 
 /// Provide a hint if the callee is a shadowed standard library function.
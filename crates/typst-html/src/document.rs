@@ -1,7 +1,7 @@
 use comemo::{Track, Tracked, TrackedMut};
 use ecow::{EcoVec, eco_vec};
 use typst_library::diag::{SourceResult, bail, error};
-use typst_library::engine::{Engine, Route, Sink, Traced};
+use typst_library::engine::{Cancellation, Engine, Fuel, Route, Sink, Traced};
 use typst_library::foundations::{Content, NativeElement, StyleChain, Styles};
 use typst_library::introspection::{
     Introspector, Locator, LocatorLink, QueryIntrospection,
@@ -32,6 +32,8 @@ pub fn html_document(
         engine.library,
         engine.introspector.into_raw(),
         engine.traced,
+        engine.cancellation,
+        engine.fuel,
         TrackedMut::reborrow_mut(&mut engine.sink),
         engine.route.track(),
         content,
@@ -47,6 +49,8 @@ fn html_document_impl(
     library: &LazyHash<Library>,
     introspector: Tracked<dyn Introspector + '_>,
     traced: Tracked<Traced>,
+    cancellation: Tracked<Cancellation>,
+    fuel: Tracked<Fuel>,
     sink: TrackedMut<Sink>,
     route: Tracked<Route>,
     content: &Content,
@@ -57,6 +61,8 @@ fn html_document_impl(
         library,
         introspector,
         traced,
+        cancellation,
+        fuel,
         sink,
         route,
         content,
@@ -86,6 +92,8 @@ pub fn html_document_for_bundle(
         engine.library,
         engine.introspector.into_raw(),
         engine.traced,
+        engine.cancellation,
+        engine.fuel,
         TrackedMut::reborrow_mut(&mut engine.sink),
         engine.route.track(),
         content,
@@ -102,6 +110,8 @@ fn html_document_for_bundle_impl(
     library: &LazyHash<Library>,
     introspector: Tracked<dyn Introspector + '_>,
     traced: Tracked<Traced>,
+    cancellation: Tracked<Cancellation>,
+    fuel: Tracked<Fuel>,
     sink: TrackedMut<Sink>,
     route: Tracked<Route>,
     content: &Content,
@@ -114,6 +124,8 @@ fn html_document_for_bundle_impl(
         library,
         introspector,
         traced,
+        cancellation,
+        fuel,
         sink,
         route,
         content,
@@ -130,6 +142,8 @@ fn html_document_common(
     library: &LazyHash<Library>,
     introspector: Tracked<dyn Introspector + '_>,
     traced: Tracked<Traced>,
+    cancellation: Tracked<Cancellation>,
+    fuel: Tracked<Fuel>,
     sink: TrackedMut<Sink>,
     route: Tracked<Route>,
     content: &Content,
@@ -143,6 +157,8 @@ fn html_document_common(
         world,
         introspector,
         traced,
+        cancellation,
+        fuel,
         sink,
         route: Route::extend(route).unnested(),
     };
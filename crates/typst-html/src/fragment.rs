@@ -1,7 +1,7 @@
 use comemo::{Track, Tracked, TrackedMut};
 use ecow::EcoVec;
-use typst_library::diag::{At, SourceResult};
-use typst_library::engine::{Engine, Route, Sink, Traced};
+use typst_library::diag::{At, SourceResult, Uncatchable};
+use typst_library::engine::{Cancellation, Engine, Fuel, Route, Sink, Traced};
 use typst_library::foundations::{Content, StyleChain};
 use typst_library::introspection::{Introspector, Locator, LocatorLink, SplitLocator};
 use typst_library::routines::{Arenas, FragmentKind, Pair, RealizationKind};
@@ -27,6 +27,8 @@ pub fn html_block_fragment(
         engine.library,
         engine.introspector.into_raw(),
         engine.traced,
+        engine.cancellation,
+        engine.fuel,
         TrackedMut::reborrow_mut(&mut engine.sink),
         engine.route.track(),
         content,
@@ -44,6 +46,8 @@ fn html_block_fragment_impl(
     library: &LazyHash<Library>,
     introspector: Tracked<dyn Introspector + '_>,
     traced: Tracked<Traced>,
+    cancellation: Tracked<Cancellation>,
+    fuel: Tracked<Fuel>,
     sink: TrackedMut<Sink>,
     route: Tracked<Route>,
     content: &Content,
@@ -59,11 +63,13 @@ fn html_block_fragment_impl(
         world,
         introspector,
         traced,
+        cancellation,
+        fuel,
         sink,
         route: Route::extend(route),
     };
 
-    engine.route.check_html_depth().at(content.span())?;
+    engine.route.check_html_depth().at(content.span()).uncatchable()?;
 
     let arenas = Arenas::default();
     let children = realize_fragment(&mut engine, &mut locator, &arenas, content, styles)?;
@@ -94,7 +100,7 @@ pub fn html_inline_fragment(
     whitespace: Whitespace,
 ) -> SourceResult<EcoVec<HtmlNode>> {
     engine.route.increase();
-    engine.route.check_html_depth().at(content.span())?;
+    engine.route.check_html_depth().at(content.span()).uncatchable()?;
 
     let arenas = Arenas::default();
     let children = realize_fragment(engine, locator, &arenas, content, styles)?;
@@ -123,7 +129,7 @@ pub fn html_math_fragment(
     whitespace: Whitespace,
 ) -> SourceResult<EcoVec<HtmlNode>> {
     engine.route.increase();
-    engine.route.check_html_depth().at(content.span())?;
+    engine.route.check_html_depth().at(content.span()).uncatchable()?;
 
     let arenas = Arenas::default();
     let children = (engine.library.routines.realize)(
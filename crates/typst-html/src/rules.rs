@@ -2,6 +2,8 @@ use std::num::NonZeroUsize;
 use std::sync::Arc;
 
 use az::SaturatingAs;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
 use comemo::Track;
 use ecow::{EcoVec, eco_format};
 use typst_library::diag::{At, warning};
@@ -26,10 +28,10 @@ use typst_library::model::{
 };
 use typst_library::routines::Arenas;
 use typst_library::text::{
-    HighlightElem, LinebreakElem, OverlineElem, RawElem, RawLine, SmallcapsElem,
-    SpaceElem, StrikeElem, SubElem, SuperElem, UnderlineElem,
+    HighlightElem, LinebreakElem, OverlineElem, RawElem, RawLine, RubyElem,
+    SmallcapsElem, SpaceElem, StrikeElem, SubElem, SuperElem, TextElem, UnderlineElem,
 };
-use typst_library::visualize::{Color, ImageElem};
+use typst_library::visualize::{ChartElem, Color, ImageElem, VideoElem};
 use typst_syntax::Span;
 
 use crate::mathml::convert_math_to_nodes;
@@ -76,11 +78,14 @@ pub fn register(rules: &mut NativeRuleMap) {
     rules.register(Html, STRIKE_RULE);
     rules.register(Html, HIGHLIGHT_RULE);
     rules.register(Html, SMALLCAPS_RULE);
+    rules.register(Html, RUBY_RULE);
     rules.register(Html, RAW_RULE);
     rules.register(Html, RAW_LINE_RULE);
 
     // Visualize.
     rules.register(Html, IMAGE_RULE);
+    rules.register(Html, VIDEO_RULE);
+    rules.register(Html, CHART_RULE);
 
     // Math.
     rules.register(Html, EQUATION_RULE);
@@ -722,6 +727,16 @@ const SMALLCAPS_RULE: ShowFn<SmallcapsElem> = |elem, _, styles| {
         .pack())
 };
 
+const RUBY_RULE: ShowFn<RubyElem> = |elem, _, styles| {
+    let rt = HtmlElem::new(tag::rt).with_body(Some(elem.annotation.clone()));
+    let mut ruby = HtmlElem::new(tag::ruby)
+        .with_body(Some(elem.base.clone() + rt.pack().spanned(elem.span())));
+    if elem.position.get(styles) == OuterVAlignment::Bottom {
+        ruby = ruby.with_css(css::Properties::new().with("ruby-position", "under"));
+    }
+    Ok(ruby.pack())
+};
+
 const RAW_RULE: ShowFn<RawElem> = |elem, _, styles| {
     let lines = elem.lines.as_deref().unwrap_or_default();
 
@@ -818,6 +833,84 @@ const IMAGE_RULE: ShowFn<ImageElem> = |elem, engine, styles| {
     ))
 };
 
+const VIDEO_RULE: ShowFn<VideoElem> = |elem, engine, styles| {
+    let span = elem.span();
+    let format = elem.determine_format(styles).at(span)?;
+
+    let mut attrs = HtmlAttrs::new();
+    let mut src = eco_format!("data:{};base64,", format.mime());
+    src.push_str(&STANDARD.encode(&elem.source.derived.data));
+    attrs.push(attr::src, src);
+
+    if let Some(poster) = elem.poster_image(styles) {
+        let image = poster.decode(engine, styles)?;
+        attrs.push(attr::poster, typst_svg::WebImage::new(&image).to_base64_url());
+
+        // Like for images, this only reserves space while the video is loading.
+        let cast = |v: f64| eco_format!("{}", v.round().saturating_as::<i64>());
+        attrs.push(attr::width, cast(image.width()));
+        attrs.push(attr::height, cast(image.height()));
+    }
+
+    if let Some(alt) = elem.alt.get_cloned(styles) {
+        attrs.push(attr::aria_label, alt);
+    }
+
+    for (attr, enabled) in [
+        (attr::controls, elem.controls.get(styles)),
+        (attr::autoplay, elem.autoplay.get(styles)),
+        (attr::r#loop, elem.looping.get(styles)),
+        (attr::muted, elem.muted.get(styles)),
+    ] {
+        if enabled {
+            attrs.push(attr, "");
+        }
+    }
+
+    let mut css = css::Properties::build((engine, span));
+
+    match elem.width.get(styles) {
+        Smart::Auto => {}
+        Smart::Custom(rel) => css.push("width", rel),
+    }
+
+    match elem.height.get(styles) {
+        Sizing::Auto => {}
+        Sizing::Rel(rel) => css.push("height", rel),
+        Sizing::Fr(_) => {}
+    }
+
+    Ok(BlockElem::packed(
+        HtmlElem::new(tag::video)
+            .with_attrs(attrs)
+            .with_css(css.finish())
+            .pack()
+            .spanned(span),
+    ))
+};
+
+const CHART_RULE: ShowFn<ChartElem> = |elem, _, styles| {
+    let span = elem.span();
+    let wrap = |tag, body| HtmlElem::new(tag).with_body(Some(body)).pack().spanned(span);
+    let tr = |tag, row: Vec<_>| {
+        let cells = row.into_iter().map(|text| wrap(tag, TextElem::packed(text)));
+        wrap(tag::tr, Content::sequence(cells))
+    };
+
+    // The chart itself is drawn as an inline SVG. Its data follows in a table
+    // for assistive technology and readers who prefer to see the raw numbers.
+    let data = elem.table(styles);
+    let head = wrap(tag::thead, tr(tag::th, data.header));
+    let body = wrap(
+        tag::tbody,
+        Content::sequence(data.rows.into_iter().map(|row| tr(tag::td, row))),
+    );
+    let table = wrap(tag::table, head + body);
+    let frame = FrameElem::new(elem.clone().pack()).pack().spanned(span);
+
+    Ok(BlockElem::packed(wrap(tag::figure, frame + table)))
+};
+
 const EQUATION_RULE: ShowFn<EquationElem> = |elem, engine, styles| {
     let arenas = Arenas::default();
     let item = resolve_equation(
@@ -5,8 +5,8 @@ use ecow::{EcoString, eco_format};
 use rustc_hash::FxHashSet;
 use serde::{Deserialize, Serialize};
 use typst::foundations::{
-    AsOutput, AutoValue, CastInfo, Func, Label, NativeElement, NoneValue, Output,
-    ParamInfo, Repr, StyleChain, Styles, Type, Value, fields_on, repr,
+    AsOutput, AutoValue, CastInfo, CustomElemDef, Func, Label, NativeElement, NoneValue,
+    Output, ParamInfo, Repr, StyleChain, Styles, Type, Value, fields_on, repr,
 };
 use typst::layout::{Alignment, Dir};
 use typst::syntax::ast::AstNode;
@@ -163,13 +163,13 @@ fn field_access_completions(
     value: &Value,
     styles: &Option<Styles>,
 ) {
+    let ty = value.ty();
     let scopes = {
-        let ty = value.ty().scope();
         let elem = match value {
             Value::Content(content) => Some(content.elem().scope()),
             _ => None,
         };
-        elem.into_iter().chain(Some(ty))
+        elem.into_iter().chain(Some(ty.scope()))
     };
 
     // Autocomplete methods from the element's or type's scope. We only complete
@@ -189,7 +189,7 @@ fn field_access_completions(
         }
     }
 
-    for &field in fields_on(value.ty()) {
+    for &field in fields_on(&value.ty()) {
         // Complete the field name along with its value. Notes:
         // 1. No parentheses since function fields cannot currently be called
         // with method syntax;
@@ -221,6 +221,11 @@ fn field_access_completions(
                 ctx.value_completion(name.clone(), value);
             }
         }
+        Value::Record(record) => {
+            for (name, value) in record.fields().iter() {
+                ctx.value_completion(name.clone(), value);
+            }
+        }
         Value::Func(func) => {
             // Autocomplete get rules.
             if let Some((elem, styles)) = func.to_element().zip(styles.as_ref()) {
@@ -231,6 +236,17 @@ fn field_access_completions(
                         ctx.value_completion(param.name, &value);
                     }
                 }
+            } else if let Some((def, styles)) = func.to_custom_elem().zip(styles.as_ref())
+            {
+                for (id, field) in def.fields().iter().enumerate() {
+                    if let Some(value) = CustomElemDef::field_from_styles(
+                        def,
+                        id as u8,
+                        StyleChain::new(styles),
+                    ) {
+                        ctx.value_completion(field.name(), &value);
+                    }
+                }
             }
         }
         _ => {}
@@ -369,10 +385,10 @@ fn set_rule_completions(ctx: &mut CompletionContext) {
 
 /// Add completions for selectors.
 fn show_rule_selector_completions(ctx: &mut CompletionContext) {
-    ctx.scope_completions(
-        false,
-        |value| matches!(value, Value::Func(func) if func.to_element().is_some()),
-    );
+    ctx.scope_completions(false, |value| {
+        matches!(value, Value::Func(func)
+                if func.to_element().is_some() || func.to_custom_elem().is_some())
+    });
 
     ctx.enrich("", ": ");
 
@@ -591,17 +607,18 @@ fn param_value_completions<'a>(
         ctx.snippet_completion("table", "table(\n  ${}\n),", "A table in a figure.");
     }
 
-    if let ParamInfo::Native(param) = param {
-        ctx.cast_completions(&param.input);
+    if let Some(input) = param.input() {
+        ctx.cast_completions(&input);
     }
 }
 
 /// Returns which file extensions to complete for the given parameter if any.
 fn path_completion(func: &Func, param: &ParamInfo) -> Option<&'static [&'static str]> {
     Some(match (func.name(), param.name().unwrap_or_default()) {
-        (Some("image"), "source") => {
-            &["png", "jpg", "jpeg", "gif", "svg", "svgz", "webp", "pdf"]
-        }
+        (Some("image"), "source") => &[
+            "png", "jpg", "jpeg", "gif", "svg", "svgz", "webp", "avif", "jxl", "bmp",
+            "tif", "tiff", "pdf",
+        ],
         (Some("csv"), "source") => &["csv"],
         (Some("plugin"), "source") => &["wasm"],
         (Some("cbor"), "source") => &["cbor"],
@@ -840,6 +857,32 @@ fn complete_code(ctx: &mut CompletionContext) -> bool {
         return true;
     }
 
+    // After a pipe operator: "{ x |>| }", "{ x |> | }", "{ x |> up| }".
+    let after_pipe = |leaf: &LinkedNode| {
+        // Skip the error for the missing right-hand side of the pipe.
+        let mut prev = leaf.prev_leaf();
+        while let Some(node) = &prev
+            && node.kind().is_error()
+            && node.is_empty()
+        {
+            prev = node.prev_leaf();
+        }
+        prev.is_some_and(|prev| prev.kind() == SyntaxKind::Pipe)
+    };
+    if ctx.leaf.kind() == SyntaxKind::Pipe
+        || ((ctx.leaf.kind().is_trivia() || ctx.leaf.kind() == SyntaxKind::Ident)
+            && after_pipe(ctx.leaf))
+    {
+        ctx.from = match ctx.leaf.kind() {
+            SyntaxKind::Ident => ctx.leaf.offset(),
+            _ => ctx.cursor,
+        };
+        ctx.scope_completions(true, |value| {
+            matches!(value, Value::Func(_) | Value::Type(_))
+        });
+        return true;
+    }
+
     // An existing identifier: "{ pa| }".
     // Ignores named pair keys as they are not variables (as in "(pa|: 23)").
     if ctx.leaf.kind() == SyntaxKind::Ident
@@ -990,6 +1033,18 @@ fn code_completions(ctx: &mut CompletionContext, hash: bool) {
         "Returns early from a function.",
     );
 
+    ctx.snippet_completion(
+        "match",
+        "match ${value} {\n\t${pattern} => ${output},\n\t_ => ${output},\n}",
+        "Computes or inserts something based on the shape of a value.",
+    );
+
+    ctx.snippet_completion(
+        "try-catch",
+        "try {\n\t${}\n} catch ${err} {\n\t${}\n}",
+        "Recovers from an error.",
+    );
+
     ctx.snippet_completion(
         "import (file)",
         "import \"${}\": ${}",
@@ -1646,6 +1701,18 @@ mod tests {
         test("#{ let x = (1, 2, 3); x. }", -3).must_include(["at", "push", "pop"]);
     }
 
+    #[test]
+    fn test_autocomplete_pipe() {
+        test("#{ \"a\" |> }", -2)
+            .must_include(["upper", "str", "calc"])
+            .must_exclude(["red", "if conditional"]);
+        test("#{ \"a\" |>}", -2).must_include(["upper"]);
+        test("#{ \"a\" |> up }", -3)
+            .must_include(["upper"])
+            .must_exclude(["red"]);
+        test("#let f(x) = x; #{ 1 |> }", -2).must_include(["f"]);
+    }
+
     /// Test that extra spaces before a '.' don't cause autocompletion in markup
     /// or math.
     #[test]
@@ -2000,4 +2067,21 @@ mod tests {
         res.at("forest").must_have_detail("More trees.");
         res.at("tree").must_have_detail("Tree with three slashes.");
     }
+
+    #[test]
+    fn test_autocomplete_typed_closure_params() {
+        test("#let f(x as bool) = x; #f()", -2).must_include(["false", "true"]);
+        test("#let f(x as bool) = x; #f(true, )", -2).must_exclude(["false"]);
+        test("#let f(on as bool: true) = on; #f(on: )", -2)
+            .must_include(["false", "true"]);
+        test("#let f(on: true) = on; #f(on: )", -2).must_exclude(["false"]);
+    }
+
+    #[test]
+    fn test_autocomplete_record_fields() {
+        let point = "#let point = type.define(\"point\", fields: (x: float, on: bool))";
+        test(format!("{point}; #point()").as_str(), -2).must_include(["x", "on"]);
+        test(format!("{point}; #point(on: )").as_str(), -2)
+            .must_include(["false", "true"]);
+    }
 }
@@ -12,6 +12,13 @@ pub fn find_value_docs(world: &dyn IdeWorld, value: &Value) -> Option<Docs> {
         return Some(Docs::Native(docs));
     }
 
+    // Plugins may declare documentation for their functions.
+    if let Value::Func(func) = value
+        && let Some(docs) = func.to_plugin().and_then(|plugin| plugin.docs())
+    {
+        return Some(Docs::Comment(docs.clone()));
+    }
+
     // Try to find doc comment before a function definition.
     if let Value::Func(func) = value
         && let span = func.span()
@@ -46,6 +53,8 @@ pub fn find_param_docs(world: &dyn IdeWorld, param: &ParamInfo) -> Option<Docs>
             None
         }
         ParamInfo::Plugin => None,
+        ParamInfo::PluginDeclared(param) => param.docs.clone().map(Docs::Comment),
+        ParamInfo::Custom(_) | ParamInfo::Record(_) => None,
     }
 }
 
@@ -135,6 +135,24 @@ pub fn named_items<T>(
                 }
             }
 
+            if let Some(v) = parent.cast::<ast::TryCatch>()
+                && node.span() == v.handler().span()
+                && let Some(ident) = v.binding()
+                && let Some(res) = recv(NamedItem::Var(ident))
+            {
+                return Some(res);
+            }
+
+            if let Some(v) = parent.cast::<ast::MatchArm>()
+                && node.span() != v.pattern().span()
+            {
+                for ident in v.pattern().bindings() {
+                    if let Some(res) = recv(NamedItem::Var(ident)) {
+                        return Some(res);
+                    }
+                }
+            }
+
             if let Some(v) = parent.cast::<ast::Closure>().filter(|v| {
                 // Check if the node is in the body of the closure.
                 let body = parent.find(v.body().span());
@@ -149,6 +167,13 @@ pub fn named_items<T>(
                                 }
                             }
                         }
+                        ast::Param::Typed(typed) => {
+                            for ident in typed.pattern().bindings() {
+                                if let Some(t) = recv(NamedItem::Var(ident)) {
+                                    return Some(t);
+                                }
+                            }
+                        }
                         ast::Param::Named(n) => {
                             if let Some(t) = recv(NamedItem::Var(n.name())) {
                                 return Some(t);
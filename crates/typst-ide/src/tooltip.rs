@@ -229,9 +229,17 @@ fn named_param_tooltip(world: &dyn IdeWorld, leaf: &LinkedNode) -> Option<Toolti
     if leaf.index() == 0
         && let Some(ident) = leaf.cast::<ast::Ident>()
         && let Some(param) = func.param(&ident)
-        && let Some(docs) = find_param_docs(world, &param)
     {
-        return Some(Tooltip::Text(docs.summary()));
+        if let Some(docs) = find_param_docs(world, &param) {
+            return Some(Tooltip::Text(docs.summary()));
+        }
+
+        // Fall back to the type annotation of a closure parameter.
+        if let ParamInfo::Closure(_) = param
+            && let Some(CastInfo::Type(ty)) = param.input()
+        {
+            return Some(Tooltip::Text(eco_format!("Expects a value of type `{ty}`.")));
+        }
     }
 
     // Hovering over a string parameter value.
@@ -497,6 +505,12 @@ mod tests {
             .must_be_text("This closure captures `f` and `y`");
     }
 
+    #[test]
+    fn test_tooltip_typed_closure_param() {
+        let world = "#let f(size as length: 1pt) = size; #f(size: 2pt)";
+        test(world, -9, Side::After).must_be_text("Expects a value of type `length`.");
+    }
+
     #[test]
     fn test_tooltip_import() {
         let world = TestWorld::new("#import \"other.typ\": a, b")
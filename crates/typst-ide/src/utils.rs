@@ -3,7 +3,7 @@ use std::ops::ControlFlow;
 
 use comemo::Track;
 use ecow::{EcoString, eco_format};
-use typst::engine::{Engine, Route, Sink, Traced};
+use typst::engine::{Cancellation, Engine, Fuel, Route, Sink, Traced};
 use typst::foundations::{Scope, Value};
 use typst::introspection::EmptyIntrospector;
 use typst::syntax::{LinkedNode, SyntaxMode};
@@ -19,12 +19,16 @@ where
 {
     let introspector = EmptyIntrospector;
     let traced = Traced::default();
+    let cancellation = Cancellation::new();
+    let fuel = Fuel::new(world.library().limits.fuel);
     let mut sink = Sink::new();
     let mut engine = Engine {
         library: world.library(),
         world: world.upcast().track(),
         introspector: Protected::new(introspector.track()),
         traced: traced.track(),
+        cancellation: cancellation.track(),
+        fuel: fuel.track(),
         sink: sink.track_mut(),
         route: Route::default(),
     };
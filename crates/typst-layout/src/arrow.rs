@@ -0,0 +1,262 @@
+use typst_library::diag::{SourceResult, bail};
+use typst_library::engine::Engine;
+use typst_library::foundations::{Packed, Resolve, StyleChain};
+use typst_library::introspection::Locator;
+use typst_library::layout::{Abs, Axes, Frame, FrameItem, Point, Region, Rel, Size};
+use typst_library::visualize::{
+    ArrowElem, ArrowMark, Curve, CurveItem, FixedStroke, Geometry, LineCap, Shape,
+};
+use typst_syntax::Span;
+use typst_utils::Numeric;
+
+/// Layout the arrow.
+#[typst_macros::time(span = elem.span())]
+pub fn layout_arrow(
+    elem: &Packed<ArrowElem>,
+    _: &mut Engine,
+    _: Locator,
+    styles: StyleChain,
+    region: Region,
+) -> SourceResult<Frame> {
+    let resolve = |axes: Axes<Rel<Abs>>| axes.zip_map(region.size, Rel::relative_to);
+    let start = resolve(elem.start.resolve(styles)).to_point();
+    let end = resolve(elem.end.resolve(styles)).to_point();
+
+    let size = start.to_size().max(end.to_size()).max(Size::zero());
+    if !size.is_finite() {
+        bail!(elem.span(), "cannot create arrow with infinite length");
+    }
+
+    // Bend the shaft into a symmetric arc by rotating the control points
+    // around the ends.
+    let bend = elem.bend.get(styles).to_rad();
+    let delta = end - start;
+    let mut curve = Curve::new();
+    curve.move_(start);
+    if bend == 0.0 {
+        curve.line(end);
+    } else {
+        curve.cubic(
+            start + rotate(delta, -bend) / 3.0,
+            end - rotate(delta, bend) / 3.0,
+            end,
+        );
+    }
+
+    let stroke = elem.stroke.resolve(styles).unwrap_or_default();
+    let mut frame = Frame::soft(size);
+    draw_marked(
+        &mut frame,
+        curve,
+        &stroke,
+        elem.start_mark.get(styles),
+        elem.end_mark.get(styles),
+        elem.span(),
+    );
+    Ok(frame)
+}
+
+/// Strokes an open curve and decorates its ends with marks.
+///
+/// The curve must start with a move and consist of at least one line or cubic
+/// segment afterwards. The shaft is shortened at ends with marks, so that it
+/// does not poke out of them.
+pub(crate) fn draw_marked(
+    frame: &mut Frame,
+    mut curve: Curve,
+    stroke: &FixedStroke,
+    start_mark: Option<ArrowMark>,
+    end_mark: Option<ArrowMark>,
+    span: Span,
+) {
+    let mut marks = vec![];
+
+    if let Some(mark) = end_mark
+        && let Some((tip, dir)) = end_tangent(&curve)
+    {
+        let retreat = dir * -mark_retreat(mark, stroke).to_raw();
+        if let Some(CurveItem::Line(p) | CurveItem::Cubic(_, _, p)) = curve.0.last_mut() {
+            *p += retreat;
+        }
+        if let Some(CurveItem::Cubic(_, c2, _)) = curve.0.last_mut() {
+            *c2 += retreat;
+        }
+        marks.push(mark_shape(mark, tip, dir, stroke));
+    }
+
+    if let Some(mark) = start_mark
+        && let Some((tip, dir)) = start_tangent(&curve)
+    {
+        let retreat = dir * -mark_retreat(mark, stroke).to_raw();
+        if let Some(CurveItem::Move(p)) = curve.0.first_mut() {
+            *p += retreat;
+        }
+        if let Some(CurveItem::Cubic(c1, _, _)) = curve.0.get_mut(1) {
+            *c1 += retreat;
+        }
+        marks.push(mark_shape(mark, tip, dir, stroke));
+    }
+
+    let shaft = Geometry::Curve(curve).stroked(stroke.clone());
+    frame.push(Point::zero(), FrameItem::Shape(shaft, span));
+    for mark in marks {
+        frame.push(Point::zero(), FrameItem::Shape(mark, span));
+    }
+}
+
+/// The tip and outward direction at the end of a curve.
+fn end_tangent(curve: &Curve) -> Option<(Point, Direction)> {
+    let mut prev = None;
+    let mut last = None;
+    for item in &curve.0 {
+        match *item {
+            CurveItem::Move(p) | CurveItem::Line(p) => {
+                prev = last;
+                last = Some(p);
+            }
+            CurveItem::Cubic(_, c2, p) => {
+                // Fall back to the previous point for degenerate controls.
+                prev = if c2 == p { last } else { Some(c2) };
+                last = Some(p);
+            }
+            CurveItem::Close => return None,
+        }
+    }
+    let (prev, last) = (prev?, last?);
+    Some((last, Direction::between(prev, last)?))
+}
+
+/// The tip and outward direction at the start of a curve.
+fn start_tangent(curve: &Curve) -> Option<(Point, Direction)> {
+    let CurveItem::Move(start) = *curve.0.first()? else { return None };
+    let next = match *curve.0.get(1)? {
+        CurveItem::Line(p) => p,
+        CurveItem::Cubic(c1, c2, p) => [c1, c2, p].into_iter().find(|&q| q != start)?,
+        _ => return None,
+    };
+    Some((start, Direction::between(next, start)?))
+}
+
+/// The length of a mark along the shaft, which grows with the stroke.
+fn mark_length(stroke: &FixedStroke) -> Abs {
+    Abs::pt(3.0) + stroke.thickness * 3.0
+}
+
+/// How far the shaft must end before the tip of a mark.
+fn mark_retreat(mark: ArrowMark, stroke: &FixedStroke) -> Abs {
+    let length = mark_length(stroke);
+    match mark {
+        ArrowMark::Arrow => stroke.thickness / 2.0,
+        ArrowMark::Triangle => length * 0.8,
+        ArrowMark::Stealth => length * 0.6,
+        ArrowMark::Circle => length * 0.35,
+        ArrowMark::Bar => Abs::zero(),
+    }
+}
+
+/// Creates the shape of a mark whose tip is at `tip` and which points in
+/// direction `dir`.
+fn mark_shape(
+    mark: ArrowMark,
+    tip: Point,
+    dir: Direction,
+    stroke: &FixedStroke,
+) -> Shape {
+    let length = mark_length(stroke).to_raw();
+    let width = length * 0.5;
+
+    // Transforms a point given in a coordinate system where the tip is the
+    // origin and the mark points towards positive x.
+    let at = |x: f64, y: f64| {
+        tip + Point::new(
+            Abs::raw(x * dir.cos - y * dir.sin),
+            Abs::raw(x * dir.sin + y * dir.cos),
+        )
+    };
+
+    let polygon = |points: &[(f64, f64)]| {
+        let mut curve = Curve::new();
+        for (i, &(x, y)) in points.iter().enumerate() {
+            if i == 0 { curve.move_(at(x, y)) } else { curve.line(at(x, y)) }
+        }
+        curve
+    };
+
+    // Marks should never be dashed.
+    let solid = FixedStroke { dash: None, ..stroke.clone() };
+
+    match mark {
+        ArrowMark::Arrow => {
+            Geometry::Curve(polygon(&[(-length, -width), (0.0, 0.0), (-length, width)]))
+                .stroked(solid)
+        }
+        ArrowMark::Triangle => {
+            let mut curve = polygon(&[(0.0, 0.0), (-length, -width), (-length, width)]);
+            curve.close();
+            Geometry::Curve(curve).filled(stroke.paint.clone())
+        }
+        ArrowMark::Stealth => {
+            let mut curve = polygon(&[
+                (0.0, 0.0),
+                (-length, -width),
+                (-length * 0.65, 0.0),
+                (-length, width),
+            ]);
+            curve.close();
+            Geometry::Curve(curve).filled(stroke.paint.clone())
+        }
+        ArrowMark::Circle => {
+            let radius = Abs::raw(length * 0.35);
+            let mut curve = Curve::ellipse(Size::splat(2.0 * radius));
+            curve.translate(at(-radius.to_raw(), 0.0) - Point::splat(radius));
+            Geometry::Curve(curve).filled(stroke.paint.clone())
+        }
+        ArrowMark::Bar => Geometry::Curve(polygon(&[(0.0, -width), (0.0, width)]))
+            .stroked(FixedStroke { cap: LineCap::Butt, ..solid }),
+    }
+}
+
+/// A normalized direction vector.
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct Direction {
+    pub cos: f64,
+    pub sin: f64,
+}
+
+impl Direction {
+    /// The direction from one point to another, if they are distinct.
+    pub fn between(from: Point, to: Point) -> Option<Self> {
+        let dx = (to.x - from.x).to_raw();
+        let dy = (to.y - from.y).to_raw();
+        let len = dx.hypot(dy);
+        (len > 1e-9 && len.is_finite()).then(|| Self { cos: dx / len, sin: dy / len })
+    }
+
+    /// The direction rotated by a right angle, which points to the right of
+    /// this direction since the y-axis points downwards.
+    pub fn perpendicular(self) -> Self {
+        Self { cos: -self.sin, sin: self.cos }
+    }
+}
+
+impl std::ops::Neg for Direction {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self { cos: -self.cos, sin: -self.sin }
+    }
+}
+
+impl std::ops::Mul<f64> for Direction {
+    type Output = Point;
+
+    fn mul(self, length: f64) -> Point {
+        Point::new(Abs::raw(self.cos * length), Abs::raw(self.sin * length))
+    }
+}
+
+/// Rotates a vector by an angle given in radians.
+fn rotate(v: Point, angle: f64) -> Point {
+    let (sin, cos) = angle.sin_cos();
+    Point::new(v.x * cos - v.y * sin, v.x * sin + v.y * cos)
+}
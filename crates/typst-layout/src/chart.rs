@@ -0,0 +1,490 @@
+use typst_library::diag::{SourceResult, bail};
+use typst_library::engine::Engine;
+use typst_library::foundations::{Content, Packed, Resolve, Smart, StyleChain, repr};
+use typst_library::introspection::{Locator, SplitLocator};
+use typst_library::layout::{Abs, Axes, Em, Frame, FrameItem, Point, Region, Size};
+use typst_library::text::TextElem;
+use typst_library::visualize::{
+    ChartElem, ChartRange, Color, Curve, FixedStroke, Geometry, LineCap, LineJoin, Paint,
+    SeriesKind,
+};
+use typst_syntax::Span;
+use typst_utils::{Numeric, round_with_precision};
+
+/// The colors that series are painted with by default, in order.
+const PALETTE: [Color; 6] =
+    [Color::BLUE, Color::RED, Color::GREEN, Color::ORANGE, Color::PURPLE, Color::TEAL];
+
+/// The width of a chart in an unbounded region, in ems.
+const DEFAULT_WIDTH: f64 = 25.0;
+
+/// The number of ticks per axis to aim for.
+const TICKS: f64 = 5.0;
+
+/// How much of the space available for a data point its bars take up.
+const BAR_WIDTH: f64 = 0.8;
+
+/// The opacity of the filled area below an area series.
+const AREA_ALPHA: f32 = 0.3;
+
+/// Layout the chart.
+#[typst_macros::time(span = elem.span())]
+pub fn layout_chart(
+    elem: &Packed<ChartElem>,
+    engine: &mut Engine,
+    locator: Locator,
+    styles: StyleChain,
+    region: Region,
+) -> SourceResult<Frame> {
+    let span = elem.span();
+    let mut locator = locator.split();
+
+    // Determine the size of the whole chart.
+    let width = match elem.width.get(styles) {
+        // In an unbounded region, e.g. in an HTML frame, fall back to a
+        // fixed width.
+        Smart::Auto if region.size.x.is_finite() => region.size.x,
+        Smart::Auto => Em::new(DEFAULT_WIDTH).resolve(styles),
+        Smart::Custom(width) => width.resolve(styles).relative_to(region.size.x),
+    };
+    let height = match elem.height.get(styles) {
+        Smart::Auto => width * (2.0 / 3.0),
+        Smart::Custom(height) => height.resolve(styles).relative_to(region.size.y),
+    };
+    let size = Size::new(width, height);
+    if !size.is_finite() {
+        bail!(
+            span, "cannot create chart with infinite size";
+            hint: "try giving the chart a fixed `width` and `height`";
+        );
+    }
+
+    let series: Vec<_> = elem
+        .children
+        .iter()
+        .enumerate()
+        .map(|(i, series)| Series {
+            kind: series.kind.get(styles),
+            points: series.points().collect(),
+            paint: series
+                .paint
+                .get_cloned(styles)
+                .unwrap_or_else(|| PALETTE[i % PALETTE.len()].into()),
+            thickness: series.thickness.resolve(styles),
+            caption: series.caption.get_cloned(styles),
+        })
+        .collect();
+
+    // Determine the ranges of the axes.
+    let categories = elem.categories.get_ref(styles);
+    let bars = series.iter().filter(|s| s.kind == SeriesKind::Bar).count();
+    let banded = bars > 0 || !categories.is_empty();
+    let band = if categories.is_empty() { min_gap(&series) } else { 1.0 };
+
+    let x_range = match elem.x_range.get(styles) {
+        Smart::Custom(range) => (range.min.get(), range.max.get()),
+        Smart::Auto => {
+            let mut range = extent(series.iter().flat_map(|s| s.xs()));
+            if !categories.is_empty() {
+                let last = categories.len() as f64 - 1.0;
+                range = Some(
+                    range.map_or((0.0, last), |(lo, hi)| (lo.min(0.0), hi.max(last))),
+                );
+            }
+            let (lo, hi) = range.unwrap_or((0.0, 1.0));
+            if banded {
+                // Leave room for the bars at the ends.
+                (lo - band / 2.0, hi + band / 2.0)
+            } else {
+                widen((lo, hi))
+            }
+        }
+    };
+
+    let (y_range, y_ticks) = match elem.y_range.get(styles) {
+        Smart::Custom(ChartRange { min, max }) => {
+            let range = (min.get(), max.get());
+            (range, ticks(range, nice_step(range)))
+        }
+        Smart::Auto => {
+            let filled = series.iter().any(|s| s.fills_to_zero());
+            let mut range = extent(series.iter().flat_map(|s| s.ys()));
+            if filled {
+                range =
+                    Some(range.map_or((0.0, 1.0), |(lo, hi)| (lo.min(0.0), hi.max(0.0))));
+            }
+
+            // Extend the range to the enclosing ticks.
+            let (lo, hi) = widen(range.unwrap_or((0.0, 1.0)));
+            let step = nice_step((lo, hi));
+            let range = ((lo / step).floor() * step, (hi / step).ceil() * step);
+            (range, ticks(range, step))
+        }
+    };
+
+    // Determine the ticks of the horizontal axis and lay out all labels.
+    let mut labels = Labels { engine, locator: &mut locator, styles, span };
+    let x_ticks: Vec<(f64, Frame)> = if categories.is_empty() {
+        ticks(x_range, nice_step(x_range))
+            .into_iter()
+            .map(|x| Ok((x, labels.number(x)?)))
+            .collect::<SourceResult<_>>()?
+    } else {
+        categories
+            .iter()
+            .enumerate()
+            .map(|(i, category)| (i as f64, category))
+            .filter(|&(x, _)| in_range(x, x_range))
+            .map(|(x, category)| Ok((x, labels.layout(category)?)))
+            .collect::<SourceResult<_>>()?
+    };
+    let y_ticks: Vec<(f64, Frame)> = y_ticks
+        .into_iter()
+        .map(|y| Ok((y, labels.number(y)?)))
+        .collect::<SourceResult<_>>()?;
+    let x_label = elem
+        .x_label
+        .get_ref(styles)
+        .as_ref()
+        .map(|c| labels.layout(c))
+        .transpose()?;
+    let y_label = elem
+        .y_label
+        .get_ref(styles)
+        .as_ref()
+        .map(|c| labels.layout(c))
+        .transpose()?;
+
+    let mut legend = vec![];
+    if elem.legend.get(styles) {
+        for series in &series {
+            if let Some(caption) = &series.caption {
+                legend.push((series, labels.layout(caption)?));
+            }
+        }
+    }
+
+    // Determine the margins around the plot.
+    let em = |v: f64| Em::new(v).resolve(styles);
+    let (tick, gap, swatch) = (em(0.3), em(0.4), em(0.8));
+    let y_tick_size = max_size(y_ticks.iter().map(|(_, frame)| frame));
+    let x_tick_size = max_size(x_ticks.iter().map(|(_, frame)| frame));
+    let legend_size = max_size(legend.iter().map(|(_, frame)| frame));
+
+    let left = y_tick_size.x + gap + tick;
+    let top = match &y_label {
+        Some(label) => label.height() + gap,
+        None => y_tick_size.y / 2.0,
+    };
+    let mut bottom = tick + gap + x_tick_size.y;
+    if let Some(label) = &x_label {
+        bottom += gap + label.height();
+    }
+    let right = if legend.is_empty() {
+        x_tick_size.x / 2.0
+    } else {
+        2.0 * gap + swatch + gap + legend_size.x
+    };
+
+    let plot = Size::new(width - left - right, height - top - bottom);
+    if plot.x <= Abs::zero() || plot.y <= Abs::zero() {
+        bail!(
+            span, "chart is too small to fit its labels";
+            hint: "try increasing the chart's `width` or `height`";
+        );
+    }
+
+    // Maps a data point to a point in the plot.
+    let map = |x: f64, y: f64| {
+        Point::new(
+            plot.x * ((x - x_range.0) / (x_range.1 - x_range.0)),
+            plot.y * (1.0 - (y - y_range.0) / (y_range.1 - y_range.0)),
+        )
+    };
+
+    let mut frame = Frame::hard(size);
+    let origin = Point::new(left, top);
+    let text_paint = styles.get_ref(TextElem::fill).as_decoration();
+    let axis_stroke = FixedStroke::from_pair(text_paint, em(0.05));
+
+    // Draw the grid behind everything else.
+    if elem.grid.get(styles) {
+        let stroke = FixedStroke::from_pair(Color::SILVER, em(0.04));
+        for &(y, _) in &y_ticks {
+            let pos = origin + map(x_range.0, y);
+            let line = Geometry::Line(Point::with_x(plot.x)).stroked(stroke.clone());
+            frame.push(pos, FrameItem::Shape(line, span));
+        }
+    }
+
+    // Draw the series into their own frame, so that data outside of the
+    // axes' ranges is clipped.
+    let mut inner = Frame::hard(plot);
+    let base = 0.0_f64.clamp(y_range.0, y_range.1);
+    let mut bar = 0;
+    for series in &series {
+        match series.kind {
+            SeriesKind::Line => {
+                draw_line(&mut inner, series, &map, span);
+            }
+            SeriesKind::Area => {
+                if let (Some(first), Some(last)) =
+                    (series.points.first(), series.points.last())
+                {
+                    let mut curve = Curve::new();
+                    curve.move_(map(first.0, base));
+                    for &(x, y) in &series.points {
+                        curve.line(map(x, y));
+                    }
+                    curve.line(map(last.0, base));
+                    curve.close();
+                    let paint = match &series.paint {
+                        Paint::Solid(color) => Paint::Solid(color.with_alpha(AREA_ALPHA)),
+                        paint => paint.clone(),
+                    };
+                    let shape = Geometry::Curve(curve).filled(paint);
+                    inner.push(Point::zero(), FrameItem::Shape(shape, span));
+                }
+                draw_line(&mut inner, series, &map, span);
+            }
+            SeriesKind::Bar => {
+                let width = band * BAR_WIDTH / bars as f64;
+                let offset = (bar as f64 - (bars - 1) as f64 / 2.0) * width;
+                for &(x, y) in &series.points {
+                    let a = map(x + offset - width / 2.0, base);
+                    let b = map(x + offset + width / 2.0, y);
+                    let pos = a.min(b);
+                    let size = (a.max(b) - pos).to_size();
+                    let shape = Geometry::Rect(size).filled(series.paint.clone());
+                    inner.push(pos, FrameItem::Shape(shape, span));
+                }
+                bar += 1;
+            }
+            SeriesKind::Scatter => {
+                let radius = series.thickness * 1.5 + Abs::pt(1.5);
+                for &(x, y) in &series.points {
+                    let pos = map(x, y) - Point::splat(radius);
+                    let dot = Geometry::Curve(Curve::ellipse(Size::splat(2.0 * radius)))
+                        .filled(series.paint.clone());
+                    inner.push(pos, FrameItem::Shape(dot, span));
+                }
+            }
+        }
+    }
+    inner.clip(Curve::rect(plot));
+    frame.push_frame(origin, inner);
+
+    // Draw the axes.
+    let line = |end: Point| {
+        FrameItem::Shape(Geometry::Line(end).stroked(axis_stroke.clone()), span)
+    };
+    frame.push(origin, line(Point::with_y(plot.y)));
+    frame.push(origin + Point::with_y(plot.y), line(Point::with_x(plot.x)));
+
+    // Draw the ticks and their labels.
+    for (y, label) in y_ticks {
+        let pos = origin + map(x_range.0, y);
+        frame.push(pos - Point::with_x(tick), line(Point::with_x(tick)));
+        let size = label.size();
+        frame.push_frame(pos - Point::new(tick + gap + size.x, size.y / 2.0), label);
+    }
+    for (x, label) in x_ticks {
+        let pos = origin + Point::new(map(x, y_range.0).x, plot.y);
+        frame.push(pos, line(Point::with_y(tick)));
+        frame.push_frame(pos + Point::new(-label.width() / 2.0, tick + gap), label);
+    }
+
+    // Place the axis labels.
+    if let Some(label) = x_label {
+        let x = left + (plot.x - label.width()) / 2.0;
+        frame.push_frame(Point::new(x, height - label.height()), label);
+    }
+    if let Some(label) = y_label {
+        frame.push_frame(Point::zero(), label);
+    }
+
+    // Draw the legend to the right of the plot.
+    let mut pos = Point::new(left + plot.x + 2.0 * gap, top);
+    for (series, label) in legend {
+        let row = label.height().max(swatch);
+        let y = pos.y + (row - swatch) / 2.0;
+        if series.kind == SeriesKind::Line {
+            let line = Geometry::Line(Point::with_x(swatch)).stroked(series.stroke());
+            frame.push(Point::new(pos.x, y + swatch / 2.0), FrameItem::Shape(line, span));
+        } else {
+            let rect = Geometry::Rect(Size::splat(swatch)).filled(series.paint.clone());
+            frame.push(Point::new(pos.x, y), FrameItem::Shape(rect, span));
+        }
+        let label_pos =
+            Point::new(pos.x + swatch + gap, pos.y + (row - label.height()) / 2.0);
+        frame.push_frame(label_pos, label);
+        pos.y += row + gap / 2.0;
+    }
+
+    Ok(frame)
+}
+
+/// A chart series with resolved properties.
+struct Series {
+    kind: SeriesKind,
+    points: Vec<(f64, f64)>,
+    paint: Paint,
+    thickness: Abs,
+    caption: Option<Content>,
+}
+
+impl Series {
+    /// The horizontal values of the series.
+    fn xs(&self) -> impl Iterator<Item = f64> + '_ {
+        self.points.iter().map(|&(x, _)| x)
+    }
+
+    /// The vertical values of the series.
+    fn ys(&self) -> impl Iterator<Item = f64> + '_ {
+        self.points.iter().map(|&(_, y)| y)
+    }
+
+    /// Whether the series is drawn from zero.
+    fn fills_to_zero(&self) -> bool {
+        matches!(self.kind, SeriesKind::Bar | SeriesKind::Area)
+    }
+
+    /// The stroke for lines of the series.
+    fn stroke(&self) -> FixedStroke {
+        FixedStroke {
+            cap: LineCap::Round,
+            join: LineJoin::Round,
+            ..FixedStroke::from_pair(self.paint.clone(), self.thickness)
+        }
+    }
+}
+
+/// Lays out the labels of a chart.
+struct Labels<'a, 'b, 'c> {
+    engine: &'a mut Engine<'b>,
+    locator: &'a mut SplitLocator<'c>,
+    styles: StyleChain<'a>,
+    span: Span,
+}
+
+impl Labels<'_, '_, '_> {
+    /// Lays out arbitrary content at its natural size.
+    fn layout(&mut self, content: &Content) -> SourceResult<Frame> {
+        let pod = Region::new(Size::splat(Abs::inf()), Axes::splat(false));
+        let locator = self.locator.next(&content.span());
+        crate::layout_frame(self.engine, content, locator, self.styles, pod)
+    }
+
+    /// Lays out a number on an axis.
+    fn number(&mut self, value: f64) -> SourceResult<Frame> {
+        // Round away floating point inaccuracies from computing the ticks.
+        let text = repr::display_float(round_with_precision(value, 12));
+        self.layout(&TextElem::packed(text).spanned(self.span))
+    }
+}
+
+/// Draws a line through the points of a series.
+fn draw_line(
+    frame: &mut Frame,
+    series: &Series,
+    map: &impl Fn(f64, f64) -> Point,
+    span: Span,
+) {
+    let mut points = series.points.iter().map(|&(x, y)| map(x, y));
+    let Some(first) = points.next() else { return };
+    let mut curve = Curve::new();
+    curve.move_(first);
+    for point in points {
+        curve.line(point);
+    }
+    let shape = Geometry::Curve(curve).stroked(series.stroke());
+    frame.push(Point::zero(), FrameItem::Shape(shape, span));
+}
+
+/// The size that fits all of the frames.
+fn max_size<'a>(frames: impl IntoIterator<Item = &'a Frame>) -> Size {
+    frames
+        .into_iter()
+        .fold(Size::zero(), |acc, frame| acc.max(frame.size()))
+}
+
+/// The smallest and largest of the values, if there are any.
+fn extent(values: impl IntoIterator<Item = f64>) -> Option<(f64, f64)> {
+    values.into_iter().fold(None, |acc, v| match acc {
+        None => Some((v, v)),
+        Some((lo, hi)) => Some((lo.min(v), hi.max(v))),
+    })
+}
+
+/// Ensures that a range is not empty.
+fn widen((lo, hi): (f64, f64)) -> (f64, f64) {
+    if lo < hi { (lo, hi) } else { (lo - 1.0, hi + 1.0) }
+}
+
+/// Whether a value lies within a range, allowing for floating point
+/// inaccuracies.
+fn in_range(value: f64, (lo, hi): (f64, f64)) -> bool {
+    let eps = (hi - lo) * 1e-9;
+    value >= lo - eps && value <= hi + eps
+}
+
+/// The smallest distance between two distinct horizontal values in any of the
+/// series, which is the space available to the bars of one data point.
+fn min_gap(series: &[Series]) -> f64 {
+    let mut xs: Vec<f64> = series.iter().flat_map(|s| s.xs()).collect();
+    xs.sort_by(f64::total_cmp);
+    xs.dedup();
+    xs.windows(2).map(|w| w[1] - w[0]).reduce(f64::min).unwrap_or(1.0)
+}
+
+/// A round distance between ticks, such that a range gets about [`TICKS`]
+/// ticks.
+fn nice_step((lo, hi): (f64, f64)) -> f64 {
+    let raw = (hi - lo) / TICKS;
+    let magnitude = 10_f64.powf(raw.log10().floor());
+    let factor = match raw / magnitude {
+        v if v < 1.5 => 1.0,
+        v if v < 3.0 => 2.0,
+        v if v < 7.0 => 5.0,
+        _ => 10.0,
+    };
+    factor * magnitude
+}
+
+/// All multiples of the step within the range.
+fn ticks((lo, hi): (f64, f64), step: f64) -> Vec<f64> {
+    if step <= 0.0 || !step.is_finite() {
+        return vec![];
+    }
+    let first = (lo / step - 1e-9).ceil() as i64;
+    let last = (hi / step + 1e-9).floor() as i64;
+    (first..=last).map(|k| k as f64 * step).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nice_step() {
+        assert_eq!(nice_step((0.0, 10.0)), 2.0);
+        assert_eq!(nice_step((0.0, 100.0)), 20.0);
+        assert_eq!(nice_step((0.0, 1.0)), 0.2);
+        assert_eq!(nice_step((2019.0, 2022.0)), 0.5);
+        assert_eq!(nice_step((0.0, 40.0)), 10.0);
+    }
+
+    #[test]
+    fn test_ticks() {
+        assert_eq!(ticks((0.0, 10.0), 2.0), [0.0, 2.0, 4.0, 6.0, 8.0, 10.0]);
+        assert_eq!(ticks((-0.5, 3.5), 1.0), [0.0, 1.0, 2.0, 3.0]);
+        assert_eq!(ticks((0.0, 1.0), f64::NAN), Vec::<f64>::new());
+    }
+
+    #[test]
+    fn test_extent() {
+        assert_eq!(extent([3.0, -1.0, 2.0]), Some((-1.0, 3.0)));
+        assert_eq!(extent([]), None);
+        assert_eq!(widen((2.0, 2.0)), (1.0, 3.0));
+    }
+}
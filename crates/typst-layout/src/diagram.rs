@@ -0,0 +1,729 @@
+//! Layout of diagrams with the layered (Sugiyama) method.
+//!
+//! The layout happens in four phases:
+//! 1. Cycles are broken by temporarily reversing the back edges found by a
+//!    depth-first search.
+//! 2. Nodes are assigned to layers by their longest path from a source. Edges
+//!    spanning multiple layers are split with dummy vertices, so that each
+//!    edge only connects adjacent layers.
+//! 3. The vertices within each layer are reordered with the barycenter
+//!    heuristic to reduce crossings.
+//! 4. The vertices are positioned within their layer, pulling each towards
+//!    its neighbours while keeping their order and spacing.
+
+use std::f64::consts::SQRT_2;
+
+use rustc_hash::FxHashMap;
+use typst_library::diag::{SourceResult, bail};
+use typst_library::engine::Engine;
+use typst_library::foundations::{Packed, Smart, StyleChain};
+use typst_library::introspection::{Locator, SplitLocator};
+use typst_library::layout::{
+    Abs, Axes, Axis, Frame, FrameItem, Point, Ratio, Region, Rel, Sides, Size,
+};
+use typst_library::visualize::{
+    Curve, DiagramChild, DiagramElem, DiagramNode, FillRule, FixedStroke, Geometry,
+    NodeShape, Shape, Stroke,
+};
+use typst_utils::Get;
+
+use crate::arrow::{Direction, draw_marked};
+
+/// How many sweeps to perform for crossing reduction and positioning.
+const SWEEPS: usize = 12;
+
+/// Layout the diagram.
+#[typst_macros::time(span = elem.span())]
+pub fn layout_diagram(
+    elem: &Packed<DiagramElem>,
+    engine: &mut Engine,
+    locator: Locator,
+    styles: StyleChain,
+    region: Region,
+) -> SourceResult<Frame> {
+    let mut locator = locator.split();
+    let pod = Region::new(region.size, Axes::splat(false));
+
+    // Lay out the nodes.
+    let mut nodes = vec![];
+    let mut indices = FxHashMap::default();
+    for child in &elem.children {
+        let DiagramChild::Node(node) = child else { continue };
+        if indices.insert(node.name.clone(), nodes.len()).is_some() {
+            bail!(node.span(), "duplicate node `{}`", node.name);
+        }
+        nodes.push(layout_node(node, engine, &mut locator, styles, pod)?);
+    }
+
+    // Resolve the nodes that the edges connect.
+    let mut edges = vec![];
+    for child in &elem.children {
+        let DiagramChild::Edge(edge) = child else { continue };
+        let find = |name: &str| match indices.get(name) {
+            Some(&index) => Ok(index),
+            None => bail!(
+                edge.span(), "unknown node `{name}`";
+                hint: "edges can only connect nodes of the same diagram";
+            ),
+        };
+        edges.push((edge, find(&edge.from)?, find(&edge.to)?));
+    }
+
+    // Run the layered layout with the main axis pointing in the diagram's
+    // direction. Loops do not influence the layout.
+    let dir = elem.dir.get(styles);
+    let main = dir.axis();
+    let cross = main.other();
+    let node_spacing = elem.node_spacing.resolve(styles);
+    let links: Vec<_> = edges
+        .iter()
+        .filter(|(_, from, to)| from != to)
+        .map(|&(_, from, to)| (from, to))
+        .collect();
+    let widths: Vec<_> =
+        nodes.iter().map(|n| n.frame.size().get(cross).to_raw()).collect();
+    let layout = layered(&widths, &links, node_spacing.to_raw());
+
+    // Determine the centers of the layers along the main axis.
+    let layer_spacing = elem.layer_spacing.resolve(styles);
+    let mut thickness = vec![Abs::zero(); layout.layers];
+    for (node, &layer) in nodes.iter().zip(&layout.layer) {
+        thickness[layer].set_max(node.frame.size().get(main));
+    }
+    let mut offsets = vec![];
+    let mut cursor = Abs::zero();
+    for &t in &thickness {
+        offsets.push(cursor + t / 2.0);
+        cursor += t + layer_spacing;
+    }
+    let length = (cursor - layer_spacing).max(Abs::zero());
+
+    // Maps a vertex to its center in the diagram.
+    let center = |v: usize| {
+        let offset = offsets[layout.layer[v]];
+        let along = if dir.is_positive() { offset } else { length - offset };
+        let across = Abs::raw(layout.pos[v]);
+        match main {
+            Axis::X => Point::new(along, across),
+            Axis::Y => Point::new(across, along),
+        }
+    };
+
+    // Count the edges between each pair of nodes, regardless of their
+    // direction, so that parallel and antiparallel edges can be bent apart.
+    let pair = |from: usize, to: usize| (from.min(to), from.max(to));
+    let mut bundles = FxHashMap::<(usize, usize), usize>::default();
+    for &(_, from, to) in &edges {
+        *bundles.entry(pair(from, to)).or_default() += 1;
+    }
+
+    let mut frame = Frame::soft(Size::zero());
+    let mut bounds = Bounds::default();
+    let mut captions = vec![];
+    let mut chains = layout.chains.iter();
+    let mut drawn = FxHashMap::<(usize, usize), usize>::default();
+
+    // Draw the edges.
+    for &(edge, from, to) in &edges {
+        let stroke = edge.stroke.resolve(styles).unwrap_or_default();
+        let start_mark = edge.start_mark.get(styles);
+        let end_mark = edge.end_mark.get(styles);
+
+        // Which of the edges between the two nodes this is.
+        let key = pair(from, to);
+        let counter = drawn.entry(key).or_default();
+        let nth = *counter;
+        *counter += 1;
+
+        let (curve, anchor, normal) = if from == to {
+            // Nest multiple loops at the same node.
+            let reach = node_spacing * (1.0 + nth as f64 / 2.0);
+            draw_loop(center(from), &nodes[from], reach)
+        } else {
+            let chain = chains.next().unwrap();
+            let mut points: Vec<Point> = chain.iter().map(|&v| center(v)).collect();
+            let n = points.len();
+            let offset = nth as f64 - (bundles[&key] - 1) as f64 / 2.0;
+            if n == 2 && offset != 0.0 {
+                // Edges spanning multiple layers already pass through distinct
+                // dummy vertices, so only direct edges need to be bent. The
+                // side is determined by the pair, not by the edge's direction,
+                // such that antiparallel edges end up on opposite sides.
+                let (a, b) = (center(key.0), center(key.1));
+                let side = Direction::between(a, b)
+                    .map_or(Direction { cos: 1.0, sin: 0.0 }, Direction::perpendicular);
+                let shift = offset * node_spacing.to_raw();
+                draw_bent(points[0], points[1], &nodes[from], &nodes[to], side, shift)
+            } else {
+                points[0] = nodes[from].boundary(points[0], points[1]);
+                points[n - 1] = nodes[to].boundary(points[n - 1], points[n - 2]);
+                draw_polyline(&points)
+            }
+        };
+
+        bounds.include_curve(&curve, &stroke);
+        draw_marked(&mut frame, curve, &stroke, start_mark, end_mark, edge.span());
+
+        if let Some(caption) = edge.caption.get_ref(styles) {
+            let caption = crate::layout_frame(
+                engine,
+                caption,
+                locator.next(&edge.span()),
+                styles,
+                pod,
+            )?;
+
+            // Move the caption away from the edge, far enough that its
+            // bounding box does not touch the edge.
+            let size = caption.size();
+            let distance = normal.cos.abs() * size.x.to_raw() / 2.0
+                + normal.sin.abs() * size.y.to_raw() / 2.0
+                + stroke.thickness.to_raw()
+                + CAPTION_GAP;
+            let pos = anchor + normal * distance - (size / 2.0).to_point();
+            bounds.include(pos, pos + size.to_point());
+            captions.push((pos, caption));
+        }
+    }
+
+    // Draw the nodes above the edges.
+    for (v, node) in nodes.into_iter().enumerate() {
+        let size = node.frame.size();
+        let pos = center(v) - (size / 2.0).to_point();
+        bounds.include(pos, pos + size.to_point());
+        frame.push_frame(pos, node.frame);
+    }
+
+    // Draw the captions on top.
+    for (pos, caption) in captions {
+        frame.push_frame(pos, caption);
+    }
+
+    // Shift everything such that the diagram starts at the origin.
+    frame.translate(-bounds.min);
+    frame.set_size((bounds.max - bounds.min).to_size());
+    Ok(frame)
+}
+
+/// The gap between an edge and its caption, in points.
+const CAPTION_GAP: f64 = 3.0;
+
+/// A laid out diagram node.
+struct Node {
+    /// The frame of the node, including its shape.
+    frame: Frame,
+    /// The shape that surrounds the node's content.
+    shape: NodeShape,
+}
+
+impl Node {
+    /// The point where the ray from the node's center towards a target point
+    /// leaves the node's shape.
+    fn boundary(&self, center: Point, target: Point) -> Point {
+        let Some(dir) = Direction::between(center, target) else { return center };
+        let half = (self.frame.size() / 2.0).to_point();
+        let (hw, hh) = (half.x.to_raw(), half.y.to_raw());
+        let t = match self.shape {
+            NodeShape::Rect => {
+                let tx = hw / dir.cos.abs();
+                let ty = hh / dir.sin.abs();
+                tx.min(ty)
+            }
+            NodeShape::Ellipse => 1.0 / (dir.cos / hw).hypot(dir.sin / hh),
+        };
+        if t.is_finite() { center + dir * t } else { center }
+    }
+}
+
+/// Layout a diagram node with its shape.
+fn layout_node(
+    node: &Packed<DiagramNode>,
+    engine: &mut Engine,
+    locator: &mut SplitLocator,
+    styles: StyleChain,
+    pod: Region,
+) -> SourceResult<Node> {
+    let shape = node.shape.get(styles);
+    let mut frame =
+        crate::layout_frame(engine, &node.body, locator.next(&node.span()), styles, pod)?;
+
+    let mut inset = Sides::splat(Rel::from(node.inset.resolve(styles)));
+    if shape == NodeShape::Ellipse {
+        // Like for ellipses, apply extra inset so that the content fits.
+        inset = inset.map(|v| v + Ratio::new(0.5 - SQRT_2 / 4.0));
+    }
+    crate::pad::grow(&mut frame, &inset);
+
+    let fill = node.fill.get_cloned(styles);
+    let stroke = match node.stroke.resolve(styles) {
+        Smart::Auto if fill.is_none() => Some(FixedStroke::default()),
+        Smart::Auto => None,
+        Smart::Custom(stroke) => stroke.map(Stroke::unwrap_or_default),
+    };
+
+    if fill.is_some() || stroke.is_some() {
+        let size = frame.size();
+        let geometry = match shape {
+            NodeShape::Rect => Geometry::Rect(size),
+            NodeShape::Ellipse => Geometry::Curve(Curve::ellipse(size)),
+        };
+        let shape = Shape {
+            geometry,
+            fill,
+            fill_rule: FillRule::default(),
+            stroke,
+        };
+        frame.prepend(Point::zero(), FrameItem::Shape(shape, node.span()));
+    }
+
+    Ok(Node { frame, shape })
+}
+
+/// Creates a curve through the given points and determines an anchor for a
+/// caption at its middle together with the direction in which to move the
+/// caption away.
+fn draw_polyline(points: &[Point]) -> (Curve, Point, Direction) {
+    let mut curve = Curve::new();
+    curve.move_(points[0]);
+    for &point in &points[1..] {
+        curve.line(point);
+    }
+
+    // Find the segment that contains the middle of the polyline.
+    let total: f64 = points.windows(2).map(|w| (w[1] - w[0]).hypot().to_raw()).sum();
+    let mut remaining = total / 2.0;
+    let mut anchor = points[0];
+    let mut along = Direction { cos: 1.0, sin: 0.0 };
+    for w in points.windows(2) {
+        let length = (w[1] - w[0]).hypot().to_raw();
+        if let Some(dir) = Direction::between(w[0], w[1]) {
+            along = dir;
+        }
+        if remaining <= length {
+            anchor = w[0] + along * remaining;
+            break;
+        }
+        remaining -= length;
+    }
+
+    // Captions go to the right of the edge's direction.
+    (curve, anchor, along.perpendicular())
+}
+
+/// Creates a curve between the centers of two nodes that is bent to the given
+/// side such that its middle is `shift` away from the straight connection.
+/// Also determines an anchor for a caption and the direction in which to move
+/// it away.
+fn draw_bent(
+    a: Point,
+    b: Point,
+    from: &Node,
+    to: &Node,
+    side: Direction,
+    shift: f64,
+) -> (Curve, Point, Direction) {
+    // Shifting both control points by 4/3 of the desired amount shifts the
+    // middle of a cubic curve by exactly that amount.
+    let control = side * (shift * 4.0 / 3.0);
+    let c1 = a + (b - a) / 3.0 + control;
+    let c2 = b + (a - b) / 3.0 + control;
+    let mut curve = Curve::new();
+    curve.move_(from.boundary(a, c1));
+    curve.cubic(c1, c2, to.boundary(b, c2));
+
+    let anchor = (a + b) / 2.0 + side * shift;
+    let normal = if shift < 0.0 { -side } else { side };
+    (curve, anchor, normal)
+}
+
+/// Creates a loop on the right side of a node and determines an anchor for a
+/// caption and the direction in which to move it away.
+fn draw_loop(center: Point, node: &Node, reach: Abs) -> (Curve, Point, Direction) {
+    let half = node.frame.size() / 2.0;
+    let start = center + Point::new(half.x, -half.y / 3.0);
+    let end = center + Point::new(half.x, half.y / 3.0);
+    let mut curve = Curve::new();
+    curve.move_(start);
+    curve.cubic(
+        start + Point::new(reach, -reach / 2.0),
+        end + Point::new(reach, reach / 2.0),
+        end,
+    );
+    let anchor = center + Point::with_x(half.x + reach * 0.75);
+    (curve, anchor, Direction { cos: 1.0, sin: 0.0 })
+}
+
+/// The bounding box of a diagram's contents.
+struct Bounds {
+    min: Point,
+    max: Point,
+}
+
+impl Default for Bounds {
+    fn default() -> Self {
+        Self { min: Point::zero(), max: Point::zero() }
+    }
+}
+
+impl Bounds {
+    /// Extends the bounds to contain a rectangle.
+    fn include(&mut self, min: Point, max: Point) {
+        self.min = self.min.min(min);
+        self.max = self.max.max(max);
+    }
+
+    /// Extends the bounds to contain a stroked curve.
+    fn include_curve(&mut self, curve: &Curve, stroke: &FixedStroke) {
+        let bbox = curve.bbox(Some(stroke));
+        self.include(bbox.min, bbox.max);
+    }
+}
+
+/// The result of the layered layout.
+#[derive(Debug)]
+struct Layered {
+    /// The layer of each vertex. The nodes come first, followed by the dummy
+    /// vertices.
+    layer: Vec<usize>,
+    /// The center of each vertex along the cross axis.
+    pos: Vec<f64>,
+    /// The number of layers.
+    layers: usize,
+    /// For each link, the vertices it passes through, from its start to its
+    /// end.
+    chains: Vec<Vec<usize>>,
+}
+
+/// Lays out a graph in layers.
+///
+/// Takes the cross-axis extent of each node and the links between them, which
+/// must not include loops.
+fn layered(widths: &[f64], links: &[(usize, usize)], spacing: f64) -> Layered {
+    let nodes = widths.len();
+
+    // Phase 1: Break cycles.
+    let reversed = find_back_links(nodes, links);
+    let oriented: Vec<_> = links
+        .iter()
+        .zip(&reversed)
+        .map(|(&(a, b), &rev)| if rev { (b, a) } else { (a, b) })
+        .collect();
+
+    // Phase 2: Assign layers and split long edges.
+    let mut layer = assign_layers(nodes, &oriented);
+    let mut widths = widths.to_vec();
+    let mut edges = vec![];
+    let mut chains = vec![];
+    for (&(a, b), &rev) in oriented.iter().zip(&reversed) {
+        let mut chain = vec![a];
+        for l in layer[a] + 1..layer[b] {
+            let dummy = layer.len();
+            layer.push(l);
+            widths.push(0.0);
+            edges.push((*chain.last().unwrap(), dummy));
+            chain.push(dummy);
+        }
+        edges.push((*chain.last().unwrap(), b));
+        chain.push(b);
+        if rev {
+            chain.reverse();
+        }
+        chains.push(chain);
+    }
+
+    let layers = layer.iter().max().map_or(0, |&l| l + 1);
+    let mut order = vec![vec![]; layers];
+    for (v, &l) in layer.iter().enumerate() {
+        order[l].push(v);
+    }
+
+    // Phase 3: Reduce crossings.
+    let graph = Adjacency::new(layer.len(), &edges);
+    reduce_crossings(&mut order, &graph);
+
+    // Phase 4: Assign positions.
+    let pos = assign_positions(&order, &widths, nodes, &graph, spacing);
+
+    Layered { layer, pos, layers, chains }
+}
+
+/// The neighbours of each vertex in the layers above and below.
+struct Adjacency {
+    ups: Vec<Vec<usize>>,
+    downs: Vec<Vec<usize>>,
+}
+
+impl Adjacency {
+    fn new(count: usize, edges: &[(usize, usize)]) -> Self {
+        let mut ups = vec![vec![]; count];
+        let mut downs = vec![vec![]; count];
+        for &(a, b) in edges {
+            downs[a].push(b);
+            ups[b].push(a);
+        }
+        Self { ups, downs }
+    }
+}
+
+/// Finds links that close a cycle with a depth-first search.
+fn find_back_links(nodes: usize, links: &[(usize, usize)]) -> Vec<bool> {
+    #[derive(Copy, Clone, Eq, PartialEq)]
+    enum State {
+        Unvisited,
+        Active,
+        Done,
+    }
+
+    let mut successors = vec![vec![]; nodes];
+    for (i, &(a, b)) in links.iter().enumerate() {
+        successors[a].push((b, i));
+    }
+
+    let mut state = vec![State::Unvisited; nodes];
+    let mut reversed = vec![false; links.len()];
+    for root in 0..nodes {
+        if state[root] != State::Unvisited {
+            continue;
+        }
+
+        state[root] = State::Active;
+        let mut stack = vec![(root, 0)];
+        while let Some(&mut (v, ref mut next)) = stack.last_mut() {
+            let Some(&(w, i)) = successors[v].get(*next) else {
+                state[v] = State::Done;
+                stack.pop();
+                continue;
+            };
+            *next += 1;
+            match state[w] {
+                State::Unvisited => {
+                    state[w] = State::Active;
+                    stack.push((w, 0));
+                }
+                State::Active => reversed[i] = true,
+                State::Done => {}
+            }
+        }
+    }
+
+    reversed
+}
+
+/// Assigns each node to the layer given by its longest path from a source.
+fn assign_layers(nodes: usize, edges: &[(usize, usize)]) -> Vec<usize> {
+    let mut successors = vec![vec![]; nodes];
+    let mut indegree = vec![0; nodes];
+    for &(a, b) in edges {
+        successors[a].push(b);
+        indegree[b] += 1;
+    }
+
+    let mut layer = vec![0; nodes];
+    let mut queue: Vec<_> = (0..nodes).filter(|&v| indegree[v] == 0).collect();
+    while let Some(v) = queue.pop() {
+        for &w in &successors[v] {
+            layer[w] = layer[w].max(layer[v] + 1);
+            indegree[w] -= 1;
+            if indegree[w] == 0 {
+                queue.push(w);
+            }
+        }
+    }
+
+    layer
+}
+
+/// Reorders the vertices within their layers to reduce edge crossings.
+///
+/// Alternately sweeps down and up, sorting each layer by the average position
+/// of each vertex's neighbours in the previous layer. Keeps the best order
+/// found.
+fn reduce_crossings(order: &mut [Vec<usize>], graph: &Adjacency) {
+    let mut index = vec![0.0; graph.ups.len()];
+    let mut best = order.to_vec();
+    let mut fewest = crossings(order, graph);
+
+    for sweep in 0..SWEEPS {
+        let down = sweep % 2 == 0;
+        let (layers, neighbours): (Vec<_>, _) = if down {
+            ((1..order.len()).collect(), &graph.ups)
+        } else {
+            ((0..order.len().saturating_sub(1)).rev().collect(), &graph.downs)
+        };
+
+        for l in layers {
+            let fixed = if down { l - 1 } else { l + 1 };
+            for (i, &v) in order[fixed].iter().enumerate() {
+                index[v] = i as f64;
+            }
+
+            let mut keyed: Vec<_> = order[l]
+                .iter()
+                .enumerate()
+                .map(|(i, &v)| {
+                    (barycenter(&neighbours[v], &index).unwrap_or(i as f64), v)
+                })
+                .collect();
+            keyed.sort_by(|a, b| a.0.total_cmp(&b.0));
+            order[l] = keyed.into_iter().map(|(_, v)| v).collect();
+        }
+
+        let count = crossings(order, graph);
+        if count < fewest {
+            fewest = count;
+            best = order.to_vec();
+        }
+    }
+
+    order.clone_from_slice(&best);
+}
+
+/// Counts the edge crossings between all adjacent layers.
+fn crossings(order: &[Vec<usize>], graph: &Adjacency) -> usize {
+    let mut index = vec![0; graph.downs.len()];
+    for layer in order {
+        for (i, &v) in layer.iter().enumerate() {
+            index[v] = i;
+        }
+    }
+
+    let mut count = 0;
+    for layer in order {
+        let segments: Vec<(usize, usize)> = layer
+            .iter()
+            .flat_map(|&v| graph.downs[v].iter().map(move |&w| (v, w)))
+            .map(|(v, w)| (index[v], index[w]))
+            .collect();
+        for (i, &(a1, b1)) in segments.iter().enumerate() {
+            for &(a2, b2) in &segments[i + 1..] {
+                if (a1 < a2 && b1 > b2) || (a1 > a2 && b1 < b2) {
+                    count += 1;
+                }
+            }
+        }
+    }
+
+    count
+}
+
+/// Assigns cross-axis positions to the vertices.
+///
+/// Alternately sweeps down and up, moving each vertex towards the average
+/// position of its neighbours in the previous layer as far as the spacing to
+/// the other vertices in its layer permits.
+fn assign_positions(
+    order: &[Vec<usize>],
+    widths: &[f64],
+    nodes: usize,
+    graph: &Adjacency,
+    spacing: f64,
+) -> Vec<f64> {
+    // The minimum distance between the centers of two adjacent vertices.
+    // Dummy vertices may be packed more tightly.
+    let gap = |a: usize, b: usize| {
+        let spacing = if a >= nodes && b >= nodes { spacing / 2.0 } else { spacing };
+        (widths[a] + widths[b]) / 2.0 + spacing
+    };
+
+    // Start with tightly packed layers.
+    let mut pos = vec![0.0; widths.len()];
+    for layer in order {
+        for i in 1..layer.len() {
+            pos[layer[i]] = pos[layer[i - 1]] + gap(layer[i - 1], layer[i]);
+        }
+    }
+
+    for sweep in 0..SWEEPS {
+        let down = sweep % 2 == 0;
+        let neighbours = if down { &graph.ups } else { &graph.downs };
+        let layers: Vec<_> =
+            if down { order.iter().collect() } else { order.iter().rev().collect() };
+
+        for layer in layers {
+            let desired: Vec<f64> = layer
+                .iter()
+                .map(|&v| barycenter(&neighbours[v], &pos).unwrap_or(pos[v]))
+                .collect();
+
+            // Both packing the vertices as far left and as far right as
+            // possible (while trying to reach their desired position) yields
+            // valid placements. Their average is valid, too, and balanced.
+            let k = layer.len();
+            let mut lo = desired.clone();
+            for i in 1..k {
+                lo[i] = lo[i].max(lo[i - 1] + gap(layer[i - 1], layer[i]));
+            }
+            let mut hi = desired;
+            for i in (0..k.saturating_sub(1)).rev() {
+                hi[i] = hi[i].min(hi[i + 1] - gap(layer[i], layer[i + 1]));
+            }
+            for i in 0..k {
+                pos[layer[i]] = (lo[i] + hi[i]) / 2.0;
+            }
+        }
+    }
+
+    // Shift everything such that the leftmost extent is at zero.
+    let min = pos
+        .iter()
+        .zip(widths)
+        .map(|(p, w)| p - w / 2.0)
+        .fold(f64::INFINITY, f64::min);
+    if min.is_finite() {
+        for p in &mut pos {
+            *p -= min;
+        }
+    }
+
+    pos
+}
+
+/// The average of the values of the given vertices, if there are any.
+fn barycenter(vertices: &[usize], values: &[f64]) -> Option<f64> {
+    (!vertices.is_empty())
+        .then(|| vertices.iter().map(|&v| values[v]).sum::<f64>() / vertices.len() as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_layered_layers() {
+        // A diamond with an additional long edge.
+        let layout = layered(&[10.0; 4], &[(0, 1), (0, 2), (1, 3), (2, 3), (0, 3)], 5.0);
+        assert_eq!(layout.layers, 3);
+        assert_eq!(&layout.layer[..4], &[0, 1, 1, 2]);
+        // The long edge passes through one dummy vertex.
+        assert_eq!(layout.chains[4].len(), 3);
+        assert_eq!(layout.layer[layout.chains[4][1]], 1);
+    }
+
+    #[test]
+    fn test_layered_cycle() {
+        let layout = layered(&[10.0; 3], &[(0, 1), (1, 2), (2, 0)], 5.0);
+        assert_eq!(&layout.layer[..3], &[0, 1, 2]);
+        // The edge closing the cycle still goes from its start to its end.
+        let chain = &layout.chains[2];
+        assert_eq!((chain[0], chain[chain.len() - 1]), (2, 0));
+    }
+
+    #[test]
+    fn test_layered_spacing() {
+        let layout = layered(&[10.0, 20.0, 30.0], &[(0, 1), (0, 2)], 5.0);
+        let (a, b) = (layout.pos[1], layout.pos[2]);
+        assert!((a - b).abs() >= 25.0 - 1e-9);
+        // The parent is centered above its children.
+        assert!((layout.pos[0] - (a + b) / 2.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_reduce_crossings() {
+        // Two edges that cross in the initial order.
+        let mut order = vec![vec![0, 1], vec![2, 3]];
+        let graph = Adjacency::new(4, &[(0, 3), (1, 2)]);
+        assert_eq!(crossings(&order, &graph), 1);
+        reduce_crossings(&mut order, &graph);
+        assert_eq!(crossings(&order, &graph), 0);
+    }
+}
@@ -6,7 +6,7 @@ use bumpalo::Bump;
 use bumpalo::boxed::Box as BumpBox;
 use comemo::{Track, Tracked, TrackedMut};
 use typst_library::diag::{SourceResult, bail, warning};
-use typst_library::engine::{Engine, Route, Sink, Traced};
+use typst_library::engine::{Cancellation, Engine, Fuel, Route, Sink, Traced};
 use typst_library::foundations::{Packed, Resolve, Smart, StyleChain};
 use typst_library::introspection::{
     Introspector, Location, Locator, LocatorLink, SplitLocator, Tag, TagElem,
@@ -20,6 +20,7 @@ use typst_library::model::ParElem;
 use typst_library::routines::Pair;
 use typst_library::text::TextElem;
 use typst_library::{Library, World};
+use typst_syntax::Span;
 use typst_utils::{LazyHash, Protected, SliceExt};
 
 use super::{FlowMode, layout_multi_block, layout_single_block};
@@ -134,7 +135,8 @@ impl<'a> Collector<'a, '_, '_> {
         }
 
         let leading = styles.resolve(ParElem::leading);
-        self.lines(lines, leading, styles);
+        let span = inner.first().map(|(c, _)| c.span()).unwrap_or_else(Span::detached);
+        self.lines(lines, leading, styles, span);
 
         for (c, _) in &self.children[end..] {
             let elem = c.to_packed::<TagElem>().unwrap();
@@ -177,7 +179,7 @@ impl<'a> Collector<'a, '_, '_> {
 
         self.output.push(Child::Rel(spacing.into(), 4));
 
-        self.lines(lines, leading, styles);
+        self.lines(lines, leading, styles, elem.span());
 
         self.output.push(Child::Rel(spacing.into(), 4));
         self.par_situation = ParSituation::Consecutive;
@@ -186,7 +188,13 @@ impl<'a> Collector<'a, '_, '_> {
     }
 
     /// Collect laid-out lines.
-    fn lines(&mut self, lines: Vec<Frame>, leading: Abs, styles: StyleChain<'a>) {
+    fn lines(
+        &mut self,
+        lines: Vec<Frame>,
+        leading: Abs,
+        styles: StyleChain<'a>,
+        span: Span,
+    ) {
         let align = styles.resolve(AlignElem::alignment);
         let costs = styles.get(TextElem::costs);
 
@@ -225,8 +233,12 @@ impl<'a> Collector<'a, '_, '_> {
                 frame.height()
             };
 
-            self.output
-                .push(Child::Line(self.boxed(LineChild { frame, align, need })));
+            self.output.push(Child::Line(self.boxed(LineChild {
+                frame,
+                align,
+                need,
+                span,
+            })));
         }
     }
 
@@ -366,12 +378,26 @@ pub enum Child<'a> {
     Break(bool),
 }
 
+impl Child<'_> {
+    /// The span of the content this child stems from, if any.
+    pub fn span(&self) -> Span {
+        match self {
+            Self::Line(line) => line.span,
+            Self::Single(single) => single.elem.span(),
+            Self::Multi(multi) => multi.elem.span(),
+            Self::Placed(placed) => placed.span(),
+            _ => Span::detached(),
+        }
+    }
+}
+
 /// A child that encapsulates a layouted line of a paragraph.
 #[derive(Debug)]
 pub struct LineChild {
     pub frame: Frame,
     pub align: Axes<FixedAlignment>,
     pub need: Abs,
+    pub span: Span,
 }
 
 /// A child that encapsulates a prepared unbreakable block.
@@ -398,6 +424,8 @@ impl SingleChild<'_> {
                 engine.library,
                 engine.introspector.into_raw(),
                 engine.traced,
+                engine.cancellation,
+                engine.fuel,
                 TrackedMut::reborrow_mut(&mut engine.sink),
                 engine.route.track(),
                 self.elem,
@@ -417,6 +445,8 @@ fn layout_single_impl(
     library: &LazyHash<Library>,
     introspector: Tracked<dyn Introspector + '_>,
     traced: Tracked<Traced>,
+    cancellation: Tracked<Cancellation>,
+    fuel: Tracked<Fuel>,
     sink: TrackedMut<Sink>,
     route: Tracked<Route>,
     elem: &Packed<BlockElem>,
@@ -432,6 +462,8 @@ fn layout_single_impl(
         world,
         introspector,
         traced,
+        cancellation,
+        fuel,
         sink,
         route: Route::extend(route),
     };
@@ -498,6 +530,8 @@ impl<'a> MultiChild<'a> {
                 engine.library,
                 engine.introspector.into_raw(),
                 engine.traced,
+                engine.cancellation,
+                engine.fuel,
                 TrackedMut::reborrow_mut(&mut engine.sink),
                 engine.route.track(),
                 self.elem,
@@ -517,6 +551,8 @@ fn layout_multi_impl(
     library: &LazyHash<Library>,
     introspector: Tracked<dyn Introspector + '_>,
     traced: Tracked<Traced>,
+    cancellation: Tracked<Cancellation>,
+    fuel: Tracked<Fuel>,
     sink: TrackedMut<Sink>,
     route: Tracked<Route>,
     elem: &Packed<BlockElem>,
@@ -532,6 +568,8 @@ fn layout_multi_impl(
         world,
         introspector,
         traced,
+        cancellation,
+        fuel,
         sink,
         route: Route::extend(route),
     };
@@ -553,6 +591,11 @@ pub struct MultiSpill<'a, 'b> {
 }
 
 impl MultiSpill<'_, '_> {
+    /// The span of the breakable block this spill stems from.
+    pub fn span(&self) -> Span {
+        self.multi.elem.span()
+    }
+
     /// Build the spill's frames given regions.
     pub fn layout(
         mut self,
@@ -663,6 +706,11 @@ impl PlacedChild<'_> {
         })
     }
 
+    /// The span of the placed element.
+    pub fn span(&self) -> Span {
+        self.elem.span()
+    }
+
     /// The element's location.
     pub fn location(&self) -> Location {
         self.elem.location().unwrap()
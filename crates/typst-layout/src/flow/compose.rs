@@ -582,6 +582,11 @@ impl<'a, 'b> Composer<'a, 'b, '_, '_> {
     pub fn insertion_width(&self) -> Abs {
         self.column_insertions.width.max(self.page_insertions.width)
     }
+
+    /// The amount of height taken up by insertions above the flow content.
+    pub fn insertion_top(&self) -> Abs {
+        self.page_insertions.top_size + self.column_insertions.top_size
+    }
 }
 
 /// Lay out the footnote separator, typically a line.
@@ -15,6 +15,7 @@ pub fn distribute(composer: &mut Composer, regions: Regions) -> FlowResult<Frame
     let mut distributor = Distributor {
         composer,
         regions,
+        initial: regions.size.y,
         items: vec![],
         sticky: None,
         stickable: None,
@@ -37,6 +38,8 @@ struct Distributor<'a, 'b, 'x, 'y, 'z> {
     composer: &'z mut Composer<'a, 'b, 'x, 'y>,
     /// Regions which are continuously shrunk as new items are added.
     regions: Regions<'z>,
+    /// The height of the first region before any items were added.
+    initial: Abs,
     /// Already laid out items, not yet aligned.
     items: Vec<Item<'a, 'b>>,
     /// A snapshot which can be restored to migrate a suffix of sticky blocks to
@@ -113,6 +116,7 @@ impl<'a, 'b> Distributor<'a, 'b, '_, '_, '_> {
         // If spill are taken care of, process children until no space is left
         // or no children are left.
         while let Some(child) = self.composer.work.head() {
+            self.composer.engine.check_cancelled()?;
             self.child(child)?;
             self.composer.work.advance();
         }
@@ -272,9 +276,15 @@ impl<'a, 'b> Distributor<'a, 'b, '_, '_, '_> {
 
     /// Processes a line of a paragraph.
     fn line(&mut self, line: &'b LineChild) -> FlowResult<()> {
+        // With a baseline grid, the line is moved down so that its baseline
+        // lands on the next grid line.
+        let shift = self.grid_shift(line.frame.baseline());
+
         // If the line doesn't fit and a followup region may improve things,
         // finish the region.
-        if !self.regions.size.y.fits(line.frame.height()) && self.regions.may_progress() {
+        if !self.regions.size.y.fits(shift + line.frame.height())
+            && self.regions.may_progress()
+        {
             return Err(Stop::Finish(false));
         }
 
@@ -282,7 +292,7 @@ impl<'a, 'b> Distributor<'a, 'b, '_, '_, '_> {
         // following lines grouped by widow/orphan prevention, does not fit into
         // the current region, but does fit into the next region, finish the
         // region.
-        if !self.regions.size.y.fits(line.need)
+        if !self.regions.size.y.fits(shift + line.need)
             && self
                 .regions
                 .iter()
@@ -292,6 +302,7 @@ impl<'a, 'b> Distributor<'a, 'b, '_, '_, '_> {
             return Err(Stop::Finish(false));
         }
 
+        self.grid_spacing(shift);
         self.frame(line.frame.clone(), line.align, false, false)
     }
 
@@ -318,7 +329,10 @@ impl<'a, 'b> Distributor<'a, 'b, '_, '_, '_> {
             return Err(Stop::Finish(false));
         }
 
-        self.frame(frame, single.align, single.sticky, false)
+        let padding = self.grid_padding(frame.height());
+        self.frame(frame, single.align, single.sticky, false)?;
+        self.grid_spacing(padding);
+        Ok(())
     }
 
     /// Processes a breakable block.
@@ -341,7 +355,9 @@ impl<'a, 'b> Distributor<'a, 'b, '_, '_, '_> {
             return Err(Stop::Finish(false));
         }
 
+        let padding = self.grid_padding(frame.height());
         self.frame(frame, multi.align, multi.sticky, true)?;
+        self.grid_spacing(padding);
 
         // If the block didn't fully fit into the current region, save it into
         // the `spill` and finish the region.
@@ -365,7 +381,9 @@ impl<'a, 'b> Distributor<'a, 'b, '_, '_, '_> {
         // Lay out the spilled remains.
         let align = spill.align();
         let (frame, spill) = spill.layout(self.composer.engine, self.regions)?;
+        let padding = self.grid_padding(frame.height());
         self.frame(frame, align, false, true)?;
+        self.grid_spacing(padding);
 
         // If there's still more, save it into the `spill` and finish the
         // region.
@@ -432,6 +450,44 @@ impl<'a, 'b> Distributor<'a, 'b, '_, '_, '_> {
         Ok(())
     }
 
+    /// Determines how far a frame with the given baseline must be moved down
+    /// for its baseline to land on the baseline grid.
+    fn grid_shift(&self, baseline: Abs) -> Abs {
+        let Some(grid) = self.composer.config.baseline_grid else {
+            return Abs::zero();
+        };
+
+        // The grid starts at the top of the page's content area, which is
+        // above the top insertions of the page and column.
+        let y =
+            self.composer.insertion_top() + self.initial - self.regions.size.y + baseline;
+        let shift = grid * (y / grid).ceil() - y;
+        if shift.approx_empty() || shift.approx_eq(grid) { Abs::zero() } else { shift }
+    }
+
+    /// Determines how much space must be added below a block of the given
+    /// height for it to occupy a whole number of grid lines.
+    fn grid_padding(&self, height: Abs) -> Abs {
+        let Some(grid) = self.composer.config.baseline_grid else {
+            return Abs::zero();
+        };
+
+        let padding = grid * (height / grid).ceil() - height;
+        if padding.approx_empty() || padding.approx_eq(grid) {
+            Abs::zero()
+        } else {
+            padding
+        }
+    }
+
+    /// Adds strong spacing that keeps content on the baseline grid.
+    fn grid_spacing(&mut self, amount: Abs) {
+        if !amount.is_zero() {
+            self.regions.size.y -= amount;
+            self.items.push(Item::Abs(amount, 0));
+        }
+    }
+
     /// Processes an absolutely or floatingly placed child.
     fn placed(&mut self, placed: &'b PlacedChild<'a>) -> FlowResult<()> {
         if placed.float {
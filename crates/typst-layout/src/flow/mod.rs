@@ -14,8 +14,8 @@ use bumpalo::Bump;
 use comemo::{Track, Tracked, TrackedMut};
 use ecow::EcoVec;
 use rustc_hash::FxHashSet;
-use typst_library::diag::{At, SourceDiagnostic, SourceResult, bail};
-use typst_library::engine::{Engine, Route, Sink, Traced};
+use typst_library::diag::{At, SourceDiagnostic, SourceResult, Uncatchable, bail};
+use typst_library::engine::{Cancellation, Engine, Fuel, Route, Sink, Traced};
 use typst_library::foundations::{Content, Packed, Resolve, StyleChain};
 use typst_library::introspection::{
     Introspector, Location, Locator, LocatorLink, SplitLocator, Tag,
@@ -29,6 +29,7 @@ use typst_library::pdf::ArtifactKind;
 use typst_library::routines::{Arenas, FragmentKind, Pair, RealizationKind};
 use typst_library::text::TextElem;
 use typst_library::{Library, World};
+use typst_syntax::Span;
 use typst_utils::{LazyHash, NonZeroExt, Numeric, Protected};
 
 use self::block::{layout_multi_block, layout_single_block};
@@ -65,6 +66,8 @@ pub fn layout_fragment(
         engine.library,
         engine.introspector.into_raw(),
         engine.traced,
+        engine.cancellation,
+        engine.fuel,
         TrackedMut::reborrow_mut(&mut engine.sink),
         engine.route.track(),
         content,
@@ -93,6 +96,8 @@ pub fn layout_columns(
         engine.library,
         engine.introspector.into_raw(),
         engine.traced,
+        engine.cancellation,
+        engine.fuel,
         TrackedMut::reborrow_mut(&mut engine.sink),
         engine.route.track(),
         &elem.body,
@@ -112,6 +117,8 @@ fn layout_fragment_impl(
     library: &LazyHash<Library>,
     introspector: Tracked<dyn Introspector + '_>,
     traced: Tracked<Traced>,
+    cancellation: Tracked<Cancellation>,
+    fuel: Tracked<Fuel>,
     sink: TrackedMut<Sink>,
     route: Tracked<Route>,
     content: &Content,
@@ -136,11 +143,13 @@ fn layout_fragment_impl(
         world,
         introspector,
         traced,
+        cancellation,
+        fuel,
         sink,
         route: Route::extend(route),
     };
 
-    engine.route.check_layout_depth().at(content.span())?;
+    engine.route.check_layout_depth().at(content.span()).uncatchable()?;
 
     let mut kind = FragmentKind::Block;
     let arenas = Arenas::default();
@@ -219,6 +228,7 @@ pub fn layout_flow<'a>(
 
     // This loop runs once per region produced by the flow layout.
     loop {
+        engine.check_cancelled()?;
         let frame = compose(engine, &mut work, &config, locator.next(&()), regions)?;
         finished.push(frame);
 
@@ -228,6 +238,16 @@ pub fn layout_flow<'a>(
             break;
         }
 
+        // A page run can't know how many pages the other runs produce, so it
+        // is only held to the full budget here. The document as a whole is
+        // checked again once all runs are combined.
+        if mode == FlowMode::Root
+            && let Some(max) = engine.library.limits.pages
+            && finished.len() >= max
+        {
+            bail!(work.span(), "document has more than the maximum of {max} pages");
+        }
+
         regions.next();
     }
 
@@ -256,6 +276,13 @@ fn configuration<'x>(
             let dir = shared.resolve(TextElem::dir);
             ColumnConfig { count, width, gutter, dir }
         },
+        baseline_grid: if mode == FlowMode::Root {
+            shared
+                .resolve(PageElem::baseline_grid)
+                .filter(|grid| grid.is_finite() && *grid > Abs::zero())
+        } else {
+            None
+        },
         footnote: FootnoteConfig {
             separator: shared
                 .get_cloned(FootnoteEntry::separator)
@@ -324,6 +351,20 @@ impl<'a, 'b> Work<'a, 'b> {
         }
     }
 
+    /// The span of the first pending content, used to point at whatever
+    /// didn't fit anymore.
+    fn span(&self) -> Span {
+        self.spill
+            .as_ref()
+            .map(MultiSpill::span)
+            .into_iter()
+            .chain(self.floats.iter().map(|placed| placed.span()))
+            .chain(self.footnotes.iter().map(|note| note.span()))
+            .chain(self.children.iter().map(Child::span))
+            .find(|span| !span.is_detached())
+            .unwrap_or_else(Span::detached)
+    }
+
     /// Get the first unprocessed child, from the start of the slice.
     fn head(&self) -> Option<&'b Child<'a>> {
         self.children.first()
@@ -362,6 +403,9 @@ struct Config<'x> {
     shared: StyleChain<'x>,
     /// Settings for columns.
     columns: ColumnConfig,
+    /// The distance between the lines of the baseline grid, if any. Only root
+    /// flows can be snapped to a grid.
+    baseline_grid: Option<Abs>,
     /// Settings for footnotes.
     footnote: FootnoteConfig,
     /// Settings for line numbers.
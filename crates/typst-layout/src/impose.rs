@@ -0,0 +1,232 @@
+use std::num::NonZeroUsize;
+
+use ecow::EcoVec;
+use typst_library::foundations::{Content, Smart};
+use typst_library::layout::{
+    Abs, Axes, Frame, FrameItem, GroupItem, Point, Ratio, Size, Transform,
+};
+use typst_library::model::Document;
+use typst_library::visualize::Curve;
+
+use crate::{Page, PagedDocument};
+
+/// How the pages of a document are arranged on printing sheets.
+#[derive(Debug, Clone, PartialEq, Hash)]
+pub struct Imposition {
+    /// The arrangement scheme.
+    pub scheme: ImpositionScheme,
+    /// The size of a sheet. Depending on what fits the pages better, the sheet
+    /// is used in portrait or landscape orientation.
+    pub sheet: Size,
+    /// How far the pages of the innermost sheet of a booklet are shifted
+    /// towards the spine to compensate for the paper's thickness. The shift
+    /// of the other sheets is interpolated, with the outermost one staying in
+    /// place. Ignored for other schemes.
+    pub creep: Abs,
+}
+
+/// A scheme for arranging pages on sheets.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum ImpositionScheme {
+    /// Two pages per sheet side, ordered such that the stacked and folded
+    /// sheets form a single signature.
+    Booklet,
+    /// A grid of pages per sheet, filled row by row in page order.
+    NUp(Axes<NonZeroUsize>),
+    /// A grid of pages per sheet, ordered such that cutting the stack of
+    /// sheets along the grid and placing the resulting stacks on top of each
+    /// other yields the pages in order.
+    CutAndStack(Axes<NonZeroUsize>),
+}
+
+/// Arranges the pages of a laid out document on sheets.
+///
+/// The resulting document contains one page per sheet side. Pages are scaled
+/// down uniformly if they do not fit into their cell, but never scaled up.
+#[typst_macros::time(name = "impose")]
+pub fn impose(document: &PagedDocument, imposition: &Imposition) -> PagedDocument {
+    let pages = document.pages();
+    let grid = match imposition.scheme {
+        ImpositionScheme::Booklet => Axes::new(2, 1),
+        ImpositionScheme::NUp(grid) | ImpositionScheme::CutAndStack(grid) => {
+            grid.map(NonZeroUsize::get)
+        }
+    };
+
+    // All pages share one scale, determined by the largest page.
+    let page = pages
+        .iter()
+        .fold(Size::zero(), |max, page| max.max(page.frame.size()));
+    let (sheet, scale) = orient(imposition.sheet, page, grid);
+    let cell = Size::new(sheet.x / grid.x as f64, sheet.y / grid.y as f64);
+
+    let order = match imposition.scheme {
+        ImpositionScheme::Booklet => booklet(pages.len()),
+        ImpositionScheme::NUp(_) => n_up(pages.len(), grid.x * grid.y),
+        ImpositionScheme::CutAndStack(_) => cut_and_stack(pages.len(), grid.x * grid.y),
+    };
+
+    // A booklet sheet has two sides: a front and a back.
+    let sheets = order.len() / 2;
+    let mut output = EcoVec::with_capacity(order.len());
+    for (i, side) in order.into_iter().enumerate() {
+        let mut frame = Frame::hard(sheet);
+        for (c, index) in side.into_iter().enumerate() {
+            // Indices past the end are blank padding pages.
+            let Some(page) = pages.get(index) else {
+                continue;
+            };
+
+            let size = page.frame.size() * scale.get();
+            let (col, row) = (c % grid.x, c / grid.x);
+            let origin = Point::new(cell.x * col as f64, cell.y * row as f64);
+            let free = cell - size;
+            let mut offset = Point::new(free.x / 2.0, free.y / 2.0);
+
+            // Booklet pages sit at the spine and inner sheets are shifted
+            // towards it to compensate for creep. The part of a page that is
+            // shifted across the spine is clipped below.
+            if imposition.scheme == ImpositionScheme::Booklet {
+                let sheet = i / 2;
+                let shift = if sheets > 1 {
+                    imposition.creep * (sheet as f64 / (sheets - 1) as f64)
+                } else {
+                    Abs::zero()
+                };
+
+                offset.x = if col == 0 { free.x + shift } else { -shift };
+            }
+
+            // Clip each page to its cell so that it never spills onto a
+            // neighbouring page.
+            let mut inner = Frame::hard(cell);
+            inner.push(offset, FrameItem::Group(place(page, scale)));
+            inner.clip(Curve::rect(cell));
+            frame.push_frame(origin, inner);
+        }
+
+        output.push(Page {
+            frame,
+            fill: Smart::Auto,
+            numbering: None,
+            supplement: Content::empty(),
+            number: 1 + i as u64,
+        });
+    }
+
+    PagedDocument::new(output, document.info().clone())
+}
+
+/// Chooses the orientation of the sheet that fits the pages best and returns
+/// the oriented sheet along with the scale of the pages.
+fn orient(sheet: Size, page: Size, grid: Axes<usize>) -> (Size, Ratio) {
+    let scale = |sheet: Size| {
+        let cell = Size::new(sheet.x / grid.x as f64, sheet.y / grid.y as f64);
+        let fit = |cell: Abs, page: Abs| {
+            if page > Abs::zero() { cell / page } else { 1.0 }
+        };
+        fit(cell.x, page.x).min(fit(cell.y, page.y)).min(1.0)
+    };
+
+    let rotated = Size::new(sheet.y, sheet.x);
+    let (a, b) = (scale(sheet), scale(rotated));
+    if b > a { (rotated, Ratio::new(b)) } else { (sheet, Ratio::new(a)) }
+}
+
+/// Scales a page's frame and paints its background.
+fn place(page: &Page, scale: Ratio) -> GroupItem {
+    let mut frame = page.frame.clone();
+    if let Some(fill) = page.fill_or_transparent() {
+        frame.fill(fill);
+    }
+
+    let mut group = GroupItem::new(frame);
+    group.transform = Transform::scale(scale, scale);
+    group
+}
+
+/// Orders pages for a booklet.
+///
+/// The page count is padded to a multiple of four. The front of the `s`-th
+/// sheet (counted from the outside) holds the pages `n - 1 - 2s` and `2s`,
+/// its back holds `2s + 1` and `n - 2 - 2s`.
+fn booklet(count: usize) -> Vec<Vec<usize>> {
+    let n = count.div_ceil(4) * 4;
+    (0..n / 4)
+        .flat_map(|s| {
+            let front = vec![n - 1 - 2 * s, 2 * s];
+            let back = vec![2 * s + 1, n - 2 - 2 * s];
+            [front, back]
+        })
+        .collect()
+}
+
+/// Orders pages in reading order, `per_sheet` pages at a time.
+fn n_up(count: usize, per_sheet: usize) -> Vec<Vec<usize>> {
+    (0..count.div_ceil(per_sheet))
+        .map(|s| (0..per_sheet).map(|c| s * per_sheet + c).collect())
+        .collect()
+}
+
+/// Orders pages such that each cell position of consecutive sheets forms a
+/// contiguous run of pages.
+fn cut_and_stack(count: usize, per_sheet: usize) -> Vec<Vec<usize>> {
+    let sheets = count.div_ceil(per_sheet);
+    (0..sheets)
+        .map(|s| (0..per_sheet).map(|c| c * sheets + s).collect())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn flatten(order: Vec<Vec<usize>>) -> Vec<usize> {
+        order.into_iter().flatten().collect()
+    }
+
+    #[test]
+    fn test_booklet_order() {
+        let order = flatten(booklet(8));
+        let expected = [7, 0, 1, 6, 5, 2, 3, 4];
+        assert_eq!(order, expected);
+        assert_eq!(flatten(booklet(5)).len(), 8);
+    }
+
+    #[test]
+    fn test_booklet_creep_is_clipped() {
+        let size = Size::new(Abs::pt(100.0), Abs::pt(200.0));
+        let pages = (0..8)
+            .map(|i| Page {
+                frame: Frame::hard(size),
+                fill: Smart::Auto,
+                numbering: None,
+                supplement: Content::empty(),
+                number: 1 + i,
+            })
+            .collect();
+        let document = PagedDocument::new(pages, Default::default());
+        let imposition = Imposition {
+            scheme: ImpositionScheme::Booklet,
+            sheet: Size::new(Abs::pt(200.0), Abs::pt(200.0)),
+            creep: Abs::pt(10.0),
+        };
+
+        let imposed = impose(&document, &imposition);
+        for page in imposed.pages() {
+            for (pos, item) in page.frame.items() {
+                let FrameItem::Group(group) = item else { panic!("expected group") };
+                assert_eq!(group.frame.size(), Size::new(Abs::pt(100.0), size.y));
+                assert!(group.clip.is_some());
+                assert!(pos.x == Abs::zero() || pos.x == Abs::pt(100.0));
+            }
+        }
+    }
+
+    #[test]
+    fn test_cut_and_stack_order() {
+        let order = flatten(cut_and_stack(8, 2));
+        let expected = [0, 4, 1, 5, 2, 6, 3, 7];
+        assert_eq!(order, expected);
+    }
+}
@@ -6,8 +6,8 @@ use typst_library::layout::{
 };
 use typst_library::routines::Pair;
 use typst_library::text::{
-    LinebreakElem, SmartQuoteElem, SmartQuoter, SmartQuotes, SpaceElem, TextElem,
-    is_default_ignorable,
+    LinebreakElem, RubyElem, SmartQuoteElem, SmartQuoter, SmartQuotes, SpaceElem,
+    TextElem, is_default_ignorable,
 };
 use typst_syntax::Span;
 use typst_utils::Numeric;
@@ -142,14 +142,29 @@ pub fn collect<'a>(
         collector.spans.push(1, Span::detached());
     }
 
-    for &(child, styles) in children {
+    // Overhang of a ruby annotation over the next character, which is applied
+    // once that character is reached.
+    let mut overhang: Option<Abs> = None;
+
+    for (i, &(child, styles)) in children.iter().enumerate() {
         let prev_len = collector.full.len();
 
+        if !child.is::<TagElem>()
+            && let Some(amount) = overhang.take()
+        {
+            collector.push_item(Item::Absolute(-amount, true));
+        }
+
         if child.is::<SpaceElem>() {
             collector.push_text(" ", styles);
         } else if let Some(elem) = child.to_packed::<TextElem>() {
             collector.build_text(styles, |full| {
-                let dir = styles.resolve(TextElem::dir);
+                // Vertical text is laid out from left to right before being
+                // rotated.
+                let dir = match styles.resolve(TextElem::dir) {
+                    Dir::TTB => Dir::LTR,
+                    dir => dir,
+                };
                 if dir != config.dir {
                     // Insert "Explicit Directional Embedding".
                     match dir {
@@ -220,6 +235,28 @@ pub fn collect<'a>(
             }
 
             collector.push_item(Item::Skip(POP_ISOLATE));
+        } else if let Some(elem) = child.to_packed::<RubyElem>() {
+            let loc = locator.next(&elem.span());
+            let Ruby { mut frame, before, after } =
+                layout_ruby(elem, engine, loc, styles, region)?;
+
+            // The annotation may only overhang adjacent Chinese or Japanese
+            // characters. The overhang is weak spacing, so that it is trimmed
+            // when the ruby starts or ends a line.
+            let prev = collector.full.chars().rev().find(|&c| !is_default_ignorable(c));
+            if !before.is_zero() && prev.is_some_and(is_of_cj_script) {
+                collector.push_item(Item::Absolute(-before, true));
+            }
+
+            frame.modify(&FrameModifiers::get_in(styles));
+            apply_shift(&engine.world, &mut frame, styles);
+            collector.push_item(Item::Frame(frame));
+
+            if !after.is_zero()
+                && next_char(&children[i + 1..]).is_some_and(is_of_cj_script)
+            {
+                overhang = Some(after);
+            }
         } else if let Some(elem) = child.to_packed::<BoxElem>() {
             let loc = locator.next(&elem.span());
             if let Sizing::Fr(v) = elem.width.get(styles) {
@@ -250,6 +287,12 @@ pub fn collect<'a>(
     Ok((collector.full, collector.segments, collector.spans))
 }
 
+/// The first character of the text that follows, skipping over tags.
+fn next_char(children: &[Pair]) -> Option<char> {
+    let (child, _) = children.iter().find(|(child, _)| !child.is::<TagElem>())?;
+    child.to_packed::<TextElem>()?.text.chars().next()
+}
+
 /// Collects segments.
 struct Collector<'a> {
     full: String,
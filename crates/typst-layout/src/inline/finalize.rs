@@ -29,7 +29,10 @@ pub fn finalize(
     // Stack the lines into one frame per region.
     lines
         .iter()
-        .map(|line| commit(engine, p, line, width, region.y, locator))
+        .map(|line| {
+            engine.check_cancelled()?;
+            commit(engine, p, line, width, region.y, locator)
+        })
         .collect::<SourceResult<_>>()
         .map(Fragment::frames)
 }
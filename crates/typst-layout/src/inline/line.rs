@@ -12,6 +12,7 @@ use typst_utils::Numeric;
 use super::*;
 use crate::inline::linebreak::Trim;
 use crate::inline::shaping::Adjustability;
+use crate::inline::vertical::orient;
 use crate::modifiers::layout_and_modify;
 
 const SHY: char = '\u{ad}';
@@ -20,6 +21,10 @@ const EN_DASH: char = '–';
 const EM_DASH: char = '—';
 const LINE_SEPARATOR: char = '\u{2028}'; // We use LS to distinguish justified breaks.
 
+/// How far a single joint may be elongated with kashidas, relative to the
+/// font size. Space beyond that is distributed between words instead.
+const MAX_KASHIDA: Em = Em::new(1.0);
+
 /// A layouted line, consisting of a sequence of layouted inline items that are
 /// mostly borrowed from the preparation phase. This type enables you to measure
 /// the size of a line in a range before committing to building the line's
@@ -52,6 +57,30 @@ impl Line<'_> {
         }
     }
 
+    /// How many joints in Arabic words can be elongated with kashidas.
+    pub fn kashida_points(&self) -> usize {
+        self.items
+            .iter()
+            .filter_map(Item::text)
+            .map(ShapedText::kashida_points)
+            .sum()
+    }
+
+    /// How far each joint in the line may be elongated with kashidas.
+    ///
+    /// All joints are elongated by the same amount, so this is limited by the
+    /// joint with the smallest font size.
+    pub fn kashida_limit(&self) -> Abs {
+        self.items
+            .iter()
+            .filter_map(Item::text)
+            .flat_map(|shaped| shaped.glyphs.iter())
+            .filter(|glyph| glyph.is_kashida_point)
+            .map(|glyph| MAX_KASHIDA.at(glyph.size))
+            .min()
+            .unwrap_or_default()
+    }
+
     /// How many glyphs are in the text where we can insert additional
     /// space when encountering underfull lines.
     pub fn justifiables(&self) -> usize {
@@ -529,6 +558,7 @@ pub fn commit(
     let fr = line.fr();
     let mut justification_ratio = 0.0;
     let mut extra_justification = Abs::zero();
+    let mut kashida_justification = Abs::zero();
 
     let shrinkability = line.shrinkability();
     let stretchability = line.stretchability();
@@ -543,6 +573,17 @@ pub fn commit(
             remaining = (remaining - stretchability).max(Abs::zero());
         }
 
+        // Elongate Arabic words with kashidas before widening the spaces. Each
+        // joint is only elongated up to a limit, so that a line with few
+        // joints doesn't end up with a single, overly long connection.
+        let kashida_points = if p.config.kashida { line.kashida_points() } else { 0 };
+        if kashida_points > 0 && remaining > Abs::zero() {
+            kashida_justification =
+                (remaining / kashida_points as f64).min(line.kashida_limit());
+            remaining -= kashida_justification * kashida_points as f64;
+            remaining.set_max(Abs::zero());
+        }
+
         let justifiables = line.justifiables();
         if justifiables > 0 && remaining > Abs::zero() {
             // Underfull line, distribute the extra space.
@@ -583,12 +624,16 @@ pub fn commit(
                 }
             }
             Item::Text(shaped) => {
-                let frame = shaped.build(
+                let mut frame = shaped.build(
                     engine,
                     &p.spans,
                     justification_ratio,
                     extra_justification,
+                    kashida_justification,
                 );
+                if p.config.vertical {
+                    orient(&mut frame, shaped.styles);
+                }
                 push(&mut offset, frame, idx);
             }
             Item::Frame(frame) => {
@@ -6,20 +6,22 @@ mod finalize;
 mod line;
 mod linebreak;
 mod prepare;
+mod ruby;
 mod shaping;
+mod vertical;
 
 pub use self::box_::layout_box;
 pub use self::shaping::{SharedShapingContext, create_shape_plan, get_font_and_covers};
 
 use comemo::{Track, Tracked, TrackedMut};
 use typst_library::diag::SourceResult;
-use typst_library::engine::{Engine, Route, Sink, Traced};
+use typst_library::engine::{Cancellation, Engine, Fuel, Route, Sink, Traced};
 use typst_library::foundations::{Packed, Smart, StyleChain};
 use typst_library::introspection::{Introspector, Locator, LocatorLink, SplitLocator};
 use typst_library::layout::{Abs, AlignElem, Dir, FixedAlignment, Fragment, Size};
 use typst_library::model::{
-    EnumElem, FirstLineIndent, JustificationLimits, Linebreaks, ListElem, ParElem,
-    ParLine, ParLineMarker, TermsElem,
+    EnumElem, FirstLineIndent, JustificationLimits, Justify, Linebreaks, ListElem,
+    ParElem, ParLine, ParLineMarker, TermsElem,
 };
 use typst_library::routines::{Arenas, Pair, RealizationKind};
 use typst_library::text::{Costs, Lang, TextElem};
@@ -32,6 +34,7 @@ use self::finalize::finalize;
 use self::line::{Line, apply_shift, commit, line};
 use self::linebreak::{Breakpoint, linebreak};
 use self::prepare::{Preparation, prepare};
+use self::ruby::{Ruby, layout_ruby};
 use self::shaping::{
     BEGIN_PUNCT_PAT, END_PUNCT_PAT, ShapedGlyph, ShapedText, cjk_punct_style,
     is_of_cj_script, shape_range,
@@ -56,6 +59,8 @@ pub fn layout_par(
         engine.library,
         engine.introspector.into_raw(),
         engine.traced,
+        engine.cancellation,
+        engine.fuel,
         TrackedMut::reborrow_mut(&mut engine.sink),
         engine.route.track(),
         locator.track(),
@@ -75,6 +80,8 @@ fn layout_par_impl(
     library: &LazyHash<Library>,
     introspector: Tracked<dyn Introspector + '_>,
     traced: Tracked<Traced>,
+    cancellation: Tracked<Cancellation>,
+    fuel: Tracked<Fuel>,
     sink: TrackedMut<Sink>,
     route: Tracked<Route>,
     locator: Tracked<Locator>,
@@ -91,6 +98,8 @@ fn layout_par_impl(
         world,
         introspector,
         traced,
+        cancellation,
+        fuel,
         sink,
         route: Route::extend(route),
     };
@@ -163,6 +172,10 @@ fn layout_inline_impl<'a>(
     // Prepare configuration that is shared across the whole inline layout.
     let config = configuration(base, children, shared, par);
 
+    // Vertical lines run along the height of the region. They are laid out
+    // like horizontal ones and rotated into columns at the end.
+    let region = if config.vertical { Size::new(region.y, region.x) } else { region };
+
     // Collect all text into one string for BiDi analysis.
     let (text, segments, spans) = collect(children, engine, locator, &config, region)?;
 
@@ -171,10 +184,19 @@ fn layout_inline_impl<'a>(
     let p = prepare(engine, &config, &text, segments, spans)?;
 
     // Break the text into lines.
+    engine.check_cancelled()?;
     let lines = linebreak(engine, &p, region.x - config.hanging_indent);
 
     // Turn the selected lines into frames.
-    finalize(engine, &p, &lines, region, expand, locator)
+    let fragment = finalize(engine, &p, &lines, region, expand, locator)?;
+
+    // Stack the lines of vertical text into columns.
+    if config.vertical {
+        let leading = shared.resolve(ParElem::leading);
+        return Ok(vertical::stack(fragment, region.y, leading, expand));
+    }
+
+    Ok(fragment)
 }
 
 /// Determine the inline layout's configuration.
@@ -184,12 +206,15 @@ fn configuration(
     shared: StyleChain,
     situation: Option<ParSituation>,
 ) -> Config {
-    let justify = base.justify;
+    let justify = base.justify.is_on();
     let font_size = shared.resolve(TextElem::size);
     let dir = shared.resolve(TextElem::dir);
+    let vertical = dir == Dir::TTB;
+    let dir = if vertical { Dir::LTR } else { dir };
 
     Config {
         justify,
+        kashida: base.justify == Justify::Kashida,
         justification_limits: shared.get(ParElem::justification_limits),
         linebreaks: base.linebreaks.unwrap_or_else(|| {
             if justify { Linebreaks::Optimized } else { Linebreaks::Simple }
@@ -232,6 +257,7 @@ fn configuration(
         align: shared.get(AlignElem::alignment).fix(dir).x,
         font_size,
         dir,
+        vertical,
         hyphenate: shared_get(children, shared, |s| s.get(TextElem::hyphenate))
             .map(|uniform| uniform.unwrap_or(justify)),
         lang: shared_get(children, shared, |s| s.get(TextElem::lang)),
@@ -259,7 +285,7 @@ pub enum ParSituation {
 
 /// Raw values from a `ParElem` or style chain. Used to initialize a [`Config`].
 struct ConfigBase {
-    justify: bool,
+    justify: Justify,
     linebreaks: Smart<Linebreaks>,
     first_line_indent: FirstLineIndent,
     hanging_indent: Abs,
@@ -269,6 +295,8 @@ struct ConfigBase {
 struct Config {
     /// Whether to justify text.
     justify: bool,
+    /// Whether to justify Arabic text with kashidas.
+    kashida: bool,
     /// Settings for justification.
     justification_limits: JustificationLimits,
     /// How to determine line breaks.
@@ -283,8 +311,11 @@ struct Config {
     align: FixedAlignment,
     /// The text size.
     font_size: Abs,
-    /// The dominant direction.
+    /// The dominant direction. For vertical text, this is left-to-right.
     dir: Dir,
+    /// Whether the text is set vertically, with lines running from top to
+    /// bottom and progressing from right to left.
+    vertical: bool,
     /// A uniform hyphenation setting (only `Some(_)` if it's the same for all
     /// children, otherwise `None`).
     hyphenate: Option<bool>,
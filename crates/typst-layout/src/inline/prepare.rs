@@ -92,7 +92,15 @@ pub fn prepare<'a>(
 
         match segment {
             Segment::Text(_, styles) => {
-                shape_range(&mut items, engine, text, &bidi, range, styles);
+                shape_range(
+                    &mut items,
+                    engine,
+                    text,
+                    &bidi,
+                    range,
+                    styles,
+                    config.vertical,
+                );
             }
             Segment::Item(item) => items.push((range, item)),
         }
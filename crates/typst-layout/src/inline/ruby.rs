@@ -0,0 +1,79 @@
+use typst_library::diag::SourceResult;
+use typst_library::engine::Engine;
+use typst_library::foundations::{Packed, Resolve, StyleChain};
+use typst_library::introspection::Locator;
+use typst_library::layout::{
+    Abs, Axes, FixAlignment, Frame, OuterVAlignment, Point, Region, Size,
+};
+use typst_library::pdf::PdfMarkerTag;
+use typst_library::text::{RubyElem, TextElem};
+
+/// A laid out ruby annotation.
+pub struct Ruby {
+    /// The base together with its annotation.
+    pub frame: Frame,
+    /// How far the annotation may overhang the preceding character.
+    pub before: Abs,
+    /// How far the annotation may overhang the following character.
+    pub after: Abs,
+}
+
+/// Lays out a ruby annotation as an atomic inline unit.
+///
+/// The resulting frame is as wide as the wider of the base and the annotation.
+/// Whether the annotation actually overhangs the adjacent characters depends on
+/// them and on where the line breaks, so this is up to the caller.
+#[typst_macros::time(name = "ruby", span = elem.span())]
+pub fn layout_ruby(
+    elem: &Packed<RubyElem>,
+    engine: &mut Engine,
+    locator: Locator,
+    styles: StyleChain,
+    region: Size,
+) -> SourceResult<Ruby> {
+    let mut locator = locator.split();
+    let pod = Region::new(region, Axes::splat(false));
+    let size = elem.size.get(styles);
+
+    let base = PdfMarkerTag::RubyBase(elem.base.clone());
+    let base = crate::layout_frame(engine, &base, locator.next(&()), styles, pod)?;
+
+    let annotation = PdfMarkerTag::RubyText(elem.annotation.clone());
+    let annotation = annotation.set(TextElem::size, size);
+    let annotation =
+        crate::layout_frame(engine, &annotation, locator.next(&()), styles, pod)?;
+
+    // Align the narrower of the two with the wider one.
+    let width = base.width().max(annotation.width());
+    let align = elem.align.get(styles).fix(styles.resolve(TextElem::dir));
+    let base_x = align.position(width - base.width());
+    let annotation_x = align.position(width - annotation.width());
+
+    // A wider annotation may overhang the adjacent characters by up to one
+    // annotation character on each side.
+    let mut before = Abs::zero();
+    let mut after = Abs::zero();
+    if elem.overhang.get(styles) && annotation.width() > base.width() {
+        let limit = size.0.resolve(styles);
+        before = base_x.min(limit);
+        after = (width - base_x - base.width()).min(limit);
+    }
+
+    let height = base.height() + annotation.height();
+    let mut frame = Frame::soft(Size::new(width, height));
+    match elem.position.get(styles) {
+        OuterVAlignment::Top => {
+            frame.set_baseline(annotation.height() + base.baseline());
+            frame.push_frame(Point::new(base_x, annotation.height()), base);
+            frame.push_frame(Point::with_x(annotation_x), annotation);
+        }
+        OuterVAlignment::Bottom => {
+            frame.set_baseline(base.baseline());
+            let base_height = base.height();
+            frame.push_frame(Point::with_x(base_x), base);
+            frame.push_frame(Point::new(annotation_x, base_height), annotation);
+        }
+    }
+
+    Ok(Ruby { frame, before, after })
+}
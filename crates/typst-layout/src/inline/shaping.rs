@@ -15,7 +15,8 @@ use typst_library::layout::{Abs, Dir, Em, Frame, FrameItem, Point, Rel, Size};
 use typst_library::model::{JustificationLimits, ParElem};
 use typst_library::text::{
     Font, FontFamily, FontVariant, Glyph, Lang, Region, ShiftSettings, TextEdgeBounds,
-    TextElem, TextItem, families, features, is_default_ignorable, language, variant,
+    TextElem, TextItem, TextOrientation, families, features, is_default_ignorable,
+    language, variant,
 };
 use typst_utils::SliceExt;
 use unicode_bidi::{BidiInfo, Level as BidiLevel};
@@ -50,6 +51,8 @@ pub struct ShapedText<'a> {
     pub styles: StyleChain<'a>,
     /// The font variant.
     pub variant: FontVariant,
+    /// Whether the text is part of a vertical paragraph.
+    pub vertical: bool,
     /// The shaped glyphs.
     pub glyphs: Glyphs<'a>,
 }
@@ -161,6 +164,9 @@ pub struct ShapedGlyph {
     pub c: char,
     /// Whether this glyph is justifiable for CJK scripts.
     pub is_justifiable: bool,
+    /// Whether a kashida may be inserted after this glyph (in logical order)
+    /// to justify Arabic text.
+    pub is_kashida_point: bool,
     /// The script of the glyph.
     pub script: Script,
 }
@@ -327,6 +333,7 @@ impl<'a> ShapedText<'a> {
         spans: &SpanMapper,
         justification_ratio: f64,
         extra_justification: Abs,
+        kashida_justification: Abs,
     ) -> Frame {
         let (top, bottom) = self.measure(engine);
         let size = Size::new(self.width(), top + bottom);
@@ -355,83 +362,110 @@ impl<'a> ShapedText<'a> {
             }
 
             let pos = Point::new(offset, top + shift - y_offset.at(size));
-            let glyphs: Vec<Glyph> = group
-                .iter()
-                .map(|shaped: &ShapedGlyph| {
-                    // Whether the glyph is _not_ trimmed end-of-line
-                    // whitespace. Trimmed whitespace has its advance width and
-                    // offset zeroed out and is not taken into account for
-                    // justification.
-                    let kept = self.glyphs.kept.contains(&i);
-
-                    let (x_advance, x_offset) = if kept {
-                        let adjustability_left = if justification_ratio < 0.0 {
-                            shaped.shrinkability().0
-                        } else {
-                            shaped.stretchability().0
-                        };
-                        let adjustability_right = if justification_ratio < 0.0 {
-                            shaped.shrinkability().1
-                        } else {
-                            shaped.stretchability().1
-                        };
-
-                        let justification_left = adjustability_left * justification_ratio;
-                        let mut justification_right =
-                            adjustability_right * justification_ratio;
-                        if shaped.is_justifiable() {
-                            justification_right +=
-                                Em::from_abs(extra_justification, glyph_size)
-                        }
-
-                        frame.size_mut().x += justification_left.at(glyph_size)
-                            + justification_right.at(glyph_size);
-
-                        (
-                            shaped.x_advance + justification_left + justification_right,
-                            shaped.x_offset + justification_left,
-                        )
+            let mut glyphs = Vec::with_capacity(group.len());
+            for shaped in group {
+                // Whether the glyph is _not_ trimmed end-of-line
+                // whitespace. Trimmed whitespace has its advance width and
+                // offset zeroed out and is not taken into account for
+                // justification.
+                let kept = self.glyphs.kept.contains(&i);
+
+                let (x_advance, x_offset) = if kept {
+                    let adjustability_left = if justification_ratio < 0.0 {
+                        shaped.shrinkability().0
                     } else {
-                        (Em::zero(), Em::zero())
+                        shaped.stretchability().0
                     };
-                    i += 1;
-
-                    // We may not be able to reach the offset completely if
-                    // it exceeds u16, but better to have a roughly correct
-                    // span offset than nothing.
-                    let mut span = spans.span_at(shaped.range.start);
-                    span.1 = span.1.saturating_add(span_offset.saturating_as());
-
-                    // |<---- a Glyph ---->|
-                    //  -->|ShapedGlyph|<--
-                    // +---+-----------+---+
-                    // |   |  *********|   |
-                    // |   |  *        |   |
-                    // |   |  *    ****|   |
-                    // |   |  *       *|   |
-                    // |   |  *********|   |
-                    // +---+--+--------+---+
-                    //   A   B     C     D
-                    // Note A, B, D could be positive, zero, or negative.
-                    // A: justification_left
-                    // B: ShapedGlyph's x_offset
-                    //    (though a small part of the glyph may go inside B)
-                    // B+C: ShapedGlyph's x_advance
-                    // D: justification_right
-                    // A+B: Glyph's x_offset
-                    // A+B+C+D: Glyph's x_advance
-                    Glyph {
-                        id: shaped.glyph_id,
-                        x_advance,
-                        x_offset,
-                        y_advance: Em::zero(),
-                        y_offset: Em::zero(),
-                        range: (shaped.range.start - range.start).saturating_as()
-                            ..(shaped.range.end - range.start).saturating_as(),
-                        span,
+                    let adjustability_right = if justification_ratio < 0.0 {
+                        shaped.shrinkability().1
+                    } else {
+                        shaped.stretchability().1
+                    };
+
+                    let justification_left = adjustability_left * justification_ratio;
+                    let mut justification_right =
+                        adjustability_right * justification_ratio;
+                    if shaped.is_justifiable() {
+                        justification_right +=
+                            Em::from_abs(extra_justification, glyph_size)
                     }
-                })
-                .collect();
+
+                    frame.size_mut().x += justification_left.at(glyph_size)
+                        + justification_right.at(glyph_size);
+
+                    (
+                        shaped.x_advance + justification_left + justification_right,
+                        shaped.x_offset + justification_left,
+                    )
+                } else {
+                    (Em::zero(), Em::zero())
+                };
+                i += 1;
+
+                // We may not be able to reach the offset completely if
+                // it exceeds u16, but better to have a roughly correct
+                // span offset than nothing.
+                let mut span = spans.span_at(shaped.range.start);
+                span.1 = span.1.saturating_add(span_offset.saturating_as());
+
+                // |<---- a Glyph ---->|
+                //  -->|ShapedGlyph|<--
+                // +---+-----------+---+
+                // |   |  *********|   |
+                // |   |  *        |   |
+                // |   |  *    ****|   |
+                // |   |  *       *|   |
+                // |   |  *********|   |
+                // +---+--+--------+---+
+                //   A   B     C     D
+                // Note A, B, D could be positive, zero, or negative.
+                // A: justification_left
+                // B: ShapedGlyph's x_offset
+                //    (though a small part of the glyph may go inside B)
+                // B+C: ShapedGlyph's x_advance
+                // D: justification_right
+                // A+B: Glyph's x_offset
+                // A+B+C+D: Glyph's x_advance
+                let mut glyph = Glyph {
+                    id: shaped.glyph_id,
+                    x_advance,
+                    x_offset,
+                    y_advance: Em::zero(),
+                    y_offset: Em::zero(),
+                    range: (shaped.range.start - range.start).saturating_as()
+                        ..(shaped.range.end - range.start).saturating_as(),
+                    span,
+                };
+
+                // Elongate the joint after the glyph with kashidas. In
+                // right-to-left text, the joint is visually to the left
+                // of the glyph.
+                if !kept
+                    || !shaped.is_kashida_point
+                    || kashida_justification <= Abs::zero()
+                {
+                    glyphs.push(glyph);
+                    continue;
+                }
+
+                frame.size_mut().x += kashida_justification;
+                let amount = Em::from_abs(kashida_justification, glyph_size);
+                match kashidas(&font, amount, &glyph) {
+                    Some(kashidas) if !self.dir.is_positive() => {
+                        glyphs.extend(kashidas);
+                        glyphs.push(glyph);
+                    }
+                    Some(kashidas) => {
+                        glyphs.push(glyph);
+                        glyphs.extend(kashidas);
+                    }
+                    // Without a tatweel in the font, widen the glyph.
+                    None => {
+                        glyph.x_advance += amount;
+                        glyphs.push(glyph);
+                    }
+                }
+            }
 
             let item = TextItem {
                 font,
@@ -513,6 +547,11 @@ impl<'a> ShapedText<'a> {
         self.glyphs.iter().filter(|g| g.is_justifiable()).count()
     }
 
+    /// How many glyphs are followed by a joint where a kashida may be inserted.
+    pub fn kashida_points(&self) -> usize {
+        self.glyphs.iter().filter(|g| g.is_kashida_point).count()
+    }
+
     /// Whether the last glyph is a CJK character which should not be justified
     /// on line end.
     pub fn cjk_justifiable_at_last(&self) -> bool {
@@ -555,6 +594,7 @@ impl<'a> ShapedText<'a> {
                 region: self.region,
                 styles: self.styles,
                 variant: self.variant,
+                vertical: self.vertical,
                 glyphs: Glyphs::from_slice(glyphs),
             }
         } else {
@@ -566,6 +606,7 @@ impl<'a> ShapedText<'a> {
                 self.dir,
                 self.lang,
                 self.region,
+                self.vertical,
             )
         }
     }
@@ -615,6 +656,7 @@ impl<'a> ShapedText<'a> {
                 region: base.region,
                 styles: base.styles,
                 variant: base.variant,
+                vertical: base.vertical,
                 glyphs: Glyphs::from_vec(vec![ShapedGlyph {
                     font,
                     glyph_id: glyph_id.0,
@@ -627,6 +669,7 @@ impl<'a> ShapedText<'a> {
                     safe_to_break: true,
                     c,
                     is_justifiable: false,
+                    is_kashida_point: false,
                     script: Script::Common,
                 }]),
             })
@@ -721,14 +764,23 @@ pub fn shape_range<'a>(
     bidi: &BidiInfo<'a>,
     range: Range,
     styles: StyleChain<'a>,
+    vertical: bool,
 ) {
     let script = styles.get(TextElem::script);
     let lang = styles.get(TextElem::lang);
     let region = styles.get(TextElem::region);
     let mut process = |range: Range, level: BidiLevel| {
         let dir = if level.is_ltr() { Dir::LTR } else { Dir::RTL };
-        let shaped =
-            shape(engine, range.start, &text[range.clone()], styles, dir, lang, region);
+        let shaped = shape(
+            engine,
+            range.start,
+            &text[range.clone()],
+            styles,
+            dir,
+            lang,
+            region,
+            vertical,
+        );
         items.push((range, Item::Text(shaped)));
     };
 
@@ -787,9 +839,19 @@ fn shape<'a>(
     dir: Dir,
     lang: Lang,
     region: Option<Region>,
+    vertical: bool,
 ) -> ShapedText<'a> {
     let size = styles.resolve(TextElem::size);
     let shift_settings = styles.get(TextElem::shift_settings);
+    let mut features = features(styles);
+
+    // Use the vertical forms of punctuation and brackets for text that is
+    // set upright. This mirrors the condition under which `orient` rotates
+    // glyphs, so that text that stays horizontal keeps its normal forms.
+    if vertical && styles.get(TextElem::orientation) != TextOrientation::Sideways {
+        features.push(Feature::new(Tag::from_bytes(b"vert"), 1, ..));
+    }
+
     let mut ctx = ShapingContext {
         world: engine.world,
         size,
@@ -797,7 +859,7 @@ fn shape<'a>(
         used: vec![],
         styles,
         variant: variant(styles),
-        features: features(styles),
+        features,
         fallback: styles.get(TextElem::fallback),
         dir,
         shift_settings,
@@ -809,6 +871,7 @@ fn shape<'a>(
 
     track_and_space(&mut ctx);
     calculate_adjustability(&mut ctx, lang, region);
+    mark_kashida_points(&mut ctx, text, base);
 
     #[cfg(debug_assertions)]
     assert_all_glyphs_in_range(&ctx.glyphs, text, base..(base + text.len()));
@@ -823,6 +886,7 @@ fn shape<'a>(
         region,
         styles,
         variant: ctx.variant,
+        vertical,
         glyphs: Glyphs::from_vec(ctx.glyphs),
     }
 }
@@ -1084,6 +1148,7 @@ fn shape_segment<'a>(
                     x_advance,
                     Adjustability::default().stretchability,
                 ),
+                is_kashida_point: false,
                 script,
             });
         } else {
@@ -1235,6 +1300,7 @@ fn shape_tofus(ctx: &mut ShapingContext, base: usize, text: &str, font: Font) {
                 x_advance,
                 Adjustability::default().stretchability,
             ),
+            is_kashida_point: false,
             script,
         });
     };
@@ -1315,6 +1381,106 @@ fn calculate_adjustability(ctx: &mut ShapingContext, lang: Lang, region: Option<
     }
 }
 
+/// Creates tatweel glyphs that elongate the joint after the given glyph by the
+/// given amount. Returns `None` if the font has no tatweel.
+fn kashidas(font: &Font, amount: Em, after: &Glyph) -> Option<Vec<Glyph>> {
+    let id = font.ttf().glyph_index('\u{0640}')?.0;
+    let advance = font.x_advance(id).filter(|advance| *advance > Em::zero())?;
+
+    // Overlapping tatweels join seamlessly, so we use just enough of them to
+    // cover the amount and shorten their advances evenly.
+    let count = (amount / advance).ceil().max(1.0);
+    let glyph = Glyph {
+        id,
+        x_advance: amount / count,
+        x_offset: Em::zero(),
+        y_advance: Em::zero(),
+        y_offset: Em::zero(),
+        range: after.range.start..after.range.start,
+        span: after.span,
+    };
+    Some(vec![glyph; count as usize])
+}
+
+/// Marks the glyphs after which a kashida may be inserted to justify Arabic
+/// text. In each word, this is the last joint between two connected letters.
+fn mark_kashida_points(ctx: &mut ShapingContext, text: &str, base: usize) {
+    // Visit the glyphs in logical order.
+    let mut indices: Vec<usize> = (0..ctx.glyphs.len()).collect();
+    if !ctx.dir.is_positive() {
+        indices.reverse();
+    }
+
+    let mut candidate: Option<usize> = None;
+    for i in indices {
+        let glyph = &ctx.glyphs[i];
+        if glyph.is_space() {
+            if let Some(k) = candidate.take() {
+                ctx.glyphs[k].is_kashida_point = true;
+            }
+            continue;
+        }
+
+        let next = text.get(glyph.range.end - base..).and_then(|s| s.chars().next());
+        if glyph.script == Script::Arabic
+            && joins_following(glyph.c)
+            && next.is_some_and(joins_preceding)
+        {
+            // For clusters with multiple glyphs, this ends up at the visually
+            // first glyph of the cluster in right-to-left text.
+            candidate = Some(i);
+        }
+    }
+
+    if let Some(k) = candidate {
+        ctx.glyphs[k].is_kashida_point = true;
+    }
+}
+
+/// Whether an Arabic letter connects to the following letter.
+fn joins_following(c: char) -> bool {
+    matches!(
+        c,
+        '\u{0626}'
+            | '\u{0628}'
+            | '\u{062A}'..='\u{062E}'
+            | '\u{0633}'..='\u{063F}'
+            | '\u{0640}'..='\u{0647}'
+            | '\u{0649}'..='\u{064A}'
+            | '\u{066E}'..='\u{066F}'
+            | '\u{0678}'..='\u{0687}'
+            | '\u{069A}'..='\u{06BF}'
+            | '\u{06C1}'..='\u{06C2}'
+            | '\u{06CC}'
+            | '\u{06CE}'
+            | '\u{06D0}'..='\u{06D1}'
+            | '\u{06FA}'..='\u{06FC}'
+            | '\u{06FF}'
+    )
+}
+
+/// Whether an Arabic letter connects to the preceding letter.
+fn joins_preceding(c: char) -> bool {
+    joins_following(c)
+        || matches!(
+            c,
+            '\u{0622}'..='\u{0625}'
+                | '\u{0627}'
+                | '\u{0629}'
+                | '\u{062F}'..='\u{0632}'
+                | '\u{0648}'
+                | '\u{0671}'..='\u{0673}'
+                | '\u{0675}'..='\u{0677}'
+                | '\u{0688}'..='\u{0699}'
+                | '\u{06C0}'
+                | '\u{06C3}'..='\u{06CB}'
+                | '\u{06CD}'
+                | '\u{06CF}'
+                | '\u{06D2}'..='\u{06D3}'
+                | '\u{06D5}'
+        )
+}
+
 /// Difference between non-breaking and normal space.
 fn nbsp_delta(font: &Font) -> Option<Em> {
     let space = font.ttf().glyph_index(' ')?.0;
@@ -0,0 +1,251 @@
+use std::ops::Range;
+
+use typst_library::foundations::StyleChain;
+use typst_library::layout::{
+    Abs, Angle, Fragment, Frame, FrameItem, GroupItem, Point, Ratio, Size, Transform,
+};
+use typst_library::text::{Glyph, TextElem, TextItem, TextOrientation};
+use typst_utils::Numeric;
+
+/// Turns horizontally laid out lines into vertical columns.
+///
+/// Each line is rotated by 90 degrees clockwise. The resulting columns are
+/// placed from right to left with `leading` between them. Whenever the columns
+/// would exceed the available `width`, a new band of columns is started. Each
+/// band becomes one frame of the resulting fragment.
+pub fn stack(lines: Fragment, width: Abs, leading: Abs, expand: bool) -> Fragment {
+    let mut bands = vec![];
+    let mut columns: Vec<Frame> = vec![];
+    let mut used = Abs::zero();
+
+    for line in lines {
+        let column = rotate(line);
+        if !columns.is_empty() && !(used + leading + column.width()).fits(width) {
+            let full = std::mem::take(&mut columns);
+            bands.push(band(full, width, used, leading, expand));
+            used = Abs::zero();
+        }
+
+        if !columns.is_empty() {
+            used += leading;
+        }
+
+        used += column.width();
+        columns.push(column);
+    }
+
+    if !columns.is_empty() || bands.is_empty() {
+        bands.push(band(columns, width, used, leading, expand));
+    }
+
+    Fragment::frames(bands)
+}
+
+/// Rotates a line by 90 degrees clockwise into a column.
+fn rotate(line: Frame) -> Frame {
+    let size = line.size();
+    let mut column = Frame::soft(Size::new(size.y, size.x));
+    let mut group = GroupItem::new(line);
+    group.transform = Transform::translate(size.y, Abs::zero())
+        .pre_concat(Transform::rotate(Angle::deg(90.0)));
+    column.push(Point::zero(), FrameItem::Group(group));
+    column
+}
+
+/// Places columns from right to left into one band.
+fn band(columns: Vec<Frame>, width: Abs, used: Abs, leading: Abs, expand: bool) -> Frame {
+    let width = if expand && width.is_finite() { width } else { used };
+    let height = columns.iter().map(Frame::height).max().unwrap_or_default();
+    let mut band = Frame::soft(Size::new(width, height));
+
+    let mut cursor = width;
+    for column in columns {
+        cursor -= column.width();
+        band.push_frame(Point::with_x(cursor), column);
+        cursor -= leading;
+    }
+
+    band
+}
+
+/// Orients the glyphs of a frame built from shaped text for vertical layout.
+///
+/// Since vertical lines are laid out horizontally and then rotated, all glyphs
+/// end up sideways by default. Glyphs that should be upright are counter-rotated
+/// around the center of their em box. Short runs of digits are counter-rotated
+/// as a whole and compressed to the width of one em (tate-chū-yoko).
+pub fn orient(frame: &mut Frame, styles: StyleChain) {
+    let orientation = styles.get(TextElem::orientation);
+    if orientation == TextOrientation::Sideways {
+        return;
+    }
+
+    let combine = styles.get(TextElem::combine_digits);
+    let items: Vec<_> = frame.items().cloned().collect();
+    frame.clear();
+
+    for (pos, item) in items {
+        match item {
+            FrameItem::Text(text) => orient_text(frame, pos, &text, orientation, combine),
+            item => frame.push(pos, item),
+        }
+    }
+}
+
+/// How a run of glyphs is oriented.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum Run {
+    /// The run stays sideways.
+    Sideways,
+    /// The run is a single upright cluster.
+    Upright,
+    /// The run is a combination of digits that is set upright as one unit.
+    Combined,
+}
+
+/// Splits a text item into runs and pushes them with the right orientation.
+fn orient_text(
+    frame: &mut Frame,
+    pos: Point,
+    text: &TextItem,
+    orientation: TextOrientation,
+    combine: usize,
+) {
+    let metrics = text.font.metrics();
+    let center = (metrics.ascender + metrics.descender).at(text.size) / 2.0;
+
+    let mut x = pos.x;
+    let mut start = 0;
+    while start < text.glyphs.len() {
+        let (run, end) = next_run(text, start, orientation, combine);
+        let part = slice(text, start..end);
+        let width = part.width();
+        let item = FrameItem::Text(part);
+
+        if run == Run::Sideways {
+            frame.push(Point::new(x, pos.y), item);
+        } else {
+            // Squeeze combined digits into one em across the column.
+            let scale = if run == Run::Combined && width > text.size {
+                text.size / width
+            } else {
+                1.0
+            };
+
+            let mut inner = Frame::soft(Size::new(width, Abs::zero()));
+            inner.push(Point::zero(), item);
+
+            let (cx, cy) = (width / 2.0, -center);
+            let mut group = GroupItem::new(inner);
+            group.transform = Transform::rotate_at(Angle::deg(-90.0), cx, cy)
+                .pre_concat(Transform::scale_at(Ratio::new(scale), Ratio::one(), cx, cy));
+            frame.push(Point::new(x, pos.y), FrameItem::Group(group));
+        }
+
+        x += width;
+        start = end;
+    }
+}
+
+/// Determines the orientation of the run starting at the given glyph and the
+/// index of the glyph after it.
+fn next_run(
+    text: &TextItem,
+    start: usize,
+    orientation: TextOrientation,
+    combine: usize,
+) -> (Run, usize) {
+    let glyphs = &text.glyphs;
+    let c = |glyph: &Glyph| {
+        text.text[usize::from(glyph.range.start)..]
+            .chars()
+            .next()
+            .unwrap_or(' ')
+    };
+
+    let digits = glyphs[start..].iter().take_while(|g| c(g).is_ascii_digit()).count();
+    if digits > 0 {
+        if digits <= combine {
+            return (Run::Combined, start + digits);
+        } else if orientation == TextOrientation::Mixed {
+            return (Run::Sideways, start + digits);
+        }
+    }
+
+    let upright = |glyph: &Glyph| match orientation {
+        TextOrientation::Upright => !c(glyph).is_whitespace(),
+        _ => is_upright(c(glyph)),
+    };
+
+    let first = &glyphs[start];
+    if upright(first) {
+        let len = glyphs[start..].iter().take_while(|g| g.range == first.range).count();
+        return (Run::Upright, start + len);
+    }
+
+    let len = glyphs[start..]
+        .iter()
+        .take_while(|g| !upright(g) && !c(g).is_ascii_digit())
+        .count();
+    (Run::Sideways, start + len.max(1))
+}
+
+/// Extracts the given glyphs of a text item into a new one.
+fn slice(text: &TextItem, glyphs: Range<usize>) -> TextItem {
+    let glyphs = &text.glyphs[glyphs];
+    let start = glyphs.iter().map(|g| g.range.start).min().unwrap_or(0);
+    let end = glyphs.iter().map(|g| g.range.end).max().unwrap_or(0);
+    TextItem {
+        font: text.font.clone(),
+        size: text.size,
+        fill: text.fill.clone(),
+        stroke: text.stroke.clone(),
+        lang: text.lang,
+        region: text.region,
+        text: text.text[usize::from(start)..usize::from(end)].into(),
+        glyphs: glyphs
+            .iter()
+            .map(|g| Glyph {
+                range: g.range.start - start..g.range.end - start,
+                ..g.clone()
+            })
+            .collect(),
+    }
+}
+
+/// Whether a character is set upright in vertical text with mixed orientation.
+///
+/// This approximates the `U`, `Tu`, and `Tr` classes of Unicode's
+/// [vertical orientation property](https://www.unicode.org/reports/tr50/).
+fn is_upright(c: char) -> bool {
+    matches!(
+        c,
+        '§' | '©'
+            | '®'
+            | '±'
+            | '¼'..='¾'
+            | '×'
+            | '÷'
+            | '\u{1100}'..='\u{11FF}'
+            | '\u{2016}'
+            | '\u{2020}'..='\u{2021}'
+            | '\u{2030}'..='\u{2031}'
+            | '\u{203B}'..='\u{203C}'
+            | '\u{2100}'..='\u{2101}'
+            | '\u{2103}'..='\u{2109}'
+            | '\u{2116}'..='\u{2117}'
+            | '\u{2150}'..='\u{218F}'
+            | '\u{2460}'..='\u{24FF}'
+            | '\u{25A0}'..='\u{27BF}'
+            | '\u{2E80}'..='\u{A4CF}'
+            | '\u{A960}'..='\u{A97F}'
+            | '\u{AC00}'..='\u{D7FF}'
+            | '\u{E000}'..='\u{FAFF}'
+            | '\u{FE10}'..='\u{FE1F}'
+            | '\u{FE30}'..='\u{FE6F}'
+            | '\u{FF01}'..='\u{FF60}'
+            | '\u{FFE0}'..='\u{FFE6}'
+            | '\u{1F000}'..='\u{1FAFF}'
+            | '\u{20000}'..='\u{3FFFD}'
+    )
+}
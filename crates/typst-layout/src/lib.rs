@@ -1,9 +1,13 @@
 //! Typst's layout engine.
 
+mod arrow;
+mod chart;
+mod diagram;
 mod document;
 mod flow;
 mod grid;
 mod image;
+mod impose;
 mod inline;
 mod introspect;
 mod lists;
@@ -19,6 +23,7 @@ mod transforms;
 
 pub use self::document::{Page, PagedDocument};
 pub use self::flow::{layout_fragment, layout_frame};
+pub use self::impose::{Imposition, ImpositionScheme, impose};
 pub use self::introspect::PagedIntrospector;
 pub use self::pages::{layout_document, layout_document_for_bundle};
 pub use self::rules::register;
@@ -6,8 +6,8 @@ mod run;
 
 use comemo::{Track, Tracked, TrackedMut};
 use ecow::EcoVec;
-use typst_library::diag::SourceResult;
-use typst_library::engine::{Engine, Route, Sink, Traced};
+use typst_library::diag::{SourceResult, bail};
+use typst_library::engine::{Cancellation, Engine, Fuel, Route, Sink, Traced};
 use typst_library::foundations::{Content, StyleChain};
 use typst_library::introspection::{
     Introspector, Locator, LocatorLink, ManualPageCounter, SplitLocator, TagElem,
@@ -16,6 +16,7 @@ use typst_library::layout::{FrameItem, Point};
 use typst_library::model::DocumentInfo;
 use typst_library::routines::{Arenas, Pair, RealizationKind};
 use typst_library::{Library, World};
+use typst_syntax::Span;
 use typst_utils::{LazyHash, Protected};
 
 use self::collect::{Item, collect};
@@ -40,6 +41,8 @@ pub fn layout_document(
         engine.library,
         engine.introspector.into_raw(),
         engine.traced,
+        engine.cancellation,
+        engine.fuel,
         TrackedMut::reborrow_mut(&mut engine.sink),
         engine.route.track(),
         content,
@@ -55,6 +58,8 @@ fn layout_document_impl(
     library: &LazyHash<Library>,
     introspector: Tracked<dyn Introspector + '_>,
     traced: Tracked<Traced>,
+    cancellation: Tracked<Cancellation>,
+    fuel: Tracked<Fuel>,
     sink: TrackedMut<Sink>,
     route: Tracked<Route>,
     content: &Content,
@@ -65,6 +70,8 @@ fn layout_document_impl(
         world,
         introspector,
         traced,
+        cancellation,
+        fuel,
         sink,
         route,
         content,
@@ -86,6 +93,8 @@ pub fn layout_document_for_bundle(
         engine.library,
         engine.introspector.into_raw(),
         engine.traced,
+        engine.cancellation,
+        engine.fuel,
         TrackedMut::reborrow_mut(&mut engine.sink),
         engine.route.track(),
         content,
@@ -102,6 +111,8 @@ fn layout_document_for_bundle_impl(
     library: &LazyHash<Library>,
     introspector: Tracked<dyn Introspector + '_>,
     traced: Tracked<Traced>,
+    cancellation: Tracked<Cancellation>,
+    fuel: Tracked<Fuel>,
     sink: TrackedMut<Sink>,
     route: Tracked<Route>,
     content: &Content,
@@ -114,6 +125,8 @@ fn layout_document_for_bundle_impl(
         world,
         introspector,
         traced,
+        cancellation,
+        fuel,
         sink,
         route,
         content,
@@ -130,6 +143,8 @@ fn layout_document_common(
     world: Tracked<dyn World + '_>,
     introspector: Tracked<dyn Introspector + '_>,
     traced: Tracked<Traced>,
+    cancellation: Tracked<Cancellation>,
+    fuel: Tracked<Fuel>,
     sink: TrackedMut<Sink>,
     route: Tracked<Route>,
     content: &Content,
@@ -143,6 +158,8 @@ fn layout_document_common(
         world,
         introspector,
         traced,
+        cancellation,
+        fuel,
         sink,
         route: Route::extend(route).unnested(),
     };
@@ -207,6 +224,7 @@ fn layout_pages<'a>(
                 for layouted in layouted {
                     let page = finalize(engine, &mut counter, &mut tags, layouted)?;
                     pages.push(page);
+                    check_pages(engine, &pages)?;
                 }
             }
             Item::Parity(parity, initial, locator) => {
@@ -217,6 +235,7 @@ fn layout_pages<'a>(
                 let layouted = layout_blank_page(engine, locator.relayout(), *initial)?;
                 let page = finalize(engine, &mut counter, &mut tags, layouted)?;
                 pages.push(page);
+                check_pages(engine, &pages)?;
             }
             Item::Tags(items) => {
                 tags.extend(
@@ -239,3 +258,13 @@ fn layout_pages<'a>(
 
     Ok(pages)
 }
+
+/// Ensures that the document stays within the maximum number of pages.
+fn check_pages(engine: &Engine, pages: &EcoVec<Page>) -> SourceResult<()> {
+    if let Some(max) = engine.library.limits.pages
+        && pages.len() > max
+    {
+        bail!(Span::detached(), "document has more than the maximum of {max} pages");
+    }
+    Ok(())
+}
@@ -24,14 +24,14 @@ use typst_library::model::{
 };
 use typst_library::pdf::{ArtifactElem, ArtifactKind, AttachElem, PdfMarkerTag};
 use typst_library::text::{
-    DecoLine, Decoration, HighlightElem, ItalicToggle, LinebreakElem, LocalName,
+    self, DecoLine, Decoration, HighlightElem, ItalicToggle, LinebreakElem, LocalName,
     OverlineElem, RawElem, RawLine, ScriptKind, ShiftSettings, Smallcaps, SmallcapsElem,
     SmartQuoteElem, SmartQuotes, SpaceElem, StrikeElem, SubElem, SuperElem, TextElem,
     TextSize, UnderlineElem, WeightDelta,
 };
 use typst_library::visualize::{
-    CircleElem, CurveElem, EllipseElem, ImageElem, LineElem, PolygonElem, RectElem,
-    SquareElem, Stroke,
+    ArrowElem, ChartElem, CircleElem, CurveElem, DiagramElem, EllipseElem, ImageElem,
+    LineElem, PolygonElem, RectElem, SquareElem, Stroke, VideoElem,
 };
 use typst_utils::{Get, Numeric};
 
@@ -101,6 +101,10 @@ pub fn register(rules: &mut NativeRuleMap) {
     rules.register(Paged, CIRCLE_RULE);
     rules.register(Paged, POLYGON_RULE);
     rules.register(Paged, CURVE_RULE);
+    rules.register(Paged, ARROW_RULE);
+    rules.register(Paged, DIAGRAM_RULE);
+    rules.register(Paged, CHART_RULE);
+    rules.register(Paged, VIDEO_RULE);
 
     // Math.
     rules.register(Paged, EQUATION_RULE);
@@ -191,8 +195,7 @@ const TERMS_RULE: ShowFn<TermsElem> = |elem, _, styles| {
         children.push(StackChild::Block(PdfMarkerTag::TermsItemBody(item)));
     }
 
-    let padding =
-        Sides::default().with(styles.resolve(TextElem::dir).start(), pad.into());
+    let padding = Sides::default().with(text::line_start(styles), pad.into());
 
     let mut realized = StackElem::new(children)
         .with_spacing(Some(gutter.into()))
@@ -287,8 +290,7 @@ const HEADING_RULE: ShowFn<HeadingElem> = |elem, engine, styles| {
 
     Ok(if indent != Abs::zero() {
         let body = HElem::new((-indent).into()).pack() + realized;
-        let inset = Sides::default()
-            .with(styles.resolve(TextElem::dir).start(), Some(indent.into()));
+        let inset = Sides::default().with(text::line_start(styles), Some(indent.into()));
         BlockElem::new()
             .with_inset(inset)
             .with_body(Some(BlockBody::Content(body)))
@@ -497,8 +499,8 @@ const BIBLIOGRAPHY_RULE: ShowFn<BibliographyElem> = |elem, engine, styles| {
             let realized = PdfMarkerTag::BibEntry(reference.clone().located(*loc));
             let block = if works.hanging_indent {
                 let body = HElem::new((-INDENT).into()).pack() + realized;
-                let inset = Sides::default()
-                    .with(styles.resolve(TextElem::dir).start(), Some(INDENT.into()));
+                let inset =
+                    Sides::default().with(text::line_start(styles), Some(INDENT.into()));
                 BlockElem::new()
                     .with_inset(inset)
                     .with_body(Some(BlockBody::Content(body)))
@@ -760,6 +762,29 @@ const IMAGE_RULE: ShowFn<ImageElem> = |elem, _, styles| {
         .pack())
 };
 
+const ARROW_RULE: ShowFn<ArrowElem> = |elem, _, _| {
+    Ok(BlockElem::single_layouter(elem.clone(), crate::arrow::layout_arrow).pack())
+};
+
+const DIAGRAM_RULE: ShowFn<DiagramElem> = |elem, _, _| {
+    Ok(BlockElem::single_layouter(elem.clone(), crate::diagram::layout_diagram).pack())
+};
+
+const CHART_RULE: ShowFn<ChartElem> = |elem, _, _| {
+    Ok(BlockElem::single_layouter(elem.clone(), crate::chart::layout_chart).pack())
+};
+
+const VIDEO_RULE: ShowFn<VideoElem> = |elem, _, styles| {
+    let Some(image) = elem.poster_image(styles) else {
+        bail!(
+            elem.span(),
+            "cannot show a video without a poster in paged export";
+            hint: "provide an image to show instead with the `poster` parameter";
+        );
+    };
+    Ok(image.pack())
+};
+
 const LINE_RULE: ShowFn<LineElem> = |elem, _, _| {
     Ok(BlockElem::single_layouter(elem.clone(), crate::shapes::layout_line).pack())
 };
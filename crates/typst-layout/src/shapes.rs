@@ -9,13 +9,15 @@ use typst_library::layout::{
     Abs, Axes, Corner, Corners, Frame, FrameItem, Point, Ratio, Region, Rel, Sides, Size,
 };
 use typst_library::visualize::{
-    CircleElem, CloseMode, Curve, CurveComponent, CurveElem, EllipseElem, FillRule,
-    FixedStroke, Geometry, LineCap, LineElem, Paint, PolygonElem, RectElem, Shape,
-    SquareElem, Stroke,
+    CircleElem, CloseMode, Curve, CurveComponent, CurveElem, CurveItem, EllipseElem,
+    FillRule, FixedStroke, Geometry, LineCap, LineElem, Paint, PolygonElem, RectElem,
+    Shape, SquareElem, Stroke,
 };
 use typst_syntax::Span;
 use typst_utils::{Get, Numeric};
 
+use crate::arrow::draw_marked;
+
 /// Layout the line.
 #[typst_macros::time(span = elem.span())]
 pub fn layout_line(
@@ -47,8 +49,17 @@ pub fn layout_line(
     }
 
     let mut frame = Frame::soft(size);
-    let shape = Geometry::Line(delta.to_point()).stroked(stroke);
-    frame.push(start.to_point(), FrameItem::Shape(shape, elem.span()));
+    let start_mark = elem.start_mark.get(styles);
+    let end_mark = elem.end_mark.get(styles);
+    if start_mark.is_some() || end_mark.is_some() {
+        let mut curve = Curve::new();
+        curve.move_(start.to_point());
+        curve.line((start + delta).to_point());
+        draw_marked(&mut frame, curve, &stroke, start_mark, end_mark, elem.span());
+    } else {
+        let shape = Geometry::Line(delta.to_point()).stroked(stroke);
+        frame.push(start.to_point(), FrameItem::Shape(shape, elem.span()));
+    }
     Ok(frame)
 }
 
@@ -130,6 +141,26 @@ pub fn layout_curve(
     };
 
     let mut frame = Frame::soft(size);
+    let start_mark = elem.start_mark.get(styles);
+    let end_mark = elem.end_mark.get(styles);
+    if let Some(stroke) = &stroke
+        && (start_mark.is_some() || end_mark.is_some())
+        && !curve.0.contains(&CurveItem::Close)
+    {
+        // The marks shorten the stroked curve, so the fill is drawn separately.
+        if fill.is_some() {
+            let shape = Shape {
+                geometry: Geometry::Curve(curve.clone()),
+                stroke: None,
+                fill,
+                fill_rule,
+            };
+            frame.push(Point::zero(), FrameItem::Shape(shape, elem.span()));
+        }
+        draw_marked(&mut frame, curve, stroke, start_mark, end_mark, elem.span());
+        return Ok(frame);
+    }
+
     let shape = Shape {
         geometry: Geometry::Curve(curve),
         stroke,
@@ -0,0 +1,97 @@
+//! Hooks for interactive debugging of evaluation.
+//!
+//! A [`Debugger`] can be attached to a compilation through
+//! [`Traced`](crate::engine::Traced). While one is attached, evaluation reports
+//! every statement it is about to execute as well as every closure call and
+//! show rule transformation it enters. The debugger may block in these hooks
+//! to pause evaluation, e.g. at a breakpoint.
+//!
+//! A memoized call that is reused instead of being evaluated again wouldn't
+//! trigger any hooks. Thus, memoized results are never reused while a
+//! debugger is attached.
+
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use typst_syntax::Span;
+
+use crate::foundations::{Scopes, StyleChain};
+
+/// Observes evaluation and may pause it.
+///
+/// Hooks may be called concurrently from multiple threads since layout is
+/// parallelized. Calls to [`enter`](Self::enter) and [`exit`](Self::exit) are
+/// balanced per thread.
+pub trait Debugger: Send + Sync {
+    /// Called before a statement in code or an embedded expression in markup
+    /// is evaluated.
+    fn statement(&self, stop: &Stop);
+
+    /// Called when a closure call or show rule transformation is entered.
+    fn enter(&self, name: &str, span: Span);
+
+    /// Called when the innermost entered closure call or show rule
+    /// transformation is exited, no matter whether it succeeded.
+    fn exit(&self);
+}
+
+/// A point at which evaluation can be paused.
+pub struct Stop<'a> {
+    /// The statement that is about to be evaluated.
+    pub span: Span,
+    /// The variables that are in scope.
+    pub scopes: &'a Scopes<'a>,
+    /// The active styles, if they are known at this point.
+    pub styles: Option<StyleChain<'a>>,
+}
+
+/// A debugger that is attached to a compilation.
+///
+/// Each handle hashes differently. Since memoized calls record the handles
+/// they received, their results are never valid again and are recomputed
+/// (triggering the hooks) instead of being reused.
+#[derive(Clone)]
+pub struct Attached {
+    debugger: Arc<dyn Debugger>,
+    nonce: u64,
+}
+
+impl Attached {
+    /// Creates a fresh handle to the debugger.
+    pub(crate) fn new(debugger: Arc<dyn Debugger>) -> Self {
+        static NONCE: AtomicU64 = AtomicU64::new(0);
+        let nonce = NONCE.fetch_add(1, Ordering::Relaxed);
+        Self { debugger, nonce }
+    }
+
+    /// Reports a statement that is about to be evaluated. The debugger may
+    /// pause evaluation here.
+    pub fn statement(&self, stop: &Stop) {
+        self.debugger.statement(stop);
+    }
+
+    /// Reports that a closure call or show rule transformation is entered.
+    ///
+    /// The returned guard reports the exit when it is dropped.
+    pub fn enter(&self, name: &str, span: Span) -> FrameGuard {
+        self.debugger.enter(name, span);
+        FrameGuard(self.debugger.clone())
+    }
+}
+
+impl Hash for Attached {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.nonce.hash(state);
+    }
+}
+
+/// Reports the exit of a closure call or show rule transformation when dropped.
+#[must_use]
+pub struct FrameGuard(Arc<dyn Debugger>);
+
+impl Drop for FrameGuard {
+    fn drop(&mut self) {
+        self.0.exit();
+    }
+}
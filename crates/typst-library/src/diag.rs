@@ -288,6 +288,10 @@ pub struct SourceDiagnostic {
     /// - When a span is given, the hint is related to a secondary piece of code
     ///   and will be annotated at that code.
     pub hints: EcoVec<Spanned<EcoString>>,
+    /// Whether the error can be caught by a `try` expression. This is false
+    /// for errors that signal compiler limits rather than problems with the
+    /// user's code.
+    pub catchable: bool,
 }
 
 /// The severity of a [`SourceDiagnostic`].
@@ -308,6 +312,7 @@ impl SourceDiagnostic {
             trace: eco_vec![],
             message: message.into(),
             hints: eco_vec![],
+            catchable: true,
         }
     }
 
@@ -319,6 +324,7 @@ impl SourceDiagnostic {
             trace: eco_vec![],
             message: message.into(),
             hints: eco_vec![],
+            catchable: true,
         }
     }
 
@@ -365,6 +371,7 @@ impl From<SyntaxError> for SourceDiagnostic {
             message: error.message,
             trace: eco_vec![],
             hints: error.hints.into_iter().map(Spanned::detached).collect(),
+            catchable: true,
         }
     }
 }
@@ -445,6 +452,23 @@ impl<T> Trace<T> for SourceResult<T> {
     }
 }
 
+/// Mark the errors of a [`SourceResult`] as impossible to catch.
+pub trait Uncatchable {
+    /// Prevent the errors from being caught by a `try` expression.
+    fn uncatchable(self) -> Self;
+}
+
+impl<T> Uncatchable for SourceResult<T> {
+    fn uncatchable(self) -> Self {
+        self.map_err(|mut errors| {
+            for error in errors.make_mut().iter_mut() {
+                error.catchable = false;
+            }
+            errors
+        })
+    }
+}
+
 /// A result type with a string error message. The recommended way to create an
 /// error for this type is with the [`bail!`] macro.
 pub type StrResult<T> = Result<T, EcoString>;
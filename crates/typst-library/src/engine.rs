@@ -1,15 +1,21 @@
 //! Definition of the central compilation context.
 
-use std::sync::atomic::{AtomicUsize, Ordering};
+use std::fmt::{self, Display, Formatter};
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
 
 use comemo::{Track, Tracked, TrackedMut};
-use ecow::EcoVec;
+use ecow::{EcoVec, eco_vec};
 use rayon::iter::{IndexedParallelIterator, IntoParallelIterator, ParallelIterator};
 use rustc_hash::FxHashSet;
 use typst_syntax::{FileId, Span};
 use typst_utils::{LazyHash, Protected};
 
-use crate::diag::{HintedStrResult, SourceDiagnostic, SourceResult, StrResult, bail};
+use crate::debug::{Attached, Debugger};
+use crate::diag::{
+    HintedStrResult, SourceDiagnostic, SourceResult, StrResult, bail, error,
+};
 use crate::foundations::{Styles, Value};
 use crate::introspection::{Introspect, Introspection, Introspector};
 use crate::{Library, World};
@@ -28,6 +34,10 @@ pub struct Engine<'a> {
     pub introspector: Protected<Tracked<'a, dyn Introspector + 'a>>,
     /// May hold a span that is currently under inspection.
     pub traced: Tracked<'a, Traced>,
+    /// Signals that the compilation should stop.
+    pub cancellation: Tracked<'a, Cancellation>,
+    /// Limits the number of evaluation steps.
+    pub fuel: Tracked<'a, Fuel>,
     /// A pure sink for warnings, delayed errors, and spans under inspection.
     pub sink: TrackedMut<'a, Sink>,
     /// The route the engine took during compilation. This is used to detect
@@ -63,7 +73,14 @@ impl Engine<'_> {
         F: Fn(&mut Engine, T) -> U + Send + Sync,
     {
         let Engine {
-            world, introspector, traced, ref route, library, ..
+            world,
+            introspector,
+            traced,
+            cancellation,
+            fuel,
+            ref route,
+            library,
+            ..
         } = *self;
 
         // We collect into a vector and then call `into_par_iter` instead of
@@ -79,6 +96,8 @@ impl Engine<'_> {
                     world,
                     introspector,
                     traced,
+                    cancellation,
+                    fuel,
                     sink: sink.track_mut(),
                     route: route.clone(),
                     library,
@@ -115,18 +134,167 @@ impl Engine<'_> {
         self.sink.introspection(Introspection::new(introspection));
         output
     }
+
+    /// Aborts with an error that can't be caught if the compilation was
+    /// cancelled.
+    pub fn check_cancelled(&self) -> SourceResult<()> {
+        if self.cancellation.flag().is_set() && self.cancellation.confirm() {
+            let mut error = error!(Span::detached(), "compilation was cancelled");
+            error.catchable = false;
+            return Err(eco_vec![error]);
+        }
+        Ok(())
+    }
+}
+
+/// Allows to stop a compilation that is in progress.
+///
+/// Clones share their state, so a clone can be cancelled from another thread
+/// while the compilation runs.
+#[derive(Debug, Default, Clone)]
+pub struct Cancellation(Arc<AtomicBool>);
+
+impl Cancellation {
+    /// Creates a new token that is not cancelled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Requests that the compilation stops as soon as possible.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    /// Whether cancellation was requested.
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+// Memoized results must not depend on whether the compilation was cancelled
+// while they were computed. Otherwise, results of a cancelled compilation
+// would be reused later. Thus, the flag is polled through an untracked handle
+// and only a confirmed cancellation, which is final, ends up in constraints.
+#[comemo::track]
+impl Cancellation {
+    /// A handle through which the flag can be polled without tracking.
+    fn flag(&self) -> Flag {
+        Flag(self.0.clone())
+    }
+
+    /// Whether cancellation was requested. Must only be called once the flag
+    /// is set, such that the result is the same for all calls.
+    fn confirm(&self) -> bool {
+        self.is_cancelled()
+    }
+}
+
+/// The error returned by a compilation that was cancelled.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Cancelled;
+
+impl Display for Cancelled {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str("compilation was cancelled")
+    }
+}
+
+impl std::error::Error for Cancelled {}
+
+/// An untracked handle to the flag of a [`Cancellation`].
+struct Flag(Arc<AtomicBool>);
+
+impl Flag {
+    /// Whether the flag is set.
+    fn is_set(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
 }
 
-/// May hold a span that is currently under inspection.
+impl Hash for Flag {
+    fn hash<H: Hasher>(&self, _: &mut H) {}
+}
+
+/// Limits the number of evaluation steps of a compilation.
+///
+/// Each compilation has its own fuel, whose amount is configured through the
+/// library's [limits](crate::Limits).
+#[derive(Debug, Default)]
+pub struct Fuel {
+    limit: Option<u64>,
+    used: Arc<AtomicU64>,
+}
+
+impl Fuel {
+    /// Creates fuel for at most `limit` evaluation steps, or for any number of
+    /// them if it is `None`.
+    pub fn new(limit: Option<u64>) -> Self {
+        Self { limit, used: Arc::default() }
+    }
+}
+
+// Like for cancellation, memoized results must not depend on how much fuel
+// was left while they were computed. Thus, fuel is consumed through an
+// untracked handle and only running out of it, which is final, ends up in
+// constraints.
+#[comemo::track]
+impl Fuel {
+    /// A handle through which fuel can be consumed without tracking.
+    pub fn meter(&self) -> Meter {
+        Meter { limit: self.limit, used: self.used.clone() }
+    }
+
+    /// Whether the fuel ran out. Must only be called once the meter reported
+    /// this, such that the result is the same for all calls.
+    pub fn confirm_empty(&self) -> bool {
+        self.limit
+            .is_some_and(|limit| self.used.load(Ordering::Relaxed) > limit)
+    }
+}
+
+/// An untracked handle through which [`Fuel`] is consumed.
+#[derive(Debug, Clone)]
+pub struct Meter {
+    limit: Option<u64>,
+    used: Arc<AtomicU64>,
+}
+
+impl Meter {
+    /// Consumes one unit of fuel. Returns `false` if none was left.
+    pub fn consume(&self) -> bool {
+        self.limit
+            .is_none_or(|limit| self.used.fetch_add(1, Ordering::Relaxed) < limit)
+    }
+
+    /// The maximum number of evaluation steps, if any.
+    pub fn limit(&self) -> Option<u64> {
+        self.limit
+    }
+}
+
+impl Hash for Meter {
+    fn hash<H: Hasher>(&self, _: &mut H) {}
+}
+
+/// May hold a span that is currently under inspection and a debugger that
+/// observes evaluation.
 #[derive(Default)]
-pub struct Traced(Option<Span>);
+pub struct Traced {
+    span: Option<Span>,
+    debugger: Option<Arc<dyn Debugger>>,
+}
 
 impl Traced {
     /// Wraps a to-be-traced `Span`.
     ///
     /// Call `Traced::default()` to trace nothing.
     pub fn new(traced: Span) -> Self {
-        Self(Some(traced))
+        Self { span: Some(traced), debugger: None }
+    }
+
+    /// Attaches a debugger that observes evaluation.
+    pub fn debugged(debugger: Arc<dyn Debugger>) -> Self {
+        Self { span: None, debugger: Some(debugger) }
     }
 }
 
@@ -138,7 +306,15 @@ impl Traced {
     /// We hide the span if it isn't in the given file so that only results for
     /// the file with the traced span are invalidated.
     pub fn get(&self, id: FileId) -> Option<Span> {
-        if self.0.and_then(Span::id) == Some(id) { self.0 } else { None }
+        if self.span.and_then(Span::id) == Some(id) { self.span } else { None }
+    }
+
+    /// Returns a handle to the attached debugger, if any.
+    ///
+    /// Each returned handle is distinct, so memoized results that observed a
+    /// debugger are never reused.
+    pub fn debugger(&self) -> Option<Attached> {
+        self.debugger.clone().map(Attached::new)
     }
 }
 
@@ -342,9 +518,6 @@ impl Route<'_> {
     /// The maximum HTML nesting depth.
     const MAX_HTML_DEPTH: usize = 72;
 
-    /// The maximum function call nesting depth.
-    const MAX_CALL_DEPTH: usize = 80;
-
     /// Ensures that we are within the maximum show rule depth.
     pub fn check_show_depth(&self) -> HintedStrResult<()> {
         if !self.within(Route::MAX_SHOW_RULE_DEPTH) {
@@ -379,9 +552,10 @@ impl Route<'_> {
         Ok(())
     }
 
-    /// Ensures that we are within the maximum function call depth.
-    pub fn check_call_depth(&self) -> StrResult<()> {
-        if !self.within(Route::MAX_CALL_DEPTH) {
+    /// Ensures that we are within the given maximum function call depth, which
+    /// is configured through the library's [limits](crate::Limits).
+    pub fn check_call_depth(&self, max: usize) -> StrResult<()> {
+        if !self.within(max) {
             bail!("maximum function call depth exceeded");
         }
         Ok(())
@@ -9,8 +9,10 @@ use serde::{Deserialize, Serialize};
 use smallvec::SmallVec;
 use typst_syntax::{Span, Spanned};
 
+use crate::Limits;
 use crate::diag::{
-    At, HintedStrResult, HintedString, SourceDiagnostic, SourceResult, StrResult, bail,
+    At, HintedStrResult, HintedString, SourceDiagnostic, SourceResult, StrResult,
+    Uncatchable, bail,
 };
 use crate::engine::Engine;
 use crate::foundations::{
@@ -375,6 +377,7 @@ impl Array {
     /// ```
     #[func]
     pub fn range(
+        engine: &mut Engine,
         args: &mut Args,
         /// The start of the range (inclusive).
         #[external]
@@ -396,6 +399,18 @@ impl Array {
 
         let step = step.get();
 
+        // Check the length before allocating the array.
+        let len = (i128::from(end) - i128::from(start) + i128::from(step)
+            - i128::from(step.signum()))
+            / i128::from(step);
+        let len = usize::try_from(len).unwrap_or(if len > 0 { usize::MAX } else { 0 });
+        engine
+            .library
+            .limits
+            .check_array_len(len)
+            .at(args.span)
+            .uncatchable()?;
+
         let mut x = start;
         let mut array = Self::new();
 
@@ -680,16 +695,12 @@ impl Array {
 
     /// Combine all nested arrays into a single flat one.
     #[func]
-    pub fn flatten(self) -> Array {
+    pub fn flatten(self, engine: &mut Engine, span: Span) -> SourceResult<Array> {
         let mut flat = EcoVec::with_capacity(self.0.len());
-        for item in self {
-            if let Value::Array(nested) = item {
-                flat.extend(nested.flatten());
-            } else {
-                flat.push(item);
-            }
-        }
-        flat.into()
+        flatten_into(&mut flat, self, &engine.library.limits)
+            .at(span)
+            .uncatchable()?;
+        Ok(flat.into())
     }
 
     /// Return a new array with the same items, but in reverse order.
@@ -719,6 +730,8 @@ impl Array {
     #[func]
     pub fn join(
         self,
+        engine: &mut Engine,
+        span: Span,
         /// A value to insert between each item of the array.
         #[default]
         separator: Option<Value>,
@@ -729,7 +742,7 @@ impl Array {
         #[named]
         #[default]
         default: Option<Value>,
-    ) -> StrResult<Value> {
+    ) -> SourceResult<Value> {
         let len = self.0.len();
 
         if let Some(result) = default
@@ -740,18 +753,25 @@ impl Array {
 
         let separator = separator.unwrap_or(Value::None);
 
+        // Ensures that the joined value stays within the maximum size before
+        // it grows.
+        let join = |lhs: Value, rhs: Value| {
+            engine.library.limits.check_join(&lhs, &rhs).at(span).uncatchable()?;
+            ops::join(lhs, rhs).at(span)
+        };
+
         let mut last = last;
         let mut result = Value::None;
         for (i, value) in self.into_iter().enumerate() {
             if i > 0 {
                 if i + 1 == len && last.is_some() {
-                    result = ops::join(result, last.take().unwrap())?;
+                    result = join(result, last.take().unwrap())?;
                 } else {
-                    result = ops::join(result, separator.clone())?;
+                    result = join(result, separator.clone())?;
                 }
             }
 
-            result = ops::join(result, value)?;
+            result = join(result, value)?;
         }
 
         Ok(result)
@@ -766,15 +786,18 @@ impl Array {
     #[func]
     pub fn intersperse(
         self,
+        engine: &mut Engine,
+        span: Span,
         /// The value that will be placed between each adjacent element.
         separator: Value,
-    ) -> Array {
+    ) -> SourceResult<Array> {
         // TODO: Use once stabilized:
         // https://doc.rust-lang.org/std/iter/trait.Iterator.html#method.intersperse
         let size = match self.len() {
-            0 => return Array::new(),
+            0 => return Ok(Array::new()),
             n => (2 * n) - 1,
         };
+        engine.library.limits.check_array_len(size).at(span).uncatchable()?;
         let mut vec = EcoVec::with_capacity(size);
         let mut iter = self.into_iter();
 
@@ -787,7 +810,7 @@ impl Array {
             vec.push(value);
         }
 
-        Array(vec)
+        Ok(Array(vec))
     }
 
     /// Splits an array into non-overlapping chunks, starting at the beginning,
@@ -1325,6 +1348,24 @@ impl<T> Default for OneOrMultiple<T> {
     }
 }
 
+/// Pushes the items of an array into `flat`, recursing into nested arrays and
+/// ensuring that the result stays within the maximum size.
+fn flatten_into(
+    flat: &mut EcoVec<Value>,
+    array: Array,
+    limits: &Limits,
+) -> StrResult<()> {
+    for item in array {
+        if let Value::Array(nested) = item {
+            flatten_into(flat, nested, limits)?;
+        } else {
+            limits.check_array_len(flat.len() + 1)?;
+            flat.push(item);
+        }
+    }
+    Ok(())
+}
+
 /// The error message when the array is empty.
 #[cold]
 fn array_is_empty() -> EcoString {
@@ -0,0 +1,380 @@
+use std::fmt::{self, Debug, Formatter};
+use std::sync::Arc;
+
+use comemo::Track;
+use ecow::{EcoString, eco_format};
+use smallvec::SmallVec;
+use typst_syntax::{Span, Spanned};
+use typst_utils::LazyHash;
+
+use crate::diag::{At, SourceResult, StrResult, bail};
+use crate::engine::Engine;
+use crate::foundations::{
+    Args, Construct, Content, Context, Dict, FieldAccessError, Func, NativeElement,
+    Packed, Property, Repr, Selector, ShowFn, Str, StyleChain, Styles, Type, Value, elem,
+    repr,
+};
+use crate::introspection::Locatable;
+
+/// Holds the fields of an element that is defined in Typst code.
+///
+/// All user-defined elements share this element's vtable. Each instance holds
+/// the element's [definition](CustomElemDef), so that set rules, show rules,
+/// and selectors can tell different user-defined elements apart.
+#[elem(Construct, PartialEq, Locatable)]
+pub struct CustomElem {
+    /// The definition of the element.
+    #[required]
+    #[internal]
+    def: Arc<LazyHash<CustomElemDef>>,
+
+    /// The values of the element's fields, indexed by field ID. Settable
+    /// fields are `None` until they are given or materialized.
+    #[required]
+    #[internal]
+    values: Vec<Option<Value>>,
+}
+
+impl Construct for CustomElem {
+    fn construct(_: &mut Engine, args: &mut Args) -> SourceResult<Content> {
+        bail!(args.span, "cannot be constructed manually");
+    }
+}
+
+impl PartialEq for CustomElem {
+    fn eq(&self, other: &Self) -> bool {
+        self.def == other.def && self.values == other.values
+    }
+}
+
+impl Packed<CustomElem> {
+    /// The definition of the element.
+    pub fn def(&self) -> &Arc<LazyHash<CustomElemDef>> {
+        &self.def
+    }
+
+    /// The element function of the element.
+    pub(super) fn func(&self) -> Func {
+        Func::from(self.def.clone())
+    }
+
+    /// Extract the field ID for the given field name.
+    pub(super) fn field_id(&self, name: &str) -> Option<u8> {
+        self.def.field_id(name)
+    }
+
+    /// Extract the field name for the given field ID.
+    pub(super) fn field_name(&self, id: u8) -> Option<&str> {
+        self.def.field_name(id)
+    }
+
+    /// Get a field by ID, falling back to the style chain for settable fields
+    /// if `styles` are given.
+    pub(super) fn field(
+        &self,
+        id: u8,
+        styles: Option<StyleChain>,
+    ) -> Result<Value, FieldAccessError> {
+        let Some(field) = self.def.fields.get(usize::from(id)) else {
+            return Err(FieldAccessError::Unknown);
+        };
+
+        if let Some(value) = &self.values[usize::from(id)] {
+            return Ok(value.clone());
+        }
+
+        match (styles, &field.default) {
+            (Some(styles), Some(default)) => {
+                Ok(styles.get_custom(&self.def, id).unwrap_or(default).clone())
+            }
+            _ => Err(FieldAccessError::Unset),
+        }
+    }
+
+    /// Whether the field with the given ID is set on the element.
+    pub(super) fn has(&self, id: u8) -> bool {
+        self.values.get(usize::from(id)).is_some_and(Option::is_some)
+    }
+
+    /// The names and values of all fields that are set on the element.
+    pub(super) fn fields(&self) -> impl Iterator<Item = (&str, &Value)> {
+        self.def
+            .fields
+            .iter()
+            .zip(&self.values)
+            .filter_map(|(field, value)| Some((field.name.as_str(), value.as_ref()?)))
+    }
+
+    /// Sets all settable fields that are unset from the styles.
+    pub(super) fn materialize(&mut self, styles: StyleChain) {
+        let def = self.def.clone();
+        for (i, field) in def.fields.iter().enumerate() {
+            if self.values[i].is_some() {
+                continue;
+            }
+
+            if let Some(default) = &field.default {
+                let value = styles.get_custom(&def, i as u8).unwrap_or(default);
+                self.values[i] = Some(value.clone());
+            }
+        }
+    }
+}
+
+impl Repr for Packed<CustomElem> {
+    fn repr(&self) -> EcoString {
+        let pieces: Vec<_> = self
+            .fields()
+            .map(|(name, value)| eco_format!("{name}: {}", value.repr()))
+            .collect();
+        eco_format!("{}{}", self.def.name, repr::pretty_array_like(&pieces, false))
+    }
+}
+
+/// Shows a user-defined element with the function it was defined with.
+pub const CUSTOM_RULE: ShowFn<CustomElem> = |elem, engine, styles| {
+    let context = Context::new(elem.location(), Some(styles));
+    let content = elem.pack_ref().clone();
+    Ok(elem.def.display.call(engine, context.track(), [content])?.display())
+};
+
+/// The definition of an element in Typst code, as created by `content.define`.
+///
+/// Element functions, instances, and style properties share the definition
+/// through reference counting. Two elements are equal if their definitions are,
+/// such that evaluating the same definition again (e.g. in a later
+/// compilation) yields the same element.
+#[derive(Hash)]
+pub struct CustomElemDef {
+    /// The element's name.
+    name: Str,
+    /// The element's fields, indexed by field ID.
+    fields: Vec<CustomField>,
+    /// Produces the element's default appearance.
+    display: Func,
+    /// Where the element was defined.
+    span: Span,
+}
+
+/// A field of a user-defined element.
+#[derive(Debug, Clone, Hash)]
+pub struct CustomField {
+    /// The field's name.
+    name: Str,
+    /// The type values of the field must have, if any.
+    ty: Option<Type>,
+    /// The value of the field if it isn't given. Fields with a default are
+    /// settable, fields without one are required.
+    default: Option<Value>,
+    /// Whether the field is given as a positional argument.
+    positional: bool,
+}
+
+impl CustomElemDef {
+    /// Defines an element from its field specifications and the function that
+    /// displays it.
+    pub fn define(name: Str, fields: Dict, display: Func, span: Span) -> StrResult<Func> {
+        if name.is_empty() {
+            bail!("element name must not be empty");
+        }
+
+        // The field ID 255 is reserved for the label.
+        if fields.len() > 255 {
+            bail!("elements can have at most 255 fields");
+        }
+
+        let fields: Vec<_> = fields
+            .into_iter()
+            .map(|(name, spec)| CustomField::parse(name, spec))
+            .collect::<StrResult<_>>()?;
+
+        if fields.iter().any(|field| field.name.as_str() == "label") {
+            bail!("field name `label` is reserved");
+        }
+
+        let def = Self { name, fields, display, span };
+        Ok(Func::from(Arc::new(LazyHash::new(def))))
+    }
+
+    /// The element's name.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Where the element was defined.
+    pub fn span(&self) -> Span {
+        self.span
+    }
+
+    /// The element's fields, indexed by field ID.
+    pub fn fields(&self) -> &[CustomField] {
+        &self.fields
+    }
+
+    /// Extract the field ID for the given field name.
+    pub fn field_id(&self, name: &str) -> Option<u8> {
+        if name == "label" {
+            return Some(255);
+        }
+        self.fields
+            .iter()
+            .position(|field| field.name.as_str() == name)
+            .map(|i| i as u8)
+    }
+
+    /// Extract the field name for the given field ID.
+    pub fn field_name(&self, id: u8) -> Option<&str> {
+        if id == 255 {
+            return Some("label");
+        }
+        self.fields.get(usize::from(id)).map(|field| field.name.as_str())
+    }
+
+    /// Create a selector for the element, filtering for those whose fields
+    /// have the given values.
+    pub fn where_(def: &Arc<LazyHash<Self>>, fields: Dict) -> StrResult<Selector> {
+        let fields = fields
+            .into_iter()
+            .map(|(key, value)| {
+                def.field_id(&key).map(|id| (id, value)).ok_or_else(|| {
+                    eco_format!("element `{}` does not have field `{}`", def.name, key)
+                })
+            })
+            .collect::<StrResult<SmallVec<_>>>()?;
+        Ok(Selector::Custom(def.clone(), Some(fields)))
+    }
+
+    /// Extract the value of a settable field from the style chain.
+    pub fn field_from_styles(
+        def: &Arc<LazyHash<Self>>,
+        id: u8,
+        styles: StyleChain,
+    ) -> Option<Value> {
+        let default = def.fields.get(usize::from(id))?.default.as_ref()?;
+        Some(styles.get_custom(def, id).unwrap_or(default).clone())
+    }
+
+    /// Construct an instance of the element.
+    pub fn construct(
+        def: &Arc<LazyHash<Self>>,
+        args: &mut Args,
+    ) -> SourceResult<Content> {
+        let mut values = Vec::with_capacity(def.fields.len());
+        for field in &def.fields {
+            let value = field.parse_arg(args)?;
+            if value.is_none() && field.default.is_none() {
+                bail!(args.span, "missing argument: {}", field.name);
+            }
+            values.push(value);
+        }
+
+        Ok(CustomElem::new(def.clone(), values).pack())
+    }
+
+    /// Execute the set rule for the element and return the resulting style map.
+    pub fn set(def: &Arc<LazyHash<Self>>, mut args: Args) -> SourceResult<Styles> {
+        let mut styles = Styles::new();
+        for (i, field) in def.fields.iter().enumerate() {
+            if field.default.is_none() {
+                continue;
+            }
+
+            if let Some(value) = field.parse_arg(&mut args)? {
+                styles.push(Property::custom(def.clone(), i as u8, value));
+            }
+        }
+        args.finish()?;
+        Ok(styles)
+    }
+}
+
+impl Debug for CustomElemDef {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "CustomElemDef({})", self.name)
+    }
+}
+
+impl CustomField {
+    /// Parses a field from its specification, which is either a type or a
+    /// dictionary with optional `type`, `default`, and `positional` keys.
+    fn parse(name: Str, spec: Value) -> StrResult<Self> {
+        let mut dict = match spec {
+            Value::Type(ty) => {
+                return Ok(Self {
+                    name,
+                    ty: Some(ty),
+                    default: None,
+                    positional: false,
+                });
+            }
+            Value::Dict(dict) => dict,
+            v => {
+                bail!("expected type or dictionary for field `{name}`, found {}", v.ty())
+            }
+        };
+
+        let ty = match dict.take("type").ok() {
+            None => None,
+            Some(Value::Type(ty)) => Some(ty),
+            Some(v) => bail!("expected type for field `{name}`, found {}", v.ty()),
+        };
+
+        let positional = match dict.take("positional").ok() {
+            None => false,
+            Some(Value::Bool(positional)) => positional,
+            Some(v) => bail!(
+                "expected boolean for `positional` of field `{name}`, found {}",
+                v.ty()
+            ),
+        };
+
+        let default = dict.take("default").ok();
+        dict.finish(&["type", "default", "positional"])?;
+
+        let mut field = Self { name, ty, default: None, positional };
+        field.default = default.map(|default| field.cast(default)).transpose()?;
+        Ok(field)
+    }
+
+    /// The field's name.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The type values of the field must have, if any.
+    pub fn ty(&self) -> Option<&Type> {
+        self.ty.as_ref()
+    }
+
+    /// The value of the field if it isn't given.
+    pub fn default(&self) -> Option<&Value> {
+        self.default.as_ref()
+    }
+
+    /// Whether the field is given as a positional argument.
+    pub fn positional(&self) -> bool {
+        self.positional
+    }
+
+    /// Takes the field's value from the arguments, if it is given.
+    fn parse_arg(&self, args: &mut Args) -> SourceResult<Option<Value>> {
+        let arg = if self.positional {
+            args.eat::<Spanned<Value>>()?
+        } else {
+            args.named::<Spanned<Value>>(&self.name)?
+        };
+
+        let Some(Spanned { v, span }) = arg else { return Ok(None) };
+        self.cast(v).at(span).map(Some)
+    }
+
+    /// Casts a value to the field's type, if it has one.
+    fn cast(&self, value: Value) -> StrResult<Value> {
+        let Some(ty) = &self.ty else { return Ok(value) };
+        let found = value.ty();
+        match ty.cast(value) {
+            Some(value) => Ok(value),
+            None => bail!("expected {ty} for field `{}`, found {found}", self.name),
+        }
+    }
+}
@@ -577,7 +577,8 @@ impl FieldAccessError {
     /// Formats the error message given the content and the field name.
     #[cold]
     pub fn message(self, content: &Content, field: &str) -> EcoString {
-        let elem_name = content.elem().name();
+        let func = content.elem_func();
+        let elem_name = func.name().unwrap_or_default();
         match self {
             FieldAccessError::Unknown => {
                 eco_format!("{elem_name} does not have field {}", field.repr())
@@ -1,9 +1,11 @@
+mod custom;
 mod element;
 mod field;
 mod packed;
 mod raw;
 mod vtable;
 
+pub use self::custom::*;
 pub use self::element::*;
 pub use self::field::*;
 pub use self::packed::Packed;
@@ -26,8 +28,8 @@ use typst_utils::singleton;
 use crate::diag::{SourceResult, StrResult};
 use crate::engine::Engine;
 use crate::foundations::{
-    Context, Dict, IntoValue, Label, Property, Recipe, RecipeIndex, Repr, Selector, Str,
-    Style, StyleChain, Styles, Value, func, repr, scope, ty,
+    Context, Dict, Func, IntoValue, Label, Property, Recipe, RecipeIndex, Repr, Selector,
+    Str, Style, StyleChain, Styles, Value, func, repr, scope, ty,
 };
 use crate::introspection::Location;
 use crate::layout::{AlignElem, Alignment, Axes, Length, MoveElem, PadElem, Rel, Sides};
@@ -78,6 +80,11 @@ use crate::text::UnderlineElem;
 /// In the web app, you can hover over a content variable to see exactly which
 /// elements the content is composed of and what fields they have.
 /// Alternatively, you can inspect the output of the @repr function.
+///
+/// = Custom elements <custom-elements>
+/// With @content.define, you can define your own elements with typed fields.
+/// They work just like built-in elements: You can configure them with set
+/// rules, restyle them with show rules, and find them with queries.
 #[ty(scope, cast)]
 #[derive(Clone, PartialEq, Hash)]
 #[repr(transparent)]
@@ -99,6 +106,14 @@ impl Content {
         self.0.elem()
     }
 
+    /// Get the element of this content.
+    ///
+    /// All user-defined elements share the element of [`CustomElem`]. Use
+    /// [`elem_func`](Self::elem_func) to tell them apart.
+    pub fn func(&self) -> Element {
+        self.elem()
+    }
+
     /// Get the span of the content.
     pub fn span(&self) -> Span {
         self.0.span()
@@ -181,6 +196,10 @@ impl Content {
             return Ok(label.into_value());
         }
 
+        if let Some(elem) = self.to_packed::<CustomElem>() {
+            return elem.field(id, styles);
+        }
+
         match self.0.handle().field(id) {
             Some(handle) => match styles {
                 Some(styles) => handle.get_with_styles(styles),
@@ -203,6 +222,11 @@ impl Content {
                 .ok_or(FieldAccessError::Unknown);
         }
 
+        if let Some(elem) = self.to_packed::<CustomElem>() {
+            let id = elem.field_id(name).ok_or(FieldAccessError::Unknown)?;
+            return elem.field(id, None);
+        }
+
         match self.elem().field_id(name).and_then(|id| self.0.handle().field(id)) {
             Some(handle) => handle.get().ok_or(FieldAccessError::Unset),
             None => Err(FieldAccessError::Unknown),
@@ -215,8 +239,11 @@ impl Content {
     /// if you have set the field IDs yourself or are using the field IDs
     /// generated by the `#[elem]` macro.
     pub fn field(&self, id: u8) -> StrResult<Value> {
-        self.get(id, None)
-            .map_err(|e| e.message(self, self.elem().field_name(id).unwrap()))
+        let name = match self.to_packed::<CustomElem>() {
+            Some(elem) => elem.field_name(id),
+            None => self.elem().field_name(id),
+        };
+        self.get(id, None).map_err(|e| e.message(self, name.unwrap()))
     }
 
     /// Get a field by name, returning a missing field error if it does not
@@ -230,6 +257,11 @@ impl Content {
 
     /// Resolve all fields with the styles and save them in-place.
     pub fn materialize(&mut self, styles: StyleChain) {
+        if let Some(elem) = self.to_packed_mut::<CustomElem>() {
+            elem.materialize(styles);
+            return;
+        }
+
         for id in 0..self.elem().vtable().fields.len() as u8 {
             self.0.handle_mut().field(id).unwrap().materialize(styles);
         }
@@ -513,9 +545,12 @@ impl Content {
     /// element contained in this content. It can be used in set and show rules
     /// for the element. Can be compared with global functions to check whether
     /// you have a specific kind of element.
-    #[func]
-    pub fn func(&self) -> Element {
-        self.elem()
+    #[func(name = "func")]
+    pub fn elem_func(&self) -> Func {
+        match self.to_packed::<CustomElem>() {
+            Some(elem) => elem.func(),
+            None => self.elem().into(),
+        }
     }
 
     /// Whether the content has the specified field.
@@ -529,6 +564,10 @@ impl Content {
             return self.label().is_some();
         }
 
+        if let Some(elem) = self.to_packed::<CustomElem>() {
+            return elem.field_id(&field).is_some_and(|id| elem.has(id));
+        }
+
         let Some(id) = self.elem().field_id(&field) else {
             return false;
         };
@@ -567,6 +606,11 @@ impl Content {
     #[func]
     pub fn fields(&self) -> Dict {
         let mut dict = Dict::new();
+        if let Some(elem) = self.to_packed::<CustomElem>() {
+            for (name, value) in elem.fields() {
+                dict.insert(name.into(), value.clone());
+            }
+        }
         for field in self.0.handle().fields() {
             if let Some(value) = field.get() {
                 dict.insert(field.name.into(), value);
@@ -587,6 +631,55 @@ impl Content {
     pub fn location(&self) -> Option<Location> {
         self.0.meta().location
     }
+
+    /// Defines a custom element with typed fields.
+    ///
+    /// The result is an element function, just like the built-in ones: Calling
+    /// it creates the element, and it can be used in set rules, show rules,
+    /// selectors, and queries. Fields with a default value can be configured
+    /// with set rules, the others are required when creating the element. The
+    /// `display` function determines the element's default appearance and can
+    /// be overridden with a show rule.
+    ///
+    /// ```example
+    /// #let callout = content.define(
+    ///   "callout",
+    ///   it => block(
+    ///     fill: if it.kind == "warning" { red.lighten(80%) } else { luma(230) },
+    ///     inset: 6pt,
+    ///     it.body,
+    ///   ),
+    ///   fields: (
+    ///     body: (type: content, positional: true),
+    ///     kind: (type: str, default: "note"),
+    ///   ),
+    /// )
+    ///
+    /// #callout[A note.]
+    /// #set callout(kind: "warning")
+    /// #callout[A warning.]
+    /// #show callout.where(kind: "warning"): strong
+    /// #callout[A strong warning.]
+    /// ```
+    #[func]
+    pub fn define(
+        span: Span,
+        /// The name of the element.
+        name: Str,
+        /// Produces the element's default appearance. Receives the element with
+        /// all its fields resolved. It is called with
+        /// @reference:context[context], so it can use contextual functions.
+        display: Func,
+        /// The element's fields. A field maps either to a type, which makes it
+        /// a required named field of that type, or to a dictionary with an
+        /// optional `type`, an optional `default` value, and an optional
+        /// `positional` flag. Fields with a `default` are settable.
+        #[named]
+        #[default]
+        fields: Dict,
+    ) -> StrResult<Func> {
+        CustomElemDef::define(name, fields, display, span)
+    }
 }
 
 impl Default for Content {
@@ -609,6 +702,10 @@ impl<T: NativeElement> From<T> for Content {
 
 impl Repr for Content {
     fn repr(&self) -> EcoString {
+        if let Some(elem) = self.to_packed::<CustomElem>() {
+            return elem.repr();
+        }
+
         self.0.handle().repr().unwrap_or_else(|| {
             let fields = self
                 .0
@@ -697,7 +794,7 @@ impl Serialize for Content {
         S: Serializer,
     {
         serializer.collect_map(
-            iter::once(("func".into(), self.func().name().into_value()))
+            iter::once(("func".into(), self.elem_func().name().into_value()))
                 .chain(self.fields()),
         )
     }
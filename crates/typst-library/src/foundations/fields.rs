@@ -13,8 +13,8 @@ use crate::visualize::Stroke;
 /// stroke and length.
 pub(crate) fn field(value: &Value, field: &str) -> StrResult<Value> {
     let ty = value.ty();
-    let nope = || Err(no_fields(ty));
-    let missing = || Err(missing_field(ty, field));
+    let nope = || Err(no_fields(&ty));
+    let missing = || Err(missing_field(&ty, field));
 
     // Special cases, such as module and dict, are handled by Value itself
     let result = match value {
@@ -63,27 +63,27 @@ pub(crate) fn field(value: &Value, field: &str) -> StrResult<Value> {
 
 /// The error message for a type not supporting field access.
 #[cold]
-fn no_fields(ty: Type) -> EcoString {
+fn no_fields(ty: &Type) -> EcoString {
     eco_format!("cannot access fields on type {ty}")
 }
 
 /// The missing field error message.
 #[cold]
-fn missing_field(ty: Type, field: &str) -> EcoString {
+fn missing_field(ty: &Type, field: &str) -> EcoString {
     eco_format!("{ty} does not contain field \"{field}\"")
 }
 
 /// List the available fields for a type.
-pub fn fields_on(ty: Type) -> &'static [&'static str] {
-    if ty == Type::of::<Version>() {
+pub fn fields_on(ty: &Type) -> &'static [&'static str] {
+    if *ty == Type::of::<Version>() {
         &Version::COMPONENTS
-    } else if ty == Type::of::<Length>() {
+    } else if *ty == Type::of::<Length>() {
         &["em", "abs"]
-    } else if ty == Type::of::<Rel>() {
+    } else if *ty == Type::of::<Rel>() {
         &["ratio", "length"]
-    } else if ty == Type::of::<Stroke>() {
+    } else if *ty == Type::of::<Stroke>() {
         &["paint", "thickness", "cap", "join", "dash", "miter-limit"]
-    } else if ty == Type::of::<Alignment>() {
+    } else if *ty == Type::of::<Alignment>() {
         &["x", "y"]
     } else {
         &[]
@@ -11,11 +11,12 @@ use either::Either;
 use typst_syntax::{Span, Spanned, SyntaxNode, ast};
 use typst_utils::{DefSite, LazyHash, Static, singleton};
 
-use crate::diag::{At, SourceResult, StrResult, WarningSink, bail};
+use crate::diag::{SourceResult, StrResult, WarningSink, bail};
 use crate::engine::Engine;
 use crate::foundations::{
-    Args, Bytes, CastInfo, Content, Context, Element, IntoArgs, PluginFunc, Repr, Scope,
-    Selector, Type, Value, cast, scope, ty,
+    Args, CastInfo, Content, Context, CustomElemDef, CustomField, Element, IntoArgs,
+    PluginFunc, PluginParamInfo, RecordField, RecordType, Repr, Scope, Selector, Type,
+    Value, cast, scope, ty,
 };
 
 /// A mapping from argument values to a return value.
@@ -108,6 +109,28 @@ use crate::foundations::{
 /// ]
 /// ```
 ///
+/// = Type annotations <type-annotations>
+/// Parameters and return values can optionally be annotated with a
+/// @type[type]. A positional parameter is annotated as `{x as int}` and a named
+/// parameter as `{size as length: 1pt}`. The return type follows the parameter
+/// list after a thin arrow: `{(body) -> content => ..}`. When the function is
+/// called, each argument is checked against its parameter's annotation and the
+/// error points at the offending argument. Like the default values, annotations
+/// are evaluated when the function is defined. Values are converted like the
+/// arguments of built-in functions, so an integer is accepted for a `{float}`
+/// parameter and a string for a `{content}` parameter.
+///
+/// ```example
+/// #let badge(count as int, fill as color: red) -> content = box(
+///   fill: fill,
+///   inset: 3pt,
+///   radius: 2pt,
+///   text(white)[#count],
+/// )
+///
+/// New messages: #badge(3)
+/// ```
+///
 /// = Importing functions <importing-functions>
 /// Functions can be imported from one file
 /// (@reference:scripting:modules[`module`]) into another using `{import}`. For
@@ -158,6 +181,10 @@ enum FuncInner {
     Plugin(Arc<PluginFunc>),
     /// A nested function with pre-applied arguments.
     With(Arc<(Func, Args)>),
+    /// The constructor of a custom type.
+    Record(Arc<LazyHash<RecordType>>),
+    /// A function for an element defined in Typst code.
+    CustomElem(Arc<LazyHash<CustomElemDef>>),
 }
 
 impl Func {
@@ -171,6 +198,8 @@ impl Func {
             FuncInner::Closure(closure) => closure.name(),
             FuncInner::Plugin(func) => Some(func.name()),
             FuncInner::With(with) => with.0.name(),
+            FuncInner::Record(record) => Some(record.name()),
+            FuncInner::CustomElem(def) => Some(def.name()),
         }
     }
 
@@ -184,6 +213,8 @@ impl Func {
             FuncInner::Closure(_) => None,
             FuncInner::Plugin(_) => None,
             FuncInner::With(with) => with.0.title(),
+            FuncInner::Record(_) => None,
+            FuncInner::CustomElem(_) => None,
         }
     }
 
@@ -195,6 +226,8 @@ impl Func {
             FuncInner::Closure(_) => None,
             FuncInner::Plugin(_) => None,
             FuncInner::With(with) => with.0.docs(),
+            FuncInner::Record(_) => None,
+            FuncInner::CustomElem(_) => None,
         }
     }
 
@@ -218,11 +251,37 @@ impl Func {
             FuncInner::Closure(closure) => {
                 Either::Right(Either::Left(closure.params().map(ParamInfo::Closure)))
             }
-            FuncInner::Plugin(_) => {
-                Either::Right(Either::Right([ParamInfo::Plugin].into_iter()))
-            }
+            FuncInner::Plugin(func) => Either::Right(Either::Right(
+                match func.signature() {
+                    Some(signature) => signature
+                        .params
+                        .iter()
+                        .cloned()
+                        .map(ParamInfo::PluginDeclared)
+                        .collect(),
+                    None => vec![ParamInfo::Plugin],
+                }
+                .into_iter(),
+            )),
             // TODO: We could take into account the known arguments.
             FuncInner::With(with) => with.0.params(),
+            FuncInner::Record(record) => Either::Right(Either::Right(
+                record
+                    .fields()
+                    .iter()
+                    .cloned()
+                    .map(ParamInfo::Record)
+                    .collect::<Vec<_>>()
+                    .into_iter(),
+            )),
+            FuncInner::CustomElem(def) => Either::Right(Either::Right(
+                def.fields()
+                    .iter()
+                    .cloned()
+                    .map(ParamInfo::Custom)
+                    .collect::<Vec<_>>()
+                    .into_iter(),
+            )),
         }
     }
 
@@ -235,12 +294,13 @@ impl Func {
     pub fn returns(&self) -> Option<&'static CastInfo> {
         match &self.inner {
             FuncInner::Native(native) => Some(&native.0.returns),
-            FuncInner::Element(_) => {
+            FuncInner::Element(_) | FuncInner::CustomElem(_) => {
                 Some(singleton!(CastInfo, CastInfo::Type(Type::of::<Content>())))
             }
             FuncInner::Closure(_) => None,
             FuncInner::Plugin(_) => None,
             FuncInner::With(with) => with.0.returns(),
+            FuncInner::Record(_) => None,
         }
     }
 
@@ -252,6 +312,8 @@ impl Func {
             FuncInner::Closure(_) => &[],
             FuncInner::Plugin(_) => &[],
             FuncInner::With(with) => with.0.keywords(),
+            FuncInner::Record(_) => &[],
+            FuncInner::CustomElem(_) => &[],
         }
     }
 
@@ -274,6 +336,8 @@ impl Func {
             FuncInner::Closure(_) => None,
             FuncInner::Plugin(_) => None,
             FuncInner::With(with) => with.0.scope(),
+            FuncInner::Record(_) => None,
+            FuncInner::CustomElem(_) => None,
         }
     }
 
@@ -302,6 +366,15 @@ impl Func {
         }
     }
 
+    /// Extract the definition of a user-defined element function, if it is
+    /// one.
+    pub fn to_custom_elem(&self) -> Option<&Arc<LazyHash<CustomElemDef>>> {
+        match &self.inner {
+            FuncInner::CustomElem(def) => Some(def),
+            _ => None,
+        }
+    }
+
     /// Extract the plugin function, if it is one.
     pub fn to_plugin(&self) -> Option<&PluginFunc> {
         match &self.inner {
@@ -346,21 +419,28 @@ impl Func {
                 engine.library,
                 engine.introspector.into_raw(),
                 engine.traced,
+                engine.cancellation,
+                engine.fuel,
                 TrackedMut::reborrow_mut(&mut engine.sink),
                 engine.route.track(),
                 context,
                 args,
             ),
-            FuncInner::Plugin(func) => {
-                let inputs = args.all::<Bytes>()?;
-                let output = func.call(inputs).at(args.span)?;
-                args.finish()?;
-                Ok(Value::Bytes(output))
-            }
+            FuncInner::Plugin(func) => func.call_with(engine, args),
             FuncInner::With(with) => {
                 args.items = with.1.items.iter().cloned().chain(args.items).collect();
                 with.0.call(engine, context, args)
             }
+            FuncInner::Record(record) => {
+                let value = RecordType::construct(record, &mut args)?;
+                args.finish()?;
+                Ok(Value::Record(value))
+            }
+            FuncInner::CustomElem(def) => {
+                let value = CustomElemDef::construct(def, &mut args)?;
+                args.finish()?;
+                Ok(Value::Content(value))
+            }
         }
     }
 
@@ -418,6 +498,10 @@ impl Func {
         let fields = args.to_named();
         args.items.retain(|arg| arg.name.is_none());
 
+        if let Some(def) = self.to_custom_elem() {
+            return CustomElemDef::where_(def, fields);
+        }
+
         let element = self
             .to_element()
             .ok_or("`where()` can only be called on element functions")?;
@@ -454,6 +538,8 @@ impl Repr for Func {
             FuncInner::Closure(closure) => closure.name().unwrap_or(DEFAULT).into(),
             FuncInner::Plugin(func) => func.name().clone(),
             FuncInner::With(_) => DEFAULT.into(),
+            FuncInner::Record(record) => record.name().into(),
+            FuncInner::CustomElem(def) => def.name().into(),
         }
     }
 }
@@ -512,6 +598,18 @@ impl From<PluginFunc> for Func {
     }
 }
 
+impl From<Arc<LazyHash<RecordType>>> for Func {
+    fn from(record: Arc<LazyHash<RecordType>>) -> Self {
+        FuncInner::Record(record).into()
+    }
+}
+
+impl From<Arc<LazyHash<CustomElemDef>>> for Func {
+    fn from(def: Arc<LazyHash<CustomElemDef>>) -> Self {
+        FuncInner::CustomElem(def).into()
+    }
+}
+
 /// Details about a function parameter.
 #[derive(Debug, Clone)]
 pub enum ParamInfo {
@@ -519,8 +617,14 @@ pub enum ParamInfo {
     Native(&'static NativeParamInfo),
     /// Details about a user-defined function.
     Closure(Spanned<ClosureParamInfo>),
-    /// A plugin's sole variadic bytes parameter.
+    /// A plugin's sole variadic parameter.
     Plugin,
+    /// A parameter that a plugin declared for one of its functions.
+    PluginDeclared(PluginParamInfo),
+    /// A field of a user-defined element.
+    Custom(CustomField),
+    /// A field of a user-defined type, given to its constructor.
+    Record(RecordField),
 }
 
 impl ParamInfo {
@@ -537,11 +641,34 @@ impl ParamInfo {
         match self {
             Self::Native(info) => Some(info.name),
             Self::Closure(info) => match &info.v {
-                ClosureParamInfo::Pos { name } => name.as_deref(),
+                ClosureParamInfo::Pos { name, .. } => name.as_deref(),
                 ClosureParamInfo::Sink { name } => name.as_deref(),
                 ClosureParamInfo::Named { name, .. } => Some(name),
             },
             Self::Plugin => None,
+            Self::PluginDeclared(info) => Some(&info.name),
+            Self::Custom(field) => Some(field.name()),
+            Self::Record(field) => Some(field.name()),
+        }
+    }
+
+    /// The values the parameter accepts, if known.
+    pub fn input(&self) -> Option<CastInfo> {
+        match self {
+            Self::Native(info) => Some(info.input.clone()),
+            Self::Closure(info) => match &info.v {
+                ClosureParamInfo::Pos { ty, .. } | ClosureParamInfo::Named { ty, .. } => {
+                    ty.clone().map(CastInfo::Type)
+                }
+                ClosureParamInfo::Sink { .. } => None,
+            },
+            Self::Plugin | Self::PluginDeclared(_) => None,
+            Self::Custom(field) => {
+                Some(field.ty().cloned().map_or(CastInfo::Any, CastInfo::Type))
+            }
+            Self::Record(field) => {
+                Some(field.ty().cloned().map_or(CastInfo::Any, CastInfo::Type))
+            }
         }
     }
 
@@ -554,6 +681,9 @@ impl ParamInfo {
                 _ => None,
             },
             Self::Plugin => None,
+            Self::PluginDeclared(info) => info.default.clone(),
+            Self::Custom(field) => field.default().cloned(),
+            Self::Record(field) => field.default().cloned(),
         }
     }
 
@@ -566,6 +696,9 @@ impl ParamInfo {
                 ClosureParamInfo::Pos { .. } | ClosureParamInfo::Sink { .. }
             ),
             Self::Plugin => true,
+            Self::PluginDeclared(info) => !info.named,
+            Self::Custom(field) => field.positional(),
+            Self::Record(_) => false,
         }
     }
 
@@ -575,6 +708,9 @@ impl ParamInfo {
             Self::Native(info) => info.named,
             Self::Closure(info) => matches!(&info.v, ClosureParamInfo::Named { .. }),
             Self::Plugin => false,
+            Self::PluginDeclared(info) => info.named,
+            Self::Custom(field) => !field.positional(),
+            Self::Record(_) => true,
         }
     }
 
@@ -584,6 +720,7 @@ impl ParamInfo {
             Self::Native(info) => info.variadic,
             Self::Closure(info) => matches!(&info.v, ClosureParamInfo::Sink { .. }),
             Self::Plugin => true,
+            Self::PluginDeclared(_) | Self::Custom(_) | Self::Record(_) => false,
         }
     }
 
@@ -593,6 +730,9 @@ impl ParamInfo {
             Self::Native(info) => info.required,
             Self::Closure(info) => matches!(&info.v, ClosureParamInfo::Pos { .. }),
             Self::Plugin => false,
+            Self::PluginDeclared(info) => !info.named && info.default.is_none(),
+            Self::Custom(field) => field.default().is_none(),
+            Self::Record(field) => field.default().is_none(),
         }
     }
 
@@ -601,7 +741,9 @@ impl ParamInfo {
         match self {
             Self::Native(info) => info.settable,
             Self::Closure(_) => false,
-            Self::Plugin => false,
+            Self::Plugin | Self::PluginDeclared(_) => false,
+            Self::Custom(field) => field.default().is_some(),
+            Self::Record(_) => false,
         }
     }
 }
@@ -719,6 +861,10 @@ pub struct Closure {
     pub captured: Scope,
     /// The number of positional parameters in the closure.
     pub num_pos_params: usize,
+    /// The annotated types of the parameters, in parameter order.
+    pub types: Vec<Option<Type>>,
+    /// The annotated return type, if any.
+    pub returns: Option<Type>,
 }
 
 impl Closure {
@@ -745,22 +891,23 @@ impl Closure {
         };
 
         let mut defaults = self.defaults.iter();
+        let mut types = self.types.iter().cloned();
         params.into_iter().flatten().map(move |param| {
+            let ty = types.next().flatten();
             let info = match param {
-                ast::Param::Pos(pattern) => ClosureParamInfo::Pos {
-                    name: match pattern {
-                        ast::Pattern::Normal(ast::Expr::Ident(ident)) => {
-                            Some(ident.get().clone())
-                        }
-                        _ => None,
-                    },
-                },
+                ast::Param::Pos(pattern) => {
+                    ClosureParamInfo::Pos { name: pattern_name(pattern), ty }
+                }
+                ast::Param::Typed(typed) => {
+                    ClosureParamInfo::Pos { name: pattern_name(typed.pattern()), ty }
+                }
                 ast::Param::Spread(spread) => ClosureParamInfo::Sink {
                     name: spread.sink_ident().map(|ident| ident.get().clone()),
                 },
                 ast::Param::Named(named) => ClosureParamInfo::Named {
                     name: named.name().get().clone(),
                     default: defaults.next().unwrap().clone(),
+                    ty,
                 },
             };
             Spanned::new(info, param.span())
@@ -768,6 +915,14 @@ impl Closure {
     }
 }
 
+/// The name of a positional parameter, if it isn't a destructuring pattern.
+fn pattern_name(pattern: ast::Pattern) -> Option<EcoString> {
+    match pattern {
+        ast::Pattern::Normal(ast::Expr::Ident(ident)) => Some(ident.get().clone()),
+        _ => None,
+    }
+}
+
 cast! {
     Closure,
     self => Value::Func(self.into()),
@@ -778,10 +933,10 @@ cast! {
 pub enum ClosureParamInfo {
     /// A positional parameter. It might have a name, but it could also be a
     /// pattern.
-    Pos { name: Option<EcoString> },
+    Pos { name: Option<EcoString>, ty: Option<Type> },
     /// A sink parameter. Might have a name, but could also just be a discarding
     /// sink.
     Sink { name: Option<EcoString> },
     /// A named parameter with its name and default value.
-    Named { name: EcoString, default: Value },
+    Named { name: EcoString, default: Value, ty: Option<Type> },
 }
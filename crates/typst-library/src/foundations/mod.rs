@@ -27,6 +27,7 @@ mod none;
 mod path;
 #[path = "plugin.rs"]
 mod plugin_;
+mod record;
 mod scope;
 mod selector;
 mod str;
@@ -58,6 +59,7 @@ pub use self::module::*;
 pub use self::none::*;
 pub use self::path::*;
 pub use self::plugin_::*;
+pub use self::record::*;
 pub use self::repr::Repr;
 pub use self::scope::*;
 pub use self::selector::*;
@@ -305,6 +307,8 @@ pub fn eval(
     (engine.library.routines.eval_string)(
         engine.world,
         engine.library,
+        engine.cancellation,
+        engine.fuel,
         TrackedMut::reborrow_mut(&mut engine.sink),
         // We create a new, detached introspector for string evaluation. Passing
         // the real introspector should not have any consequences with
@@ -2,14 +2,23 @@ use std::fmt::{self, Debug, Formatter};
 use std::hash::{Hash, Hasher};
 use std::sync::{Arc, Mutex};
 
-use ecow::{EcoString, eco_format};
-use typst_syntax::Spanned;
+use comemo::{Track, Tracked, TrackedMut};
+use ecow::{EcoString, EcoVec, eco_format, eco_vec};
+use rustc_hash::FxHashMap;
+use serde::Deserialize;
+use typst_syntax::{FileId, Span, Spanned, SyntaxMode};
 use wasmi::Memory;
 
-use crate::diag::{At, SourceResult, StrResult, bail};
+use crate::World;
+use crate::diag::{At, HintedString, SourceDiagnostic, SourceResult, StrResult, bail};
 use crate::engine::Engine;
-use crate::foundations::{Binding, Bytes, Func, Module, Scope, Value, cast, func, scope};
+use crate::foundations::{
+    Args, Array, Binding, Bytes, Context, Dict, Func, Module, PathOrStr, Scope, Value,
+    cast, func, scope,
+};
+use crate::introspection::EmptyIntrospector;
 use crate::loading::{DataSource, Load};
+use crate::routines::SpanMode;
 
 /// Loads a WebAssembly module.
 ///
@@ -23,10 +32,13 @@ use crate::loading::{DataSource, Load};
 /// They should typically be wrapped in idiomatic Typst functions that perform
 /// the necessary conversions between native Typst types and bytes by leveraging
 /// @str.constructor[`str`], @bytes.constructor[`bytes`], and
-/// @reference:data-loading[data loading functions].
+/// @reference:data-loading[data loading functions]. Alternatively, plugins can
+/// @plugin:structured-values[exchange Typst values] directly.
 ///
 /// For security reasons, plugins run in isolation from your system. This means
-/// that printing, reading files, or similar things are not supported.
+/// that printing, network access, or similar things are not supported. Plugins
+/// can only read the files from your project that you
+/// @plugin:file-access[grant them access to].
 ///
 /// = Example <example>
 /// ```example
@@ -73,6 +85,23 @@ use crate::loading::{DataSource, Load};
 /// will observe the side effects produced by the transition call. The original
 /// plugin remains unaffected.
 ///
+/// = File access <file-access>
+/// By default, plugins cannot read any files. When loading a plugin, you can
+/// grant it access to specific files with the `files` argument. Its functions
+/// may then request these files by the exact paths that were granted. Paths are
+/// resolved like with the @read function, relative to the file in which the
+/// plugin is loaded, no matter where its functions are called. Like all file
+/// access in Typst, this is limited to the project or package root.
+///
+/// ```typ
+/// #let renderer = plugin("diagram.wasm", files: ("figures/setup.diag",))
+/// #renderer.render(bytes("figures/setup.diag"))
+/// ```
+///
+/// The granted files are read when a plugin function is called and are tracked
+/// like any other file that your document depends on: When such a file
+/// changes, the plugin call is re-run.
+///
 /// = Plugins and Packages <plugins-and-packages>
 /// Any Typst code can make use of a plugin simply by including a WebAssembly
 /// file and loading it. However, because the byte-based plugin interface is
@@ -136,6 +165,54 @@ use crate::loading::{DataSource, Load};
 ///   immediately after this function returns. If the message should be
 ///   interpreted as an error message, it should be encoded as UTF-8.
 ///
+/// Plugins that want to @plugin:file-access[read files] can additionally
+/// import the following two functions:
+///
+/// - `(import "typst_env" "wasm_minimal_protocol_read_file" (func (param i32 i32) (result i32)))`
+///
+///   Requests a file. The parameters are a pointer to and the length of a
+///   UTF-8 encoded path. Returns the length of the file or `-1` if it could not
+///   be read. If the plugin was not granted access to the path, the call fails.
+///
+/// - `(import "typst_env" "wasm_minimal_protocol_write_file_to_buffer" (func (param i32)))`
+///
+///   Writes the most recently requested file into a plugin-allocated buffer
+///   whose capacity is at least the length returned by
+///   `wasm_minimal_protocol_read_file`.
+///
+/// == Structured values <structured-values>
+/// Instead of exchanging raw bytes, a plugin can opt into exchanging Typst
+/// values. Its functions can then be called like normal Typst functions,
+/// without any wrappers to convert between bytes and values.
+///
+/// To opt in, a plugin exports a function `typst_plugin_metadata` that takes
+/// no arguments and follows the export protocol described above. It sends a
+/// #link("https://cbor.io/")[CBOR]-encoded map to the host with the following
+/// keys:
+///
+/// - `abi`: Must be the string `"values"`.
+/// - `functions` (optional): A map from function names to their signatures.
+///   A signature is a map with an optional `docs` string and an optional
+///   `params` array. Each parameter is a map with a `name`, an optional `docs`
+///   string, an optional `named` boolean, and an optional `default` value.
+///   Signatures are used to match the arguments of calls as well as for
+///   autocompletion and tooltips in editors.
+///
+/// With this ABI, each argument is passed as a separate CBOR-encoded buffer,
+/// in the same way that @cbor.encode would encode it. For a function with a
+/// signature, there is one buffer per declared parameter, with missing
+/// arguments filled in with their default value or `{none}`. For a function
+/// without a signature, there is one buffer per positional argument.
+///
+/// The result buffer is decoded from CBOR like with the @cbor function.
+/// Additionally, a text string tagged with the CBOR tag `29817` is evaluated as
+/// Typst markup and turns into @content. If the function signals an error,
+/// the buffer must contain either a CBOR string with the message or a map with
+/// a `message` string and an optional `hints` array of strings.
+///
+/// Values passed to @plugin.transition must still be bytes. For functions of
+/// plugins that use this ABI, encode them with @cbor.encode.
+///
 /// = Resources <resources>
 /// For more resources, check out the
 /// #link("https://github.com/typst-community/wasm-minimal-protocol")[wasm-minimal-protocol repository].
@@ -150,9 +227,26 @@ pub fn plugin(
     engine: &mut Engine,
     /// A path to a WebAssembly file or raw WebAssembly bytes.
     source: Spanned<DataSource>,
+    /// The paths of the files that the plugin may read. See the section on
+    /// @plugin:file-access[file access] for details.
+    #[named]
+    files: Option<Spanned<Vec<EcoString>>>,
 ) -> SourceResult<Module> {
     let loaded = source.load(engine.world)?;
-    Plugin::module(loaded.data).at(source.span)
+
+    // Grants are resolved relative to the file that loads the plugin, so that
+    // they mean the same when the plugin functions are called elsewhere.
+    let mut grants = vec![];
+    if let Some(Spanned { v: paths, span }) = files {
+        for path in paths {
+            let resolved = PathOrStr::Str(path.as_str().into())
+                .resolve_if_some(span.id())
+                .at(span)?;
+            grants.push((path, resolved.intern()));
+        }
+    }
+
+    Plugin::module(loaded.data, engine.library.limits.plugin_fuel, grants).at(source.span)
 }
 
 #[scope]
@@ -191,13 +285,15 @@ impl plugin {
     /// ```
     #[func]
     pub fn transition(
+        engine: &mut Engine,
+        span: Span,
         /// The plugin function to call.
         func: PluginFunc,
         /// The byte buffers to call the function with.
         #[variadic]
         arguments: Vec<Bytes>,
-    ) -> StrResult<Module> {
-        func.transition(arguments)
+    ) -> SourceResult<Module> {
+        func.transition(engine.world, arguments, span)
     }
 }
 
@@ -216,18 +312,124 @@ impl PluginFunc {
         &self.name
     }
 
+    /// Whether the plugin exchanges Typst values instead of bytes.
+    pub fn structured(&self) -> bool {
+        self.plugin.base.metadata.is_some()
+    }
+
+    /// The signature the plugin declared for this function, if any.
+    pub fn signature(&self) -> Option<&PluginSignature> {
+        self.plugin.base.metadata.as_ref()?.functions.get(&self.name)
+    }
+
+    /// The documentation the plugin declared for this function, if any.
+    pub fn docs(&self) -> Option<&EcoString> {
+        self.signature()?.docs.as_ref()
+    }
+
+    /// Call the function with the arguments of a Typst function call.
+    pub fn call_with(&self, engine: &mut Engine, mut args: Args) -> SourceResult<Value> {
+        if !self.structured() {
+            let inputs = args.all::<Bytes>()?;
+            let output = self.call(engine.world, inputs, args.span)?;
+            args.finish()?;
+            return Ok(Value::Bytes(output));
+        }
+
+        let values = match self.signature() {
+            Some(signature) => {
+                let mut values = vec![];
+                for param in &signature.params {
+                    let value = if param.named {
+                        args.named::<Value>(&param.name)?
+                    } else if param.default.is_some() {
+                        args.eat::<Value>()?
+                    } else {
+                        Some(args.expect::<Value>(&param.name)?)
+                    };
+                    values.push(
+                        value.or_else(|| param.default.clone()).unwrap_or(Value::None),
+                    );
+                }
+                values
+            }
+            None => args.all::<Value>()?,
+        };
+        let span = args.span;
+        args.finish()?;
+
+        let inputs = values
+            .iter()
+            .map(|value| {
+                let mut buf = Vec::new();
+                ciborium::into_writer(value, &mut buf)
+                    .map(|_| Bytes::new(buf))
+                    .map_err(|err| {
+                        eco_format!("failed to encode argument as CBOR ({err})")
+                    })
+            })
+            .collect::<StrResult<_>>()
+            .at(span)?;
+
+        match self.call_raw(engine.world, inputs).map_err(|err| err.at(span))? {
+            Output::Success(output) => {
+                let value = ciborium::from_reader(output.as_slice())
+                    .map_err(|err| eco_format!("plugin returned invalid CBOR ({err})"))
+                    .at(span)?;
+                decode_value(engine, value, span)
+            }
+            Output::Failure(output) => Err::<Value, _>(decode_error(&output)).at(span),
+        }
+    }
+
     /// Call the WebAssembly function with the given arguments.
+    ///
+    /// If the plugin may read files, it reads them through the `world`.
+    pub fn call(
+        &self,
+        world: Tracked<dyn World + '_>,
+        args: Vec<Bytes>,
+        span: Span,
+    ) -> SourceResult<Bytes> {
+        match self.call_raw(world, args).map_err(|err| err.at(span))? {
+            Output::Success(output) => Ok(output),
+            Output::Failure(output) => match std::str::from_utf8(&output) {
+                Ok(message) => bail!(span, "plugin errored with: {message}"),
+                Err(_) => {
+                    bail!(
+                        span,
+                        "plugin errored, but did not return a valid error message"
+                    )
+                }
+            },
+        }
+    }
+
+    /// Call the WebAssembly function and return its output, regardless of
+    /// whether it signalled success or failure.
     #[comemo::memoize]
     #[typst_macros::time(name = "call plugin")]
-    pub fn call(&self, args: Vec<Bytes>) -> StrResult<Bytes> {
-        self.plugin.call(&self.name, args)
+    fn call_raw(
+        &self,
+        world: Tracked<dyn World + '_>,
+        args: Vec<Bytes>,
+    ) -> Result<Output, CallError> {
+        self.plugin.call(&self.name, args, world)
     }
 
     /// Transition a plugin and turn the result into a module.
     #[comemo::memoize]
     #[typst_macros::time(name = "transition plugin")]
-    pub fn transition(&self, args: Vec<Bytes>) -> StrResult<Module> {
-        self.plugin.transition(&self.name, args).map(Plugin::into_module)
+    pub fn transition(
+        &self,
+        world: Tracked<dyn World + '_>,
+        args: Vec<Bytes>,
+        span: Span,
+    ) -> SourceResult<Module> {
+        self.plugin
+            .transition(&self.name, args, world)
+            .map(Plugin::into_module)
+            .map_err(|err| err.at(span))
     }
 }
 
@@ -260,16 +462,26 @@ impl Plugin {
     /// Create a plugin and turn it into a module.
     #[comemo::memoize]
     #[typst_macros::time(name = "load plugin")]
-    fn module(bytes: Bytes) -> StrResult<Module> {
-        Self::new(bytes).map(Self::into_module)
+    fn module(
+        bytes: Bytes,
+        fuel: Option<u64>,
+        files: Vec<(EcoString, FileId)>,
+    ) -> StrResult<Module> {
+        Self::new(bytes, fuel, files).map(Self::into_module)
     }
 
-    /// Create a new plugin from raw WebAssembly bytes.
-    fn new(bytes: Bytes) -> StrResult<Self> {
+    /// Create a new plugin from raw WebAssembly bytes, optionally limiting the
+    /// fuel that each call may consume and granting access to files.
+    fn new(
+        bytes: Bytes,
+        fuel: Option<u64>,
+        files: Vec<(EcoString, FileId)>,
+    ) -> StrResult<Self> {
         let mut config = wasmi::Config::default();
 
         // Disable relaxed SIMD as it can introduce non-determinism.
         config.wasm_relaxed_simd(false);
+        config.consume_fuel(fuel.is_some());
 
         let engine = wasmi::Engine::new(&config);
         let module = wasmi::Module::new(&engine, bytes.as_slice())
@@ -295,9 +507,38 @@ impl Plugin {
                 wasm_minimal_protocol_write_args_to_buffer,
             )
             .unwrap();
+        linker
+            .func_wrap(
+                "typst_env",
+                "wasm_minimal_protocol_read_file",
+                wasm_minimal_protocol_read_file,
+            )
+            .unwrap();
+        linker
+            .func_wrap(
+                "typst_env",
+                "wasm_minimal_protocol_write_file_to_buffer",
+                wasm_minimal_protocol_write_file_to_buffer,
+            )
+            .unwrap();
 
-        let base = Arc::new(PluginBase { bytes, linker, module });
-        let instance = PluginInstance::new(&base, None)?;
+        let reads_files = module.imports().any(|import| {
+            import.module() == "typst_env"
+                && import.name() == "wasm_minimal_protocol_read_file"
+        });
+
+        let mut base = PluginBase {
+            bytes,
+            linker,
+            module,
+            fuel,
+            files,
+            reads_files,
+            metadata: None,
+        };
+        let mut instance = PluginInstance::new(&base, None)?;
+        base.metadata = PluginMetadata::load(&base.module, &mut instance)?;
+        let base = Arc::new(base);
 
         Ok(Self {
             base,
@@ -308,14 +549,16 @@ impl Plugin {
     }
 
     /// Execute a function with access to an instance.
-    fn call(&self, func: &str, args: Vec<Bytes>) -> StrResult<Bytes> {
-        // Acquire an instance from the pool (potentially creating a new one).
-        let mut instance = self.acquire()?;
-
+    fn call(
+        &self,
+        func: &str,
+        args: Vec<Bytes>,
+        world: Tracked<dyn World + '_>,
+    ) -> Result<Output, CallError> {
         // Execute the call on an instance from the pool. If the call fails, we
         // return early and _don't_ return the instance to the pool as it might
         // be irrecoverably damaged.
-        let output = instance.call(func, args)?;
+        let (instance, output) = self.run(func, args, world)?;
 
         // Return the instance to the pool.
         self.pool.lock().unwrap().push(instance);
@@ -325,16 +568,29 @@ impl Plugin {
 
     /// Call a mutable plugin function, producing a new mutable whose functions
     /// are guaranteed to be able to observe the mutation.
-    fn transition(&self, func: &str, args: Vec<Bytes>) -> StrResult<Plugin> {
+    fn transition(
+        &self,
+        func: &str,
+        args: Vec<Bytes>,
+        world: Tracked<dyn World + '_>,
+    ) -> Result<Plugin, CallError> {
         // Derive a new transition hash from the old one and the function and arguments.
         let fingerprint = typst_utils::hash128(&(self.fingerprint, func, &args));
 
-        // Execute the mutable call on an instance.
-        let mut instance = self.acquire()?;
-
         // Call the function. If the call fails, we return early and _don't_
         // return the instance to the pool as it might be irrecoverably damaged.
-        instance.call(func, args)?;
+        let (instance, output) = self.run(func, args, world)?;
+        if let Output::Failure(output) = output {
+            match &self.base.metadata {
+                Some(_) => return Err(decode_error(&output).message().clone().into()),
+                None => match std::str::from_utf8(&output) {
+                    Ok(message) => bail!("plugin errored with: {message}"),
+                    Err(_) => {
+                        bail!("plugin errored, but did not return a valid error message")
+                    }
+                },
+            }
+        }
 
         // Snapshot the instance after the mutable call.
         let snapshot = instance.snapshot();
@@ -351,6 +607,40 @@ impl Plugin {
         })
     }
 
+    /// Run a function on an instance from the pool, providing it with the
+    /// files it was granted access to.
+    fn run(
+        &self,
+        func: &str,
+        args: Vec<Bytes>,
+        world: Tracked<dyn World + '_>,
+    ) -> Result<(PluginInstance, Output), CallError> {
+        let files = self.load_files(world);
+        let mut instance = self.acquire()?;
+        let output = instance.call(func, args, files)?;
+        Ok((instance, output))
+    }
+
+    /// Loads the files the plugin was granted access to, by the path they were
+    /// granted under. Files that cannot be read map to `None`.
+    ///
+    /// Plugins that cannot request files don't read (and thus don't depend on)
+    /// any of them.
+    fn load_files(
+        &self,
+        world: Tracked<dyn World + '_>,
+    ) -> FxHashMap<EcoString, Option<Bytes>> {
+        if !self.base.reads_files {
+            return FxHashMap::default();
+        }
+
+        self.base
+            .files
+            .iter()
+            .map(|(path, id)| (path.clone(), world.file(*id).ok()))
+            .collect()
+    }
+
     /// Acquire an instance from the pool (or create a new one).
     fn acquire(&self) -> StrResult<PluginInstance> {
         // Don't use match to ensure that the lock is released before we create
@@ -369,7 +659,9 @@ impl Plugin {
         // Build a scope from the collected functions.
         let mut scope = Scope::new();
         for export in shared.base.module.exports() {
-            if matches!(export.ty(), wasmi::ExternType::Func(_)) {
+            if matches!(export.ty(), wasmi::ExternType::Func(_))
+                && export.name() != PluginMetadata::EXPORT
+            {
                 let name = EcoString::from(export.name());
                 let func = PluginFunc { plugin: shared.clone(), name: name.clone() };
                 scope.bind(name, Binding::detached(Func::from(func)));
@@ -388,13 +680,18 @@ impl Debug for Plugin {
 
 impl PartialEq for Plugin {
     fn eq(&self, other: &Self) -> bool {
-        self.base.bytes == other.base.bytes && self.fingerprint == other.fingerprint
+        self.base.bytes == other.base.bytes
+            && self.base.fuel == other.base.fuel
+            && self.base.files == other.base.files
+            && self.fingerprint == other.fingerprint
     }
 }
 
 impl Hash for Plugin {
     fn hash<H: Hasher>(&self, state: &mut H) {
         self.base.bytes.hash(state);
+        self.base.fuel.hash(state);
+        self.base.files.hash(state);
         self.fingerprint.hash(state);
     }
 }
@@ -407,6 +704,218 @@ struct PluginBase {
     module: wasmi::Module,
     /// A linker used to create a `Store` for execution.
     linker: wasmi::Linker<CallData>,
+    /// The fuel available to each call, if it is limited.
+    fuel: Option<u64>,
+    /// The files the plugin may read, by the path they were granted under.
+    files: Vec<(EcoString, FileId)>,
+    /// Whether the plugin imports the function to request files.
+    reads_files: bool,
+    /// The metadata of a plugin that exchanges Typst values instead of bytes.
+    metadata: Option<PluginMetadata>,
+}
+
+/// Metadata declared by a plugin that exchanges Typst values.
+#[derive(Deserialize)]
+struct PluginMetadata {
+    /// The ABI used by the plugin.
+    abi: EcoString,
+    /// Signatures for the plugin's functions.
+    #[serde(default)]
+    functions: FxHashMap<EcoString, PluginSignature>,
+}
+
+impl PluginMetadata {
+    /// The name of the export through which a plugin declares its metadata.
+    const EXPORT: &str = "typst_plugin_metadata";
+
+    /// Retrieves the metadata from a plugin instance if the plugin provides
+    /// any.
+    fn load(
+        module: &wasmi::Module,
+        instance: &mut PluginInstance,
+    ) -> StrResult<Option<Self>> {
+        if !matches!(module.get_export(Self::EXPORT), Some(wasmi::ExternType::Func(_))) {
+            return Ok(None);
+        }
+
+        let output = match instance
+            .call(Self::EXPORT, vec![], FxHashMap::default())
+            .map_err(CallError::into_message)?
+        {
+            Output::Success(output) => output,
+            Output::Failure(output) => {
+                bail!(
+                    "failed to retrieve plugin metadata ({})",
+                    decode_error(&output).message()
+                )
+            }
+        };
+
+        let metadata: Self = ciborium::from_reader(output.as_slice())
+            .map_err(|err| eco_format!("plugin returned invalid metadata ({err})"))?;
+        if metadata.abi != "values" {
+            bail!("plugin uses unsupported ABI `{}`", metadata.abi);
+        }
+
+        Ok(Some(metadata))
+    }
+}
+
+/// The signature of a plugin function, as declared by the plugin.
+#[derive(Debug, Clone, Deserialize)]
+pub struct PluginSignature {
+    /// Documentation for the function.
+    #[serde(default)]
+    pub docs: Option<EcoString>,
+    /// The function's parameters.
+    #[serde(default)]
+    pub params: Vec<PluginParamInfo>,
+}
+
+/// Details about a parameter of a plugin function, as declared by the plugin.
+#[derive(Debug, Clone, Deserialize)]
+pub struct PluginParamInfo {
+    /// The parameter's name.
+    pub name: EcoString,
+    /// Documentation for the parameter.
+    #[serde(default)]
+    pub docs: Option<EcoString>,
+    /// Whether the parameter is named instead of positional.
+    #[serde(default)]
+    pub named: bool,
+    /// The value used when the argument is missing.
+    #[serde(default)]
+    pub default: Option<Value>,
+}
+
+/// The output of a plugin call that adhered to the protocol.
+#[derive(Debug, Clone, PartialEq, Hash)]
+enum Output {
+    /// The function succeeded with the given result.
+    Success(Bytes),
+    /// The function signalled an error with the given message.
+    Failure(Bytes),
+}
+
+/// An error that occurred while calling a plugin function.
+#[derive(Debug, Clone, PartialEq, Hash)]
+enum CallError {
+    /// The call failed with the given message.
+    Failed(EcoString),
+    /// The call ran out of fuel. Since this signals a compiler limit rather
+    /// than a problem with the plugin's inputs, it can't be caught.
+    OutOfFuel(EcoString),
+}
+
+impl CallError {
+    /// The error's message.
+    fn into_message(self) -> EcoString {
+        match self {
+            Self::Failed(message) | Self::OutOfFuel(message) => message,
+        }
+    }
+
+    /// Attach a span to the error.
+    fn at(self, span: Span) -> EcoVec<SourceDiagnostic> {
+        let catchable = matches!(self, Self::Failed(_));
+        let mut error = SourceDiagnostic::error(span, self.into_message());
+        error.catchable = catchable;
+        eco_vec![error]
+    }
+}
+
+impl From<EcoString> for CallError {
+    fn from(message: EcoString) -> Self {
+        Self::Failed(message)
+    }
+}
+
+/// The CBOR tag that marks a string as Typst markup.
+const MARKUP_TAG: u64 = 29817;
+
+/// Converts a CBOR value returned by a plugin into a Typst value, evaluating
+/// markup strings.
+fn decode_value(
+    engine: &mut Engine,
+    value: ciborium::Value,
+    span: Span,
+) -> SourceResult<Value> {
+    Ok(match value {
+        ciborium::Value::Null => Value::None,
+        ciborium::Value::Bool(v) => Value::Bool(v),
+        ciborium::Value::Integer(v) => match i64::try_from(v) {
+            Ok(v) => Value::Int(v),
+            Err(_) => Value::Float(i128::from(v) as f64),
+        },
+        ciborium::Value::Float(v) => Value::Float(v),
+        ciborium::Value::Text(v) => Value::Str(v.into()),
+        ciborium::Value::Bytes(v) => Value::Bytes(Bytes::new(v)),
+        ciborium::Value::Array(items) => Value::Array(
+            items
+                .into_iter()
+                .map(|item| decode_value(engine, item, span))
+                .collect::<SourceResult<Array>>()?,
+        ),
+        ciborium::Value::Map(pairs) => {
+            let mut dict = Dict::new();
+            for (key, value) in pairs {
+                let ciborium::Value::Text(key) = key else {
+                    bail!(span, "plugin returned a map with a non-string key");
+                };
+                dict.insert(key.into(), decode_value(engine, value, span)?);
+            }
+            Value::Dict(dict)
+        }
+        ciborium::Value::Tag(MARKUP_TAG, inner) => {
+            let ciborium::Value::Text(markup) = *inner else {
+                bail!(span, "plugin returned markup that is not a string");
+            };
+            (engine.library.routines.eval_string)(
+                engine.world,
+                engine.library,
+                engine.cancellation,
+                engine.fuel,
+                TrackedMut::reborrow_mut(&mut engine.sink),
+                EmptyIntrospector.track(),
+                Context::none().track(),
+                &markup,
+                SpanMode::Uniform(span),
+                SyntaxMode::Markup,
+                Scope::new(),
+            )?
+        }
+        ciborium::Value::Tag(_, inner) => decode_value(engine, *inner, span)?,
+        _ => bail!(span, "plugin returned an unsupported CBOR value"),
+    })
+}
+
+/// Decodes the error payload of a plugin that exchanges Typst values.
+fn decode_error(output: &[u8]) -> HintedString {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Payload {
+        Message(EcoString),
+        Detailed {
+            message: EcoString,
+            #[serde(default)]
+            hints: Vec<EcoString>,
+        },
+    }
+
+    match ciborium::from_reader(output) {
+        Ok(Payload::Message(message)) => {
+            eco_format!("plugin errored with: {message}").into()
+        }
+        Ok(Payload::Detailed { message, hints }) => {
+            let mut error =
+                HintedString::from(eco_format!("plugin errored with: {message}"));
+            for hint in hints {
+                error.hint(hint);
+            }
+            error
+        }
+        Err(_) => "plugin errored, but did not return a valid error message".into(),
+    }
 }
 
 /// An single plugin instance for single-threaded execution.
@@ -415,6 +924,8 @@ struct PluginInstance {
     instance: wasmi::Instance,
     /// The execution store of this concrete plugin instance.
     store: wasmi::Store<CallData>,
+    /// The fuel available to each call, if it is limited.
+    fuel: Option<u64>,
 }
 
 /// A snapshot of a plugin instance.
@@ -431,20 +942,30 @@ impl PluginInstance {
     #[typst_macros::time(name = "create plugin instance")]
     fn new(base: &PluginBase, snapshot: Option<&Snapshot>) -> StrResult<PluginInstance> {
         let mut store = wasmi::Store::new(base.linker.engine(), CallData::default());
+        if let Some(fuel) = base.fuel {
+            store.set_fuel(fuel).unwrap();
+        }
+
         let instance = base
             .linker
             .instantiate_and_start(&mut store, &base.module)
             .map_err(|e| eco_format!("{e}"))?;
 
-        let mut instance = PluginInstance { instance, store };
+        let mut instance = PluginInstance { instance, store, fuel: base.fuel };
         if let Some(snapshot) = snapshot {
             instance.restore(snapshot);
         }
         Ok(instance)
     }
 
-    /// Call a plugin function with byte arguments.
-    fn call(&mut self, func: &str, args: Vec<Bytes>) -> StrResult<Bytes> {
+    /// Call a plugin function with byte arguments, providing it with the given
+    /// files.
+    fn call(
+        &mut self,
+        func: &str,
+        args: Vec<Bytes>,
+        files: FxHashMap<EcoString, Option<Bytes>>,
+    ) -> Result<Output, CallError> {
         let handle = self
             .instance
             .get_export(&self.store, func)
@@ -484,12 +1005,34 @@ impl PluginInstance {
 
         // Store the input data.
         self.store.data_mut().args = args;
+        self.store.data_mut().files = files;
+
+        // Refuel the store so that each call gets the full budget.
+        if let Some(fuel) = self.fuel {
+            self.store.set_fuel(fuel).unwrap();
+        }
 
         // Call the function.
         let mut code = wasmi::Val::I32(-1);
-        handle
-            .call(&mut self.store, &lengths, std::slice::from_mut(&mut code))
-            .map_err(|err| eco_format!("plugin panicked: {err}"))?;
+        let result =
+            handle.call(&mut self.store, &lengths, std::slice::from_mut(&mut code));
+
+        // Fail if the plugin tried to read a file it has no access to.
+        let data = self.store.data_mut();
+        data.files.clear();
+        data.file = None;
+        if let Some(path) = data.denied_file.take() {
+            bail!("plugin tried to read `{path}`, but was not granted access to it");
+        }
+
+        result.map_err(|err| match self.fuel {
+            Some(fuel) if err.as_trap_code() == Some(wasmi::TrapCode::OutOfFuel) => {
+                CallError::OutOfFuel(eco_format!(
+                    "plugin function `{func}` ran out of fuel (limit is {fuel})"
+                ))
+            }
+            _ => CallError::Failed(eco_format!("plugin panicked: {err}")),
+        })?;
 
         if let Some(MemoryError { offset, length, write }) =
             self.store.data_mut().memory_error.take()
@@ -498,7 +1041,8 @@ impl PluginInstance {
                 "plugin tried to {kind} out of bounds: \
                  pointer {offset:#x} is out of bounds for {kind} of length {length}",
                 kind = if write { "write" } else { "read" }
-            ));
+            )
+            .into());
         }
 
         // Extract the returned data.
@@ -506,17 +1050,10 @@ impl PluginInstance {
 
         // Parse the functions return value.
         match code {
-            wasmi::Val::I32(0) => {}
-            wasmi::Val::I32(1) => match std::str::from_utf8(&output) {
-                Ok(message) => bail!("plugin errored with: {message}"),
-                Err(_) => {
-                    bail!("plugin errored, but did not return a valid error message")
-                }
-            },
+            wasmi::Val::I32(0) => Ok(Output::Success(Bytes::new(output))),
+            wasmi::Val::I32(1) => Ok(Output::Failure(Bytes::new(output))),
             _ => bail!("plugin did not respect the protocol"),
-        };
-
-        Ok(Bytes::new(output))
+        }
     }
 
     /// Creates a snapshot of this instance from which another one can be
@@ -563,6 +1100,13 @@ struct CallData {
     output: Vec<u8>,
     /// A memory error that occurred during execution of the current call.
     memory_error: Option<MemoryError>,
+    /// The files the current call may read, by path. `None` marks files that
+    /// could not be read.
+    files: FxHashMap<EcoString, Option<Bytes>>,
+    /// The file most recently requested by the current call.
+    file: Option<Bytes>,
+    /// A path that the current call tried to read without permission.
+    denied_file: Option<EcoString>,
 }
 
 /// If there was an error reading/writing memory, keep the offset + length to
@@ -594,6 +1138,54 @@ fn wasm_minimal_protocol_write_args_to_buffer(
     }
 }
 
+/// Looks up a file requested by the plugin and returns its length.
+///
+/// Aborts the call if the plugin was not granted access to the file.
+fn wasm_minimal_protocol_read_file(
+    mut caller: wasmi::Caller<CallData>,
+    ptr: u32,
+    len: u32,
+) -> Result<i32, wasmi::Error> {
+    let memory = caller.get_export("memory").unwrap().into_memory().unwrap();
+    let mut buffer = vec![0; len as usize];
+    if memory.read(&caller, ptr as _, &mut buffer).is_err() {
+        caller.data_mut().memory_error =
+            Some(MemoryError { offset: ptr, length: len, write: false });
+        return Ok(-1);
+    }
+
+    let data = caller.data_mut();
+    let path = String::from_utf8_lossy(&buffer);
+    match data.files.get(path.as_ref()) {
+        Some(Some(file)) => {
+            let Ok(len) = i32::try_from(file.len()) else { return Ok(-1) };
+            data.file = Some(file.clone());
+            Ok(len)
+        }
+        Some(None) => Ok(-1),
+        None => {
+            data.denied_file = Some(path.into());
+            Err(wasmi::Error::new("file access was not granted"))
+        }
+    }
+}
+
+/// Writes the most recently requested file into the plugin's memory.
+fn wasm_minimal_protocol_write_file_to_buffer(
+    mut caller: wasmi::Caller<CallData>,
+    ptr: u32,
+) {
+    let memory = caller.get_export("memory").unwrap().into_memory().unwrap();
+    let Some(file) = caller.data_mut().file.take() else { return };
+    if memory.write(&mut caller, ptr as usize, file.as_slice()).is_err() {
+        caller.data_mut().memory_error = Some(MemoryError {
+            offset: ptr,
+            length: file.len() as u32,
+            write: true,
+        });
+    }
+}
+
 /// Extracts the output of the plugin function from the plugin's memory.
 fn wasm_minimal_protocol_send_result_to_host(
     mut caller: wasmi::Caller<CallData>,
@@ -610,3 +1202,37 @@ fn wasm_minimal_protocol_send_result_to_host(
     }
     caller.data_mut().output = buffer;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode(value: &Value) -> Vec<u8> {
+        let mut buf = Vec::new();
+        ciborium::into_writer(value, &mut buf).unwrap();
+        buf
+    }
+
+    #[test]
+    fn test_decode_error() {
+        let message = decode_error(&encode(&Value::Str("oops".into())));
+        assert_eq!(message.message(), "plugin errored with: oops");
+        assert!(message.hints().is_empty());
+
+        let mut dict = Dict::new();
+        dict.insert("message".into(), Value::Str("oops".into()));
+        dict.insert(
+            "hints".into(),
+            Value::Array(Array::from_iter([Value::Str("try again".into())])),
+        );
+        let detailed = decode_error(&encode(&Value::Dict(dict)));
+        assert_eq!(detailed.message(), "plugin errored with: oops");
+        assert_eq!(detailed.hints(), ["try again"]);
+
+        let invalid = decode_error(b"\xff");
+        assert_eq!(
+            invalid.message(),
+            "plugin errored, but did not return a valid error message"
+        );
+    }
+}
@@ -0,0 +1,247 @@
+use std::fmt::{self, Debug, Formatter};
+use std::sync::Arc;
+
+use ecow::{EcoString, eco_format};
+use serde::{Serialize, Serializer};
+use typst_syntax::{Span, Spanned};
+use typst_utils::LazyHash;
+
+use crate::diag::{At, HintedStrResult, SourceResult, StrResult, bail};
+use crate::foundations::{
+    Args, Binding, Dict, IntoValue, Repr, Scope, Str, Type, Value, repr,
+};
+
+/// The definition of a custom type, as created by `type.define`.
+///
+/// Types and their values share the definition through reference counting.
+/// Two types are equal if their definitions are, such that evaluating the
+/// same definition again (e.g. in a later compilation) yields the same type.
+#[derive(Hash)]
+pub struct RecordType {
+    /// The type's name.
+    name: Str,
+    /// The type's fields, in definition order.
+    fields: Vec<RecordField>,
+    /// The type's methods.
+    scope: Scope,
+    /// Where the type was defined.
+    span: Span,
+}
+
+/// A field of a custom type.
+#[derive(Debug, Clone, Hash)]
+pub struct RecordField {
+    /// The field's name.
+    name: Str,
+    /// The type values of the field must have, if any.
+    ty: Option<Type>,
+    /// The value of the field if it isn't given, if any.
+    default: Option<Value>,
+}
+
+impl RecordType {
+    /// Defines a custom type from its field specifications and methods.
+    pub fn define(name: Str, fields: Dict, methods: Dict, span: Span) -> StrResult<Type> {
+        if name.is_empty() {
+            bail!("type name must not be empty");
+        }
+
+        let fields = fields
+            .into_iter()
+            .map(|(name, spec)| RecordField::parse(name, spec))
+            .collect::<StrResult<_>>()?;
+
+        let mut scope = Scope::new();
+        for (name, method) in methods {
+            match method {
+                Value::Func(func) => scope.bind(name.into(), Binding::new(func, span)),
+                v => bail!("method `{name}` must be a function, found {}", v.ty()),
+            };
+        }
+
+        let ty = Self { name, fields, scope, span };
+        Ok(Type::from(Arc::new(LazyHash::new(ty))))
+    }
+
+    /// The type's name.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The names of the type's fields, in definition order.
+    pub fn field_names(&self) -> impl Iterator<Item = &str> {
+        self.fields.iter().map(|field| field.name.as_str())
+    }
+
+    /// The type's fields, in definition order.
+    pub fn fields(&self) -> &[RecordField] {
+        &self.fields
+    }
+
+    /// The type's methods.
+    pub fn scope(&self) -> &Scope {
+        &self.scope
+    }
+
+    /// Where the type was defined.
+    pub fn span(&self) -> Span {
+        self.span
+    }
+
+    /// Creates a value of the given type from named arguments.
+    pub fn construct(ty: &Arc<LazyHash<Self>>, args: &mut Args) -> SourceResult<Record> {
+        let mut fields = Dict::new();
+        for field in &ty.fields {
+            let value = match args.named::<Spanned<Value>>(&field.name)? {
+                Some(Spanned { v, span }) => field.cast(v).at(span)?,
+                None => match &field.default {
+                    Some(default) => default.clone(),
+                    None => bail!(args.span, "missing argument: {}", field.name),
+                },
+            };
+            fields.insert(field.name.clone(), value);
+        }
+
+        Ok(Record { ty: ty.clone(), fields })
+    }
+}
+
+impl RecordField {
+    /// The field's name.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The type values of the field must have, if any.
+    pub fn ty(&self) -> Option<&Type> {
+        self.ty.as_ref()
+    }
+
+    /// The value of the field if it isn't given, if any.
+    pub fn default(&self) -> Option<&Value> {
+        self.default.as_ref()
+    }
+
+    /// Parses a field from its specification, which is either a type or a
+    /// dictionary with optional `type` and `default` keys.
+    fn parse(name: Str, spec: Value) -> StrResult<Self> {
+        let mut dict = match spec {
+            Value::Type(ty) => return Ok(Self { name, ty: Some(ty), default: None }),
+            Value::Dict(dict) => dict,
+            v => {
+                bail!("expected type or dictionary for field `{name}`, found {}", v.ty())
+            }
+        };
+
+        let ty = match dict.take("type").ok() {
+            None => None,
+            Some(Value::Type(ty)) => Some(ty),
+            Some(v) => bail!("expected type for field `{name}`, found {}", v.ty()),
+        };
+
+        let default = dict.take("default").ok();
+        dict.finish(&["type", "default"])?;
+
+        let mut field = Self { name, ty, default: None };
+        field.default = default.map(|default| field.cast(default)).transpose()?;
+        Ok(field)
+    }
+
+    /// Casts a value to the field's type, if it has one.
+    fn cast(&self, value: Value) -> StrResult<Value> {
+        let Some(ty) = &self.ty else { return Ok(value) };
+        let found = value.ty();
+        match ty.cast(value) {
+            Some(value) => Ok(value),
+            None => bail!("expected {ty} for field `{}`, found {found}", self.name),
+        }
+    }
+}
+
+impl Debug for RecordType {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "RecordType({})", self.name)
+    }
+}
+
+/// A value of a custom type.
+#[derive(Clone, PartialEq, Hash)]
+pub struct Record {
+    /// The value's type.
+    ty: Arc<LazyHash<RecordType>>,
+    /// The values of the fields, in definition order.
+    fields: Dict,
+}
+
+impl Record {
+    /// The value's type.
+    pub fn ty(&self) -> Type {
+        Type::from(self.ty.clone())
+    }
+
+    /// The value's fields.
+    pub fn fields(&self) -> &Dict {
+        &self.fields
+    }
+
+    /// Get the value of a field.
+    pub fn field(&self, field: &str) -> StrResult<&Value> {
+        self.fields.get(field).map_err(|_| self.missing_field(field))
+    }
+
+    /// Mutably borrow the value of a field.
+    pub fn field_mut(&mut self, field: &str) -> HintedStrResult<&mut Value> {
+        if !self.fields.contains(field) {
+            return Err(self.missing_field(field).into());
+        }
+        self.fields.at_mut(field)
+    }
+
+    /// Set the value of a field, casting it to the field's type.
+    pub fn set(&mut self, field: &str, value: Value) -> StrResult<()> {
+        let Some(def) = self.ty.fields.iter().find(|def| def.name.as_str() == field)
+        else {
+            return Err(self.missing_field(field));
+        };
+        let value = def.cast(value)?;
+        self.fields.insert(def.name.clone(), value);
+        Ok(())
+    }
+
+    /// The error message for a field that does not exist.
+    fn missing_field(&self, field: &str) -> EcoString {
+        eco_format!("type {} does not have field `{field}`", self.ty.name)
+    }
+}
+
+impl Debug for Record {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}{:?}", self.ty.name, self.fields)
+    }
+}
+
+impl Repr for Record {
+    fn repr(&self) -> EcoString {
+        let pieces: Vec<_> = self
+            .fields
+            .iter()
+            .map(|(name, value)| eco_format!("{name}: {}", value.repr()))
+            .collect();
+        eco_format!("{}{}", self.ty.name, repr::pretty_array_like(&pieces, false))
+    }
+}
+
+impl Serialize for Record {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.fields.serialize(serializer)
+    }
+}
+
+impl IntoValue for Record {
+    fn into_value(self) -> Value {
+        Value::Record(self)
+    }
+}
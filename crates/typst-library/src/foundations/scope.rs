@@ -149,8 +149,7 @@ impl Scope {
     /// Define a native type.
     #[track_caller]
     pub fn define_type<T: NativeType>(&mut self) -> &mut Binding {
-        let ty = T::ty();
-        self.define(ty.short_name(), ty)
+        self.define(T::data().name, T::ty())
     }
 
     /// Define a native element.
@@ -5,12 +5,14 @@ use comemo::Tracked;
 use ecow::{EcoString, EcoVec, eco_format};
 use smallvec::SmallVec;
 use typst_syntax::Span;
+use typst_utils::LazyHash;
 
 use crate::diag::{At, HintedStrResult, SourceResult, StrResult, bail};
 use crate::engine::Engine;
 use crate::foundations::{
-    CastInfo, Content, Context, Dict, Element, FromValue, Func, Label, Reflect, Regex,
-    Repr, Str, StyleChain, Symbol, Type, Value, cast, func, repr, scope, ty,
+    CastInfo, Content, Context, CustomElem, CustomElemDef, Dict, Element, FromValue,
+    Func, Label, Reflect, Regex, Repr, Str, StyleChain, Symbol, Type, Value, cast, func,
+    repr, scope, ty,
 };
 use crate::introspection::{Locatable, Location, QueryUniqueIntrospection, Unqueriable};
 
@@ -79,6 +81,10 @@ pub enum Selector {
     /// If there is a dictionary, only elements with the fields from the
     /// dictionary match.
     Elem(Element, Option<SmallVec<[(u8, Value); 1]>>),
+    /// Matches a specific type of element defined in Typst code.
+    ///
+    /// Like for [`Selector::Elem`], there can be fields to filter for.
+    Custom(Arc<LazyHash<CustomElemDef>>, Option<SmallVec<[(u8, Value); 1]>>),
     /// Matches the element at the specified location.
     Location(Location),
     /// Matches elements with a specific label.
@@ -137,6 +143,12 @@ impl Selector {
                         target.get(*id, styles).as_ref().ok() == Some(value)
                     })
             }
+            Self::Custom(def, dict) => {
+                target.to_packed::<CustomElem>().is_some_and(|elem| elem.def() == def)
+                    && dict.iter().flat_map(|dict| dict.iter()).all(|(id, value)| {
+                        target.get(*id, styles).as_ref().ok() == Some(value)
+                    })
+            }
             Self::Label(label) => target.label() == Some(*label),
             Self::Can(cap) => target.func().can_type_id(*cap),
             Self::Or(selectors) => {
@@ -318,6 +330,18 @@ impl Repr for Selector {
                     elem.name().into()
                 }
             }
+            Self::Custom(def, dict) => {
+                if let Some(dict) = dict {
+                    let dict = dict
+                        .iter()
+                        .map(|(id, value)| (def.field_name(*id).unwrap(), value.clone()))
+                        .map(|(name, value)| (EcoString::from(name).into(), value))
+                        .collect::<Dict>();
+                    eco_format!("{}.where{}", def.name(), dict.repr())
+                } else {
+                    def.name().into()
+                }
+            }
             Self::Label(label) => label.repr(),
             Self::Regex(regex) => regex.repr(),
             Self::Can(_) => eco_format!("selector(..)"),
@@ -354,10 +378,13 @@ impl Repr for Selector {
 cast! {
     type Selector,
     text: EcoString => Self::text(&text)?,
-    func: Func => func
-        .to_element()
-        .ok_or("only element functions can be used as selectors")?
-        .select(),
+    func: Func => match func.to_custom_elem() {
+        Some(def) => Self::Custom(def.clone(), None),
+        None => func
+            .to_element()
+            .ok_or("only element functions can be used as selectors")?
+            .select(),
+    },
     label: Label => Self::Label(label),
     regex: Regex => Self::regex(regex)?,
     location: Location => Self::Location(location),
@@ -427,6 +454,7 @@ impl FromValue for LocatableSelector {
                         Err(eco_format!("{} is not locatable", elem.name()))?
                     }
                 }
+                Selector::Custom(_, _) => {}
                 Selector::Location(_) => {}
                 Selector::Label(_) => {}
                 Selector::Regex(_) => bail!("text is not locatable"),
@@ -509,7 +537,7 @@ impl FromValue for ShowableSelector {
     fn from_value(value: Value) -> HintedStrResult<Self> {
         fn validate(selector: &Selector, nested: bool) -> HintedStrResult<()> {
             match selector {
-                Selector::Elem(_, _) => {}
+                Selector::Elem(_, _) | Selector::Custom(_, _) => {}
                 Selector::Label(_) => {}
                 Selector::Regex(_) if !nested => {}
                 Selector::Or(list) | Selector::And(list) => {
@@ -6,11 +6,11 @@ use std::ops::{Add, AddAssign, Deref, Range};
 use comemo::Tracked;
 use ecow::EcoString;
 use serde::{Deserialize, Serialize};
-use typst_syntax::Spanned;
+use typst_syntax::{Span, Spanned};
 use unicode_normalization::UnicodeNormalization;
 use unicode_segmentation::UnicodeSegmentation;
 
-use crate::diag::{At, SourceResult, StrResult, bail};
+use crate::diag::{At, SourceResult, StrResult, Uncatchable, bail};
 use crate::engine::Engine;
 use crate::foundations::{
     Array, Bytes, Cast, Context, Decimal, Dict, Func, IntoValue, Label, Repr, Type,
@@ -502,6 +502,7 @@ impl Str {
         &self,
         engine: &mut Engine,
         context: Tracked<Context>,
+        span: Span,
         /// The pattern to search for.
         pattern: StrPattern,
         /// The string to replace the matches with or a function that gets a
@@ -527,17 +528,19 @@ impl Str {
             output.push_str(&self[last_match..range.start]);
             last_match = range.end;
 
-            // Determine and push the replacement.
-            match &replacement {
-                Replacement::Str(s) => output.push_str(s),
+            // Determine the replacement.
+            let piece = match &replacement {
+                Replacement::Str(s) => s.clone(),
                 Replacement::Func(func) => {
-                    let piece = func
-                        .call(engine, context, [dict])?
-                        .cast::<Str>()
-                        .at(func.span())?;
-                    output.push_str(&piece);
+                    func.call(engine, context, [dict])?.cast::<Str>().at(func.span())?
                 }
-            }
+            };
+
+            // Ensure that the output stays within the maximum size before it
+            // grows, so that repeated replacements can't exhaust memory.
+            let len = output.len() + piece.len();
+            engine.library.limits.check_str_len(len).at(span).uncatchable()?;
+            output.push_str(&piece);
 
             Ok(())
         };
@@ -561,7 +564,10 @@ impl Str {
         }
 
         // Push the remainder.
-        output.push_str(&self[last_match..]);
+        let rest = &self[last_match..];
+        let len = output.len() + rest.len();
+        engine.library.limits.check_str_len(len).at(span).uncatchable()?;
+        output.push_str(rest);
         Ok(output.into())
     }
 
@@ -1,10 +1,11 @@
 use std::any::{Any, TypeId};
 use std::fmt::{self, Debug, Formatter};
 use std::hash::{Hash, Hasher};
+use std::sync::Arc;
 use std::{mem, ptr};
 
 use comemo::Tracked;
-use ecow::{EcoString, EcoVec, eco_vec};
+use ecow::{EcoString, EcoVec, eco_format, eco_vec};
 use indexmap::IndexMap;
 use rustc_hash::FxBuildHasher;
 use smallvec::SmallVec;
@@ -14,8 +15,9 @@ use typst_utils::LazyHash;
 use crate::diag::{SourceResult, Trace, Tracepoint};
 use crate::engine::Engine;
 use crate::foundations::{
-    Content, Context, Element, Field, Func, NativeElement, OneOrMultiple, Packed,
-    RefableProperty, Repr, Selector, SettableProperty, Target, cast, ty,
+    Content, Context, CustomElem, CustomElemDef, Element, Field, Func, NativeElement,
+    OneOrMultiple, Packed, RefableProperty, Repr, Selector, SettableProperty, Target,
+    Value, cast, ty,
 };
 use crate::introspection::TagElem;
 
@@ -347,6 +349,21 @@ impl Property {
         }
     }
 
+    /// Create a new property for a field of a user-defined element.
+    ///
+    /// All user-defined elements share the element of [`CustomElem`], so the
+    /// property's value also holds the element's definition.
+    pub fn custom(def: Arc<LazyHash<CustomElemDef>>, id: u8, value: Value) -> Self {
+        Self {
+            elem: CustomElem::ELEM,
+            id,
+            value: Block::new((def, value)),
+            span: Span::detached(),
+            liftable: false,
+            outside: false,
+        }
+    }
+
     /// Whether this property is the given one.
     pub fn is(&self, elem: Element, id: u8) -> bool {
         self.elem == elem && self.id == id
@@ -495,6 +512,10 @@ impl Recipe {
         let mut content = match &self.transform {
             Transformation::Content(content) => content.clone(),
             Transformation::Func(func) => {
+                let _frame = engine.traced.debugger().map(|debugger| {
+                    let name = eco_format!("show {}", content.func().name());
+                    debugger.enter(&name, self.span)
+                });
                 let mut result = func.call(engine, context, [content.clone()]);
                 if self.selector.is_some() {
                     let point = || Tracepoint::Show(content.func().name().into());
@@ -621,6 +642,24 @@ impl<'a> StyleChain<'a> {
         self.get_unfolded(E::ELEM, I).unwrap_or_else(|| E::default_ref())
     }
 
+    /// Retrieves a reference to the value of a user-defined element's field
+    /// from the style chain.
+    pub fn get_custom(
+        self,
+        def: &Arc<LazyHash<CustomElemDef>>,
+        id: u8,
+    ) -> Option<&'a Value> {
+        self.properties(CustomElem::ELEM, id)
+            .map(|block| {
+                block.downcast::<(Arc<LazyHash<CustomElemDef>>, Value)>(
+                    CustomElem::ELEM,
+                    id,
+                )
+            })
+            .find(|(other, _)| other == def)
+            .map(|(_, value)| value)
+    }
+
     /// Retrieves the value and then immediately [resolves](Resolve) it.
     pub fn resolve<E, const I: u8>(
         self,
@@ -1014,6 +1053,10 @@ impl NativeRuleMap {
             // could, in theory, also be special cased in realization.
             rules.register(target, crate::foundations::CONTEXT_RULE);
 
+            // User-defined elements are shown with the function they were
+            // defined with, unless a show rule overrides it.
+            rules.register(target, crate::foundations::CUSTOM_RULE);
+
             // CounterDisplayElem only exists because the compiler can't
             // currently express the equivalent of `context
             // counter(..).display(..)` in native code (no native closures).
@@ -3,15 +3,18 @@ pub use typst_macros::{scope, ty};
 
 use std::cmp::Ordering;
 use std::fmt::{self, Debug, Display, Formatter};
-use std::sync::LazyLock;
+use std::sync::{Arc, LazyLock};
 
 use ecow::{EcoString, eco_format};
-use typst_utils::{DefSite, Static};
+use typst_syntax::Span;
+use typst_utils::{DefSite, LazyHash, Static};
 
 use crate::diag::{StrResult, WarningSink, bail};
 use crate::foundations::{
-    AutoValue, Func, NativeFuncData, NoneValue, Repr, Scope, Value, cast, func,
+    AutoValue, Content, Dict, FromValue, Func, IntoValue, NativeFuncData, NoneValue,
+    RecordType, Repr, Scope, Str, Value, cast, func,
 };
+use crate::layout::Rel;
 
 /// Describes a kind of value.
 ///
@@ -60,9 +63,23 @@ use crate::foundations::{
 /// Note that `type` will return @content for all document elements. To
 /// programmatically determine which kind of content you are dealing with, see
 /// @content.func.
+///
+/// = Custom types <custom-types>
+/// With @type.define, you can define your own types with named fields and
+/// methods. Values of such a type know their type, check their fields when
+/// they are created, and show up with a readable representation.
 #[ty(scope, cast)]
-#[derive(Copy, Clone, Eq, PartialEq, Hash)]
-pub struct Type(Static<NativeTypeData>);
+#[derive(Clone, Eq, PartialEq, Hash)]
+pub struct Type(TypeInner);
+
+/// The different kinds of type representations.
+#[derive(Clone, Eq, PartialEq, Hash)]
+enum TypeInner {
+    /// A native Rust type.
+    Native(Static<NativeTypeData>),
+    /// A type defined in Typst code.
+    Record(Arc<LazyHash<RecordType>>),
+}
 
 impl Type {
     /// Get the type for `T`.
@@ -71,55 +88,103 @@ impl Type {
     }
 
     /// The type's short name, how it is used in code (e.g. `str`).
-    pub fn short_name(&self) -> &'static str {
-        self.0.name
+    pub fn short_name(&self) -> &str {
+        match &self.0 {
+            TypeInner::Native(native) => native.0.name,
+            TypeInner::Record(record) => record.name(),
+        }
     }
 
     /// The type's long name, for use in diagnostics (e.g. `string`).
-    pub fn long_name(&self) -> &'static str {
-        self.0.long_name
+    pub fn long_name(&self) -> &str {
+        match &self.0 {
+            TypeInner::Native(native) => native.0.long_name,
+            TypeInner::Record(record) => record.name(),
+        }
     }
 
     /// The type's title case name, for use in documentation (e.g. `String`).
-    pub fn title(&self) -> &'static str {
-        self.0.title
+    pub fn title(&self) -> &str {
+        match &self.0 {
+            TypeInner::Native(native) => native.0.title,
+            TypeInner::Record(record) => record.name(),
+        }
     }
 
     /// Documentation for the type (as Markdown).
     pub fn docs(&self) -> &'static str {
-        self.0.docs
+        match &self.0 {
+            TypeInner::Native(native) => native.0.docs,
+            TypeInner::Record(_) => "",
+        }
     }
 
-    /// Where the function is type in the Rust source code.
-    pub fn def_site(&self) -> DefSite {
-        self.0.def_site
+    /// Where the type is defined in the Rust source code (only `Some(_)` if
+    /// it is native).
+    pub fn def_site(&self) -> Option<DefSite> {
+        match &self.0 {
+            TypeInner::Native(native) => Some(native.0.def_site),
+            TypeInner::Record(_) => None,
+        }
     }
 
     /// Search keywords for the type.
     pub fn keywords(&self) -> &'static [&'static str] {
-        self.0.keywords
+        match &self.0 {
+            TypeInner::Native(native) => native.0.keywords,
+            TypeInner::Record(_) => &[],
+        }
     }
 
     /// This type's constructor function.
     pub fn constructor(&self) -> StrResult<Func> {
-        self.0
-            .constructor
-            .as_ref()
-            .map(|lazy| Func::from(*lazy))
-            .ok_or_else(|| eco_format!("type {self} does not have a constructor"))
+        match &self.0 {
+            TypeInner::Native(native) => native
+                .0
+                .constructor
+                .as_ref()
+                .map(|lazy| Func::from(*lazy))
+                .ok_or_else(|| eco_format!("type {self} does not have a constructor")),
+            TypeInner::Record(record) => Ok(Func::from(record.clone())),
+        }
+    }
+
+    /// Converts a value to this type with the same implicit conversions that a
+    /// native function parameter of the type applies, for example from an
+    /// integer to a float or from a string to content. Returns `None` if the
+    /// value is not of a compatible type.
+    pub fn cast(&self, value: Value) -> Option<Value> {
+        fn cast<T: FromValue + IntoValue>(value: Value) -> Option<Value> {
+            T::from_value(value).ok().map(IntoValue::into_value)
+        }
+
+        if value.ty() == *self {
+            Some(value)
+        } else if *self == Type::of::<f64>() {
+            cast::<f64>(value)
+        } else if *self == Type::of::<Rel>() {
+            cast::<Rel>(value)
+        } else if *self == Type::of::<Str>() {
+            cast::<Str>(value)
+        } else if *self == Type::of::<Content>() {
+            cast::<Content>(value)
+        } else if *self == Type::of::<Func>() {
+            cast::<Func>(value)
+        } else {
+            None
+        }
     }
 
     /// The type's associated scope that holds sub-definitions.
-    pub fn scope(&self) -> &'static Scope {
-        &(self.0).0.scope
+    pub fn scope(&self) -> &Scope {
+        match &self.0 {
+            TypeInner::Native(native) => &native.0.scope,
+            TypeInner::Record(record) => record.scope(),
+        }
     }
 
     /// Get a field from this type's scope, if possible.
-    pub fn field(
-        &self,
-        field: &str,
-        sink: impl WarningSink,
-    ) -> StrResult<&'static Value> {
+    pub fn field(&self, field: &str, sink: impl WarningSink) -> StrResult<&Value> {
         match self.scope().get(field) {
             Some(binding) => Ok(binding.read_checked(sink)),
             None => bail!("type {self} does not contain field `{field}`"),
@@ -147,6 +212,53 @@ impl Type {
     ) -> Type {
         value.ty()
     }
+
+    /// Defines a custom type with named fields and methods.
+    ///
+    /// Calling the resulting type creates a value of it. Fields are passed as
+    /// named arguments and converted to the field's type like the arguments of
+    /// built-in functions. Methods receive
+    /// the value as their first argument, just like the methods of built-in
+    /// types. Two values of a custom type are equal if all their fields are.
+    ///
+    /// ```example
+    /// #let point = type.define(
+    ///   "point",
+    ///   fields: (
+    ///     x: float,
+    ///     y: (type: float, default: 0.0),
+    ///   ),
+    ///   methods: (
+    ///     norm: self => calc.sqrt(self.x * self.x + self.y * self.y),
+    ///     scale: (self, k) => type(self)(x: self.x * k, y: self.y * k),
+    ///   ),
+    /// )
+    ///
+    /// #let p = point(x: 3, y: 4)
+    /// #p \
+    /// #p.norm() \
+    /// #p.scale(2.0) \
+    /// #type(p)
+    /// ```
+    #[func]
+    pub fn define(
+        span: Span,
+        /// The name of the type.
+        name: Str,
+        /// The type's fields. A field maps either to a type, which makes it a
+        /// required field of that type, or to a dictionary with an optional
+        /// `type` and an optional `default` value.
+        #[named]
+        #[default]
+        fields: Dict,
+        /// The type's methods. Each method must be a function that takes the
+        /// value as its first argument.
+        #[named]
+        #[default]
+        methods: Dict,
+    ) -> StrResult<Type> {
+        RecordType::define(name, fields, methods, span)
+    }
 }
 
 impl Debug for Type {
@@ -225,7 +337,13 @@ pub struct NativeTypeData {
 
 impl From<&'static NativeTypeData> for Type {
     fn from(data: &'static NativeTypeData) -> Self {
-        Self(Static(data))
+        Self(TypeInner::Native(Static(data)))
+    }
+}
+
+impl From<Arc<LazyHash<RecordType>>> for Type {
+    fn from(data: Arc<LazyHash<RecordType>>) -> Self {
+        Self(TypeInner::Record(data))
     }
 }
 
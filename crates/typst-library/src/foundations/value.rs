@@ -15,8 +15,8 @@ use crate::diag::{HintedStrResult, HintedString, StrResult, WarningSink};
 use crate::foundations::{
     Args, Array, AutoValue, Bytes, CastInfo, Content, Datetime, Decimal, Dict, Duration,
     Fold, FromValue, Func, IntoValue, Label, Module, NativeElement, NativeType,
-    NoneValue, Reflect, Repr, Resolve, Scope, Str, Styles, Symbol, SymbolElem, Type,
-    Version, fields, ops, repr,
+    NoneValue, Record, Reflect, Repr, Resolve, Scope, Str, Styles, Symbol, SymbolElem,
+    Type, Version, fields, ops, repr,
 };
 use crate::layout::{Abs, Angle, Em, Fr, Length, Ratio, Rel};
 use crate::text::{RawContent, RawElem, TextElem};
@@ -84,6 +84,8 @@ pub enum Value {
     Type(Type),
     /// A module.
     Module(Module),
+    /// A value of a custom type.
+    Record(Record),
     /// A dynamic value.
     Dyn(Dynamic),
 }
@@ -145,6 +147,7 @@ impl Value {
             Self::Args(_) => Type::of::<Args>(),
             Self::Type(_) => Type::of::<Type>(),
             Self::Module(_) => Type::of::<Module>(),
+            Self::Record(v) => v.ty(),
             Self::Dyn(v) => v.ty(),
         }
     }
@@ -166,6 +169,7 @@ impl Value {
             Self::Type(ty) => ty.field(field, sink).cloned(),
             Self::Func(func) => func.field(field, sink).cloned(),
             Self::Module(module) => module.field(field, sink).cloned(),
+            Self::Record(record) => record.field(field).cloned(),
             _ => fields::field(self, field),
         }
     }
@@ -250,6 +254,7 @@ impl Debug for Value {
             Self::Args(v) => Debug::fmt(v, f),
             Self::Type(v) => Debug::fmt(v, f),
             Self::Module(v) => Debug::fmt(v, f),
+            Self::Record(v) => Debug::fmt(v, f),
             Self::Dyn(v) => Debug::fmt(v, f),
         }
     }
@@ -287,6 +292,7 @@ impl Repr for Value {
             Self::Args(v) => v.repr(),
             Self::Type(v) => v.repr(),
             Self::Module(v) => v.repr(),
+            Self::Record(v) => v.repr(),
             Self::Dyn(v) => v.repr(),
         }
     }
@@ -337,6 +343,7 @@ impl Hash for Value {
             Self::Args(v) => v.hash(state),
             Self::Type(v) => v.hash(state),
             Self::Module(v) => v.hash(state),
+            Self::Record(v) => v.hash(state),
             Self::Dyn(v) => v.hash(state),
         }
     }
@@ -358,6 +365,7 @@ impl Serialize for Value {
             Self::Content(v) => v.serialize(serializer),
             Self::Array(v) => v.serialize(serializer),
             Self::Dict(v) => v.serialize(serializer),
+            Self::Record(v) => v.serialize(serializer),
 
             // Fall back to repr() for other things.
             other => serializer.serialize_str(&other.repr()),
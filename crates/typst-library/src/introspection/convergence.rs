@@ -10,7 +10,7 @@ use typst_utils::Protected;
 
 use crate::World;
 use crate::diag::{SourceDiagnostic, warning};
-use crate::engine::{Engine, Route, Sink, Traced};
+use crate::engine::{Cancellation, Engine, Fuel, Route, Sink, Traced};
 use crate::introspection::Introspector;
 
 pub const MAX_ITERS: usize = 5;
@@ -24,12 +24,15 @@ const INSTANCES: usize = MAX_ITERS + 1;
 #[typst_macros::time(name = "analyze introspections")]
 pub fn analyze(
     world: Tracked<dyn World + '_>,
+    cancellation: Tracked<Cancellation>,
     introspectors: [&dyn Introspector; INSTANCES],
     introspections: &[Introspection],
 ) -> EcoVec<SourceDiagnostic> {
     let mut sink = Sink::new();
     for introspection in introspections {
-        if let Some(warning) = introspection.0.diagnose(world, introspectors) {
+        if let Some(warning) =
+            introspection.0.diagnose(world, cancellation, introspectors)
+        {
             sink.warn(warning);
         }
     }
@@ -165,6 +168,7 @@ trait Bounds: Debug + Send + Sync + Any + 'static {
     fn diagnose(
         &self,
         world: Tracked<dyn World + '_>,
+        cancellation: Tracked<Cancellation>,
         introspectors: [&dyn Introspector; INSTANCES],
     ) -> Option<SourceDiagnostic>;
     fn dyn_eq(&self, other: &Introspection) -> bool;
@@ -178,11 +182,15 @@ where
     fn diagnose(
         &self,
         world: Tracked<dyn World + '_>,
+        cancellation: Tracked<Cancellation>,
         introspectors: [&dyn Introspector; INSTANCES],
     ) -> Option<SourceDiagnostic> {
-        let history = History::compute(world, introspectors, |engine, introspector| {
-            self.introspect(engine, introspector)
-        });
+        let history = History::compute(
+            world,
+            cancellation,
+            introspectors,
+            |engine, introspector| self.introspect(engine, introspector),
+        );
         (!history.converged()).then(|| self.diagnose(&history))
     }
 
@@ -216,18 +224,22 @@ impl<'a, T> History<'a, T> {
     /// Computes the value for each introspector with an ad-hoc engine.
     fn compute(
         world: Tracked<dyn World + '_>,
+        cancellation: Tracked<Cancellation>,
         introspectors: [&'a dyn Introspector; INSTANCES],
         f: impl Fn(&mut Engine, Tracked<'a, dyn Introspector + '_>) -> T,
     ) -> Self {
         Self(introspectors.map(|introspector| {
             let tracked = introspector.track();
             let traced = Traced::default();
+            let fuel = Fuel::new(world.library().limits.fuel);
             let mut sink = Sink::new();
             let mut engine = Engine {
                 library: world.library(),
                 world,
                 introspector: Protected::new(tracked),
                 traced: traced.track(),
+                cancellation,
+                fuel: fuel.track(),
                 sink: sink.track_mut(),
                 route: Route::default(),
             };
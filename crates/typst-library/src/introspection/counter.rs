@@ -10,7 +10,7 @@ use typst_syntax::Span;
 use typst_utils::{LazyHash, NonZeroExt, Protected};
 
 use crate::diag::{At, HintedStrResult, SourceDiagnostic, SourceResult, bail, warning};
-use crate::engine::{Engine, Route, Sink, Traced};
+use crate::engine::{Cancellation, Engine, Fuel, Route, Sink, Traced};
 use crate::foundations::{
     Args, Array, Construct, Content, Context, Element, Func, IntoValue, Label,
     LocatableSelector, NativeElement, Packed, Repr, Selector, ShowFn, Smart, Str,
@@ -900,6 +900,8 @@ fn sequence(
         engine.library,
         introspector,
         engine.traced,
+        engine.cancellation,
+        engine.fuel,
         TrackedMut::reborrow_mut(&mut engine.sink),
         engine.route.track(),
     )
@@ -915,6 +917,8 @@ fn sequence_impl(
     library: &LazyHash<Library>,
     introspector: Tracked<dyn Introspector + '_>,
     traced: Tracked<Traced>,
+    cancellation: Tracked<Cancellation>,
+    fuel: Tracked<Fuel>,
     sink: TrackedMut<Sink>,
     route: Tracked<Route>,
 ) -> SourceResult<EcoVec<(CounterState, NonZeroUsize)>> {
@@ -923,6 +927,8 @@ fn sequence_impl(
         world,
         introspector: Protected::from_raw(introspector),
         traced,
+        cancellation,
+        fuel,
         sink,
         route: Route::extend(route).unnested(),
     };
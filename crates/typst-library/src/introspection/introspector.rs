@@ -198,7 +198,7 @@ impl<P> ElementIntrospector<P> {
         }
 
         let output = match selector {
-            Selector::Elem(..) => self
+            Selector::Elem(..) | Selector::Custom(..) => self
                 .all()
                 .filter(|elem| selector.matches(elem, None))
                 .cloned()
@@ -290,6 +290,8 @@ fn format_selector(selector: &Selector, kind: &str) -> EcoString {
     match selector {
         Selector::Elem(elem, None) => eco_format!("{} {kind}", elem.name()),
         Selector::Elem(elem, _) => eco_format!("matching {} {kind}", elem.name()),
+        Selector::Custom(def, None) => eco_format!("{} {kind}", def.name()),
+        Selector::Custom(def, _) => eco_format!("matching {} {kind}", def.name()),
         Selector::Label(label) => eco_format!("{kind} labelled `{}`", label.repr()),
         other => eco_format!("{kind} matching `{}`", other.repr()),
     }
@@ -4,7 +4,7 @@ use typst_syntax::Span;
 use typst_utils::{LazyHash, Protected};
 
 use crate::diag::{At, SourceDiagnostic, SourceResult, bail, warning};
-use crate::engine::{Engine, Route, Sink, Traced};
+use crate::engine::{Cancellation, Engine, Fuel, Route, Sink, Traced};
 use crate::foundations::{
     Args, Construct, Content, Context, Func, LocatableSelector, NativeElement, Repr,
     Selector, Str, Value, cast, elem, func, scope, select_where, ty,
@@ -467,6 +467,8 @@ fn sequence(
         engine.library,
         introspector,
         engine.traced,
+        engine.cancellation,
+        engine.fuel,
         TrackedMut::reborrow_mut(&mut engine.sink),
         engine.route.track(),
     )
@@ -474,12 +476,15 @@ fn sequence(
 
 /// Memoized implementation of `sequence`.
 #[comemo::memoize]
+#[allow(clippy::too_many_arguments)]
 fn sequence_impl(
     state: &State,
     world: Tracked<dyn World + '_>,
     library: &LazyHash<Library>,
     introspector: Tracked<dyn Introspector + '_>,
     traced: Tracked<Traced>,
+    cancellation: Tracked<Cancellation>,
+    fuel: Tracked<Fuel>,
     sink: TrackedMut<Sink>,
     route: Tracked<Route>,
 ) -> SourceResult<EcoVec<Value>> {
@@ -488,6 +493,8 @@ fn sequence_impl(
         world,
         introspector: Protected::from_raw(introspector),
         traced,
+        cancellation,
+        fuel,
         sink,
         route: Route::extend(route).unnested(),
     };
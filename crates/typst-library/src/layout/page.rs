@@ -197,6 +197,36 @@ pub struct PageElem {
     #[ghost]
     pub columns: NonZeroUsize,
 
+    /// The distance between the lines of the page's baseline grid.
+    ///
+    /// When set, each line of a paragraph in the page's body is moved down
+    /// such that its baseline lands on a multiple of this distance, measured
+    /// from the top of the page's content area. Since all columns and pages
+    /// share the same grid, the lines of neighbouring columns and facing pages
+    /// sit on the same baselines. Other blocks, like headings and figures, are
+    /// not snapped themselves, but always occupy a whole number of grid lines.
+    ///
+    /// Lines are only ever moved down, so the grid should typically match the
+    /// distance between the baselines of two lines in a paragraph, i.e. the
+    /// @par.leading[leading] plus the height of a line. Fractional spacing
+    /// and non-top vertical alignment in the page's body move content off the
+    /// grid.
+    ///
+    /// ```example
+    /// #set page(height: 120pt, columns: 2, baseline-grid: 12pt)
+    /// #set par(leading: 5pt, spacing: 5pt)
+    /// #set text(9pt)
+    ///
+    /// = Grid
+    /// #lorem(10)
+    ///
+    /// #text(7pt, lorem(8))
+    /// #colbreak()
+    /// #lorem(20)
+    /// ```
+    #[ghost]
+    pub baseline_grid: Option<Length>,
+
     /// The page's background fill.
     ///
     /// Setting this to something non-transparent instructs the printer to color
@@ -12,6 +12,7 @@
 
 extern crate self as typst_library;
 
+pub mod debug;
 pub mod diag;
 pub mod engine;
 pub mod foundations;
@@ -32,9 +33,10 @@ use serde::{Deserialize, Serialize};
 use typst_syntax::{FileId, Source, Span};
 use typst_utils::{LazyHash, SmallBitSet};
 
-use crate::diag::FileResult;
+use crate::diag::{FileResult, StrResult, bail};
 use crate::foundations::{
-    Array, Binding, Bytes, Datetime, Dict, Duration, Module, NativeRuleMap, Scope, Styles,
+    Array, Binding, Bytes, Datetime, Dict, Duration, Module, NativeRuleMap, Scope,
+    Styles, Value,
 };
 use crate::layout::{Alignment, Dir};
 use crate::routines::Routines;
@@ -174,6 +176,8 @@ pub struct Library {
     pub std: Binding,
     /// In-development features that were enabled.
     pub features: Features,
+    /// Limits on the resources that a compilation may use.
+    pub limits: Limits,
 }
 
 /// Configurable builder for the standard library.
@@ -184,6 +188,7 @@ pub struct LibraryBuilder {
     routines: &'static Routines,
     inputs: Option<Dict>,
     features: Features,
+    limits: Limits,
 }
 
 impl LibraryBuilder {
@@ -194,6 +199,7 @@ impl LibraryBuilder {
             routines,
             inputs: None,
             features: Features::default(),
+            limits: Limits::default(),
         }
     }
 
@@ -211,6 +217,12 @@ impl LibraryBuilder {
         self
     }
 
+    /// Configure limits on the resources that a compilation may use.
+    pub fn with_limits(mut self, limits: Limits) -> Self {
+        self.limits = limits;
+        self
+    }
+
     /// Consumes the builder and returns a `Library`.
     pub fn build(self) -> Library {
         let math = math::module();
@@ -224,6 +236,7 @@ impl LibraryBuilder {
             rules: (self.routines.rules)(),
             std: Binding::detached(global),
             features: self.features,
+            limits: self.limits,
         }
     }
 }
@@ -277,6 +290,122 @@ impl Feature {
     }
 }
 
+/// Limits on the resources that a compilation may use.
+///
+/// The defaults only guard against runaway recursion and loops. When compiling
+/// untrusted documents, e.g. on a server, stricter limits can be configured
+/// via [`LibraryBuilder::with_limits`]. Exceeding a limit aborts the
+/// compilation with an error that can't be caught with `try`.
+///
+/// The number of layout iterations is not configurable here: the document is
+/// laid out at most [`MAX_ITERS`](introspection::MAX_ITERS) times until
+/// introspections converge, and the `fuel` is shared across all of them.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Limits {
+    /// The maximum nesting depth of function calls.
+    ///
+    /// Show rule and layout depth errors are more helpful, so they should
+    /// remain within the limit, which is the case above a depth of 72.
+    pub call_depth: usize,
+    /// The maximum number of iterations of a single `while` loop.
+    pub loop_iterations: usize,
+    /// The maximum number of expressions that may be evaluated in one
+    /// compilation, if any.
+    pub fuel: Option<u64>,
+    /// The maximum number of pages of a laid out document, if any.
+    pub pages: Option<usize>,
+    /// The maximum length of strings and bytes and the maximum number of items
+    /// in arrays and dictionaries, if any.
+    pub size: Option<usize>,
+    /// The maximum amount of fuel that a single WebAssembly plugin call may
+    /// consume, if any. Roughly, one unit of fuel is used per executed
+    /// instruction.
+    pub plugin_fuel: Option<u64>,
+}
+
+impl Limits {
+    /// Ensures that a value is within the maximum size.
+    pub fn check_size(&self, value: &Value) -> StrResult<()> {
+        match measure(value) {
+            Some((kind, len)) => self.check_len(kind, len),
+            None => Ok(()),
+        }
+    }
+
+    /// Ensures that repeating a value `n` times, as the multiplication of a
+    /// string or array with an integer does, stays within the maximum size.
+    ///
+    /// This is checked before the repeated value is allocated.
+    pub fn check_repeat(&self, value: &Value, n: usize) -> StrResult<()> {
+        match measure(value) {
+            Some((kind, len)) => self.check_len(kind, len.saturating_mul(n)),
+            None => Ok(()),
+        }
+    }
+
+    /// Ensures that joining two values of the same kind, as their addition
+    /// does, stays within the maximum size.
+    ///
+    /// This is checked before the joined value is allocated.
+    pub fn check_join(&self, lhs: &Value, rhs: &Value) -> StrResult<()> {
+        match (measure(lhs), measure(rhs)) {
+            (Some((kind, a)), Some((other, b))) if kind == other => {
+                self.check_len(kind, a.saturating_add(b))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Ensures that an array with `len` items stays within the maximum size.
+    ///
+    /// This is checked before the array is allocated.
+    pub fn check_array_len(&self, len: usize) -> StrResult<()> {
+        self.check_len("array", len)
+    }
+
+    /// Ensures that a string with `len` bytes stays within the maximum size.
+    ///
+    /// This is checked before the string grows to that length.
+    pub fn check_str_len(&self, len: usize) -> StrResult<()> {
+        self.check_len("string", len)
+    }
+
+    /// Ensures that a value of the given kind with the given length stays
+    /// within the maximum size.
+    fn check_len(&self, kind: &str, len: usize) -> StrResult<()> {
+        let Some(max) = self.size else { return Ok(()) };
+        if len > max {
+            let unit = if matches!(kind, "string" | "bytes") { "bytes" } else { "items" };
+            bail!("{kind} is too large ({len} {unit}, but the maximum is {max})");
+        }
+        Ok(())
+    }
+}
+
+/// The kind and length of a value whose size is limited.
+fn measure(value: &Value) -> Option<(&'static str, usize)> {
+    Some(match value {
+        Value::Str(v) => ("string", v.len()),
+        Value::Bytes(v) => ("bytes", v.len()),
+        Value::Array(v) => ("array", v.len()),
+        Value::Dict(v) => ("dictionary", v.len()),
+        _ => return None,
+    })
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            call_depth: 80,
+            loop_iterations: 10_000,
+            fuel: None,
+            pages: None,
+            size: None,
+            plugin_fuel: None,
+        }
+    }
+}
+
 /// A group of related standard library definitions.
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
@@ -1,9 +1,19 @@
+use std::fmt::Display;
+
 use az::SaturatingAs;
+use ecow::{EcoString, eco_format};
+use time::macros::format_description;
 use typst_syntax::Spanned;
 
-use crate::diag::{LineCol, LoadError, LoadedWithin, ReportPos, SourceResult, bail};
+use crate::diag::{
+    At, HintedStrResult, LoadError, LoadedWithin, ReportPos, SourceResult, StrResult,
+    bail,
+};
 use crate::engine::Engine;
-use crate::foundations::{Array, Dict, IntoValue, Type, Value, cast, func};
+use crate::foundations::{
+    Array, AutoValue, Cast, Datetime, Dict, IntoValue, NoneValue, Repr, Smart, Str, Type,
+    Value, cast, func, repr, scope,
+};
 use crate::loading::{DataSource, Load};
 
 /// Reads structured data from a CSV file.
@@ -13,6 +23,9 @@ use crate::loading::{DataSource, Load};
 /// rows will be collected into a single array. Header rows will not be
 /// stripped.
 ///
+/// Using the @csv.types[`types`] parameter, the fields can also be parsed into
+/// numbers, booleans, and dates. To write CSV data, use @csv.encode.
+///
 /// = Example <example>
 /// ```example
 /// #let results = csv("example.csv")
@@ -23,7 +36,7 @@ use crate::loading::{DataSource, Load};
 ///   ..results.flatten(),
 /// )
 /// ```
-#[func(title = "CSV")]
+#[func(scope, title = "CSV")]
 pub fn csv(
     engine: &mut Engine,
     /// A path to a CSV file or raw CSV bytes.
@@ -43,6 +56,48 @@ pub fn csv(
     #[named]
     #[default(RowType::Array)]
     row_type: RowType,
+    /// How to convert the fields of each row.
+    ///
+    /// - If set to `{none}`, all fields are kept as strings.
+    /// - If set to `{auto}`, the type of each field is inferred: Fields that
+    ///   look like booleans (`true` and `false`), integers, or floats are
+    ///   converted accordingly, while all others remain strings.
+    /// - If set to an array, its items define the types of the columns in
+    ///   order. Columns without a type are kept as strings.
+    /// - If set to a dictionary, it maps from header keys to types. This
+    ///   requires `row-type` to be `dictionary`.
+    ///
+    /// The possible types are @str, @int, @float, @bool, @datetime, and
+    /// `{auto}` for inference. Dates are expected in the format `YYYY-MM-DD`,
+    /// times in `HH:MM` or `HH:MM:SS`, and datetimes as a date and a time
+    /// separated by a `T` or a space. Empty fields in columns of a type other
+    /// than @str are turned into `{none}`.
+    ///
+    /// ```example
+    /// #let data = csv(
+    ///   bytes("name,born,alive\nAda,1815-12-10,false"),
+    ///   row-type: dictionary,
+    ///   types: (born: datetime, alive: bool),
+    /// )
+    ///
+    /// #let ada = data.first()
+    /// #ada.name was born in #ada.born.year().
+    /// ```
+    #[named]
+    #[default]
+    types: ColumnTypes,
+    /// A character that marks comment lines, which are skipped. Must be a
+    /// single ASCII character.
+    ///
+    /// ```example
+    /// #csv(
+    ///   bytes("# Results\nCondition,Result\n# Pending\nA,1"),
+    ///   comment: "#",
+    /// )
+    /// ```
+    #[named]
+    #[default]
+    comment: Option<Delimiter>,
 ) -> SourceResult<Array> {
     let loaded = source.load(engine.world)?;
 
@@ -50,39 +105,68 @@ pub fn csv(
     let has_headers = row_type == RowType::Dict;
     builder.has_headers(has_headers);
     builder.delimiter(delimiter.0 as u8);
+    let comment = comment.map(|c| c.0 as u8);
+    builder.comment(comment);
 
-    // Counting lines from 1 by default.
-    let mut line_offset: usize = 1;
-    let mut reader = builder.from_reader(loaded.data.as_slice());
+    if matches!(types, ColumnTypes::Named(_)) && !has_headers {
+        bail!(
+            source.span,
+            "a dictionary of types requires dictionary rows";
+            hint: "try setting `row-type` to `dictionary`";
+        );
+    }
+
+    let data = Data { bytes: loaded.data.as_slice(), comment };
+    let mut reader = builder.from_reader(data.bytes);
     let mut headers: Option<::csv::StringRecord> = None;
 
     if has_headers {
-        // Counting lines from 2 because we have a header.
-        line_offset += 1;
         headers = Some(
             reader
                 .headers()
                 .cloned()
-                .map_err(|err| format_csv_error(err, 1))
+                .map_err(|err| format_csv_error(err, data))
                 .within(&loaded)?,
         );
     }
 
+    if let (ColumnTypes::Named(types), Some(headers)) = (&types, &headers)
+        && let Some((key, _)) = types
+            .iter()
+            .find(|(key, _)| !headers.iter().any(|h| h == key.as_str()))
+    {
+        bail!(
+            source.span,
+            "`types` has key `{key}`, which is not in the header";
+            hint: "the header has the keys {}",
+            headers.iter().map(|h| eco_format!("`{h}`")).collect::<Vec<_>>().join(", ");
+        );
+    }
+
     let mut array = Array::new();
-    for (line, result) in reader.records().enumerate() {
-        // Original solution was to use line from error, but that is
-        // incorrect with `has_headers` set to `false`. See issue:
-        // https://github.com/BurntSushi/rust-csv/issues/184
-        let line = line + line_offset;
-        let row = result.map_err(|err| format_csv_error(err, line)).within(&loaded)?;
+    for result in reader.records() {
+        let row = result.map_err(|err| format_csv_error(err, data)).within(&loaded)?;
+        let pos = row.position().map(|pos| data.pos(pos)).unwrap_or_default();
+        let convert = |i: usize, key: Option<&str>, field: &str| {
+            types.get(i, key).convert(field).map_err(|message| {
+                let message = eco_format!("{message} in column {}", i + 1);
+                LoadError::new(pos.clone(), "failed to parse CSV", message)
+            })
+        };
         let item = if let Some(headers) = &headers {
             let mut dict = Dict::new();
-            for (field, value) in headers.iter().zip(&row) {
-                dict.insert(field.into(), value.into_value());
+            for (i, (key, field)) in headers.iter().zip(&row).enumerate() {
+                let value = convert(i, Some(key), field).within(&loaded)?;
+                dict.insert(key.into(), value);
             }
             dict.into_value()
         } else {
-            let sub = row.into_iter().map(|field| field.into_value()).collect();
+            let sub = row
+                .iter()
+                .enumerate()
+                .map(|(i, field)| convert(i, None, field))
+                .collect::<Result<_, _>>()
+                .within(&loaded)?;
             Value::Array(sub)
         };
         array.push(item);
@@ -91,6 +175,117 @@ pub fn csv(
     Ok(array)
 }
 
+#[scope]
+impl csv {
+    /// Encodes structured data into a CSV string.
+    ///
+    /// ```example
+    /// #let rows = (
+    ///   (name: "Ada", year: 1815),
+    ///   (name: "Grace", year: 1906),
+    /// )
+    ///
+    /// #raw(csv.encode(rows))
+    /// ```
+    #[func(title = "Encode CSV")]
+    pub fn encode(
+        /// The rows to encode.
+        ///
+        /// Each row can either be an array of fields or a dictionary mapping
+        /// from header keys to fields, but all rows must be of the same kind.
+        /// Fields can be strings, numbers, booleans, datetimes, or `{none}`
+        /// (which results in an empty field).
+        value: Spanned<Array>,
+        /// The delimiter that separates columns. Must be a single ASCII
+        /// character.
+        #[named]
+        #[default]
+        delimiter: Delimiter,
+        /// When to put fields into quotes.
+        #[named]
+        #[default]
+        quoting: Quoting,
+        /// Whether to write a header row with the keys of dictionary rows.
+        ///
+        /// The header's columns are the keys of the first row, in order.
+        /// Subsequent rows may omit keys, in which case their fields stay
+        /// empty. Has no effect for rows that are arrays.
+        #[named]
+        #[default(true)]
+        header: bool,
+    ) -> SourceResult<Str> {
+        let Spanned { v: rows, span } = value;
+        let mut writer = ::csv::WriterBuilder::new()
+            .delimiter(delimiter.0 as u8)
+            .quote_style(quoting.into())
+            .terminator(::csv::Terminator::Any(b'\n'))
+            .flexible(true)
+            .from_writer(vec![]);
+
+        let failed =
+            |err: &dyn Display| eco_format!("failed to encode value as CSV ({err})");
+        let first = rows.iter().next();
+        let keys: Option<Vec<Str>> = match first {
+            Some(Value::Dict(dict)) => {
+                Some(dict.iter().map(|(key, _)| key.clone()).collect())
+            }
+            _ => None,
+        };
+
+        if let Some(keys) = &keys
+            && header
+        {
+            writer
+                .write_record(keys.iter().map(|key| key.as_bytes()))
+                .map_err(|err| failed(&err))
+                .at(span)?;
+        }
+
+        for (i, row) in rows.iter().enumerate() {
+            let fields: Vec<EcoString> = match (row, &keys) {
+                (Value::Array(row), None) => {
+                    row.iter().map(encode_field).collect::<StrResult<_>>().at(span)?
+                }
+                (Value::Dict(row), Some(keys)) => {
+                    if let Some((key, _)) =
+                        row.iter().find(|(key, _)| !keys.contains(*key))
+                    {
+                        bail!(
+                            span,
+                            "row {} has key `{key}`, which is not in the header",
+                            i + 1,
+                        );
+                    }
+                    keys.iter()
+                        .map(|key| row.get(key).map_or(Ok("".into()), encode_field))
+                        .collect::<StrResult<_>>()
+                        .at(span)?
+                }
+                (Value::Array(_) | Value::Dict(_), _) => bail!(
+                    span,
+                    "row {} is {}, but the first row is {}",
+                    i + 1,
+                    row.ty(),
+                    first.map_or(Type::of::<NoneValue>(), Value::ty),
+                ),
+                (v, _) => bail!(
+                    span,
+                    "expected rows to be arrays or dictionaries, found {}",
+                    v.ty(),
+                ),
+            };
+            writer
+                .write_record(fields.iter().map(|field| field.as_bytes()))
+                .map_err(|err| failed(&err))
+                .at(span)?;
+        }
+
+        let bytes = writer.into_inner().map_err(|err| failed(err.error())).at(span)?;
+        let text = String::from_utf8(bytes).map_err(|err| failed(&err)).at(span)?;
+        Ok(text.into())
+    }
+}
+
 /// The delimiter to use when parsing CSV files.
 pub struct Delimiter(char);
 
@@ -134,24 +329,285 @@ cast! {
     },
 }
 
+/// How to convert the fields of parsed rows.
+#[derive(Debug, Default, Clone, PartialEq, Hash)]
+pub enum ColumnTypes {
+    /// Keep all fields as strings.
+    #[default]
+    None,
+    /// Infer the types of all fields.
+    Auto,
+    /// Types by column position.
+    Positional(Vec<ColumnType>),
+    /// Types by header key.
+    Named(Vec<(Str, ColumnType)>),
+}
+
+impl ColumnTypes {
+    /// The type of the column at the given index, with the given header key.
+    fn get(&self, index: usize, key: Option<&str>) -> ColumnType {
+        match self {
+            Self::None => ColumnType::Str,
+            Self::Auto => ColumnType::Auto,
+            Self::Positional(types) => types.get(index).copied().unwrap_or_default(),
+            Self::Named(types) => types
+                .iter()
+                .find(|(k, _)| Some(k.as_str()) == key)
+                .map(|&(_, ty)| ty)
+                .unwrap_or_default(),
+        }
+    }
+}
+
+cast! {
+    ColumnTypes,
+    self => match self {
+        Self::None => Value::None,
+        Self::Auto => Value::Auto,
+        Self::Positional(types) => types.into_value(),
+        Self::Named(types) => types
+            .into_iter()
+            .map(|(key, ty)| (key, ty.into_value()))
+            .collect::<Dict>()
+            .into_value(),
+    },
+    _: NoneValue => Self::None,
+    _: AutoValue => Self::Auto,
+    v: Vec<ColumnType> => Self::Positional(v),
+    v: Dict => Self::Named(
+        v.into_iter()
+            .map(|(key, value)| Ok((key, value.cast()?)))
+            .collect::<HintedStrResult<_>>()?,
+    ),
+}
+
+/// The type a column's fields are converted into.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum ColumnType {
+    #[default]
+    Str,
+    Int,
+    Float,
+    Bool,
+    Datetime,
+    Auto,
+}
+
+impl ColumnType {
+    /// Convert a field into a value of this type.
+    fn convert(self, field: &str) -> Result<Value, EcoString> {
+        let expected = match self {
+            Self::Str => return Ok(field.into_value()),
+            Self::Auto => return Ok(infer_field(field)),
+            _ if field.is_empty() => return Ok(Value::None),
+            Self::Int => match field.trim().parse::<i64>() {
+                Ok(v) => return Ok(v.into_value()),
+                Err(_) => "integer",
+            },
+            Self::Float => match field.trim().parse::<f64>() {
+                Ok(v) => return Ok(v.into_value()),
+                Err(_) => "float",
+            },
+            Self::Bool => match parse_bool(field.trim()) {
+                Some(v) => return Ok(v.into_value()),
+                None => "boolean",
+            },
+            Self::Datetime => match parse_datetime(field.trim()) {
+                Some(v) => return Ok(v.into_value()),
+                None => "datetime",
+            },
+        };
+        Err(eco_format!("expected {expected}, found {}", field.repr()))
+    }
+}
+
+cast! {
+    ColumnType,
+    self => match self {
+        Self::Str => Type::of::<Str>().into_value(),
+        Self::Int => Type::of::<i64>().into_value(),
+        Self::Float => Type::of::<f64>().into_value(),
+        Self::Bool => Type::of::<bool>().into_value(),
+        Self::Datetime => Type::of::<Datetime>().into_value(),
+        Self::Auto => Value::Auto,
+    },
+    _: AutoValue => Self::Auto,
+    ty: Type => {
+        if ty == Type::of::<Str>() {
+            Self::Str
+        } else if ty == Type::of::<i64>() {
+            Self::Int
+        } else if ty == Type::of::<f64>() {
+            Self::Float
+        } else if ty == Type::of::<bool>() {
+            Self::Bool
+        } else if ty == Type::of::<Datetime>() {
+            Self::Datetime
+        } else {
+            bail!("expected `str`, `int`, `float`, `bool`, `datetime`, or `auto`");
+        }
+    },
+}
+
+/// Infer the type of a field.
+///
+/// Like for typed columns, surrounding whitespace is ignored when parsing.
+/// Fields that remain strings are kept as is.
+fn infer_field(field: &str) -> Value {
+    let trimmed = field.trim();
+    if let Some(v) = parse_bool(trimmed) {
+        v.into_value()
+    } else if let Ok(v) = trimmed.parse::<i64>() {
+        v.into_value()
+    } else if let Some(v) = trimmed.parse::<f64>().ok().filter(|_| looks_numeric(trimmed))
+    {
+        v.into_value()
+    } else {
+        field.into_value()
+    }
+}
+
+/// Whether a field is a plain decimal number, as opposed to something like
+/// `inf` or `NaN`, which Rust would also parse as a float.
+fn looks_numeric(field: &str) -> bool {
+    field.bytes().any(|b| b.is_ascii_digit())
+        && field
+            .bytes()
+            .all(|b| b.is_ascii_digit() || matches!(b, b'.' | b'-' | b'+' | b'e' | b'E'))
+}
+
+/// Parse a boolean field.
+fn parse_bool(field: &str) -> Option<bool> {
+    match field {
+        "true" | "TRUE" | "True" => Some(true),
+        "false" | "FALSE" | "False" => Some(false),
+        _ => None,
+    }
+}
+
+/// Parse a date, time, or datetime field.
+fn parse_datetime(field: &str) -> Option<Datetime> {
+    let date = format_description!("[year]-[month]-[day]");
+    let time = format_description!("[hour]:[minute]:[second]");
+    let short_time = format_description!("[hour]:[minute]");
+
+    if let Ok(date) = time::Date::parse(field, &date) {
+        return Some(Datetime::Date(date));
+    }
+    let parse_time = |s: &str| {
+        time::Time::parse(s, &time)
+            .or_else(|_| time::Time::parse(s, &short_time))
+            .ok()
+    };
+    if let Some(time) = parse_time(field) {
+        return Some(Datetime::Time(time));
+    }
+    let (d, t) = field.split_once(['T', ' '])?;
+    let date = time::Date::parse(d, &date).ok()?;
+    let time = parse_time(t)?;
+    Some(Datetime::Datetime(time::PrimitiveDateTime::new(date, time)))
+}
+
+/// Convert a value into a CSV field.
+fn encode_field(value: &Value) -> StrResult<EcoString> {
+    Ok(match value {
+        Value::None => EcoString::new(),
+        Value::Str(v) => v.clone().into(),
+        Value::Bool(v) => eco_format!("{v}"),
+        Value::Int(v) => eco_format!("{v}"),
+        Value::Float(v) => eco_format!("{v}"),
+        Value::Decimal(v) => eco_format!("{v}").replace(repr::MINUS_SIGN, "-"),
+        Value::Datetime(v) => v.display(Smart::Auto)?,
+        v => bail!("cannot encode {} as a CSV field", v.ty()),
+    })
+}
+
+/// When to quote fields in encoded CSV.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum Quoting {
+    /// Only quote fields that contain the delimiter, quotes, or newlines.
+    #[default]
+    Necessary,
+    /// Quote all fields.
+    Always,
+    /// Quote all fields that aren't numbers.
+    NonNumeric,
+    /// Never quote fields. This may result in invalid CSV.
+    Never,
+}
+
+impl From<Quoting> for ::csv::QuoteStyle {
+    fn from(quoting: Quoting) -> Self {
+        match quoting {
+            Quoting::Necessary => Self::Necessary,
+            Quoting::Always => Self::Always,
+            Quoting::NonNumeric => Self::NonNumeric,
+            Quoting::Never => Self::Never,
+        }
+    }
+}
+
+/// CSV data that is being parsed, used to locate records.
+#[derive(Copy, Clone)]
+struct Data<'a> {
+    /// The raw bytes.
+    bytes: &'a [u8],
+    /// The character that marks comment lines, if any.
+    comment: Option<u8>,
+}
+
+impl Data<'_> {
+    /// The byte offset at which the record at the given position starts.
+    ///
+    /// The reader reports the end of the previous record as the position of
+    /// the next one, so skipped comment and empty lines in between must be
+    /// stepped over. The line tracked by the reader is not used since it is
+    /// also off by one without headers (see
+    /// <https://github.com/BurntSushi/rust-csv/issues/184>).
+    fn start(self, pos: &::csv::Position) -> usize {
+        let mut start: usize = pos.byte().saturating_as();
+        while let Some(&first) = self.bytes.get(start)
+            && (Some(first) == self.comment || matches!(first, b'\n' | b'\r'))
+        {
+            match self.bytes[start..].iter().position(|&b| b == b'\n') {
+                Some(i) => start += i + 1,
+                None => start = self.bytes.len(),
+            }
+        }
+        start
+    }
+
+    /// The position to report for the record at the given position.
+    fn pos(self, pos: &::csv::Position) -> ReportPos {
+        let start = self.start(pos);
+        ReportPos::from(start..start)
+    }
+
+    /// The 1-based line of the record at the given position.
+    fn line(self, pos: &::csv::Position) -> usize {
+        let start = self.start(pos);
+        self.bytes[..start].iter().filter(|&&b| b == b'\n').count() + 1
+    }
+}
+
 /// Format the user-facing CSV error message.
-fn format_csv_error(err: ::csv::Error, line: usize) -> LoadError {
+fn format_csv_error(err: ::csv::Error, data: Data) -> LoadError {
     let msg = "failed to parse CSV";
-    let pos = (err.kind().position())
-        .map(|pos| {
-            let start = pos.byte().saturating_as();
-            ReportPos::from(start..start)
-        })
-        .unwrap_or(LineCol::one_based(line, 1).into());
+    let pos = err.kind().position().map(|pos| data.pos(pos)).unwrap_or_default();
     match err.kind() {
         ::csv::ErrorKind::Utf8 { .. } => {
             LoadError::new(pos, msg, "file is not valid UTF-8")
         }
-        ::csv::ErrorKind::UnequalLengths { expected_len, len, .. } => {
+        ::csv::ErrorKind::UnequalLengths { pos: Some(at), expected_len, len } => {
+            let line = data.line(at);
             let err =
                 format!("found {len} instead of {expected_len} fields in line {line}");
             LoadError::new(pos, msg, err)
         }
-        _ => LoadError::new(pos, "failed to parse CSV", err),
+        ::csv::ErrorKind::UnequalLengths { expected_len, len, .. } => {
+            let err = format!("found {len} instead of {expected_len} fields");
+            LoadError::new(pos, msg, err)
+        }
+        _ => LoadError::new(pos, msg, err),
     }
 }
@@ -0,0 +1,443 @@
+use std::num::NonZeroUsize;
+
+use ecow::EcoString;
+use pulldown_cmark as md;
+use rustc_hash::FxHashMap;
+use smallvec::smallvec;
+use typst_syntax::{Span, Spanned};
+use typst_utils::{PicoStr, hash128};
+
+use crate::diag::{At, LoadedWithin, SourceResult};
+use crate::engine::Engine;
+use crate::foundations::{
+    Content, Derived, Label, NativeElement, PathOrStr, Smart, func,
+};
+use crate::layout::{Alignment, HAlignment, Rel, Sizing, TrackSizings};
+use crate::loading::{DataSource, Load, LoadSource, Loaded};
+use crate::model::{
+    Destination, EmphElem, EnumElem, EnumItem, FootnoteElem, HeadingElem, LinkElem,
+    LinkTarget, ListElem, ListItem, ParbreakElem, QuoteElem, StrongElem, TableCell,
+    TableChild, TableElem, TableHeader, TableItem, Url,
+};
+use crate::text::{LinebreakElem, RawContent, RawElem, SpaceElem, StrikeElem, TextElem};
+use crate::visualize::{ImageElem, LineElem};
+
+/// Converts a Markdown document into content.
+///
+/// The document is parsed as [CommonMark](https://commonmark.org/) with the
+/// GitHub extensions for tables, footnotes, strikethrough, and task lists.
+/// Each Markdown construct becomes the element you would get from the
+/// equivalent Typst markup: Headings become @heading[headings], lists become
+/// @list[bullet] and @enum[numbered lists], code becomes @raw[raw text], and so
+/// on. This means that the imported content picks up your document's set and
+/// show rules just like the rest of your document.
+///
+/// Images are loaded like with the @image function. Relative image paths are
+/// resolved relative to the Markdown file or, if the Markdown was given as
+/// bytes, relative to the file where this function is called. Since Typst
+/// cannot fetch files from the internet, images with a URL like
+/// `https://example.com/logo.png` are replaced by their alternative text. Raw
+/// HTML in the Markdown is ignored.
+///
+/// A footnote that is referenced multiple times is only shown once, with all
+/// references pointing to it.
+///
+/// = Example <example>
+/// ```example
+/// #set heading(numbering: "1.")
+/// #show link: underline
+///
+/// #markdown(bytes(
+///   "# Release notes\n"
+///   + "- Faster *layout*\n"
+///   + "- Support for `markdown`\n\n"
+///   + "See the [website](https://typst.app).",
+/// ))
+/// ```
+#[func]
+pub fn markdown(
+    engine: &mut Engine,
+    /// A path to a Markdown file or raw Markdown bytes.
+    source: Spanned<DataSource>,
+) -> SourceResult<Content> {
+    let loaded = source.load(engine.world)?;
+    let text = loaded.data.as_str().within(&loaded)?;
+
+    let options = md::Options::ENABLE_TABLES
+        | md::Options::ENABLE_FOOTNOTES
+        | md::Options::ENABLE_STRIKETHROUGH
+        | md::Options::ENABLE_TASKLISTS;
+    let events: Vec<_> = md::Parser::new_ext(text, options).collect();
+
+    let mut converter = Converter::new(engine, &loaded, source.span);
+
+    // Footnote definitions may come after their references, so we convert
+    // them upfront.
+    let mut depth = 0;
+    for event in &events {
+        let inside = depth > 0;
+        match event {
+            md::Event::Start(md::Tag::FootnoteDefinition(_)) => depth += 1,
+            md::Event::End(md::TagEnd::FootnoteDefinition) => depth -= 1,
+            _ => {}
+        }
+        if inside || depth > 0 {
+            converter.event(event.clone())?;
+        }
+    }
+
+    for event in events {
+        converter.event(event)?;
+    }
+
+    Ok(converter.finish())
+}
+
+/// Converts a stream of Markdown events into content.
+struct Converter<'a, 'b, 'w> {
+    engine: &'a mut Engine<'w>,
+    loaded: &'b Loaded,
+    span: Span,
+    /// The elements that are currently open, innermost last.
+    stack: Vec<Frame<'b>>,
+    /// The converted bodies of footnote definitions.
+    footnotes: FxHashMap<EcoString, Content>,
+    /// The labels of footnotes that were already referenced.
+    labels: FxHashMap<EcoString, Label>,
+}
+
+/// An open Markdown element and the content converted so far.
+struct Frame<'b> {
+    tag: Option<md::Tag<'b>>,
+    children: Vec<Content>,
+    /// Whether a list contains paragraphs instead of bare text.
+    loose: bool,
+    /// The raw text of a code block.
+    text: EcoString,
+}
+
+impl<'a, 'b, 'w> Converter<'a, 'b, 'w> {
+    fn new(engine: &'a mut Engine<'w>, loaded: &'b Loaded, span: Span) -> Self {
+        Self {
+            engine,
+            loaded,
+            span,
+            stack: vec![Frame::new(None)],
+            footnotes: FxHashMap::default(),
+            labels: FxHashMap::default(),
+        }
+    }
+
+    /// Returns the converted document.
+    fn finish(mut self) -> Content {
+        let root = self.stack.pop().unwrap();
+        sequence(&root.children)
+    }
+
+    /// Processes a single event.
+    fn event(&mut self, event: md::Event<'b>) -> SourceResult<()> {
+        match event {
+            md::Event::Start(tag) => self.start(tag),
+            md::Event::End(_) => {
+                let frame = self.stack.pop().unwrap();
+                if let Some(content) = self.end(frame)? {
+                    self.push(content);
+                }
+            }
+            md::Event::Text(text) => {
+                let frame = self.top();
+                if matches!(frame.tag, Some(md::Tag::CodeBlock(_))) {
+                    frame.text.push_str(&text);
+                } else {
+                    self.push(TextElem::packed(text.as_ref()));
+                }
+            }
+            md::Event::Code(code) => {
+                self.push(RawElem::new(RawContent::Text(code.as_ref().into())).pack())
+            }
+            md::Event::SoftBreak => self.push(SpaceElem::shared().clone()),
+            md::Event::HardBreak => self.push(LinebreakElem::shared().clone()),
+            md::Event::Rule => {
+                self.push_block(LineElem::new().with_length(Rel::one()).pack())
+            }
+            md::Event::TaskListMarker(checked) => {
+                self.push(TextElem::packed(if checked { "☑" } else { "☐" }));
+                self.push(SpaceElem::shared().clone());
+            }
+            md::Event::FootnoteReference(name) => {
+                let content = self.footnote(&name);
+                self.push(content);
+            }
+            // Raw HTML and the syntax of extensions that we don't enable.
+            md::Event::Html(_)
+            | md::Event::InlineHtml(_)
+            | md::Event::InlineMath(_)
+            | md::Event::DisplayMath(_) => {}
+        }
+        Ok(())
+    }
+
+    /// Opens an element.
+    fn start(&mut self, tag: md::Tag<'b>) {
+        // Paragraphs in list items make the whole list loose.
+        if matches!(tag, md::Tag::Paragraph) && self.stack.len() >= 2 {
+            let len = self.stack.len();
+            if matches!(self.stack[len - 1].tag, Some(md::Tag::Item)) {
+                self.stack[len - 2].loose = true;
+            }
+        }
+        self.stack.push(Frame::new(Some(tag)));
+    }
+
+    /// Closes an element and returns its content, if any.
+    fn end(&mut self, frame: Frame<'b>) -> SourceResult<Option<Content>> {
+        let Some(tag) = frame.tag else { return Ok(None) };
+        let body = sequence(&frame.children);
+
+        let content = match tag {
+            md::Tag::Paragraph => {
+                for child in frame.children {
+                    self.push(child);
+                }
+                self.push(ParbreakElem::shared().clone());
+                return Ok(None);
+            }
+            md::Tag::Heading { level, .. } => {
+                let depth = NonZeroUsize::new(level as usize).unwrap();
+                self.push_block(HeadingElem::new(body).with_depth(depth).pack());
+                return Ok(None);
+            }
+            md::Tag::BlockQuote(_) => {
+                self.push_block(QuoteElem::new(body).with_block(true).pack());
+                return Ok(None);
+            }
+            md::Tag::CodeBlock(kind) => {
+                let mut text = frame.text;
+                if text.ends_with('\n') {
+                    text.pop();
+                }
+                let lang = match kind {
+                    md::CodeBlockKind::Fenced(info) => {
+                        info.split_whitespace().next().map(EcoString::from)
+                    }
+                    md::CodeBlockKind::Indented => None,
+                };
+                let raw =
+                    RawElem::new(RawContent::Text(text)).with_block(true).with_lang(lang);
+                self.push_block(raw.pack());
+                return Ok(None);
+            }
+            md::Tag::List(start) => {
+                let tight = !frame.loose;
+                let list = match start {
+                    Some(start) => EnumElem::new(
+                        frame
+                            .children
+                            .into_iter()
+                            .filter_map(|child| child.into_packed::<EnumItem>().ok())
+                            .collect(),
+                    )
+                    .with_start(Smart::Custom(start))
+                    .with_tight(tight)
+                    .pack(),
+                    None => ListElem::new(
+                        frame
+                            .children
+                            .into_iter()
+                            .filter_map(|child| child.into_packed::<ListItem>().ok())
+                            .collect(),
+                    )
+                    .with_tight(tight)
+                    .pack(),
+                };
+                self.push_block(list);
+                return Ok(None);
+            }
+            md::Tag::Item => match self.top().tag {
+                Some(md::Tag::List(Some(_))) => EnumItem::new(body).pack(),
+                _ => ListItem::new(body).pack(),
+            },
+            md::Tag::FootnoteDefinition(name) => {
+                self.footnotes.insert(name.as_ref().into(), body);
+                return Ok(None);
+            }
+            md::Tag::Table(alignments) => {
+                let columns = alignments.len();
+                let children = frame
+                    .children
+                    .into_iter()
+                    .filter_map(|child| {
+                        child
+                            .into_packed::<TableHeader>()
+                            .map(TableChild::Header)
+                            .or_else(|child| {
+                                child
+                                    .into_packed::<TableCell>()
+                                    .map(|cell| TableChild::Item(TableItem::Cell(cell)))
+                            })
+                            .ok()
+                    })
+                    .collect();
+                let table = TableElem::new(children)
+                    .with_columns(TrackSizings(smallvec![Sizing::Auto; columns]));
+                self.push_block(table.pack());
+                return Ok(None);
+            }
+            md::Tag::TableHead => TableHeader::new(
+                frame
+                    .children
+                    .into_iter()
+                    .filter_map(|child| child.into_packed::<TableCell>().ok())
+                    .map(TableItem::Cell)
+                    .collect(),
+            )
+            .pack(),
+            md::Tag::TableRow => {
+                for child in frame.children {
+                    self.push(child);
+                }
+                return Ok(None);
+            }
+            md::Tag::TableCell => {
+                // The cell's table is below the enclosing head or row.
+                let column = self.top().children.len();
+                let len = self.stack.len();
+                let align = match &self.stack[len - 2].tag {
+                    Some(md::Tag::Table(alignments)) => alignments.get(column),
+                    _ => None,
+                };
+                let cell = TableCell::new(body);
+                match align.and_then(convert_alignment) {
+                    Some(align) => cell.with_align(Smart::Custom(align)).pack(),
+                    None => cell.pack(),
+                }
+            }
+            md::Tag::Emphasis => EmphElem::new(body).pack(),
+            md::Tag::Strong => StrongElem::new(body).pack(),
+            md::Tag::Strikethrough => StrikeElem::new(body).pack(),
+            md::Tag::Link { link_type, dest_url, .. } => {
+                let url = match link_type {
+                    md::LinkType::Email => format!("mailto:{dest_url}"),
+                    _ => dest_url.into_string(),
+                };
+                match Url::new(url) {
+                    Ok(url) => {
+                        LinkElem::new(LinkTarget::Dest(Destination::Url(url)), body)
+                            .pack()
+                    }
+                    Err(_) => body,
+                }
+            }
+            md::Tag::Image { dest_url, .. } if is_url(&dest_url) => body,
+            md::Tag::Image { dest_url, .. } => {
+                let alt = body.plain_text();
+                self.image(&dest_url)?
+                    .with_alt((!alt.is_empty()).then_some(alt))
+                    .pack()
+            }
+            // Raw HTML and the syntax of extensions that we don't enable.
+            md::Tag::HtmlBlock
+            | md::Tag::DefinitionList
+            | md::Tag::DefinitionListTitle
+            | md::Tag::DefinitionListDefinition
+            | md::Tag::Superscript
+            | md::Tag::Subscript
+            | md::Tag::MetadataBlock(_) => return Ok(None),
+        };
+
+        Ok(Some(content))
+    }
+
+    /// Creates a footnote for a reference. Repeated references to the same
+    /// footnote refer back to the first one.
+    fn footnote(&mut self, name: &str) -> Content {
+        if let Some(&label) = self.labels.get(name) {
+            return FootnoteElem::with_label(label).pack();
+        }
+
+        let Some(body) = self.footnotes.get(name) else {
+            return TextElem::packed(format!("[^{name}]"));
+        };
+
+        // The label must be unique in the whole document, so it is derived
+        // from the Markdown source and the call site.
+        let hash = hash128(&(&self.loaded.data, self.span, name));
+        let label =
+            Label::new(PicoStr::intern(&format!("markdown-footnote-{hash:032x}")))
+                .unwrap();
+        self.labels.insert(name.into(), label);
+        FootnoteElem::with_content(body.clone()).pack().labelled(label)
+    }
+
+    /// Loads an image relative to the Markdown file.
+    fn image(&mut self, path: &str) -> SourceResult<ImageElem> {
+        let path = PathOrStr::Str(path.into());
+        let within = match self.loaded.source.v {
+            LoadSource::Path(id) => Some(id),
+            LoadSource::Bytes => self.span.id(),
+        };
+        let resolved = path.resolve_if_some(within).at(self.span)?;
+        let source = Spanned::new(DataSource::Path(PathOrStr::Path(resolved)), self.span);
+        let loaded = source.load(self.engine.world)?;
+        Ok(ImageElem::new(Derived::new(source.v, loaded)))
+    }
+
+    /// The innermost open element.
+    fn top(&mut self) -> &mut Frame<'b> {
+        self.stack.last_mut().unwrap()
+    }
+
+    /// Adds inline content to the innermost open element.
+    fn push(&mut self, content: Content) {
+        let span = self.span;
+        self.top().children.push(content.spanned(span));
+    }
+
+    /// Adds block-level content, separating it from its surroundings.
+    fn push_block(&mut self, content: Content) {
+        self.push(content);
+        self.push(ParbreakElem::shared().clone());
+    }
+}
+
+impl<'b> Frame<'b> {
+    fn new(tag: Option<md::Tag<'b>>) -> Self {
+        Self {
+            tag,
+            children: vec![],
+            loose: false,
+            text: EcoString::new(),
+        }
+    }
+}
+
+/// Joins converted children, dropping the trailing paragraph break after the
+/// last block.
+fn sequence(children: &[Content]) -> Content {
+    let mut children = children;
+    while let [rest @ .., last] = children
+        && last.is::<ParbreakElem>()
+    {
+        children = rest;
+    }
+    Content::sequence(children.iter().cloned())
+}
+
+/// Whether an image destination is a URL rather than a path.
+fn is_url(dest: &str) -> bool {
+    dest.split_once(':').is_some_and(|(scheme, _)| {
+        scheme.len() > 1
+            && scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+            && scheme.chars().all(|c| c.is_ascii_alphanumeric() || "+-.".contains(c))
+    })
+}
+
+/// Converts a Markdown table column alignment.
+fn convert_alignment(align: &md::Alignment) -> Option<Alignment> {
+    match align {
+        md::Alignment::None => None,
+        md::Alignment::Left => Some(HAlignment::Left.into()),
+        md::Alignment::Center => Some(HAlignment::Center.into()),
+        md::Alignment::Right => Some(HAlignment::Right.into()),
+    }
+}
@@ -6,8 +6,12 @@ mod cbor_;
 mod csv_;
 #[path = "json.rs"]
 mod json_;
+#[path = "markdown.rs"]
+mod markdown_;
 #[path = "read.rs"]
 mod read_;
+#[path = "sqlite.rs"]
+mod sqlite_;
 #[path = "toml.rs"]
 mod toml_;
 #[path = "xml.rs"]
@@ -18,7 +22,9 @@ mod yaml_;
 pub use self::cbor_::*;
 pub use self::csv_::*;
 pub use self::json_::*;
+pub use self::markdown_::*;
 pub use self::read_::*;
+pub use self::sqlite_::*;
 pub use self::toml_::*;
 pub use self::xml_::*;
 pub use self::yaml_::*;
@@ -40,6 +46,8 @@ pub(super) fn define(global: &mut Scope) {
     global.define_func::<yaml>();
     global.define_func::<cbor>();
     global.define_func::<xml>();
+    global.define_func::<markdown>();
+    global.define_type::<Sqlite>();
     global.reset_category();
 }
 
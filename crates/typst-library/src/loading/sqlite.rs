@@ -0,0 +1,527 @@
+use std::fmt::{self, Debug, Formatter};
+
+use ecow::{EcoString, eco_format};
+use typst_syntax::Spanned;
+
+use crate::diag::{At, SourceResult, StrResult, bail};
+use crate::engine::Engine;
+use crate::foundations::{Array, Bytes, Dict, Repr, Str, Value, cast, func, scope, ty};
+use crate::loading::{DataSource, Load};
+
+/// A read-only SQLite database.
+///
+/// Opens an SQLite database file, which can then be queried with
+/// @sqlite.query. Each query results in an array of dictionaries, one per
+/// row, mapping from column names to values. The values are converted as
+/// listed in the @sqlite:conversion[table below].
+///
+/// ```typ
+/// #let db = sqlite("sales.db")
+/// #let rows = db.query(
+///   "SELECT region, SUM(amount) AS total
+///    FROM sales WHERE year = ? GROUP BY region",
+///   (2024,),
+/// )
+///
+/// #table(
+///   columns: 2,
+///   [*Region*], [*Total*],
+///   ..rows.map(row => (row.region, str(row.total))).flatten(),
+/// )
+/// ```
+///
+/// = Read-only and deterministic access <restrictions>
+/// Like all other data loading functions, the database is read through Typst's
+/// file system, so it is watched for changes like any other file. It is never
+/// written to: Only read-only statements like `SELECT` are allowed, and
+/// statements that would modify the database, attach other databases, or
+/// change settings are rejected.
+///
+/// To keep documents reproducible, queries must also be deterministic. Hence,
+/// the functions `random`, `randomblob`, `changes`, `total_changes`, and
+/// `last_insert_rowid` as well as the `CURRENT_DATE`, `CURRENT_TIME`, and
+/// `CURRENT_TIMESTAMP` keywords are not available. Date and time functions
+/// must be given explicit time values: Calling them without one or with the
+/// `'now'` time value fails, as does using the `localtime` and `utc`
+/// modifiers, which depend on the time zone of the compiling machine.
+///
+/// = #short-or-long[Conversion][Conversion details] <conversion>
+/// #docs-table(
+///   table.header[SQLite value][Converted into Typst],
+///
+///   [`NULL`],
+///   [`{none}`],
+///
+///   [`INTEGER`],
+///   [@int],
+///
+///   [`REAL`],
+///   [@float],
+///
+///   [`TEXT`],
+///   [@str],
+///
+///   [`BLOB`],
+///   [@bytes],
+/// )
+///
+/// Query parameters are converted in the opposite direction, with booleans
+/// becoming integers.
+#[ty(scope, title = "SQLite")]
+#[derive(Clone, PartialEq, Hash)]
+pub struct Sqlite(Bytes);
+
+#[scope]
+impl Sqlite {
+    /// Opens an SQLite database.
+    #[func(constructor)]
+    pub fn construct(
+        engine: &mut Engine,
+        /// A path to an SQLite database file or the raw bytes of one.
+        source: Spanned<DataSource>,
+    ) -> SourceResult<Sqlite> {
+        let loaded = source.load(engine.world)?;
+        Ok(Sqlite(loaded.data))
+    }
+
+    /// Runs an SQL query against the database and returns the resulting rows.
+    ///
+    /// Each row is a dictionary mapping from column names to values.
+    ///
+    /// ```typ
+    /// #let db = sqlite("library.db")
+    /// #for book in db.query(
+    ///   "SELECT title, year FROM books WHERE author = :author",
+    ///   (author: "Ada Lovelace"),
+    /// ) [
+    ///   - #book.title (#book.year)
+    /// ]
+    /// ```
+    #[func]
+    pub fn query(
+        &self,
+        /// The SQL query to run. Must be a single read-only statement.
+        query: Spanned<Str>,
+        /// Values for the query's parameters.
+        ///
+        /// An array provides values for positional parameters (`?` or `?NNN`)
+        /// in order. A dictionary provides values for named parameters, with
+        /// keys given without their prefix (that is, `author` for `:author`,
+        /// `@author`, or `$author`). Every parameter of the query must be given a
+        /// value.
+        #[default]
+        params: SqlParams,
+    ) -> SourceResult<Array> {
+        query_impl(&self.0, &query.v, &params).at(query.span)
+    }
+}
+
+impl Debug for Sqlite {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_tuple("Sqlite").field(&self.0.len()).finish()
+    }
+}
+
+impl Repr for Sqlite {
+    fn repr(&self) -> EcoString {
+        eco_format!("sqlite({})", self.0.repr())
+    }
+}
+
+/// The parameters of an SQL query.
+#[derive(Debug, Clone, PartialEq, Hash)]
+pub enum SqlParams {
+    /// Values for positional parameters.
+    Positional(Array),
+    /// Values for named parameters.
+    Named(Dict),
+}
+
+impl Default for SqlParams {
+    fn default() -> Self {
+        Self::Positional(Array::new())
+    }
+}
+
+cast! {
+    SqlParams,
+    self => match self {
+        Self::Positional(v) => v.into_value(),
+        Self::Named(v) => v.into_value(),
+    },
+    v: Array => Self::Positional(v),
+    v: Dict => Self::Named(v),
+}
+
+/// Functions that are unavailable in queries because their results are not
+/// determined by the database and the query alone.
+#[cfg(not(target_arch = "wasm32"))]
+const DENIED_FUNCS: &[&str] =
+    &["changes", "total_changes", "last_insert_rowid", "load_extension"];
+
+/// Functions that are overridden to fail because their results are not
+/// determined by the database and the query alone, with their number of
+/// arguments.
+///
+/// The `CURRENT_*` keywords are implemented as calls to these functions.
+#[cfg(not(target_arch = "wasm32"))]
+const FAILING_FUNCS: &[(&str, i32)] = &[
+    ("random", 0),
+    ("randomblob", 1),
+    ("current_date", 0),
+    ("current_time", 0),
+    ("current_timestamp", 0),
+];
+
+/// Date and time functions, which are overridden to reject the current time
+/// and the local time zone, with the index of their first time value and
+/// their number of time values.
+#[cfg(not(target_arch = "wasm32"))]
+const TIME_FUNCS: &[(&str, usize, usize)] = &[
+    ("date", 0, 1),
+    ("time", 0, 1),
+    ("datetime", 0, 1),
+    ("julianday", 0, 1),
+    ("unixepoch", 0, 1),
+    ("strftime", 1, 1),
+    ("timediff", 0, 2),
+];
+
+/// Runs a query against a database.
+#[cfg(not(target_arch = "wasm32"))]
+#[comemo::memoize]
+#[typst_macros::time(name = "sqlite query")]
+fn query_impl(data: &Bytes, sql: &str, params: &SqlParams) -> StrResult<Array> {
+    use rusqlite::hooks::{AuthAction, AuthContext, Authorization};
+    use rusqlite::types::ValueRef;
+    use rusqlite::{Connection, MAIN_DB};
+
+    let mut conn = Connection::open_in_memory().map_err(format_sqlite_error)?;
+    conn.deserialize_read_exact(MAIN_DB, data.as_slice(), data.len(), true)
+        .map_err(format_sqlite_error)?;
+    make_deterministic(&conn).map_err(format_sqlite_error)?;
+    conn.authorizer(Some(|ctx: AuthContext<'_>| match ctx.action {
+        AuthAction::Select | AuthAction::Read { .. } | AuthAction::Recursive => {
+            Authorization::Allow
+        }
+        AuthAction::Function { function_name }
+            if !DENIED_FUNCS
+                .iter()
+                .any(|name| name.eq_ignore_ascii_case(function_name)) =>
+        {
+            Authorization::Allow
+        }
+        _ => Authorization::Deny,
+    }));
+
+    let mut stmt = conn.prepare(sql).map_err(format_sqlite_error)?;
+    if !stmt.readonly() {
+        bail!("only read-only queries are allowed");
+    }
+
+    let expected = stmt.parameter_count();
+    match params {
+        SqlParams::Positional(values) => {
+            if values.len() != expected {
+                bail!(
+                    "query has {expected} parameter{}, but {} value{} given",
+                    if expected == 1 { "" } else { "s" },
+                    values.len(),
+                    if values.len() == 1 { " was" } else { "s were" },
+                );
+            }
+            for (i, value) in values.iter().enumerate() {
+                stmt.raw_bind_parameter(i + 1, to_sql(value)?)
+                    .map_err(format_sqlite_error)?;
+            }
+        }
+        SqlParams::Named(values) => {
+            // Parameter names include their prefix, which the keys omit.
+            let names = (1..=expected)
+                .map(|i| {
+                    stmt.parameter_name(i)
+                        .filter(|name| !name.starts_with('?'))
+                        .map(EcoString::from)
+                })
+                .collect::<Option<Vec<_>>>();
+            let Some(names) = names else {
+                bail!("query has positional parameters, but a dictionary was given");
+            };
+            if let Some((key, _)) = values
+                .iter()
+                .find(|(key, _)| !names.iter().any(|name| name[1..] == ***key))
+            {
+                bail!("query has no parameter named `{key}`");
+            }
+            for (i, name) in names.into_iter().enumerate() {
+                let Ok(value) = values.get(&name[1..]) else {
+                    bail!("missing value for query parameter `{name}`");
+                };
+                stmt.raw_bind_parameter(i + 1, to_sql(value)?)
+                    .map_err(format_sqlite_error)?;
+            }
+        }
+    }
+
+    let columns: Vec<Str> = stmt.column_names().into_iter().map(Str::from).collect();
+
+    let mut array = Array::new();
+    let mut rows = stmt.raw_query();
+    while let Some(row) = rows.next().map_err(format_sqlite_error)? {
+        let mut dict = Dict::new();
+        for (i, column) in columns.iter().enumerate() {
+            let value = match row.get_ref(i).map_err(format_sqlite_error)? {
+                ValueRef::Null => Value::None,
+                ValueRef::Integer(v) => Value::Int(v),
+                ValueRef::Real(v) => Value::Float(v),
+                ValueRef::Text(v) => {
+                    Value::Str(String::from_utf8_lossy(v).as_ref().into())
+                }
+                ValueRef::Blob(v) => Value::Bytes(Bytes::new(v.to_vec())),
+            };
+            dict.insert(column.clone(), value);
+        }
+        array.push(Value::Dict(dict));
+    }
+
+    Ok(array)
+}
+
+/// Runs a query against a database.
+#[cfg(target_arch = "wasm32")]
+fn query_impl(_: &Bytes, _: &str, _: &SqlParams) -> StrResult<Array> {
+    bail!("SQLite databases are not supported on this platform")
+}
+
+/// Overrides the functions of the connection whose results are not determined
+/// by the database and the query alone.
+///
+/// This happens at the function level rather than by inspecting the query, as
+/// arguments like the `'now'` time value may also be computed or passed as
+/// parameters.
+#[cfg(not(target_arch = "wasm32"))]
+fn make_deterministic(conn: &rusqlite::Connection) -> rusqlite::Result<()> {
+    use std::sync::{Arc, Mutex};
+
+    use rusqlite::functions::FunctionFlags;
+    use rusqlite::types::{Value as SqlValue, ValueRef};
+    use rusqlite::{Connection, Error, params_from_iter};
+
+    let flags = FunctionFlags::SQLITE_UTF8 | FunctionFlags::SQLITE_DETERMINISTIC;
+
+    for &(name, n_arg) in FAILING_FUNCS {
+        conn.create_scalar_function(
+            name,
+            n_arg,
+            flags,
+            move |_| -> rusqlite::Result<SqlValue> {
+                Err(Error::UserFunctionError(
+                    format!("`{name}` is not available in queries").into(),
+                ))
+            },
+        )?;
+    }
+
+    // The overridden date and time functions delegate to the built-in ones of
+    // a separate connection once their arguments were checked.
+    let builtin = Arc::new(Mutex::new(Connection::open_in_memory()?));
+    for &(name, first, count) in TIME_FUNCS {
+        let builtin = builtin.clone();
+        conn.create_scalar_function(name, -1, flags, move |ctx| {
+            let text = |i: usize| match ctx.get_raw(i) {
+                ValueRef::Text(v) => {
+                    Some(String::from_utf8_lossy(v).trim().to_lowercase())
+                }
+                _ => None,
+            };
+            if ctx.len() < first + count
+                || (first..first + count).any(|i| text(i).as_deref() == Some("now"))
+            {
+                return Err(Error::UserFunctionError(
+                    "the current time is not available in queries".into(),
+                ));
+            }
+            if (first + count..ctx.len())
+                .any(|i| matches!(text(i).as_deref(), Some("localtime" | "utc")))
+            {
+                return Err(Error::UserFunctionError(
+                    "the local time zone is not available in queries".into(),
+                ));
+            }
+
+            let args = (0..ctx.len()).map(|i| SqlValue::from(ctx.get_raw(i)));
+            let placeholders = (1..=ctx.len())
+                .map(|i| format!("?{i}"))
+                .collect::<Vec<_>>()
+                .join(", ");
+            let builtin = builtin.lock().unwrap();
+            let mut stmt =
+                builtin.prepare_cached(&format!("SELECT {name}({placeholders})"))?;
+            stmt.query_row(params_from_iter(args), |row| row.get::<_, SqlValue>(0))
+        })?;
+    }
+
+    Ok(())
+}
+
+/// Converts a Typst value into an SQLite value.
+#[cfg(not(target_arch = "wasm32"))]
+fn to_sql(value: &Value) -> StrResult<rusqlite::types::Value> {
+    use rusqlite::types::Value as SqlValue;
+    Ok(match value {
+        Value::None => SqlValue::Null,
+        Value::Bool(v) => SqlValue::Integer(*v as i64),
+        Value::Int(v) => SqlValue::Integer(*v),
+        Value::Float(v) => SqlValue::Real(*v),
+        Value::Str(v) => SqlValue::Text(v.as_str().into()),
+        Value::Bytes(v) => SqlValue::Blob(v.to_vec()),
+        v => bail!("cannot use {} as a query parameter", v.ty()),
+    })
+}
+
+/// Format the user-facing SQLite error message.
+#[cfg(not(target_arch = "wasm32"))]
+fn format_sqlite_error(err: rusqlite::Error) -> EcoString {
+    // Denied functions fail to prepare with a generic error code, so the
+    // message needs to be checked, too.
+    if err.sqlite_error_code()
+        == Some(rusqlite::ErrorCode::AuthorizationForStatementDenied)
+        || err.to_string().starts_with("not authorized")
+    {
+        return "query is not allowed (queries must be read-only and deterministic)"
+            .into();
+    }
+
+    match err {
+        rusqlite::Error::SqliteFailure(_, Some(msg)) => {
+            eco_format!("failed to query SQLite database ({msg})")
+        }
+        err => eco_format!("failed to query SQLite database ({err})"),
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crate::foundations::{array, dict};
+
+    /// Creates a small database for testing.
+    fn database() -> Bytes {
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE animals (name TEXT, legs INTEGER, weight REAL, data BLOB);
+             INSERT INTO animals VALUES ('cat', 4, 4.5, x'01');
+             INSERT INTO animals VALUES ('bird', 2, NULL, NULL);",
+        )
+        .unwrap();
+        let data = conn.serialize(rusqlite::MAIN_DB).unwrap();
+        Bytes::new(data.to_vec())
+    }
+
+    fn query(sql: &str, params: SqlParams) -> StrResult<Array> {
+        query_impl(&database(), sql, &params)
+    }
+
+    #[test]
+    fn test_sqlite_query_types() {
+        let rows = query("SELECT * FROM animals ORDER BY legs", SqlParams::default());
+        assert_eq!(
+            rows.unwrap(),
+            array![
+                dict! {
+                    "name" => "bird",
+                    "legs" => 2,
+                    "weight" => Value::None,
+                    "data" => Value::None,
+                },
+                dict! {
+                    "name" => "cat",
+                    "legs" => 4,
+                    "weight" => 4.5,
+                    "data" => Bytes::new(vec![1u8]),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sqlite_query_params() {
+        let positional = SqlParams::Positional(array![2]);
+        let rows = query("SELECT name FROM animals WHERE legs = ?", positional);
+        assert_eq!(rows.unwrap(), array![dict! { "name" => "bird" }]);
+
+        let named = SqlParams::Named(dict! { "legs" => 4 });
+        let rows = query("SELECT name FROM animals WHERE legs = :legs", named);
+        assert_eq!(rows.unwrap(), array![dict! { "name" => "cat" }]);
+
+        let missing = SqlParams::Positional(array![]);
+        let err = query("SELECT name FROM animals WHERE legs = ?", missing);
+        assert_eq!(err.unwrap_err(), "query has 1 parameter, but 0 values were given");
+
+        let missing = SqlParams::Named(dict! { "legs" => 4 });
+        let err =
+            query("SELECT name FROM animals WHERE legs = :legs OR name = @name", missing);
+        assert_eq!(err.unwrap_err(), "missing value for query parameter `@name`");
+
+        let unknown = SqlParams::Named(dict! { "legs" => 4, "wings" => 2 });
+        let err = query("SELECT name FROM animals WHERE legs = $legs", unknown);
+        assert_eq!(err.unwrap_err(), "query has no parameter named `wings`");
+
+        let mixed = SqlParams::Named(dict! { "legs" => 4 });
+        let err = query("SELECT name FROM animals WHERE legs = :legs OR name = ?", mixed);
+        assert_eq!(
+            err.unwrap_err(),
+            "query has positional parameters, but a dictionary was given"
+        );
+    }
+
+    #[test]
+    fn test_sqlite_query_read_only() {
+        let err = query("DELETE FROM animals", SqlParams::default()).unwrap_err();
+        assert!(err.contains("not allowed") || err.contains("read-only"), "{err}");
+        let err = query("ATTACH 'other.db' AS other", SqlParams::default()).unwrap_err();
+        assert!(err.contains("not allowed") || err.contains("read-only"), "{err}");
+    }
+
+    #[track_caller]
+    fn test_rejected(sql: &str, params: SqlParams, message: &str) {
+        let err = query(sql, params).unwrap_err();
+        assert!(err.contains(message), "{sql}: {err}");
+    }
+
+    #[test]
+    fn test_sqlite_query_deterministic() {
+        let none = SqlParams::default;
+        let now = "the current time is not available";
+        test_rejected("SELECT date()", none(), now);
+        test_rejected("SELECT time()", none(), now);
+        test_rejected("SELECT datetime()", none(), now);
+        test_rejected("SELECT julianday()", none(), now);
+        test_rejected("SELECT unixepoch()", none(), now);
+        test_rejected("SELECT strftime('%s')", none(), now);
+        test_rejected("SELECT date('now')", none(), now);
+        test_rejected("SELECT date(' NOW ', '+1 day')", none(), now);
+        test_rejected("SELECT date('n' || 'ow')", none(), now);
+        test_rejected("SELECT timediff('2024-01-01', 'now')", none(), now);
+        test_rejected("SELECT date(?)", SqlParams::Positional(array!["now"]), now);
+        test_rejected(
+            "SELECT date('2024-01-01', 'localtime')",
+            none(),
+            "the local time zone is not available",
+        );
+        test_rejected("SELECT CURRENT_TIMESTAMP", none(), "`current_timestamp`");
+        test_rejected("SELECT random()", none(), "`random` is not available");
+        test_rejected("SELECT randomblob(4)", none(), "`randomblob` is not available");
+        test_rejected("SELECT changes()", none(), "not allowed");
+
+        let rows = query("SELECT date('2024-01-31', '+1 day') AS d", none());
+        assert_eq!(rows.unwrap(), array![dict! { "d" => "2024-02-01" }]);
+        let rows = query("SELECT strftime('%Y', '2024-05-01') AS y", none());
+        assert_eq!(rows.unwrap(), array![dict! { "y" => "2024" }]);
+        let rows = query(
+            "SELECT unixepoch(?) AS t",
+            SqlParams::Positional(array!["1970-01-02"]),
+        );
+        assert_eq!(rows.unwrap(), array![dict! { "t" => 86400 }]);
+    }
+}
@@ -1,9 +1,15 @@
+use std::fmt::Write;
+
+use ecow::{EcoString, eco_format};
 use roxmltree::ParsingOptions;
 use typst_syntax::Spanned;
+use unscanny::Scanner;
 
-use crate::diag::{LoadError, LoadedWithin, SourceResult, format_xml_like_error};
+use crate::diag::{
+    At, LoadError, LoadedWithin, SourceResult, StrResult, bail, format_xml_like_error,
+};
 use crate::engine::Engine;
-use crate::foundations::{Array, Dict, IntoValue, Str, Value, dict, func};
+use crate::foundations::{Array, Dict, IntoValue, Repr, Str, Value, dict, func, scope};
 use crate::loading::{DataSource, Load};
 
 /// Reads structured data from an XML file.
@@ -13,9 +19,14 @@ use crate::loading::{DataSource, Load};
 /// the following keys:
 ///
 /// - `tag`: The name of the element as a string.
+/// - `namespace`: The namespace URI of the element as a string, or `{none}` if
+///   it is not in a namespace.
 /// - `attrs`: A dictionary of the element's attributes as strings.
 /// - `children`: An array of the element's child nodes.
 ///
+/// Instead of walking this structure by hand, you can use @xml.query to find
+/// elements with a CSS-style selector. To write XML, use @xml.encode.
+///
 /// The XML file in the example contains a root `news` tag with multiple
 /// `article` tags. Each article has a `title`, `author`, and `content` tag. The
 /// `content` tag contains one or more paragraphs, which are represented as `p`
@@ -23,15 +34,9 @@ use crate::loading::{DataSource, Load};
 ///
 /// = Example <example>
 /// ```example
-/// #let find-child(elem, tag) = {
-///   elem.children
-///     .find(e => "tag" in e and e.tag == tag)
-/// }
-///
 /// #let article(elem) = {
-///   let title = find-child(elem, "title")
-///   let author = find-child(elem, "author")
-///   let pars = find-child(elem, "content")
+///   let title = xml.query(elem, "title").first()
+///   let author = xml.query(elem, "author").first()
 ///
 ///   [= #title.children.first()]
 ///   text(10pt, weight: "medium")[
@@ -39,22 +44,18 @@ use crate::loading::{DataSource, Load};
 ///     #author.children.first()
 ///   ]
 ///
-///   for p in pars.children {
-///     if type(p) == dictionary {
-///       parbreak()
-///       p.children.first()
-///     }
+///   for p in xml.query(elem, "content > p") {
+///     parbreak()
+///     p.children.first()
 ///   }
 /// }
 ///
 /// #let data = xml("example.xml")
-/// #for elem in data.first().children {
-///   if type(elem) == dictionary {
-///     article(elem)
-///   }
+/// #for elem in xml.query(data, "news > article") {
+///   article(elem)
 /// }
 /// ```
-#[func(title = "XML")]
+#[func(scope, title = "XML")]
 pub fn xml(
     engine: &mut Engine,
     /// A path to an XML file or raw XML bytes.
@@ -71,6 +72,138 @@ pub fn xml(
     Ok(convert_xml(document.root()))
 }
 
+#[scope]
+impl xml {
+    /// Finds all elements matching a selector in loaded XML data.
+    ///
+    /// The selector syntax is a subset of CSS. It supports
+    /// - tag names like `{"title"}` and the universal selector `{"*"}`,
+    /// - attribute selectors like `{"[lang]"}`, `{"[lang=\"en\"]"}`, and
+    ///   `{"[rel~=\"tag\"]"}`,
+    /// - shorthands for the `id` and `class` attributes like `{"#intro"}` and
+    ///   `{".note"}`,
+    /// - namespace prefixes like `{"atom|entry"}` (see
+    ///   @xml.query.namespaces[`namespaces`]),
+    /// - descendant (`{"a b"}`) and child (`{"a > b"}`) combinators,
+    /// - and lists of alternatives (`{"h1, h2"}`).
+    ///
+    /// The matching elements are returned in document order.
+    ///
+    /// ```example
+    /// #let data = xml(bytes(
+    ///   "<feed><entry><title>A</title></entry>
+    ///    <entry draft=\"true\"><title>B</title></entry></feed>"
+    /// ))
+    ///
+    /// #for title in xml.query(data, "entry > title") {
+    ///   title.children.first()
+    /// }
+    /// #xml.query(data, "entry[draft]").len()
+    /// ```
+    #[func(title = "Query XML")]
+    pub fn query(
+        /// The nodes to search in, as returned by @xml. Can be an array of
+        /// nodes or a single element.
+        ///
+        /// The given elements and all of their descendants are searched.
+        nodes: Spanned<Value>,
+        /// The selector to match elements against.
+        selector: Spanned<Str>,
+        /// Maps namespace prefixes used in the selector to namespace URIs.
+        ///
+        /// A tag name without a prefix matches elements in any namespace. A
+        /// tag name with the prefix `*` does so as well, while a tag name
+        /// with an empty prefix (as in `{"|title"}`) only matches elements
+        /// without a namespace.
+        ///
+        /// ```example
+        /// #let data = xml(bytes(
+        ///   "<doc xmlns:m=\"urn:meta\">
+        ///      <title>Body</title>
+        ///      <m:title>Meta</m:title>
+        ///    </doc>"
+        /// ))
+        ///
+        /// #xml.query(
+        ///   data,
+        ///   "m|title",
+        ///   namespaces: (m: "urn:meta"),
+        /// ).first().children
+        /// ```
+        #[named]
+        #[default]
+        namespaces: Dict,
+    ) -> SourceResult<Array> {
+        let selector = Selector::parse(&selector.v, &namespaces).at(selector.span)?;
+        let roots = match nodes.v {
+            Value::Array(array) => array,
+            Value::Dict(dict) => std::iter::once(Value::Dict(dict)).collect(),
+            v => bail!(
+                nodes.span,
+                "expected array or dictionary, found {}",
+                v.ty();
+                hint: "pass the result of `xml` or one of the elements in it";
+            ),
+        };
+
+        let mut matches = Array::new();
+        let mut ancestors = vec![];
+        for root in roots.iter() {
+            selector.collect(root, &mut ancestors, &mut matches);
+        }
+        Ok(matches)
+    }
+
+    /// Encodes XML nodes into an XML string.
+    ///
+    /// The nodes must be in the same format as the one produced by @xml:
+    /// Strings are encoded as text and dictionaries as elements with the keys
+    /// `tag`, `attrs`, `children`, and `namespace`, of which only `tag` is
+    /// required. An element without a `namespace` key inherits the namespace
+    /// of its parent. Namespaces are declared with an `xmlns` attribute where
+    /// they differ from the parent's.
+    ///
+    /// ```example
+    /// #let feed = (
+    ///   tag: "feed",
+    ///   namespace: "http://www.w3.org/2005/Atom",
+    ///   children: (
+    ///     (tag: "title", children: ("Notes",)),
+    ///     (tag: "link", attrs: (href: "https://example.com")),
+    ///   ),
+    /// )
+    ///
+    /// #raw(lang: "xml", xml.encode(feed))
+    /// ```
+    #[func(title = "Encode XML")]
+    pub fn encode(
+        /// The nodes to encode. Can be an array of nodes or a single element.
+        value: Spanned<Value>,
+        /// Whether to pretty-print the resulting XML.
+        ///
+        /// Only elements whose children are all elements (or whitespace) are
+        /// indented. Text content is always written as-is.
+        #[named]
+        #[default(true)]
+        pretty: bool,
+    ) -> SourceResult<Str> {
+        let Spanned { v: value, span } = value;
+        let nodes = match value {
+            Value::Array(nodes) => nodes,
+            node => std::iter::once(node).collect(),
+        };
+
+        let mut encoder = Encoder { buf: String::new(), pretty };
+        for node in nodes.iter() {
+            encoder
+                .node(node, None, 0)
+                .map_err(|err| eco_format!("failed to encode value as XML ({err})"))
+                .at(span)?;
+        }
+        Ok(encoder.buf.trim_end().into())
+    }
+}
+
 /// Convert an XML node to a Typst value.
 fn convert_xml(node: roxmltree::Node) -> Value {
     if node.is_text() {
@@ -101,3 +234,427 @@ fn convert_xml(node: roxmltree::Node) -> Value {
 fn format_xml_error(error: roxmltree::Error) -> LoadError {
     format_xml_like_error("XML", error)
 }
+
+/// A parsed selector for @xml.query.
+struct Selector(Vec<Complex>);
+
+/// Compound selectors joined by combinators. The combinator of the first
+/// compound selector is meaningless.
+struct Complex(Vec<(Combinator, Compound)>);
+
+/// How two compound selectors relate to each other.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum Combinator {
+    /// The right element is a descendant of the left one.
+    Descendant,
+    /// The right element is a direct child of the left one.
+    Child,
+}
+
+/// A sequence of simple selectors that all need to match the same element.
+#[derive(Default)]
+struct Compound {
+    namespace: NamespaceMatch,
+    tag: Option<EcoString>,
+    attrs: Vec<(EcoString, AttrMatch)>,
+}
+
+/// Which namespaces an element may be in.
+#[derive(Default)]
+enum NamespaceMatch {
+    #[default]
+    Any,
+    None,
+    Uri(EcoString),
+}
+
+/// A condition on an attribute.
+enum AttrMatch {
+    /// The attribute exists.
+    Exists,
+    /// The attribute has exactly the given value.
+    Equals(EcoString),
+    /// The attribute is a whitespace-separated list containing the value.
+    Includes(EcoString),
+}
+
+impl Selector {
+    /// Parse a selector, resolving namespace prefixes with the given map.
+    fn parse(text: &str, namespaces: &Dict) -> StrResult<Self> {
+        let mut s = Scanner::new(text);
+        let mut list = vec![];
+        loop {
+            list.push(Complex::parse(&mut s, namespaces)?);
+            s.eat_whitespace();
+            if s.done() {
+                break;
+            } else if !s.eat_if(',') {
+                bail!("invalid selector (unexpected `{}`)", s.peek().unwrap());
+            }
+        }
+        Ok(Self(list))
+    }
+
+    /// Collect all matches in the subtree of `node`, in document order.
+    fn collect<'a>(
+        &self,
+        node: &'a Value,
+        ancestors: &mut Vec<&'a Dict>,
+        out: &mut Array,
+    ) {
+        let Value::Dict(elem) = node else { return };
+        if self.0.iter().any(|complex| complex.matches(elem, ancestors)) {
+            out.push(node.clone());
+        }
+        if let Ok(Value::Array(children)) = elem.get("children") {
+            ancestors.push(elem);
+            for child in children.iter() {
+                self.collect(child, ancestors, out);
+            }
+            ancestors.pop();
+        }
+    }
+}
+
+impl Complex {
+    fn parse(s: &mut Scanner, namespaces: &Dict) -> StrResult<Self> {
+        let mut parts = vec![];
+        let mut combinator = Combinator::Descendant;
+        loop {
+            s.eat_whitespace();
+            parts.push((combinator, Compound::parse(s, namespaces)?));
+            let had_space = !s.eat_whitespace().is_empty();
+            combinator = if s.eat_if('>') {
+                Combinator::Child
+            } else if had_space && !s.done() && !s.at(',') {
+                Combinator::Descendant
+            } else {
+                break;
+            };
+        }
+        Ok(Self(parts))
+    }
+
+    /// Whether this selector matches the element with the given ancestors
+    /// (outermost first).
+    fn matches(&self, elem: &Dict, ancestors: &[&Dict]) -> bool {
+        Self::matches_parts(&self.0, elem, ancestors)
+    }
+
+    fn matches_parts(
+        parts: &[(Combinator, Compound)],
+        elem: &Dict,
+        ancestors: &[&Dict],
+    ) -> bool {
+        let Some(((combinator, compound), rest)) = parts.split_last() else {
+            return true;
+        };
+        if !compound.matches(elem) {
+            return false;
+        }
+        if rest.is_empty() {
+            return true;
+        }
+        match combinator {
+            Combinator::Child => ancestors
+                .split_last()
+                .is_some_and(|(parent, above)| Self::matches_parts(rest, parent, above)),
+            Combinator::Descendant => (0..ancestors.len())
+                .rev()
+                .any(|i| Self::matches_parts(rest, ancestors[i], &ancestors[..i])),
+        }
+    }
+}
+
+impl Compound {
+    fn parse(s: &mut Scanner, namespaces: &Dict) -> StrResult<Self> {
+        let mut compound = Self::default();
+        let start = s.cursor();
+
+        // Type selector with optional namespace prefix.
+        let first = if s.eat_if('*') { Some("*") } else { name(s) };
+        if s.eat_if('|') {
+            compound.namespace = match first {
+                Some("*") => NamespaceMatch::Any,
+                Some(prefix) => match namespaces.get(prefix) {
+                    Ok(Value::Str(uri)) => NamespaceMatch::Uri(uri.clone().into()),
+                    _ => bail!("unknown namespace prefix `{prefix}`"),
+                },
+                None => NamespaceMatch::None,
+            };
+            let tag = if s.eat_if('*') { Some("*") } else { name(s) };
+            let Some(tag) = tag else { bail!("invalid selector (expected tag name)") };
+            compound.tag = (tag != "*").then(|| tag.into());
+        } else {
+            compound.tag = first.filter(|&tag| tag != "*").map(Into::into);
+        }
+
+        loop {
+            if s.eat_if('#') {
+                let Some(id) = name(s) else { bail!("invalid selector (expected id)") };
+                compound.attrs.push(("id".into(), AttrMatch::Equals(id.into())));
+            } else if s.eat_if('.') {
+                let Some(class) = name(s) else {
+                    bail!("invalid selector (expected class name)")
+                };
+                compound
+                    .attrs
+                    .push(("class".into(), AttrMatch::Includes(class.into())));
+            } else if s.eat_if('[') {
+                s.eat_whitespace();
+                let Some(attr) = name(s) else {
+                    bail!("invalid selector (expected attribute name)")
+                };
+                s.eat_whitespace();
+                let op = if s.eat_if(']') {
+                    compound.attrs.push((attr.into(), AttrMatch::Exists));
+                    continue;
+                } else if s.eat_if("~=") {
+                    AttrMatch::Includes
+                } else if s.eat_if('=') {
+                    AttrMatch::Equals
+                } else {
+                    bail!("invalid selector (expected `]`, `=`, or `~=`)");
+                };
+                s.eat_whitespace();
+                let value =
+                    if let Some(quote) = s.eat_if(['"', '\'']).then(|| s.scout(-1)) {
+                        let value = s.eat_until(|c| Some(c) == quote);
+                        if !s.eat_if(|c| Some(c) == quote) {
+                            bail!("invalid selector (unclosed string)");
+                        }
+                        value
+                    } else {
+                        let Some(value) = name(s) else {
+                            bail!("invalid selector (expected attribute value)")
+                        };
+                        value
+                    };
+                s.eat_whitespace();
+                if !s.eat_if(']') {
+                    bail!("invalid selector (expected `]`)");
+                }
+                compound.attrs.push((attr.into(), op(value.into())));
+            } else {
+                break;
+            }
+        }
+
+        if s.cursor() == start {
+            match s.peek() {
+                Some(c) => bail!("invalid selector (unexpected `{c}`)"),
+                None => bail!("invalid selector (expected tag name)"),
+            }
+        }
+
+        Ok(compound)
+    }
+
+    fn matches(&self, elem: &Dict) -> bool {
+        let Ok(Value::Str(tag)) = elem.get("tag") else { return false };
+        if self
+            .tag
+            .as_ref()
+            .is_some_and(|expected| expected.as_str() != tag.as_str())
+        {
+            return false;
+        }
+
+        let namespace = match elem.get("namespace") {
+            Ok(Value::Str(uri)) => Some(uri.as_str()),
+            _ => None,
+        };
+        let namespace_ok = match &self.namespace {
+            NamespaceMatch::Any => true,
+            NamespaceMatch::None => namespace.is_none(),
+            NamespaceMatch::Uri(uri) => namespace == Some(uri.as_str()),
+        };
+        if !namespace_ok {
+            return false;
+        }
+
+        let attrs = match elem.get("attrs") {
+            Ok(Value::Dict(attrs)) => Some(attrs),
+            _ => None,
+        };
+        self.attrs.iter().all(|(name, condition)| {
+            let Some(value) = attrs.and_then(|attrs| attrs.get(name).ok()) else {
+                return false;
+            };
+            let Value::Str(value) = value else {
+                return matches!(condition, AttrMatch::Exists);
+            };
+            match condition {
+                AttrMatch::Exists => true,
+                AttrMatch::Equals(expected) => value.as_str() == expected.as_str(),
+                AttrMatch::Includes(expected) => {
+                    value.split_whitespace().any(|word| word == expected.as_str())
+                }
+            }
+        })
+    }
+}
+
+/// Eat an XML name, as used for tags and attributes in selectors.
+fn name<'a>(s: &mut Scanner<'a>) -> Option<&'a str> {
+    let name = s.eat_while(|c: char| c.is_alphanumeric() || matches!(c, '-' | '_'));
+    (!name.is_empty()).then_some(name)
+}
+
+/// Writes XML nodes into a string.
+struct Encoder {
+    buf: String,
+    pretty: bool,
+}
+
+impl Encoder {
+    /// Encode a single node. `namespace` is the default namespace in scope.
+    fn node(
+        &mut self,
+        node: &Value,
+        namespace: Option<&str>,
+        depth: usize,
+    ) -> StrResult<()> {
+        match node {
+            Value::Str(text) => {
+                escape(&mut self.buf, text, false);
+                Ok(())
+            }
+            Value::Dict(elem) => self.elem(elem, namespace, depth),
+            v => bail!("expected string or dictionary, found {}", v.ty()),
+        }
+    }
+
+    /// Encode an element.
+    fn elem(
+        &mut self,
+        elem: &Dict,
+        namespace: Option<&str>,
+        depth: usize,
+    ) -> StrResult<()> {
+        let tag = match elem.get("tag") {
+            Ok(Value::Str(tag)) if tag.is_empty() => bail!("tag must not be empty"),
+            Ok(Value::Str(tag)) if !is_name(tag) => {
+                bail!("tag {} is not a valid XML name", tag.repr())
+            }
+            Ok(Value::Str(tag)) => tag,
+            Ok(v) => bail!("expected tag to be a string, found {}", v.ty()),
+            Err(_) => bail!("element is missing a tag"),
+        };
+
+        write!(self.buf, "<{tag}").unwrap();
+
+        let own_namespace = match elem.get("namespace") {
+            Ok(Value::Str(uri)) => Some(uri.as_str()),
+            Ok(Value::None) => None,
+            Ok(v) => bail!("expected namespace to be a string or none, found {}", v.ty()),
+            Err(_) => namespace,
+        };
+        let attrs = match elem.get("attrs") {
+            Ok(Value::Dict(attrs)) => attrs.clone(),
+            Ok(v) => bail!("expected attributes to be a dictionary, found {}", v.ty()),
+            Err(_) => Dict::new(),
+        };
+        if own_namespace != namespace && !attrs.contains("xmlns") {
+            self.buf.push_str(" xmlns=\"");
+            escape(&mut self.buf, own_namespace.unwrap_or_default(), true);
+            self.buf.push('"');
+        }
+        for (name, value) in attrs.iter() {
+            if !is_name(name) {
+                bail!("attribute {} is not a valid XML name", name.repr());
+            }
+            let Value::Str(value) = value else {
+                bail!("expected attribute `{name}` to be a string, found {}", value.ty());
+            };
+            write!(self.buf, " {name}=\"").unwrap();
+            escape(&mut self.buf, value, true);
+            self.buf.push('"');
+        }
+
+        let children = match elem.get("children") {
+            Ok(Value::Array(children)) => children.clone(),
+            Ok(v) => bail!("expected children to be an array, found {}", v.ty()),
+            Err(_) => Array::new(),
+        };
+        if children.is_empty() {
+            self.buf.push_str("/>");
+        } else {
+            self.buf.push('>');
+            // Only reindent elements without meaningful text, so as not to
+            // change the content of mixed text and elements.
+            let indent = self.pretty
+                && children.iter().all(|child| match child {
+                    Value::Str(text) => text.as_str().trim().is_empty(),
+                    _ => true,
+                });
+            for child in children.iter() {
+                if indent {
+                    if matches!(child, Value::Str(_)) {
+                        continue;
+                    }
+                    self.newline(depth + 1);
+                }
+                self.node(child, own_namespace, depth + 1)?;
+            }
+            if indent {
+                self.newline(depth);
+            }
+            write!(self.buf, "</{tag}>").unwrap();
+        }
+
+        if self.pretty && depth == 0 {
+            self.buf.push('\n');
+        }
+
+        Ok(())
+    }
+
+    /// Start a new, indented line.
+    fn newline(&mut self, depth: usize) {
+        self.buf.push('\n');
+        for _ in 0..depth {
+            self.buf.push_str("  ");
+        }
+    }
+}
+
+/// Whether the string matches the `Name` production of the XML specification.
+fn is_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(is_name_start_char) && chars.all(is_name_char)
+}
+
+/// Whether the character may start an XML name.
+fn is_name_start_char(c: char) -> bool {
+    matches!(c,
+        ':' | 'A'..='Z' | '_' | 'a'..='z'
+        | '\u{C0}'..='\u{D6}' | '\u{D8}'..='\u{F6}' | '\u{F8}'..='\u{2FF}'
+        | '\u{370}'..='\u{37D}' | '\u{37F}'..='\u{1FFF}' | '\u{200C}'..='\u{200D}'
+        | '\u{2070}'..='\u{218F}' | '\u{2C00}'..='\u{2FEF}' | '\u{3001}'..='\u{D7FF}'
+        | '\u{F900}'..='\u{FDCF}' | '\u{FDF0}'..='\u{FFFD}' | '\u{10000}'..='\u{EFFFF}'
+    )
+}
+
+/// Whether the character may appear in an XML name after the first one.
+fn is_name_char(c: char) -> bool {
+    is_name_start_char(c)
+        || matches!(c,
+            '-' | '.' | '0'..='9' | '\u{B7}'
+            | '\u{300}'..='\u{36F}' | '\u{203F}'..='\u{2040}'
+        )
+}
+
+/// Escape text or an attribute value for inclusion in XML.
+fn escape(buf: &mut String, text: &str, attr: bool) {
+    for c in text.chars() {
+        match c {
+            '&' => buf.push_str("&amp;"),
+            '<' => buf.push_str("&lt;"),
+            '>' => buf.push_str("&gt;"),
+            '"' if attr => buf.push_str("&quot;"),
+            '\n' if attr => buf.push_str("&#10;"),
+            c => buf.push(c),
+        }
+    }
+}
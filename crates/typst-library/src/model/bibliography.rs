@@ -23,7 +23,7 @@ use crate::diag::{
     At, HintedStrResult, LoadError, LoadResult, LoadedWithin, ReportPos,
     SourceDiagnostic, SourceResult, StrResult, bail, error, warning,
 };
-use crate::engine::{Engine, Sink};
+use crate::engine::{Cancellation, Engine, Fuel, Sink};
 use crate::foundations::{
     Bytes, CastInfo, Content, Context, Derived, FromValue, IntoValue, Label,
     NativeElement, OneOrMultiple, Packed, Reflect, Scope, ShowSet, Smart, StyleChain,
@@ -1059,6 +1059,8 @@ impl ElemRenderer<'_> {
         (library.routines.eval_string)(
             self.world,
             library,
+            Cancellation::new().track(),
+            Fuel::new(library.limits.fuel).track(),
             // TODO: propagate warnings
             Sink::new().track_mut(),
             EmptyIntrospector.track(),
@@ -20,9 +20,9 @@ use crate::layout::{
     Abs, Axes, BlockBody, BlockElem, BoxElem, Dir, Em, Fr, HElem, Length, Region, Rel,
     RepeatElem, Sides,
 };
-use crate::model::{HeadingElem, NumberingPattern, ParElem, Refable};
+use crate::model::{HeadingElem, Justify, NumberingPattern, ParElem, Refable};
 use crate::pdf::PdfMarkerTag;
-use crate::text::{LocalName, SpaceElem, TextElem};
+use crate::text::{self, LocalName, SpaceElem, TextElem};
 
 /// A table of contents, figures, or other elements.
 ///
@@ -399,7 +399,7 @@ impl ShowSet for Packed<OutlineElem> {
         let mut out = Styles::new();
         out.set(HeadingElem::outlined, false);
         out.set(HeadingElem::numbering, None);
-        out.set(ParElem::justify, false);
+        out.set(ParElem::justify, Justify::Off);
         out.set(BlockElem::above, Smart::Custom(styles.get(ParElem::leading).into()));
         // Makes the outline itself available to its entries. Should be
         // superseded by a proper ancestry mechanism in the future.
@@ -610,7 +610,7 @@ impl OutlineEntry {
         };
 
         let inset = Sides::default().with(
-            styles.resolve(TextElem::dir).start(),
+            text::line_start(styles),
             Some(base_indent + Rel::from(hanging_indent.unwrap_or_default())),
         );
 
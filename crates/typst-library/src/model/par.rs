@@ -237,8 +237,22 @@ pub struct ParElem {
     /// justification. However, you can also allow it to adjust the spacing
     /// between individual characters using the
     /// @par.justification-limits[`justification-limits` property].
-    #[default(false)]
-    pub justify: bool,
+    ///
+    /// Text in Arabic scripts is traditionally justified by elongating the
+    /// connections between letters with _kashidas_ (tatweels) rather than by
+    /// widening the spaces between words. Setting this to `{"kashida"}`
+    /// inserts kashidas at the last joint of each Arabic word of a justified
+    /// line. Each joint is elongated by at most one em. Lines without any such
+    /// joint and space that remains after elongating all joints are handled by
+    /// adjusting the spacing as usual.
+    ///
+    /// ```example
+    /// #set page(width: 160pt)
+    /// #set text(lang: "ar", font: "Noto Sans Arabic")
+    /// #set par(justify: "kashida")
+    /// لا يمكن أن يكون هناك سلام دائم في العالم بدون عدالة اجتماعية
+    /// ```
+    pub justify: Justify,
 
     /// How much the spacing between words and characters may be adjusted during
     /// justification.
@@ -621,6 +635,38 @@ impl Limit for Rel<Length> {
     }
 }
 
+/// How to justify the lines of a paragraph.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Justify {
+    /// Don't justify the lines.
+    #[default]
+    Off,
+    /// Justify the lines by adjusting the spacing.
+    Spacing,
+    /// Justify the lines by inserting kashidas into Arabic words and adjusting
+    /// the spacing where that is not possible.
+    Kashida,
+}
+
+impl Justify {
+    /// Whether the lines are justified at all.
+    pub fn is_on(self) -> bool {
+        self != Self::Off
+    }
+}
+
+cast! {
+    Justify,
+    self => match self {
+        Self::Off => false.into_value(),
+        Self::Spacing => true.into_value(),
+        Self::Kashida => "kashida".into_value(),
+    },
+    v: bool => if v { Self::Spacing } else { Self::Off },
+    /// Justify Arabic text with kashidas.
+    "kashida" => Self::Kashida,
+}
+
 /// How to determine line breaks in a paragraph.
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
 pub enum Linebreaks {
@@ -339,4 +339,8 @@ pdf_marker_tag! {
     TermsItemBody,
     /// A generic `Lbl`.
     Label,
+    /// `RB` base text of a ruby annotation.
+    RubyBase,
+    /// `RT` text of a ruby annotation.
+    RubyText,
 }
@@ -6,7 +6,7 @@ use typst_syntax::{FileId, RangeMapper, Span, SyntaxMode};
 use typst_utils::LazyHash;
 
 use crate::diag::SourceResult;
-use crate::engine::{Engine, Route, Sink, Traced};
+use crate::engine::{Cancellation, Engine, Fuel, Route, Sink, Traced};
 use crate::foundations::{
     Args, Closure, Content, Context, Func, Module, NativeRuleMap, Scope, StyleChain,
     Styles, Value,
@@ -55,6 +55,8 @@ routines! {
     fn eval_string(
         world: Tracked<dyn World + '_>,
         library: &LazyHash<Library>,
+        cancellation: Tracked<Cancellation>,
+        fuel: Tracked<Fuel>,
         sink: TrackedMut<Sink>,
         introspector: Tracked<dyn Introspector + '_>,
         context: Tracked<Context>,
@@ -72,6 +74,8 @@ routines! {
         library: &LazyHash<Library>,
         introspector: Tracked<dyn Introspector + '_>,
         traced: Tracked<Traced>,
+        cancellation: Tracked<Cancellation>,
+        fuel: Tracked<Fuel>,
         sink: TrackedMut<Sink>,
         route: Tracked<Route>,
         context: Tracked<Context>,
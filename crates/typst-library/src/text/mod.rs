@@ -9,6 +9,7 @@ mod linebreak;
 #[path = "lorem.rs"]
 mod lorem_;
 mod raw;
+mod ruby;
 mod shift;
 #[path = "smallcaps.rs"]
 mod smallcaps_;
@@ -23,6 +24,7 @@ pub use self::lang::*;
 pub use self::linebreak::*;
 pub use self::lorem_::*;
 pub use self::raw::*;
+pub use self::ruby::*;
 pub use self::shift::*;
 pub use self::smallcaps_::*;
 pub use self::smartquote::*;
@@ -52,7 +54,7 @@ use crate::foundations::{
     NoneValue, Packed, PlainText, Regex, Repr, Resolve, Scope, Set, Smart, Str,
     StyleChain, cast, dict, elem,
 };
-use crate::layout::{Abs, Axis, Dir, Em, Length, Ratio, Rel};
+use crate::layout::{Abs, Dir, Em, Length, Ratio, Rel, Side};
 use crate::math::{EquationElem, MathSize};
 use crate::visualize::{Color, Paint, RelativeTo, Stroke};
 
@@ -69,6 +71,7 @@ pub(super) fn define(global: &mut Scope) {
     global.define_elem::<StrikeElem>();
     global.define_elem::<HighlightElem>();
     global.define_elem::<SmallcapsElem>();
+    global.define_elem::<RubyElem>();
     global.define_elem::<RawElem>();
     global.define_func::<lower>();
     global.define_func::<upper>();
@@ -489,6 +492,12 @@ pub struct TextElem {
     /// - `{auto}`: Automatically infer the direction from the `lang` property.
     /// - `{ltr}`: Layout text from left to right.
     /// - `{rtl}`: Layout text from right to left.
+    /// - `{ttb}`: Layout the lines of a paragraph from top to bottom as columns
+    ///   that progress from right to left, as is customary for vertical
+    ///   Chinese, Japanese, and Korean text. This only affects paragraphs, not
+    ///   the page as a whole (see below). How individual characters are
+    ///   oriented is configured through the @text.orientation[`orientation`]
+    ///   property.
     ///
     /// When writing in right-to-left scripts like Arabic or Hebrew, you should
     /// set the @text.lang[text language] or direction. While individual runs of
@@ -509,9 +518,67 @@ pub struct TextElem {
     /// #set text(dir: rtl)
     /// هذا عربي.
     /// ```
+    ///
+    /// Vertical text is laid out paragraph by paragraph: The lines of a
+    /// paragraph become columns that are grouped into bands as wide as the
+    /// available space. Vertical layout of the page itself is not supported
+    /// yet. This means that consecutive paragraphs and other blocks are still
+    /// stacked from top to bottom instead of progressing from right to left
+    /// across the page. Indents and insets of lists, terms, headings, and
+    /// outlines are applied at the left.
+    ///
+    /// ```example
+    /// #set page(width: 120pt, height: 120pt)
+    /// #set text(lang: "ja", dir: ttb)
+    /// 吾輩は猫である。名前はまだ無い。どこで生れたかとんと見当がつかぬ。
+    /// ```
     #[ghost]
     pub dir: TextDir,
 
+    /// How characters are oriented in vertical text. Possible values are:
+    ///
+    /// - `{"mixed"}`: Set characters from scripts that are traditionally
+    ///   written vertically (such as Han, Kana, and Hangul) upright and rotate
+    ///   all other text by 90 degrees clockwise.
+    /// - `{"upright"}`: Set all characters upright.
+    /// - `{"sideways"}`: Rotate all text by 90 degrees clockwise.
+    ///
+    /// Unless the orientation is `{"sideways"}`, text is shaped with the
+    /// font's vertical alternates (the `vert` OpenType feature) so that
+    /// punctuation and brackets take their vertical forms. Rotated text is
+    /// rotated as a whole and keeps its horizontal forms.
+    ///
+    /// This has no effect unless the @text.dir[text direction] of the
+    /// enclosing paragraph is `{ttb}`.
+    ///
+    /// ```example
+    /// #set page(width: 100pt, height: 110pt)
+    /// #set text(lang: "ja", dir: ttb)
+    /// 東京（Tokyo）
+    ///
+    /// #set text(orientation: "upright")
+    /// 東京（Tokyo）
+    /// ```
+    #[ghost]
+    pub orientation: TextOrientation,
+
+    /// The maximum number of consecutive ASCII digits that are combined into a
+    /// single upright unit in vertical text. This is known as _tate-chū-yoko_
+    /// (horizontal-in-vertical). Longer runs of digits are rotated like other
+    /// horizontal text. Set this to `{0}` to disable the combination.
+    ///
+    /// This has no effect unless the @text.dir[text direction] is `{ttb}` and
+    /// the @text.orientation[orientation] is not `{"sideways"}`.
+    ///
+    /// ```example
+    /// #set page(width: 60pt, height: 120pt)
+    /// #set text(lang: "ja", dir: ttb)
+    /// 平成31年4月
+    /// ```
+    #[ghost]
+    #[default(2)]
+    pub combine_digits: usize,
+
     /// Whether to hyphenate text to improve line breaking. When `{auto}`, text
     /// will be hyphenated if and only if justification is enabled.
     ///
@@ -1163,8 +1230,8 @@ cast! {
     TextDir,
     self => self.0.into_value(),
     v: Smart<Dir> => {
-        if v.is_custom_and(|dir| dir.axis() == Axis::Y) {
-            bail!("text direction must be horizontal");
+        if v.is_custom_and(|dir| dir == Dir::BTT) {
+            bail!("text direction must be horizontal or top-to-bottom");
         }
         Self(v)
     },
@@ -1181,6 +1248,18 @@ impl Resolve for TextDir {
     }
 }
 
+/// How characters are oriented in vertical text.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum TextOrientation {
+    /// Set characters from vertical scripts upright and rotate the rest.
+    #[default]
+    Mixed,
+    /// Set all characters upright.
+    Upright,
+    /// Rotate all characters by 90 degrees clockwise.
+    Sideways,
+}
+
 /// A selection into the Stylistic Alternates.
 #[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
 pub struct Alternates(u32);
@@ -1417,6 +1496,18 @@ pub fn features(styles: StyleChain) -> Vec<Feature> {
     tags
 }
 
+/// The physical side at which the lines of a paragraph start.
+///
+/// Vertical paragraphs are stacked from top to bottom like horizontal ones and
+/// their columns are placed from right to left within them, so indents and
+/// insets still belong to the left rather than to the top.
+pub fn line_start(styles: StyleChain) -> Side {
+    match styles.resolve(TextElem::dir) {
+        Dir::TTB => Side::Left,
+        dir => dir.start(),
+    }
+}
+
 /// Process the language and region of a style chain into a
 /// rustybuzz-compatible BCP 47 language.
 pub fn language(styles: StyleChain) -> rustybuzz::Language {
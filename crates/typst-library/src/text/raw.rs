@@ -23,7 +23,7 @@ use crate::foundations::{
 use crate::introspection::{Locatable, Tagged};
 use crate::layout::{Em, HAlignment};
 use crate::loading::{DataSource, Load};
-use crate::model::{Figurable, ParElem};
+use crate::model::{Figurable, Justify, ParElem};
 use crate::routines::Routines;
 use crate::text::{FontFamily, FontList, LocalName, TextElem, TextSize};
 use crate::visualize::Color;
@@ -506,7 +506,7 @@ impl ShowSet for Packed<RawElem> {
         out.set(TextElem::font, FontList(vec![FontFamily::new("DejaVu Sans Mono")]));
         out.set(TextElem::cjk_latin_spacing, Smart::Custom(None));
         if self.block.get(styles) {
-            out.set(ParElem::justify, false);
+            out.set(ParElem::justify, Justify::Off);
         }
         out
     }
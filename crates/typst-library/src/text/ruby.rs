@@ -0,0 +1,75 @@
+use crate::foundations::{Content, elem};
+use crate::introspection::Tagged;
+use crate::layout::{Em, HAlignment, OuterVAlignment};
+use crate::text::TextSize;
+
+/// Annotates text with ruby.
+///
+/// Ruby are short runs of text placed above or below a base text to indicate
+/// its pronunciation or meaning. They are commonly used in East-Asian
+/// typography, for example as _furigana_ for Japanese or _bopomofo_ for
+/// Chinese.
+///
+/// = Example <example>
+/// ```example
+/// #set text(lang: "ja", font: "Noto Serif CJK SC")
+/// #ruby[東京][とうきょう]に行く。
+/// ```
+///
+/// = Layout <layout>
+/// The base and the annotation are laid out as one unit that is never broken
+/// across lines. When the annotation is wider than the base, it may overhang
+/// the adjacent characters by up to one annotation character on each side
+/// instead of widening the unit. It only overhangs Chinese and Japanese
+/// characters (such as kana and ideographs) and never the start or end of a
+/// line. This can be turned off through the @ruby.overhang[`overhang`]
+/// property.
+///
+/// = Accessibility <accessibility>
+/// In HTML export, ruby are exported as `<ruby>` elements with the annotation
+/// in an `<rt>` element. In PDF export, they are tagged with the `Ruby`, `RB`,
+/// and `RT` structure types, so that Assistive Technology can distinguish the
+/// base from its annotation.
+#[elem(Tagged)]
+pub struct RubyElem {
+    /// The text that is annotated.
+    #[required]
+    pub base: Content,
+
+    /// The annotation.
+    #[required]
+    pub annotation: Content,
+
+    /// Whether the annotation is placed above or below the base.
+    ///
+    /// ```example
+    /// #set text(lang: "zh", font: "Noto Serif CJK SC")
+    /// #ruby(position: bottom)[汉字][hànzì]
+    /// ```
+    pub position: OuterVAlignment,
+
+    /// How the annotation is aligned with the base if their widths differ.
+    ///
+    /// ```example
+    /// #set text(lang: "ja", font: "Noto Serif CJK SC")
+    /// #ruby(align: start)[東][ひがし]
+    /// #ruby(align: end)[東][ひがし]
+    /// ```
+    #[default(HAlignment::Center)]
+    pub align: HAlignment,
+
+    /// The size of the annotation.
+    #[default(TextSize(Em::new(0.5).into()))]
+    pub size: TextSize,
+
+    /// Whether an annotation that is wider than its base may overhang the
+    /// adjacent Chinese or Japanese characters.
+    ///
+    /// ```example
+    /// #set text(lang: "ja", font: "Noto Serif CJK SC")
+    /// あ#ruby[子][こども]い \
+    /// あ#ruby(overhang: false)[子][こども]い
+    /// ```
+    #[default(true)]
+    pub overhang: bool,
+}
@@ -0,0 +1,88 @@
+use crate::foundations::{Cast, elem};
+use crate::layout::{Angle, Axes, Length, Rel};
+use crate::visualize::Stroke;
+
+/// An arrow from one point to another.
+///
+/// The arrow's shaft can be straight or bent into an arc. Both of its ends can
+/// be decorated with a @arrow.end-mark[mark], which is drawn in the stroke's
+/// paint and grows with the stroke's thickness.
+///
+/// = Example <example>
+/// ```example
+/// #arrow((0pt, 0pt), (80pt, 0pt))
+/// #arrow(
+///   (0pt, 10pt),
+///   (80pt, 10pt),
+///   bend: 30deg,
+///   start-mark: "bar",
+///   end-mark: "stealth",
+///   stroke: 1.5pt + blue,
+/// )
+/// ```
+#[elem]
+pub struct ArrowElem {
+    /// The point where the arrow starts.
+    ///
+    /// Must be an array of exactly two relative lengths.
+    #[required]
+    pub start: Axes<Rel<Length>>,
+
+    /// The point the arrow points to.
+    #[required]
+    pub end: Axes<Rel<Length>>,
+
+    /// How much the shaft is bent.
+    ///
+    /// This is the angle between the straight connection of the two points and
+    /// the direction in which the arrow leaves its start. Positive angles bend
+    /// the arrow to the left of its direction, negative angles to the right.
+    ///
+    /// ```example
+    /// #for angle in (-40deg, 0deg, 40deg) {
+    ///   arrow((0pt, 15pt), (80pt, 15pt), bend: angle)
+    /// }
+    /// ```
+    pub bend: Angle,
+
+    /// How to @stroke[stroke] the arrow.
+    ///
+    /// The marks take their paint, line cap, and line join from the stroke.
+    /// Dash patterns only apply to the shaft.
+    #[fold]
+    pub stroke: Stroke,
+
+    /// The mark at the start of the arrow.
+    pub start_mark: Option<ArrowMark>,
+
+    /// The mark at the end of the arrow.
+    ///
+    /// ```example
+    /// #set arrow(start-mark: "circle")
+    /// #stack(
+    ///   spacing: 8pt,
+    ///   arrow((0pt, 0pt), (80pt, 0pt), end-mark: "arrow"),
+    ///   arrow((0pt, 0pt), (80pt, 0pt), end-mark: "triangle"),
+    ///   arrow((0pt, 0pt), (80pt, 0pt), end-mark: "stealth"),
+    ///   arrow((0pt, 0pt), (80pt, 0pt), end-mark: "bar"),
+    ///   arrow((0pt, 0pt), (80pt, 0pt), end-mark: none),
+    /// )
+    /// ```
+    #[default(Some(ArrowMark::Arrow))]
+    pub end_mark: Option<ArrowMark>,
+}
+
+/// A mark at the end of an arrow, line, curve, or diagram edge.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum ArrowMark {
+    /// An open arrowhead, drawn with the stroke.
+    Arrow,
+    /// A filled triangle.
+    Triangle,
+    /// A filled arrowhead with a notch at the back.
+    Stealth,
+    /// A filled circle.
+    Circle,
+    /// A bar orthogonal to the shaft.
+    Bar,
+}
@@ -0,0 +1,353 @@
+use ecow::{EcoString, eco_format};
+use typst_macros::{Cast, scope};
+use typst_utils::Scalar;
+
+use crate::diag::{SourceResult, bail};
+use crate::engine::Engine;
+use crate::foundations::{
+    Array, Content, Packed, Smart, StyleChain, Synthesize, array, cast, elem, repr,
+};
+use crate::introspection::{Locatable, Tagged};
+use crate::layout::{Abs, Length, Rel};
+use crate::text::Locale;
+use crate::visualize::Paint;
+
+/// A chart that plots numeric data.
+///
+/// A chart consists of one or multiple @chart.series[series], which are drawn
+/// as lines, bars, scattered points, or filled areas. The axes are scaled to
+/// fit the data and get ticks at round numbers. Tick labels and axis labels
+/// are set in the surrounding text style.
+///
+/// If at least one series has a @chart.series.caption[caption], a legend is
+/// shown next to the plot.
+///
+/// = Example <example>
+/// ```example
+/// #chart(
+///   x-label: [Year],
+///   y-label: [Revenue],
+///   chart.series(
+///     ((2019, 4), (2020, 3), (2021, 6), (2022, 8)),
+///     caption: [Product A],
+///   ),
+///   chart.series(
+///     ((2019, 2), (2020, 5), (2021, 5), (2022, 6)),
+///     caption: [Product B],
+///     kind: "scatter",
+///   ),
+/// )
+/// ```
+///
+/// = Accessibility <accessibility>
+/// A chart carries its data in tabular form. In HTML export, the chart is
+/// embedded as an inline SVG followed by a table with its data. In PDF export,
+/// the chart is tagged as a figure followed by a table with its data. The
+/// figure gets a short generated alternative description that names the
+/// series, unless you provide an @chart.alt[alternative description] yourself.
+#[elem(scope, Locatable, Tagged, Synthesize)]
+pub struct ChartElem {
+    /// The width of the chart, including its axes and legend.
+    ///
+    /// Defaults to the full width of the container or, if the container's
+    /// width is unbounded, to `{25em}`.
+    pub width: Smart<Rel<Length>>,
+
+    /// The height of the chart, including its axes and legend.
+    ///
+    /// Defaults to two thirds of the chart's width.
+    pub height: Smart<Rel<Length>>,
+
+    /// A label for the horizontal axis.
+    pub x_label: Option<Content>,
+
+    /// A label for the vertical axis.
+    pub y_label: Option<Content>,
+
+    /// The range of values shown on the horizontal axis.
+    ///
+    /// When set to `{auto}`, the range fits the data.
+    pub x_range: Smart<ChartRange>,
+
+    /// The range of values shown on the vertical axis.
+    ///
+    /// When set to `{auto}`, the range fits the data and extends to the
+    /// closest ticks. Bar and area charts always include zero.
+    ///
+    /// ```example
+    /// #chart(
+    ///   height: 80pt,
+    ///   y-range: (0, 100),
+    ///   chart.series((42, 57, 61, 80), kind: "bar"),
+    /// )
+    /// ```
+    pub y_range: Smart<ChartRange>,
+
+    /// Names for the values on the horizontal axis.
+    ///
+    /// If given, the data points of the series should be plain numbers. The
+    /// _n_-th value of each series then belongs to the _n_-th category.
+    ///
+    /// ```example
+    /// #chart(
+    ///   height: 100pt,
+    ///   categories: ([Q1], [Q2], [Q3], [Q4]),
+    ///   chart.series((3, 5, 4, 7), kind: "bar", caption: [2024]),
+    ///   chart.series((4, 6, 6, 9), kind: "bar", caption: [2025]),
+    /// )
+    /// ```
+    pub categories: Vec<Content>,
+
+    /// Whether to draw grid lines at the ticks of the vertical axis.
+    #[default(true)]
+    pub grid: bool,
+
+    /// Whether to show a legend for the series with a caption.
+    #[default(true)]
+    pub legend: bool,
+
+    /// An alternative description of the chart.
+    ///
+    /// When none is given, a short description that names the chart's series
+    /// is generated.
+    pub alt: Option<EcoString>,
+
+    /// The data series of the chart.
+    #[variadic]
+    pub children: Vec<Packed<ChartSeries>>,
+
+    /// The data of the chart in tabular form (used for the PDF tags).
+    #[internal]
+    #[synthesized]
+    pub data: ChartTable,
+
+    /// The locale of this element (used for the alternative description).
+    #[internal]
+    #[synthesized]
+    pub locale: Locale,
+}
+
+#[scope]
+impl ChartElem {
+    #[elem]
+    type ChartSeries;
+}
+
+impl Synthesize for Packed<ChartElem> {
+    fn synthesize(&mut self, _: &mut Engine, styles: StyleChain) -> SourceResult<()> {
+        let data = self.table(styles);
+        if self.alt.get_ref(styles).is_none() {
+            self.as_mut().alt.set(Some(data.describe()));
+        }
+        self.as_mut().data = Some(data);
+        self.as_mut().locale = Some(Locale::get_in(styles));
+        Ok(())
+    }
+}
+
+impl Packed<ChartElem> {
+    /// The data of the chart in tabular form.
+    ///
+    /// There is one row per distinct horizontal value across all series and
+    /// one column per series. If a series has multiple values for the same
+    /// horizontal value, there are multiple rows for it. Cells for which a
+    /// series has no value are empty.
+    pub fn table(&self, styles: StyleChain) -> ChartTable {
+        let categories = self.categories.get_ref(styles);
+        let name = |caption: &Option<Content>, fallback: &str| match caption {
+            Some(caption) => caption.plain_text(),
+            None => fallback.into(),
+        };
+
+        let mut header = vec![name(self.x_label.get_ref(styles), "x")];
+        let mut xs = vec![];
+        for (i, series) in self.children.iter().enumerate() {
+            let fallback = eco_format!("Series {}", i + 1);
+            header.push(name(series.caption.get_ref(styles), &fallback));
+            xs.extend(series.points().map(|(x, _)| x));
+        }
+
+        xs.sort_by(f64::total_cmp);
+        xs.dedup();
+
+        let mut rows = vec![];
+        for x in xs {
+            let category = (x >= 0.0 && x.fract() == 0.0)
+                .then(|| categories.get(x as usize))
+                .flatten();
+            let name = match category {
+                Some(category) => category.plain_text(),
+                None => repr::display_float(x),
+            };
+
+            // The values of each series at this horizontal value.
+            let columns: Vec<Vec<f64>> = self
+                .children
+                .iter()
+                .map(|series| {
+                    series.points().filter(|&(px, _)| px == x).map(|(_, y)| y).collect()
+                })
+                .collect();
+
+            let count = columns.iter().map(Vec::len).max().unwrap_or(0);
+            for i in 0..count {
+                let mut row = vec![name.clone()];
+                row.extend(columns.iter().map(|ys| {
+                    ys.get(i).map(|&y| repr::display_float(y)).unwrap_or_default()
+                }));
+                rows.push(row);
+            }
+        }
+
+        ChartTable { header, rows }
+    }
+}
+
+/// The data of a chart in tabular form.
+///
+/// This serves as an accessible fallback for the chart's visual
+/// representation.
+#[derive(Debug, Clone, PartialEq, Hash)]
+pub struct ChartTable {
+    /// The column headers: first the one for the horizontal values and then
+    /// one per series.
+    pub header: Vec<EcoString>,
+    /// The rows, each starting with the horizontal value followed by one cell
+    /// per series.
+    pub rows: Vec<Vec<EcoString>>,
+}
+
+impl ChartTable {
+    /// Briefly describes the chart, such that it can be used as an
+    /// alternative description. The data itself is left to the table.
+    pub fn describe(&self) -> EcoString {
+        let series = self.header.get(1..).unwrap_or_default();
+        eco_format!("Chart with {} series: {}.", series.len(), series.join(", "))
+    }
+}
+
+/// A range of values on a chart axis.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct ChartRange {
+    /// The smallest value on the axis.
+    pub min: Scalar,
+    /// The largest value on the axis.
+    pub max: Scalar,
+}
+
+cast! {
+    ChartRange,
+    self => array![self.min.get(), self.max.get()].into_value(),
+    array: Array => {
+        let mut iter = array.into_iter();
+        match (iter.next(), iter.next(), iter.next()) {
+            (Some(a), Some(b), None) => {
+                let (min, max) = (a.cast::<f64>()?, b.cast::<f64>()?);
+                if !min.is_finite() || !max.is_finite() || min >= max {
+                    bail!("range start must be smaller than range end");
+                }
+                Self { min: Scalar::new(min), max: Scalar::new(max) }
+            }
+            _ => bail!("range must contain exactly two numbers"),
+        }
+    },
+}
+
+/// A data series in a chart.
+///
+/// ```example
+/// #chart(
+///   height: 100pt,
+///   chart.series(
+///     (1, 3, 2, 5, 4),
+///     kind: "area",
+///     paint: eastern,
+///     caption: [Visitors],
+///   ),
+/// )
+/// ```
+#[elem(name = "series", title = "Chart Series")]
+pub struct ChartSeries {
+    /// The data points of the series.
+    ///
+    /// Each point is either an array of a horizontal and a vertical value or
+    /// just a vertical value. In the latter case, the point's horizontal value
+    /// is its index in the series.
+    #[required]
+    pub data: Vec<ChartPoint>,
+
+    /// How to draw the series.
+    pub kind: SeriesKind,
+
+    /// The paint of the series.
+    ///
+    /// When set to `{auto}`, each series gets a different color from a
+    /// predefined palette.
+    pub paint: Smart<Paint>,
+
+    /// The thickness of lines and of the outlines of areas.
+    #[default(Abs::pt(1.0).into())]
+    pub thickness: Length,
+
+    /// A caption for the series, which is shown in the chart's legend.
+    pub caption: Option<Content>,
+}
+
+cast! {
+    ChartSeries,
+    v: Content => v.unpack::<Self>().map_err(|_| "expected chart series")?,
+}
+
+impl ChartSeries {
+    /// The data points as pairs of horizontal and vertical values.
+    pub fn points(&self) -> impl Iterator<Item = (f64, f64)> + '_ {
+        self.data
+            .iter()
+            .enumerate()
+            .map(|(i, p)| (p.x.map_or(i as f64, Scalar::get), p.y.get()))
+            .filter(|(x, y)| x.is_finite() && y.is_finite())
+    }
+}
+
+/// A data point in a chart series.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct ChartPoint {
+    /// The horizontal value, if it was given explicitly.
+    pub x: Option<Scalar>,
+    /// The vertical value.
+    pub y: Scalar,
+}
+
+cast! {
+    ChartPoint,
+    self => match self.x {
+        Some(x) => array![x.get(), self.y.get()].into_value(),
+        None => self.y.get().into_value(),
+    },
+    y: f64 => Self { x: None, y: Scalar::new(y) },
+    array: Array => {
+        let mut iter = array.into_iter();
+        match (iter.next(), iter.next(), iter.next()) {
+            (Some(x), Some(y), None) => Self {
+                x: Some(Scalar::new(x.cast()?)),
+                y: Scalar::new(y.cast()?),
+            },
+            _ => bail!("data point must contain exactly two numbers"),
+        }
+    },
+}
+
+/// How a chart series is drawn.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum SeriesKind {
+    /// A line through the data points.
+    #[default]
+    Line,
+    /// A bar from zero to each data point.
+    Bar,
+    /// A dot at each data point.
+    Scatter,
+    /// A line through the data points with the area between it and zero
+    /// filled.
+    Area,
+}
@@ -33,12 +33,13 @@ pub type Luma = palette::luma::Lumaa<encoding::Srgb, f32>;
 /// to convert from CMYK to RGB. It is based on the CGATS TR 001-1995
 /// specification. See
 /// <https://github.com/saucecontrol/Compact-ICC-Profiles#cmyk>.
-static CMYK_TO_XYZ: LazyLock<ColorProfile> = LazyLock::new(|| {
+pub(crate) static CMYK_TO_XYZ: LazyLock<ColorProfile> = LazyLock::new(|| {
     ColorProfile::new_from_slice(typst_assets::icc::CMYK_TO_XYZ).unwrap()
 });
 
 /// The target sRGB profile.
-static SRGB_PROFILE: LazyLock<ColorProfile> = LazyLock::new(ColorProfile::new_srgb);
+pub(crate) static SRGB_PROFILE: LazyLock<ColorProfile> =
+    LazyLock::new(ColorProfile::new_srgb);
 
 static TO_SRGB: LazyLock<Box<moxcms::Transform8BitExecutor>> = LazyLock::new(|| {
     CMYK_TO_XYZ
@@ -5,7 +5,7 @@ use typst_utils::Numeric;
 use crate::diag::{HintedStrResult, HintedString, bail};
 use crate::foundations::{Content, Packed, Smart, cast, elem};
 use crate::layout::{Abs, Axes, Length, Point, Rect, Rel, Size};
-use crate::visualize::{FillRule, Paint, Stroke};
+use crate::visualize::{ArrowMark, FillRule, Paint, Stroke};
 
 use super::FixedStroke;
 
@@ -86,6 +86,25 @@ pub struct CurveElem {
     #[fold]
     pub stroke: Smart<Option<Stroke>>,
 
+    /// The mark at the start of the curve.
+    ///
+    /// Marks are drawn in the stroke's paint and grow with its thickness, just
+    /// like the marks of an @arrow. They are only drawn if the curve is
+    /// stroked and not closed.
+    pub start_mark: Option<ArrowMark>,
+
+    /// The mark at the end of the curve.
+    ///
+    /// ```example
+    /// #curve(
+    ///   start-mark: "circle",
+    ///   end-mark: "stealth",
+    ///   curve.move((0pt, 30pt)),
+    ///   curve.cubic((20pt, -10pt), (60pt, 40pt), (80pt, 0pt)),
+    /// )
+    /// ```
+    pub end_mark: Option<ArrowMark>,
+
     /// The components of the curve, in the form of moves, line and Bézier
     /// segment, and closes.
     #[variadic]
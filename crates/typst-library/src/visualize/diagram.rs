@@ -0,0 +1,196 @@
+use ecow::EcoString;
+use typst_macros::{Cast, scope};
+
+use crate::diag::{HintedStrResult, HintedString, bail};
+use crate::foundations::{Content, Packed, Smart, cast, elem};
+use crate::layout::{Abs, Dir, Em, Length};
+use crate::visualize::{ArrowMark, Paint, Stroke};
+
+/// A diagram of nodes connected by edges, which is laid out automatically.
+///
+/// The nodes are arranged in layers such that edges flow in the diagram's
+/// @diagram.dir[direction] wherever possible. Within each layer, nodes are
+/// ordered to keep the number of crossing edges low. This makes the diagram
+/// well-suited for flowcharts, dependency graphs, and state machines.
+///
+/// Nodes are identified by their name. Edges can refer to nodes that are
+/// defined later in the diagram.
+///
+/// = Example <example>
+/// ```example
+/// #diagram(
+///   diagram.node("start", shape: "ellipse")[Start],
+///   diagram.node("check")[Is it valid?],
+///   diagram.node("fix")[Fix it],
+///   diagram.node("done", shape: "ellipse")[Done],
+///   diagram.edge("start", "check"),
+///   diagram.edge("check", "fix", caption: [no]),
+///   diagram.edge("fix", "check"),
+///   diagram.edge("check", "done", caption: [yes]),
+/// )
+/// ```
+#[elem(scope)]
+pub struct DiagramElem {
+    /// The direction in which edges flow.
+    ///
+    /// ```example
+    /// #diagram(
+    ///   dir: ltr,
+    ///   diagram.node("a")[A],
+    ///   diagram.node("b")[B],
+    ///   diagram.node("c")[C],
+    ///   diagram.edge("a", "b"),
+    ///   diagram.edge("a", "c"),
+    /// )
+    /// ```
+    #[default(Dir::TTB)]
+    pub dir: Dir,
+
+    /// The spacing between consecutive layers of nodes.
+    #[default(Em::new(2.5).into())]
+    pub layer_spacing: Length,
+
+    /// The spacing between adjacent nodes within a layer.
+    #[default(Em::new(1.5).into())]
+    pub node_spacing: Length,
+
+    /// The nodes and edges of the diagram.
+    #[variadic]
+    pub children: Vec<DiagramChild>,
+}
+
+#[scope]
+impl DiagramElem {
+    #[elem]
+    type DiagramNode;
+
+    #[elem]
+    type DiagramEdge;
+}
+
+/// A node or an edge in a diagram.
+#[derive(Debug, Clone, PartialEq, Hash)]
+pub enum DiagramChild {
+    Node(Packed<DiagramNode>),
+    Edge(Packed<DiagramEdge>),
+}
+
+cast! {
+    DiagramChild,
+    self => match self {
+        Self::Node(element) => element.into_value(),
+        Self::Edge(element) => element.into_value(),
+    },
+    v: Content => {
+        v.try_into()?
+    }
+}
+
+impl TryFrom<Content> for DiagramChild {
+    type Error = HintedString;
+
+    fn try_from(value: Content) -> HintedStrResult<Self> {
+        value
+            .into_packed::<DiagramNode>()
+            .map(Self::Node)
+            .or_else(|value| value.into_packed::<DiagramEdge>().map(Self::Edge))
+            .or_else(|_| bail!("expected a diagram node or edge"))
+    }
+}
+
+/// A node in a diagram.
+///
+/// ```example
+/// #diagram(
+///   dir: ltr,
+///   diagram.node("in", fill: aqua, stroke: none)[Input],
+///   diagram.node("out", shape: "ellipse")[Output],
+///   diagram.edge("in", "out"),
+/// )
+/// ```
+#[elem(name = "node", title = "Diagram Node")]
+pub struct DiagramNode {
+    /// The name by which edges refer to the node.
+    ///
+    /// Must be unique within the diagram.
+    #[required]
+    pub name: EcoString,
+
+    /// The content of the node.
+    #[required]
+    pub body: Content,
+
+    /// The shape drawn around the node's content.
+    #[default(NodeShape::Rect)]
+    pub shape: NodeShape,
+
+    /// How to fill the node's shape.
+    pub fill: Option<Paint>,
+
+    /// How to @stroke[stroke] the node's shape.
+    ///
+    /// Can be set to `{none}` to disable the stroke or to `{auto}` for a stroke
+    /// of `{1pt}` black if and only if no fill is given.
+    #[fold]
+    pub stroke: Smart<Option<Stroke>>,
+
+    /// How much to pad the node's content.
+    #[default(Abs::pt(5.0).into())]
+    pub inset: Length,
+}
+
+/// The shape of a diagram node.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum NodeShape {
+    /// A rectangle.
+    Rect,
+    /// An ellipse.
+    Ellipse,
+}
+
+/// An edge between two nodes in a diagram.
+///
+/// Edges are drawn from the boundary of one node to the boundary of the other
+/// one. If an edge connects a node with itself, it is drawn as a loop. Multiple
+/// edges between the same two nodes, in either direction, are bent apart from
+/// each other.
+///
+/// ```example
+/// #diagram(
+///   dir: ltr,
+///   diagram.node("a")[A],
+///   diagram.node("b")[B],
+///   diagram.edge(
+///     "a", "b",
+///     caption: [uses],
+///     stroke: (paint: blue, dash: "dashed"),
+///     end-mark: "stealth",
+///   ),
+///   diagram.edge("b", "a"),
+///   diagram.edge("b", "b"),
+/// )
+/// ```
+#[elem(name = "edge", title = "Diagram Edge")]
+pub struct DiagramEdge {
+    /// The name of the node at which the edge starts.
+    #[required]
+    pub from: EcoString,
+
+    /// The name of the node at which the edge ends.
+    #[required]
+    pub to: EcoString,
+
+    /// A caption that is placed next to the middle of the edge.
+    pub caption: Option<Content>,
+
+    /// How to @stroke[stroke] the edge.
+    #[fold]
+    pub stroke: Stroke,
+
+    /// The mark at the start of the edge.
+    pub start_mark: Option<ArrowMark>,
+
+    /// The mark at the end of the edge.
+    #[default(Some(ArrowMark::Arrow))]
+    pub end_mark: Option<ArrowMark>,
+}
@@ -6,7 +6,7 @@ mod svg;
 
 pub use self::pdf::PdfImage;
 pub use self::raster::{
-    ExchangeFormat, PixelEncoding, PixelFormat, RasterFormat, RasterImage,
+    CmykSamples, ExchangeFormat, PixelEncoding, PixelFormat, RasterFormat, RasterImage,
 };
 pub use self::svg::SvgImage;
 
@@ -83,7 +83,16 @@ pub struct ImageElem {
     /// format automatically, but that's not always possible).
     ///
     /// Supported formats are `{"png"}`, `{"jpg"}`, `{"gif"}`, `{"svg"}`,
-    /// `{"pdf"}`, `{"webp"}` as well as raw pixel data.
+    /// `{"pdf"}`, `{"webp"}`, `{"avif"}`, `{"jxl"}`, `{"bmp"}`, `{"tiff"}` as
+    /// well as raw pixel data. Decoding AVIF images requires a build of Typst
+    /// with the `avif` feature, which depends on the dav1d library being
+    /// installed on the system.
+    ///
+    /// When exporting to PDF, JPEGs are embedded as-is. Images in all other
+    /// raster formats are decoded and re-compressed losslessly, keeping their
+    /// bit depth and ICC profile. This includes CMYK TIFFs, which keep their
+    /// CMYK samples. For all other export targets, CMYK TIFFs are converted to
+    /// RGB through their embedded ICC profile.
     ///
     /// Note that several restrictions apply when using PDF files as images:
     ///
@@ -342,6 +351,10 @@ fn determine_format_from_path(path: &VirtualPath) -> Option<ImageFormat> {
         "jpg" | "jpeg" => Some(ExchangeFormat::Jpg.into()),
         "gif" => Some(ExchangeFormat::Gif.into()),
         "webp" => Some(ExchangeFormat::Webp.into()),
+        "avif" => Some(ExchangeFormat::Avif.into()),
+        "jxl" => Some(ExchangeFormat::Jxl.into()),
+        "bmp" => Some(ExchangeFormat::Bmp.into()),
+        "tif" | "tiff" => Some(ExchangeFormat::Tiff.into()),
         // Vector formats
         "svg" | "svgz" => Some(VectorFormat::Svg.into()),
         "pdf" => Some(VectorFormat::Pdf.into()),
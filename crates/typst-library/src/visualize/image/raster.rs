@@ -5,14 +5,25 @@ use std::sync::Arc;
 
 use crate::diag::{StrResult, bail};
 use crate::foundations::{Bytes, Cast, Dict, Smart, Value, cast, dict};
+use crate::visualize::color::{CMYK_TO_XYZ, SRGB_PROFILE};
 use ecow::{EcoString, eco_format};
+#[cfg(feature = "avif")]
+use image::codecs::avif::AvifDecoder;
+use image::codecs::bmp::BmpDecoder;
 use image::codecs::gif::GifDecoder;
 use image::codecs::jpeg::JpegDecoder;
 use image::codecs::png::PngDecoder;
+use image::codecs::tiff::TiffDecoder;
 use image::codecs::webp::WebPDecoder;
+use image::error::{DecodingError, ImageFormatHint};
 use image::{
-    DynamicImage, ImageBuffer, ImageDecoder, ImageResult, Limits, Pixel, guess_format,
+    DynamicImage, ImageBuffer, ImageDecoder, ImageError, ImageResult, Limits, Pixel,
+    guess_format,
 };
+use jxl_oxide::integration::JxlDecoder;
+use moxcms::{DataColorSpace, Layout, RenderingIntent, TransformOptions};
+use tiff::ColorType;
+use tiff::decoder::DecodingResult;
 
 /// A decoded raster image.
 #[derive(Clone, Hash)]
@@ -26,6 +37,7 @@ struct RasterImageInner {
     exif_rotation: Option<u32>,
     icc: Option<Bytes>,
     dpi: Option<f64>,
+    cmyk: Option<CmykSamples>,
 }
 
 impl RasterImage {
@@ -52,6 +64,7 @@ impl RasterImage {
         icc: Smart<Bytes>,
     ) -> StrResult<RasterImage> {
         let mut exif_rot = None;
+        let mut cmyk = None;
 
         let (dynamic, icc, dpi) = match format {
             RasterFormat::Exchange(format) => {
@@ -79,6 +92,32 @@ impl RasterImage {
                     ExchangeFormat::Png => decode(PngDecoder::new(cursor), icc),
                     ExchangeFormat::Gif => decode(GifDecoder::new(cursor), icc),
                     ExchangeFormat::Webp => decode(WebPDecoder::new(cursor), icc),
+                    #[cfg(feature = "avif")]
+                    ExchangeFormat::Avif => decode(AvifDecoder::new(cursor), icc),
+                    #[cfg(not(feature = "avif"))]
+                    ExchangeFormat::Avif => {
+                        bail!("AVIF images are not supported by this build of Typst")
+                    }
+                    ExchangeFormat::Jxl => decode(
+                        JxlDecoder::new(cursor).map_err(|err| {
+                            ImageError::Decoding(DecodingError::new(
+                                ImageFormatHint::Name("JPEG XL".into()),
+                                err,
+                            ))
+                        }),
+                        icc,
+                    ),
+                    ExchangeFormat::Bmp => decode(BmpDecoder::new(cursor), icc),
+                    ExchangeFormat::Tiff => match decode_cmyk_tiff(&data, &icc) {
+                        // The dynamic image holds the CMYK data converted to
+                        // sRGB, so the original profile only applies to the
+                        // retained samples.
+                        Some(result) => result.map(|(dynamic, samples)| {
+                            cmyk = Some(samples);
+                            (dynamic, None)
+                        }),
+                        None => decode(TiffDecoder::new(cursor), icc),
+                    },
                 }
                 .map_err(format_image_error)?;
 
@@ -148,6 +187,7 @@ impl RasterImage {
             dynamic: Arc::new(dynamic),
             icc,
             dpi,
+            cmyk,
         })))
     }
 
@@ -195,6 +235,34 @@ impl RasterImage {
     pub fn icc(&self) -> Option<&Bytes> {
         self.0.icc.as_ref()
     }
+
+    /// The original samples of a CMYK image.
+    ///
+    /// The [dynamic](Self::dynamic) image holds these pixels converted to
+    /// sRGB. Exporters that support CMYK can use the samples instead.
+    pub fn cmyk(&self) -> Option<&CmykSamples> {
+        self.0.cmyk.as_ref()
+    }
+}
+
+/// The samples of a CMYK raster image, as they were stored in the image file.
+///
+/// Unlike the [dynamic](RasterImage::dynamic) image, the samples are not
+/// rotated according to the image's [EXIF
+/// orientation](RasterImage::exif_rotation).
+#[derive(Debug, Clone, Hash)]
+pub struct CmykSamples {
+    /// The pixel width.
+    pub width: u32,
+    /// The pixel height.
+    pub height: u32,
+    /// The interleaved samples. 16-bit samples are stored in big-endian byte
+    /// order.
+    pub data: Bytes,
+    /// Whether each sample has 16 instead of 8 bits.
+    pub wide: bool,
+    /// The CMYK profile of the samples, if there is a usable one.
+    pub icc: Option<Bytes>,
 }
 
 impl Hash for RasterImageInner {
@@ -244,28 +312,51 @@ pub enum ExchangeFormat {
     Png,
     /// Lossy raster format suitable for photos.
     Jpg,
-    /// Raster format that is typically used for short animated clips. Typst can
-    /// load GIFs, but they will become static.
+    /// Raster format that is typically used for short animated clips. Animated
+    /// GIFs keep their animation in HTML and SVG export, but become static
+    /// (showing their first frame) in PDF and PNG export.
     Gif,
-    /// Raster format that supports both lossy and lossless compression.
+    /// Raster format that supports both lossy and lossless compression. Like
+    /// GIFs, animated WebPs only keep their animation in HTML and SVG export.
     Webp,
+    /// Modern raster format based on the AV1 video codec, with efficient lossy
+    /// and lossless compression.
+    Avif,
+    /// Modern raster format for photos and graphics that supports high bit
+    /// depths and can losslessly recompress JPEGs.
+    Jxl,
+    /// Simple, mostly uncompressed raster format originating from Windows.
+    Bmp,
+    /// Flexible raster format common in scanning, print, and scientific
+    /// imaging. Typst supports 8- and 16-bit TIFFs, including CMYK ones.
+    Tiff,
 }
 
 impl ExchangeFormat {
     /// Try to detect the format of data in a buffer.
     pub fn detect(data: &[u8]) -> Option<Self> {
+        // The `image` crate does not know about JPEG XL.
+        if is_jxl(data) {
+            return Some(Self::Jxl);
+        }
         guess_format(data).ok().and_then(|format| format.try_into().ok())
     }
 }
 
-impl From<ExchangeFormat> for image::ImageFormat {
-    fn from(format: ExchangeFormat) -> Self {
-        match format {
+impl TryFrom<ExchangeFormat> for image::ImageFormat {
+    type Error = EcoString;
+
+    fn try_from(format: ExchangeFormat) -> StrResult<Self> {
+        Ok(match format {
             ExchangeFormat::Png => image::ImageFormat::Png,
             ExchangeFormat::Jpg => image::ImageFormat::Jpeg,
             ExchangeFormat::Gif => image::ImageFormat::Gif,
             ExchangeFormat::Webp => image::ImageFormat::WebP,
-        }
+            ExchangeFormat::Avif => image::ImageFormat::Avif,
+            ExchangeFormat::Bmp => image::ImageFormat::Bmp,
+            ExchangeFormat::Tiff => image::ImageFormat::Tiff,
+            ExchangeFormat::Jxl => bail!("format not supported by the image crate"),
+        })
     }
 }
 
@@ -278,6 +369,9 @@ impl TryFrom<image::ImageFormat> for ExchangeFormat {
             image::ImageFormat::Jpeg => ExchangeFormat::Jpg,
             image::ImageFormat::Gif => ExchangeFormat::Gif,
             image::ImageFormat::WebP => ExchangeFormat::Webp,
+            image::ImageFormat::Avif => ExchangeFormat::Avif,
+            image::ImageFormat::Bmp => ExchangeFormat::Bmp,
+            image::ImageFormat::Tiff => ExchangeFormat::Tiff,
             _ => bail!("format not yet supported"),
         })
     }
@@ -331,6 +425,96 @@ impl From<PixelFormat> for Dict {
     }
 }
 
+/// Whether the data is a JPEG XL image, either as a bare codestream or wrapped
+/// in an ISOBMFF container.
+fn is_jxl(data: &[u8]) -> bool {
+    data.starts_with(b"\xFF\x0A") || data.starts_with(b"\0\0\0\x0CJXL \x0D\x0A\x87\x0A")
+}
+
+/// Decodes a CMYK TIFF into its samples and a version converted to sRGB.
+///
+/// Returns `None` if the data is not a CMYK TIFF, in which case the regular
+/// decoder should be used.
+fn decode_cmyk_tiff(
+    data: &[u8],
+    icc: &Smart<Bytes>,
+) -> Option<ImageResult<(DynamicImage, CmykSamples)>> {
+    let mut decoder = tiff::decoder::Decoder::new(io::Cursor::new(data)).ok()?;
+    if !matches!(decoder.colortype().ok()?, ColorType::CMYK(_)) {
+        return None;
+    }
+    Some(convert_cmyk_tiff(decoder, icc))
+}
+
+/// Reads the samples of a CMYK TIFF and converts them to sRGB.
+///
+/// The conversion uses the custom or embedded ICC profile if there is a usable
+/// one and otherwise falls back to the profile that is also used for CMYK
+/// colors.
+fn convert_cmyk_tiff<R: io::Read + io::Seek>(
+    mut decoder: tiff::decoder::Decoder<R>,
+    icc: &Smart<Bytes>,
+) -> ImageResult<(DynamicImage, CmykSamples)> {
+    let err = |err: &dyn std::fmt::Display| {
+        ImageError::Decoding(DecodingError::new(
+            ImageFormatHint::Exact(image::ImageFormat::Tiff),
+            err.to_string(),
+        ))
+    };
+
+    let (width, height) = decoder.dimensions().map_err(|e| err(&e))?;
+    let icc = match icc {
+        Smart::Custom(icc) => Some(icc.clone()),
+        // The tag under which TIFFs store their ICC profile.
+        Smart::Auto => decoder
+            .get_tag_u8_vec(tiff::tags::Tag::Unknown(34675))
+            .ok()
+            .map(Bytes::new),
+    };
+    let (icc, embedded) = icc
+        .and_then(|icc| {
+            let profile = moxcms::ColorProfile::new_from_slice(&icc).ok()?;
+            Some((icc, profile))
+        })
+        .filter(|(_, profile)| profile.color_space == DataColorSpace::Cmyk)
+        .unzip();
+    let profile = embedded.as_ref().unwrap_or(&CMYK_TO_XYZ);
+    let options = TransformOptions {
+        // Our fallback profile only supports perceptual intent.
+        rendering_intent: RenderingIntent::Perceptual,
+        ..TransformOptions::default()
+    };
+
+    let (dynamic, data, wide) = match decoder.read_image().map_err(|e| err(&e))? {
+        DecodingResult::U8(cmyk) => {
+            let transform = profile
+                .create_transform_8bit(Layout::Rgba, &SRGB_PROFILE, Layout::Rgb, options)
+                .map_err(|e| err(&e))?;
+            let mut rgb = vec![0; cmyk.len() / 4 * 3];
+            transform.transform(&cmyk, &mut rgb).map_err(|e| err(&e))?;
+            let dynamic =
+                ImageBuffer::from_raw(width, height, rgb).map(DynamicImage::ImageRgb8);
+            (dynamic, Bytes::new(cmyk), false)
+        }
+        DecodingResult::U16(cmyk) => {
+            let transform = profile
+                .create_transform_16bit(Layout::Rgba, &SRGB_PROFILE, Layout::Rgb, options)
+                .map_err(|e| err(&e))?;
+            let mut rgb = vec![0; cmyk.len() / 4 * 3];
+            transform.transform(&cmyk, &mut rgb).map_err(|e| err(&e))?;
+            let dynamic =
+                ImageBuffer::from_raw(width, height, rgb).map(DynamicImage::ImageRgb16);
+            let data: Vec<u8> =
+                cmyk.iter().flat_map(|sample| sample.to_be_bytes()).collect();
+            (dynamic, Bytes::new(data), true)
+        }
+        _ => return Err(err(&"unsupported CMYK sample layout")),
+    };
+
+    let dynamic = dynamic.ok_or_else(|| err(&"unsupported CMYK sample layout"))?;
+    Ok((dynamic, CmykSamples { width, height, data, wide, icc }))
+}
+
 /// Try to get the rotation from the EXIF metadata.
 fn exif_rotation(exif: &exif::Exif) -> Option<u32> {
     exif.get_field(exif::Tag::Orientation, exif::In::PRIMARY)?
@@ -470,4 +654,87 @@ mod tests {
         test("images/tiger.jpg", ExchangeFormat::Jpg, 72.0);
         test("images/graph.png", ExchangeFormat::Png, 144.0);
     }
+
+    #[test]
+    fn test_detect_format() {
+        #[track_caller]
+        fn test(data: &[u8], format: ExchangeFormat) {
+            assert_eq!(ExchangeFormat::detect(data), Some(format));
+        }
+
+        test(b"\xFF\x0A\xFA\x1F", ExchangeFormat::Jxl);
+        test(b"\0\0\0\x0CJXL \x0D\x0A\x87\x0A\0\0", ExchangeFormat::Jxl);
+        test(b"\0\0\0\x1Cftypavif\0\0\0\0", ExchangeFormat::Avif);
+        test(b"BM\0\0\0\0", ExchangeFormat::Bmp);
+        test(b"II*\0\0\0\0\0", ExchangeFormat::Tiff);
+        test(b"MM\0*\0\0\0\0", ExchangeFormat::Tiff);
+    }
+
+    #[test]
+    fn test_tiff_cmyk() {
+        use tiff::encoder::{TiffEncoder, colortype};
+
+        let mut data = io::Cursor::new(vec![]);
+        let pixels = [0, 0, 0, 0, 0, 0, 0, 255];
+        TiffEncoder::new(&mut data)
+            .unwrap()
+            .write_image::<colortype::CMYK8>(2, 1, &pixels)
+            .unwrap();
+
+        let bytes = Bytes::new(data.into_inner());
+        let image = RasterImage::plain(bytes, ExchangeFormat::Tiff).unwrap();
+        let DynamicImage::ImageRgb8(rgb) = image.dynamic().as_ref() else {
+            panic!("expected an RGB image");
+        };
+        assert!(rgb.get_pixel(0, 0).0.iter().all(|&c| c > 240));
+        assert!(rgb.get_pixel(1, 0).0.iter().all(|&c| c < 80));
+        assert_eq!(image.icc(), None);
+
+        let cmyk = image.cmyk().unwrap();
+        assert_eq!((cmyk.width, cmyk.height, cmyk.wide), (2, 1, false));
+        assert_eq!(cmyk.data.as_slice(), pixels);
+        assert_eq!(cmyk.icc, None);
+    }
+
+    #[test]
+    fn test_tiff_cmyk_16_bit_with_icc() {
+        use tiff::encoder::{TiffEncoder, colortype};
+
+        let mut data = io::Cursor::new(vec![]);
+        TiffEncoder::new(&mut data)
+            .unwrap()
+            .write_image::<colortype::CMYK16>(1, 1, &[0, 1, 256, u16::MAX])
+            .unwrap();
+
+        let bytes = Bytes::new(data.into_inner());
+        let icc = Bytes::new(typst_assets::icc::CMYK_TO_XYZ);
+        let image =
+            RasterImage::new(bytes, ExchangeFormat::Tiff, Smart::Custom(icc.clone()))
+                .unwrap();
+        assert!(matches!(image.dynamic().as_ref(), DynamicImage::ImageRgb16(_)));
+        assert_eq!(image.icc(), None);
+
+        let cmyk = image.cmyk().unwrap();
+        assert_eq!((cmyk.width, cmyk.height, cmyk.wide), (1, 1, true));
+        assert_eq!(cmyk.data.as_slice(), [0, 0, 0, 1, 1, 0, 255, 255]);
+        assert_eq!(cmyk.icc, Some(icc));
+    }
+
+    #[test]
+    fn test_tiff_16_bit() {
+        use tiff::encoder::{TiffEncoder, colortype};
+
+        let mut data = io::Cursor::new(vec![]);
+        TiffEncoder::new(&mut data)
+            .unwrap()
+            .write_image::<colortype::RGB16>(1, 1, &[0, 1000, u16::MAX])
+            .unwrap();
+
+        let bytes = Bytes::new(data.into_inner());
+        let image = RasterImage::plain(bytes, ExchangeFormat::Tiff).unwrap();
+        let DynamicImage::ImageRgb16(rgb) = image.dynamic().as_ref() else {
+            panic!("expected a 16-bit RGB image");
+        };
+        assert_eq!(rgb.get_pixel(0, 0).0, [0, 1000, u16::MAX]);
+    }
 }
@@ -412,6 +412,14 @@ impl<'a> ImageResolver<'a> {
                                 ExchangeFormat::Webp => {
                                     Ok(usvg::ImageKind::WEBP(arc_data))
                                 }
+                                ExchangeFormat::Avif
+                                | ExchangeFormat::Jxl
+                                | ExchangeFormat::Bmp
+                                | ExchangeFormat::Tiff => {
+                                    Err("only PNG, JPEG, GIF, and WebP images are \
+                                     supported in SVGs"
+                                        .into())
+                                }
                             }
                         }
                         RasterFormat::Pixel(_) => {
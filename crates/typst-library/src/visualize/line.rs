@@ -1,6 +1,6 @@
 use crate::foundations::elem;
 use crate::layout::{Abs, Angle, Axes, Length, Rel};
-use crate::visualize::Stroke;
+use crate::visualize::{ArrowMark, Stroke};
 
 /// A line from one point to another.
 ///
@@ -47,4 +47,22 @@ pub struct LineElem {
     /// ```
     #[fold]
     pub stroke: Stroke,
+
+    /// The mark at the start of the line.
+    ///
+    /// Marks are drawn in the stroke's paint and grow with its thickness, just
+    /// like the marks of an @arrow.
+    pub start_mark: Option<ArrowMark>,
+
+    /// The mark at the end of the line.
+    ///
+    /// ```example
+    /// #set line(length: 100%)
+    /// #stack(
+    ///   spacing: 1em,
+    ///   line(end-mark: "arrow"),
+    ///   line(start-mark: "bar", end-mark: "stealth", stroke: 2pt + blue),
+    /// )
+    /// ```
+    pub end_mark: Option<ArrowMark>,
 }
@@ -1,7 +1,10 @@
 //! Drawing and visualization.
 
+mod arrow;
+mod chart;
 mod color;
 mod curve;
+mod diagram;
 mod gradient;
 mod image;
 mod line;
@@ -10,9 +13,13 @@ mod polygon;
 mod shape;
 mod stroke;
 mod tiling;
+mod video;
 
+pub use self::arrow::*;
+pub use self::chart::*;
 pub use self::color::*;
 pub use self::curve::*;
+pub use self::diagram::*;
 pub use self::gradient::*;
 pub use self::image::*;
 pub use self::line::*;
@@ -21,6 +28,7 @@ pub use self::polygon::*;
 pub use self::shape::*;
 pub use self::stroke::*;
 pub use self::tiling::*;
+pub use self::video::*;
 
 use crate::foundations::Scope;
 
@@ -39,5 +47,9 @@ pub(super) fn define(global: &mut Scope) {
     global.define_elem::<CircleElem>();
     global.define_elem::<PolygonElem>();
     global.define_elem::<CurveElem>();
+    global.define_elem::<ArrowElem>();
+    global.define_elem::<DiagramElem>();
+    global.define_elem::<ChartElem>();
+    global.define_elem::<VideoElem>();
     global.reset_category();
 }
@@ -0,0 +1,167 @@
+use ecow::EcoString;
+use typst_syntax::{Spanned, VirtualPath};
+
+use crate::diag::StrResult;
+use crate::foundations::{Cast, Derived, Packed, Smart, StyleChain, elem};
+use crate::layout::{Length, Rel, Sizing};
+use crate::loading::{DataSource, Load, Loaded};
+use crate::visualize::ImageElem;
+
+/// A video with a poster frame.
+///
+/// In HTML export, this emits a `<video>` element that embeds the video file.
+/// In paged export, videos cannot play, so the
+/// @video.poster[poster frame] is shown in their place instead, just like an
+/// @image.
+///
+/// = Example <example>
+/// ```typ
+/// #video(
+///   "experiment.mp4",
+///   poster: "experiment.png",
+///   width: 80%,
+/// )
+/// ```
+#[elem]
+pub struct VideoElem {
+    /// A path to a video file or raw bytes making up a video in one of the
+    /// supported @video.format[formats].
+    #[required]
+    #[parse(
+        let source = args.expect::<Spanned<DataSource>>("source")?;
+        let loaded = source.load(engine.world)?;
+        Derived::new(source.v, loaded)
+    )]
+    pub source: Derived<DataSource, Loaded>,
+
+    /// The video's format.
+    ///
+    /// By default, the format is detected from the file extension or, failing
+    /// that, from the data itself. Supported formats are `{"mp4"}`,
+    /// `{"webm"}`, and `{"ogg"}`.
+    pub format: Smart<VideoFormat>,
+
+    /// An image to show in place of the video.
+    ///
+    /// In HTML export, browsers show this image until the video starts
+    /// playing. In paged export, it is shown instead of the video. It can be
+    /// provided in any of the @image.format[formats supported by images].
+    ///
+    /// A poster is required when the video is shown in paged export.
+    #[parse(match args.named::<Spanned<Option<DataSource>>>("poster")? {
+        Some(Spanned { v: Some(source), span }) => Some(Some({
+            let loaded = Spanned::new(&source, span).load(engine.world)?;
+            Derived::new(source, loaded)
+        })),
+        Some(Spanned { v: None, .. }) => Some(None),
+        None => None,
+    })]
+    pub poster: Option<Derived<DataSource, Loaded>>,
+
+    /// The width of the video.
+    pub width: Smart<Rel<Length>>,
+
+    /// The height of the video.
+    pub height: Sizing,
+
+    /// An alternative description of the video.
+    ///
+    /// In paged export, this describes the poster frame. In HTML export, it
+    /// becomes the accessible label of the video.
+    pub alt: Option<EcoString>,
+
+    /// Whether the browser should offer controls for playback.
+    #[default(true)]
+    pub controls: bool,
+
+    /// Whether the video should start playing as soon as possible.
+    ///
+    /// Most browsers only allow this for @video.muted[muted] videos.
+    #[default(false)]
+    pub autoplay: bool,
+
+    /// Whether the video should start over when it reaches its end.
+    #[default(false)]
+    pub looping: bool,
+
+    /// Whether the video's audio should initially be silenced.
+    #[default(false)]
+    pub muted: bool,
+}
+
+impl Packed<VideoElem> {
+    /// An image element showing the poster frame in the video's place, if the
+    /// video has a poster.
+    pub fn poster_image(&self, styles: StyleChain) -> Option<Packed<ImageElem>> {
+        let poster = self.poster.get_cloned(styles)?;
+        let image = ImageElem::new(poster)
+            .with_width(self.width.get(styles))
+            .with_height(self.height.get(styles))
+            .with_alt(self.alt.get_cloned(styles));
+        Some(Packed::new(image).spanned(self.span()))
+    }
+
+    /// Tries to determine the video format based on the format that was
+    /// explicitly defined, or else the extension, or else the data.
+    pub fn determine_format(&self, styles: StyleChain) -> StrResult<VideoFormat> {
+        if let Smart::Custom(v) = self.format.get(styles) {
+            return Ok(v);
+        };
+
+        let Derived { source, derived: loaded } = &self.source;
+        if let DataSource::Path(path) = source
+            && let Ok(id) = path.resolve_if_some(self.span().id())
+            && let Some(format) = VideoFormat::from_path(id.vpath())
+        {
+            return Ok(format);
+        }
+
+        Ok(VideoFormat::detect(&loaded.data).ok_or("unknown video format")?)
+    }
+}
+
+/// A video format.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum VideoFormat {
+    /// MPEG-4 video, typically encoded with H.264. Supported by all major
+    /// browsers.
+    Mp4,
+    /// Open video format, typically encoded with VP9 or AV1.
+    Webm,
+    /// Ogg video, typically encoded with Theora.
+    Ogg,
+}
+
+impl VideoFormat {
+    /// Try to detect the format of data in a buffer.
+    pub fn detect(data: &[u8]) -> Option<Self> {
+        if data.get(4..8) == Some(&b"ftyp"[..]) {
+            Some(Self::Mp4)
+        } else if data.starts_with(b"\x1A\x45\xDF\xA3") {
+            Some(Self::Webm)
+        } else if data.starts_with(b"OggS") {
+            Some(Self::Ogg)
+        } else {
+            None
+        }
+    }
+
+    /// Derive the format from the file extension of a path.
+    fn from_path(path: &VirtualPath) -> Option<Self> {
+        match path.extension()? {
+            "mp4" | "m4v" => Some(Self::Mp4),
+            "webm" => Some(Self::Webm),
+            "ogv" | "ogg" => Some(Self::Ogg),
+            _ => None,
+        }
+    }
+
+    /// The MIME type for this format.
+    pub fn mime(self) -> &'static str {
+        match self {
+            Self::Mp4 => "video/mp4",
+            Self::Webm => "video/webm",
+            Self::Ogg => "video/ogg",
+        }
+    }
+}
@@ -94,6 +94,9 @@ struct PdfRasterImageInner {
     /// guaranteed to either be in luma8 or rgb8, and thus can be used for the
     /// `color_channel` method of `CustomImage`.
     actual_dynamic: OnceLock<Arc<DynamicImage>>,
+    /// The color channel of a 16-bit image as big-endian luma16 or rgb16
+    /// samples. Used instead of `actual_dynamic` to preserve the bit depth.
+    wide_color_channel: OnceLock<Vec<u8>>,
 }
 
 impl PdfRasterImage {
@@ -103,8 +106,29 @@ impl PdfRasterImage {
             raster,
             alpha_channel: OnceLock::new(),
             actual_dynamic: OnceLock::new(),
+            wide_color_channel: OnceLock::new(),
         }))
     }
+
+    /// Whether the image has 16-bit integer samples, which are kept as-is.
+    fn is_wide(&self) -> bool {
+        if let Some(cmyk) = self.0.raster.cmyk() {
+            return cmyk.wide;
+        }
+
+        matches!(
+            self.0.raster.dynamic().as_ref(),
+            DynamicImage::ImageLuma16(_)
+                | DynamicImage::ImageLumaA16(_)
+                | DynamicImage::ImageRgb16(_)
+                | DynamicImage::ImageRgba16(_)
+        )
+    }
+}
+
+/// Serializes 16-bit samples in big-endian byte order, as PDF expects them.
+fn be_bytes(samples: &[u16]) -> Vec<u8> {
+    samples.iter().flat_map(|sample| sample.to_be_bytes()).collect()
 }
 
 impl Hash for PdfRasterImage {
@@ -117,6 +141,22 @@ impl Hash for PdfRasterImage {
 
 impl CustomImage for PdfRasterImage {
     fn color_channel(&self) -> &[u8] {
+        // CMYK images keep their original samples instead of the sRGB version.
+        if let Some(cmyk) = self.0.raster.cmyk() {
+            return cmyk.data.as_slice();
+        }
+
+        if self.is_wide() {
+            return self.0.wide_color_channel.get_or_init(|| {
+                let dynamic = self.0.raster.dynamic();
+                if dynamic.color().has_color() {
+                    be_bytes(&dynamic.to_rgb16())
+                } else {
+                    be_bytes(&dynamic.to_luma16())
+                }
+            });
+        }
+
         self.0
             .actual_dynamic
             .get_or_init(|| {
@@ -141,6 +181,11 @@ impl CustomImage for PdfRasterImage {
             .alpha_channel
             .get_or_init(|| {
                 self.0.raster.dynamic().color().has_alpha().then(|| {
+                    if self.is_wide() {
+                        let rgba = self.0.raster.dynamic().to_rgba16();
+                        let alpha: Vec<u16> = rgba.pixels().map(|p| p[3]).collect();
+                        return be_bytes(&alpha);
+                    }
                     self.0
                         .raster
                         .dynamic()
@@ -154,20 +199,31 @@ impl CustomImage for PdfRasterImage {
     }
 
     fn bits_per_component(&self) -> BitsPerComponent {
-        BitsPerComponent::Eight
+        if self.is_wide() { BitsPerComponent::Sixteen } else { BitsPerComponent::Eight }
     }
 
     fn size(&self) -> (u32, u32) {
-        (self.0.raster.width(), self.0.raster.height())
+        match self.0.raster.cmyk() {
+            Some(cmyk) => (cmyk.width, cmyk.height),
+            None => (self.0.raster.width(), self.0.raster.height()),
+        }
     }
 
     fn icc_profile(&self) -> Option<&[u8]> {
+        if let Some(cmyk) = self.0.raster.cmyk() {
+            return cmyk.icc.as_ref().map(|b| b.as_bytes());
+        }
+
         if matches!(
             self.0.raster.dynamic().as_ref(),
             DynamicImage::ImageLuma8(_)
                 | DynamicImage::ImageLumaA8(_)
                 | DynamicImage::ImageRgb8(_)
                 | DynamicImage::ImageRgba8(_)
+                | DynamicImage::ImageLuma16(_)
+                | DynamicImage::ImageLumaA16(_)
+                | DynamicImage::ImageRgb16(_)
+                | DynamicImage::ImageRgba16(_)
         ) {
             self.0.raster.icc().map(|b| b.as_bytes())
         } else {
@@ -178,8 +234,11 @@ impl CustomImage for PdfRasterImage {
     }
 
     fn color_space(&self) -> ImageColorspace {
-        // Remember that we convert all images to either RGB or luma.
-        if self.0.raster.dynamic().color().has_color() {
+        // Remember that we convert all images except for CMYK ones to either
+        // RGB or luma.
+        if self.0.raster.cmyk().is_some() {
+            ImageColorspace::Cmyk
+        } else if self.0.raster.dynamic().color().has_color() {
             ImageColorspace::Rgb
         } else {
             ImageColorspace::Luma
@@ -216,10 +275,12 @@ fn convert_pdf(pdf: &PdfImage) -> PdfDocument {
 }
 
 fn exif_transform(image: &RasterImage, size: Size) -> (Transform, Size) {
-    // For JPEGs, we want to apply the EXIF orientation as a transformation
-    // because we don't recode them. For other formats, the transform is already
-    // baked into the dynamic image data.
-    if image.format() != RasterFormat::Exchange(ExchangeFormat::Jpg) {
+    // For JPEGs and CMYK images, we want to apply the EXIF orientation as a
+    // transformation because we don't recode them. For other formats, the
+    // transform is already baked into the dynamic image data.
+    if image.format() != RasterFormat::Exchange(ExchangeFormat::Jpg)
+        && image.cmyk().is_none()
+    {
         return (Transform::identity(), size);
     }
 
@@ -8,7 +8,7 @@ use typst_library::layout::{GridCell, Inherit};
 use typst_library::math::EquationElem;
 use typst_library::model::{LinkMarker, OutlineEntry, TableCell};
 use typst_library::text::Locale;
-use typst_library::visualize::ImageElem;
+use typst_library::visualize::{ChartElem, ImageElem};
 use typst_syntax::Span;
 
 use crate::tags::context::{
@@ -130,6 +130,7 @@ impl Groups {
             GroupKind::Figure(..) => Never,
             GroupKind::FigureCaption(..) => Never,
             GroupKind::Image(..) => Never,
+            GroupKind::Chart(..) => Never,
             GroupKind::Formula(..) => Never,
             GroupKind::Link(..) => NoPdfUa(BreakPriority::Span),
             GroupKind::CodeBlock(..) => Never,
@@ -215,6 +216,7 @@ impl Groups {
             | GroupKind::Figure(..)
             | GroupKind::FigureCaption(..)
             | GroupKind::Image(..)
+            | GroupKind::Chart(..)
             | GroupKind::Formula(..)
             | GroupKind::CodeBlock(..)
             | GroupKind::CodeBlockLine(..)
@@ -442,6 +444,7 @@ pub enum GroupKind {
     /// into table, or next to the figure tag.
     FigureCaption(BBoxId, Option<Locale>),
     Image(Packed<ImageElem>, BBoxId, Option<Locale>),
+    Chart(Packed<ChartElem>, BBoxId, Option<Locale>),
     Formula(Packed<EquationElem>, BBoxId, Option<Locale>),
     Link(Packed<LinkMarker>, Option<Locale>),
     CodeBlock(Option<Locale>),
@@ -478,6 +481,7 @@ impl std::fmt::Debug for GroupKind {
             Self::Figure(..) => "Figure",
             Self::FigureCaption(..) => "FigureCaption",
             Self::Image(..) => "Image",
+            Self::Chart(..) => "Chart",
             Self::Formula(..) => "Formula",
             Self::Link(..) => "Link",
             Self::CodeBlock(..) => "CodeBlock",
@@ -528,6 +532,7 @@ impl GroupKind {
             GroupKind::Figure(_, id, _) => Some(*id),
             GroupKind::FigureCaption(id, _) => Some(*id),
             GroupKind::Image(_, id, _) => Some(*id),
+            GroupKind::Chart(_, id, _) => Some(*id),
             GroupKind::Formula(_, id, _) => Some(*id),
             _ => None,
         }
@@ -555,6 +560,7 @@ impl GroupKind {
             GroupKind::Figure(_, _, lang) => lang,
             GroupKind::FigureCaption(_, lang) => lang,
             GroupKind::Image(_, _, lang) => lang,
+            GroupKind::Chart(_, _, lang) => lang,
             GroupKind::Formula(_, _, lang) => lang,
             GroupKind::Link(_, lang) => lang,
             GroupKind::CodeBlock(lang) => lang,
@@ -588,6 +594,7 @@ impl GroupKind {
             GroupKind::Figure(_, _, lang) => lang,
             GroupKind::FigureCaption(_, lang) => lang,
             GroupKind::Image(_, _, lang) => lang,
+            GroupKind::Chart(_, _, lang) => lang,
             GroupKind::Formula(_, _, lang) => lang,
             GroupKind::Link(_, lang) => lang,
             GroupKind::CodeBlock(lang) => lang,
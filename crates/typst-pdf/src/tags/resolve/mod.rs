@@ -1,11 +1,12 @@
 use std::num::NonZeroU16;
 
-use ecow::EcoVec;
+use ecow::{EcoString, EcoVec};
 use krilla::tagging::{self as kt, Node, Tag, TagKind};
 use krilla::tagging::{Identifier, TagTree};
 use smallvec::SmallVec;
 use typst_library::diag::{At, SourceDiagnostic, SourceResult, error};
 use typst_library::text::Locale;
+use typst_library::visualize::ChartTable;
 use typst_syntax::Span;
 
 use crate::PdfOptions;
@@ -196,6 +197,50 @@ fn resolve_group_node(
     }
 
     accum.push(Node::Group(kt::TagGroup::with_children(tag, nodes)));
+
+    // The chart's figure only carries a short alternative description, the
+    // data itself follows in a table.
+    if let GroupKind::Chart(chart, _, _) = &group.kind
+        && let Some(data) = &chart.data
+    {
+        accum.push(Node::Group(build_chart_table(data)));
+    }
+}
+
+/// Builds a table tag with the data of a chart.
+///
+/// The table has no marked content of its own, so the text of each cell is
+/// provided as its alternative description.
+fn build_chart_table(data: &ChartTable) -> kt::TagGroup {
+    let alt = |text: &EcoString| Some(text.to_string());
+    let cell = |tag: TagKind| Node::Group(kt::TagGroup::with_children(tag, vec![]));
+
+    let header = data
+        .header
+        .iter()
+        .map(|text| {
+            cell(Tag::TH(kt::TableHeaderScope::Column).with_alt_text(alt(text)).into())
+        })
+        .collect();
+    let head = Node::Group(kt::TagGroup::with_children(
+        Tag::THead,
+        vec![Node::Group(kt::TagGroup::with_children(Tag::TR, header))],
+    ));
+
+    let rows = data
+        .rows
+        .iter()
+        .map(|row| {
+            let cells = row
+                .iter()
+                .map(|text| cell(Tag::TD.with_alt_text(alt(text)).into()))
+                .collect();
+            Node::Group(kt::TagGroup::with_children(Tag::TR, cells))
+        })
+        .collect();
+    let body = Node::Group(kt::TagGroup::with_children(Tag::TBody, rows));
+
+    kt::TagGroup::with_children(Tag::Table, vec![head, body])
 }
 
 fn resolve_text(
@@ -299,6 +344,12 @@ fn build_group_tag(rs: &mut Resolver, group: &Group) -> Option<TagKind> {
             let alt = image.alt.opt_ref().map(Into::into);
             Tag::Figure(alt).with_placement(Some(kt::Placement::Block)).into()
         }
+        GroupKind::Chart(chart, _, _) => {
+            // The data is provided by a table next to the figure, see
+            // `build_chart_table`.
+            let alt = chart.alt.opt_ref().map(Into::into);
+            Tag::Figure(alt).with_placement(Some(kt::Placement::Block)).into()
+        }
         GroupKind::Formula(equation, _, _) => {
             let alt = equation.alt.opt_ref().map(Into::into);
             let placement = equation.block.val().then_some(kt::Placement::Block);
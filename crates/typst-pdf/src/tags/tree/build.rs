@@ -38,10 +38,10 @@ use typst_library::model::{
 };
 use typst_library::pdf::{ArtifactElem, PdfMarkerTag, PdfMarkerTagKind};
 use typst_library::text::{
-    HighlightElem, OverlineElem, RawElem, RawLine, StrikeElem, SubElem, SuperElem,
-    UnderlineElem,
+    HighlightElem, OverlineElem, RawElem, RawLine, RubyElem, StrikeElem, SubElem,
+    SuperElem, UnderlineElem,
 };
-use typst_library::visualize::ImageElem;
+use typst_library::visualize::{ChartElem, ImageElem};
 use typst_syntax::Span;
 
 use crate::PdfOptions;
@@ -370,6 +370,8 @@ fn progress_tree_start(tree: &mut TreeBuilder, elem: &Content) -> GroupId {
                 push_group(tree, elem, GroupKind::TermsItemBody(None, None))
             }
             PdfMarkerTagKind::Label => push_tag(tree, elem, Tag::Lbl),
+            PdfMarkerTagKind::RubyBase => push_tag(tree, elem, Tag::RB),
+            PdfMarkerTagKind::RubyText => push_tag(tree, elem, Tag::RT),
         }
     } else if let Some(link) = elem.to_packed::<LinkMarker>() {
         push_group(tree, elem, GroupKind::Link(link.clone(), None))
@@ -404,6 +406,10 @@ fn progress_tree_start(tree: &mut TreeBuilder, elem: &Content) -> GroupId {
         let lang = image.locale;
         let bbox = tree.ctx.new_bbox();
         push_group(tree, elem, GroupKind::Image(image.clone(), bbox, lang))
+    } else if let Some(chart) = elem.to_packed::<ChartElem>() {
+        let lang = chart.locale;
+        let bbox = tree.ctx.new_bbox();
+        push_group(tree, elem, GroupKind::Chart(chart.clone(), bbox, lang))
     } else if let Some(equation) = elem.to_packed::<EquationElem>() {
         let lang = equation.locale;
         let bbox = tree.ctx.new_bbox();
@@ -484,6 +490,8 @@ fn progress_tree_start(tree: &mut TreeBuilder, elem: &Content) -> GroupId {
         } else {
             push_tag(tree, elem, Tag::InlineQuote)
         }
+    } else if elem.is::<RubyElem>() {
+        push_tag(tree, elem, Tag::Ruby)
     } else if let Some(raw) = elem.to_packed::<RawElem>() {
         if raw.block.val() {
             push_group(tree, elem, GroupKind::CodeBlock(None))
@@ -545,6 +545,9 @@ fn close_group(tree: &mut Tree, surface: &mut Surface, id: GroupId) -> GroupId {
         GroupKind::Image(..) => {
             tree.groups.push_group(direct_parent, id);
         }
+        GroupKind::Chart(..) => {
+            tree.groups.push_group(direct_parent, id);
+        }
         GroupKind::Formula(..) => {
             tree.groups.push_group(direct_parent, id);
         }
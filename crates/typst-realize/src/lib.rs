@@ -14,7 +14,7 @@ use bumpalo::collections::{CollectIn, String as BumpString, Vec as BumpVec};
 use comemo::Track;
 use ecow::EcoString;
 use typst_html::HtmlElem;
-use typst_library::diag::{At, SourceResult, bail, warning};
+use typst_library::diag::{At, SourceResult, Uncatchable, bail, warning};
 use typst_library::engine::Engine;
 use typst_library::foundations::{
     Content, Context, ContextElem, Element, NativeElement, NativeShowRule, Packed,
@@ -33,7 +33,7 @@ use typst_library::model::{
     ParElem, ParbreakElem, TermsElem,
 };
 use typst_library::routines::{Arenas, FragmentKind, Pair, RealizationKind};
-use typst_library::text::{LinebreakElem, SmartQuoteElem, SpaceElem, TextElem};
+use typst_library::text::{LinebreakElem, RubyElem, SmartQuoteElem, SpaceElem, TextElem};
 use typst_syntax::Span;
 use typst_utils::{ListSet, SliceExt, SmallBitSet};
 
@@ -69,6 +69,7 @@ pub fn realize<'a>(
         kind,
     };
 
+    s.engine.check_cancelled()?;
     visit(&mut s, content, styles)?;
     finish(&mut s)?;
 
@@ -419,7 +420,8 @@ fn visit_show_rules<'a>(
     let prev_outside = s.outside;
     s.outside &= content.is::<ContextElem>();
     s.engine.route.increase();
-    s.engine.route.check_show_depth().at(content.span())?;
+    s.engine.route.check_show_depth().at(content.span()).uncatchable()?;
+    s.engine.check_cancelled()?;
 
     visit_styled(s, realized, Cow::Owned(map), styles)?;
 
@@ -1054,6 +1056,7 @@ static PAR: GroupingRule = GroupingRule {
             || elem == SmartQuoteElem::ELEM
             || elem == InlineElem::ELEM
             || elem == BoxElem::ELEM
+            || elem == RubyElem::ELEM
         {
             GroupingEffect::Trigger
         } else if elem == SpaceElem::ELEM {
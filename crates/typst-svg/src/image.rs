@@ -68,6 +68,8 @@ pub enum WebImageFormat {
     Jpg,
     Gif,
     Webp,
+    Avif,
+    Bmp,
     Svg,
 }
 
@@ -79,6 +81,8 @@ impl WebImageFormat {
             Self::Jpg => "image/jpeg",
             Self::Gif => "image/gif",
             Self::Webp => "image/webp",
+            Self::Avif => "image/avif",
+            Self::Bmp => "image/bmp",
             Self::Svg => "image/svg+xml",
         }
     }
@@ -90,6 +94,8 @@ impl WebImageFormat {
             Self::Jpg => "jpg",
             Self::Gif => "gif",
             Self::Webp => "webp",
+            Self::Avif => "avif",
+            Self::Bmp => "bmp",
             Self::Svg => "svg",
         }
     }
@@ -100,26 +106,33 @@ impl WebImage {
     #[comemo::memoize]
     pub fn new(image: &Image) -> WebImage {
         let (format, data) = match image.kind() {
-            ImageKind::Raster(raster) => match raster.format() {
-                RasterFormat::Exchange(format) => (
-                    match format {
-                        ExchangeFormat::Png => WebImageFormat::Png,
-                        ExchangeFormat::Jpg => WebImageFormat::Jpg,
-                        ExchangeFormat::Gif => WebImageFormat::Gif,
-                        ExchangeFormat::Webp => WebImageFormat::Webp,
+            ImageKind::Raster(raster) => {
+                let format = match raster.format() {
+                    RasterFormat::Exchange(format) => match format {
+                        ExchangeFormat::Png => Some(WebImageFormat::Png),
+                        ExchangeFormat::Jpg => Some(WebImageFormat::Jpg),
+                        ExchangeFormat::Gif => Some(WebImageFormat::Gif),
+                        ExchangeFormat::Webp => Some(WebImageFormat::Webp),
+                        ExchangeFormat::Avif => Some(WebImageFormat::Avif),
+                        ExchangeFormat::Bmp => Some(WebImageFormat::Bmp),
+                        // Not widely supported by browsers.
+                        ExchangeFormat::Jxl | ExchangeFormat::Tiff => None,
                     },
-                    raster.data().clone(),
-                ),
-                RasterFormat::Pixel(_) => (WebImageFormat::Png, {
-                    let mut buf = vec![];
-                    let mut encoder = PngEncoder::new(&mut buf);
-                    if let Some(icc_profile) = raster.icc() {
-                        encoder.set_icc_profile(icc_profile.to_vec()).ok();
-                    }
-                    raster.dynamic().write_with_encoder(encoder).unwrap();
-                    Bytes::new(buf)
-                }),
-            },
+                    RasterFormat::Pixel(_) => None,
+                };
+                match format {
+                    Some(format) => (format, raster.data().clone()),
+                    None => (WebImageFormat::Png, {
+                        let mut buf = vec![];
+                        let mut encoder = PngEncoder::new(&mut buf);
+                        if let Some(icc_profile) = raster.icc() {
+                            encoder.set_icc_profile(icc_profile.to_vec()).ok();
+                        }
+                        raster.dynamic().write_with_encoder(encoder).unwrap();
+                        Bytes::new(buf)
+                    }),
+                }
+            }
             ImageKind::Svg(svg) => (WebImageFormat::Svg, svg.data().clone()),
             ImageKind::Pdf(pdf) => {
                 (WebImageFormat::Svg, Bytes::from_string(pdf_to_svg(pdf)))
@@ -362,6 +362,10 @@ pub enum Expr<'a> {
     WhileLoop(WhileLoop<'a>),
     /// A for loop: `for x in y { z }`.
     ForLoop(ForLoop<'a>),
+    /// A try-catch expression: `try { x } catch err { y }`.
+    TryCatch(TryCatch<'a>),
+    /// A match expression: `match x { 1 => y, _ => z }`.
+    MatchExpr(MatchExpr<'a>),
     /// A module import: `import "utils.typ": a, b, c`.
     ModuleImport(ModuleImport<'a>),
     /// A module include: `include "chapter1.typ"`.
@@ -448,6 +452,8 @@ impl<'a> AstNode<'a> for Expr<'a> {
             SyntaxKind::Conditional => Some(Self::Conditional(Conditional(node))),
             SyntaxKind::WhileLoop => Some(Self::WhileLoop(WhileLoop(node))),
             SyntaxKind::ForLoop => Some(Self::ForLoop(ForLoop(node))),
+            SyntaxKind::TryCatch => Some(Self::TryCatch(TryCatch(node))),
+            SyntaxKind::MatchExpr => Some(Self::MatchExpr(MatchExpr(node))),
             SyntaxKind::ModuleImport => Some(Self::ModuleImport(ModuleImport(node))),
             SyntaxKind::ModuleInclude => Some(Self::ModuleInclude(ModuleInclude(node))),
             SyntaxKind::LoopBreak => Some(Self::LoopBreak(LoopBreak(node))),
@@ -515,6 +521,8 @@ impl<'a> AstNode<'a> for Expr<'a> {
             Self::Conditional(v) => v.to_untyped(),
             Self::WhileLoop(v) => v.to_untyped(),
             Self::ForLoop(v) => v.to_untyped(),
+            Self::TryCatch(v) => v.to_untyped(),
+            Self::MatchExpr(v) => v.to_untyped(),
             Self::ModuleImport(v) => v.to_untyped(),
             Self::ModuleInclude(v) => v.to_untyped(),
             Self::LoopBreak(v) => v.to_untyped(),
@@ -555,6 +563,8 @@ impl Expr<'_> {
                 | Self::Conditional(_)
                 | Self::WhileLoop(_)
                 | Self::ForLoop(_)
+                | Self::TryCatch(_)
+                | Self::MatchExpr(_)
                 | Self::ModuleImport(_)
                 | Self::ModuleInclude(_)
                 | Self::LoopBreak(_)
@@ -1647,6 +1657,18 @@ impl<'a> Named<'a> {
     pub fn pattern(self) -> Pattern<'a> {
         self.0.cast_last()
     }
+
+    /// The type annotation of a named parameter: `length` in
+    /// `size as length: 1pt`.
+    ///
+    /// This only exists if this `Named` is contained in a `Param`.
+    pub fn ty(self) -> Option<Expr<'a>> {
+        self.0
+            .children()
+            .skip_while(|&c| c.kind() != SyntaxKind::As)
+            .take_while(|&c| c.kind() != SyntaxKind::Colon)
+            .find_map(SyntaxNode::cast)
+    }
 }
 
 node! {
@@ -1745,7 +1767,7 @@ impl UnOp {
     /// The precedence of this operator.
     pub fn precedence(self) -> u8 {
         match self {
-            Self::Pos | Self::Neg => 7,
+            Self::Pos | Self::Neg => 8,
             Self::Not => 4,
         }
     }
@@ -1834,6 +1856,8 @@ pub enum BinOp {
     MulAssign,
     /// The divide-assign operator: `/=`.
     DivAssign,
+    /// The pipe operator: `|>`.
+    Pipe,
 }
 
 impl BinOp {
@@ -1858,6 +1882,7 @@ impl BinOp {
             SyntaxKind::HyphEq => Self::SubAssign,
             SyntaxKind::StarEq => Self::MulAssign,
             SyntaxKind::SlashEq => Self::DivAssign,
+            SyntaxKind::Pipe => Self::Pipe,
             _ => return Option::None,
         })
     }
@@ -1865,10 +1890,11 @@ impl BinOp {
     /// The precedence of this operator.
     pub fn precedence(self) -> u8 {
         match self {
-            Self::Mul => 6,
-            Self::Div => 6,
-            Self::Add => 5,
-            Self::Sub => 5,
+            Self::Mul => 7,
+            Self::Div => 7,
+            Self::Add => 6,
+            Self::Sub => 6,
+            Self::Pipe => 5,
             Self::Eq => 4,
             Self::Neq => 4,
             Self::Lt => 4,
@@ -1909,6 +1935,7 @@ impl BinOp {
             Self::SubAssign => Assoc::Right,
             Self::MulAssign => Assoc::Right,
             Self::DivAssign => Assoc::Right,
+            Self::Pipe => Assoc::Left,
         }
     }
 
@@ -1934,6 +1961,7 @@ impl BinOp {
             Self::SubAssign => "-=",
             Self::MulAssign => "*=",
             Self::DivAssign => "/=",
+            Self::Pipe => "|>",
         }
     }
 }
@@ -2054,6 +2082,14 @@ impl<'a> Closure<'a> {
         self.0.cast_first()
     }
 
+    /// The return type annotation: `content` in `(x) -> content => x`.
+    pub fn returns(self) -> Option<Expr<'a>> {
+        self.0
+            .children()
+            .skip_while(|&c| c.kind() != SyntaxKind::ThinArrow)
+            .find_map(SyntaxNode::cast)
+    }
+
     /// The body of the closure.
     pub fn body(self) -> Expr<'a> {
         self.0.cast_last()
@@ -2077,6 +2113,8 @@ impl<'a> Params<'a> {
 pub enum Param<'a> {
     /// A positional parameter: `x`.
     Pos(Pattern<'a>),
+    /// A positional parameter with a type annotation: `x as int`.
+    Typed(TypedParam<'a>),
     /// A named parameter with a default value: `draw: false`.
     Named(Named<'a>),
     /// An argument sink: `..args` or `..`.
@@ -2086,6 +2124,7 @@ pub enum Param<'a> {
 impl<'a> AstNode<'a> for Param<'a> {
     fn from_untyped(node: &'a SyntaxNode) -> Option<Self> {
         match node.kind() {
+            SyntaxKind::TypedParam => Some(Self::Typed(TypedParam(node))),
             SyntaxKind::Named => Some(Self::Named(Named(node))),
             SyntaxKind::Spread => Some(Self::Spread(Spread(node))),
             _ => node.cast().map(Self::Pos),
@@ -2095,6 +2134,7 @@ impl<'a> AstNode<'a> for Param<'a> {
     fn to_untyped(self) -> &'a SyntaxNode {
         match self {
             Self::Pos(v) => v.to_untyped(),
+            Self::Typed(v) => v.to_untyped(),
             Self::Named(v) => v.to_untyped(),
             Self::Spread(v) => v.to_untyped(),
         }
@@ -2105,6 +2145,23 @@ impl<'a> AstNode<'a> for Param<'a> {
     }
 }
 
+node! {
+    /// A positional parameter with a type annotation: `x as int`.
+    struct TypedParam
+}
+
+impl<'a> TypedParam<'a> {
+    /// The pattern the argument is bound to: `x`.
+    pub fn pattern(self) -> Pattern<'a> {
+        self.0.cast_first()
+    }
+
+    /// The type annotation: `int`.
+    pub fn ty(self) -> Expr<'a> {
+        self.0.cast_last()
+    }
+}
+
 /// The kind of a pattern.
 #[derive(Debug, Copy, Clone, Hash)]
 pub enum Pattern<'a> {
@@ -2408,6 +2465,156 @@ impl<'a> ForLoop<'a> {
     }
 }
 
+node! {
+    /// A try-catch expression: `try { x } catch err { y }`.
+    struct TryCatch
+}
+
+impl<'a> TryCatch<'a> {
+    /// The expression whose errors are caught.
+    pub fn body(self) -> Expr<'a> {
+        self.0.cast_first()
+    }
+
+    /// The name the caught error is bound to.
+    pub fn binding(self) -> Option<Ident<'a>> {
+        self.0
+            .children()
+            .skip_while(|&c| c.kind() != SyntaxKind::Catch)
+            .find_map(SyntaxNode::cast)
+    }
+
+    /// The expression to evaluate if the body fails.
+    pub fn handler(self) -> Expr<'a> {
+        self.0
+            .children()
+            .skip_while(|&c| c.kind() != SyntaxKind::Catch)
+            .filter(|c| {
+                matches!(c.kind(), SyntaxKind::CodeBlock | SyntaxKind::ContentBlock)
+            })
+            .find_map(SyntaxNode::cast)
+            .unwrap_or_else(Expr::placeholder)
+    }
+}
+
+node! {
+    /// A match expression: `match x { 1 => y, _ => z }`.
+    struct MatchExpr
+}
+
+impl<'a> MatchExpr<'a> {
+    /// The value to match against the arms' patterns.
+    pub fn value(self) -> Expr<'a> {
+        self.0.cast_first()
+    }
+
+    /// The arms of the match expression.
+    pub fn arms(self) -> impl DoubleEndedIterator<Item = MatchArm<'a>> {
+        self.0.children().filter_map(SyntaxNode::cast)
+    }
+}
+
+node! {
+    /// An arm of a match expression: `(a, b) if a < b => a`.
+    struct MatchArm
+}
+
+impl<'a> MatchArm<'a> {
+    /// The pattern the value must match.
+    pub fn pattern(self) -> MatchPattern<'a> {
+        self.0.cast_first()
+    }
+
+    /// An additional condition that must hold for the arm to be taken.
+    pub fn guard(self) -> Option<Expr<'a>> {
+        self.0
+            .children()
+            .skip_while(|&c| c.kind() != SyntaxKind::If)
+            .take_while(|&c| c.kind() != SyntaxKind::Arrow)
+            .find_map(SyntaxNode::cast)
+    }
+
+    /// The expression to evaluate if the arm is taken.
+    pub fn body(self) -> Expr<'a> {
+        self.0
+            .children()
+            .skip_while(|&c| c.kind() != SyntaxKind::Arrow)
+            .find_map(SyntaxNode::cast)
+            .unwrap_or_else(Expr::placeholder)
+    }
+}
+
+/// The pattern of a match arm.
+#[derive(Debug, Copy, Clone, Hash)]
+pub enum MatchPattern<'a> {
+    /// A literal that the value must be equal to: `1`, `"a"`, `none`.
+    Literal(Expr<'a>),
+    /// A check for the value's type: `x: int`.
+    Type(TypePattern<'a>),
+    /// A binding or destructuring pattern: `x`, `_`, `(a, ..b)`.
+    Binding(Pattern<'a>),
+}
+
+impl<'a> AstNode<'a> for MatchPattern<'a> {
+    fn from_untyped(node: &'a SyntaxNode) -> Option<Self> {
+        match node.kind() {
+            SyntaxKind::None
+            | SyntaxKind::Auto
+            | SyntaxKind::Bool
+            | SyntaxKind::Int
+            | SyntaxKind::Float
+            | SyntaxKind::Numeric
+            | SyntaxKind::Str
+            | SyntaxKind::Unary => node.cast().map(Self::Literal),
+            SyntaxKind::TypePattern => node.cast().map(Self::Type),
+            _ => node.cast().map(Self::Binding),
+        }
+    }
+
+    fn to_untyped(self) -> &'a SyntaxNode {
+        match self {
+            Self::Literal(v) => v.to_untyped(),
+            Self::Type(v) => v.to_untyped(),
+            Self::Binding(v) => v.to_untyped(),
+        }
+    }
+
+    fn placeholder() -> Self {
+        Self::Binding(Pattern::placeholder())
+    }
+}
+
+impl<'a> MatchPattern<'a> {
+    /// Returns a list of all new bindings introduced by the pattern.
+    pub fn bindings(self) -> Vec<Ident<'a>> {
+        match self {
+            Self::Literal(_) => vec![],
+            Self::Type(v) => v.binding().into_iter().collect(),
+            Self::Binding(v) => v.bindings(),
+        }
+    }
+}
+
+node! {
+    /// A pattern that checks the type of a value: `x: int`.
+    struct TypePattern
+}
+
+impl<'a> TypePattern<'a> {
+    /// The name the value is bound to, if it is not a placeholder.
+    pub fn binding(self) -> Option<Ident<'a>> {
+        self.0
+            .children()
+            .take_while(|&c| c.kind() != SyntaxKind::Colon)
+            .find_map(SyntaxNode::cast)
+    }
+
+    /// The expected type.
+    pub fn ty(self) -> Expr<'a> {
+        self.0.cast_last()
+    }
+}
+
 node! {
     /// A module import: `import "utils.typ": a, b, c`.
     struct ModuleImport
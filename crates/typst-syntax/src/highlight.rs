@@ -238,6 +238,8 @@ pub fn highlight(node: &LinkedNode) -> Option<Tag> {
         SyntaxKind::SlashEq => Some(Tag::Operator),
         SyntaxKind::Dots => Some(Tag::Operator),
         SyntaxKind::Arrow => Some(Tag::Operator),
+        SyntaxKind::ThinArrow => Some(Tag::Operator),
+        SyntaxKind::Pipe => Some(Tag::Operator),
         SyntaxKind::Root => Some(Tag::MathOperator),
         SyntaxKind::Bang => None,
 
@@ -258,6 +260,9 @@ pub fn highlight(node: &LinkedNode) -> Option<Tag> {
         SyntaxKind::Break => Some(Tag::Keyword),
         SyntaxKind::Continue => Some(Tag::Keyword),
         SyntaxKind::Return => Some(Tag::Keyword),
+        SyntaxKind::Try => Some(Tag::Keyword),
+        SyntaxKind::Catch => Some(Tag::Keyword),
+        SyntaxKind::Match => Some(Tag::Keyword),
         SyntaxKind::Import => Some(Tag::Keyword),
         SyntaxKind::Include => Some(Tag::Keyword),
         SyntaxKind::As => Some(Tag::Keyword),
@@ -284,6 +289,7 @@ pub fn highlight(node: &LinkedNode) -> Option<Tag> {
         SyntaxKind::Spread => None,
         SyntaxKind::Closure => None,
         SyntaxKind::Params => None,
+        SyntaxKind::TypedParam => None,
         SyntaxKind::LetBinding => None,
         SyntaxKind::SetRule => None,
         SyntaxKind::ShowRule => None,
@@ -291,6 +297,10 @@ pub fn highlight(node: &LinkedNode) -> Option<Tag> {
         SyntaxKind::Conditional => None,
         SyntaxKind::WhileLoop => None,
         SyntaxKind::ForLoop => None,
+        SyntaxKind::TryCatch => None,
+        SyntaxKind::MatchExpr => None,
+        SyntaxKind::MatchArm => None,
+        SyntaxKind::TypePattern => None,
         SyntaxKind::ModuleImport => None,
         SyntaxKind::ImportItems => None,
         SyntaxKind::ImportItemPath => None,
@@ -168,6 +168,10 @@ pub enum SyntaxKind {
     Dots,
     /// An arrow between a closure's parameters and body: `=>`.
     Arrow,
+    /// An arrow between a closure's parameters and its return type: `->`.
+    ThinArrow,
+    /// The pipe operator: `|>`.
+    Pipe,
     /// A root: `√`, `∛` or `∜`.
     Root,
     /// An exclamation mark; groups with directly preceding text in math: `!`.
@@ -207,6 +211,12 @@ pub enum SyntaxKind {
     Continue,
     /// The `return` keyword.
     Return,
+    /// The `try` keyword.
+    Try,
+    /// The `catch` keyword.
+    Catch,
+    /// The `match` keyword.
+    Match,
     /// The `import` keyword.
     Import,
     /// The `include` keyword.
@@ -258,6 +268,8 @@ pub enum SyntaxKind {
     Closure,
     /// A closure's parameters: `(x, y)`.
     Params,
+    /// A positional parameter with a type annotation: `x as int`.
+    TypedParam,
     /// A let binding: `let x = 1`.
     LetBinding,
     /// A set rule: `set text(...)`.
@@ -272,6 +284,14 @@ pub enum SyntaxKind {
     WhileLoop,
     /// A for loop: `for x in y { z }`.
     ForLoop,
+    /// A try-catch expression: `try { x } catch err { y }`.
+    TryCatch,
+    /// A match expression: `match x { 1 => y, _ => z }`.
+    MatchExpr,
+    /// An arm of a match expression: `(a, b) if a < b => a`.
+    MatchArm,
+    /// A pattern that checks the type of a value: `x: int`.
+    TypePattern,
     /// A module import: `import "utils.typ": a, b, c`.
     ModuleImport,
     /// Items to import from a module: `a, b, c`.
@@ -358,6 +378,9 @@ impl SyntaxKind {
                 | Self::Break
                 | Self::Continue
                 | Self::Return
+                | Self::Try
+                | Self::Catch
+                | Self::Match
                 | Self::Import
                 | Self::Include
                 | Self::As
@@ -461,6 +484,8 @@ impl SyntaxKind {
             Self::SlashEq => "divide-assign operator",
             Self::Dots => "dots",
             Self::Arrow => "arrow",
+            Self::ThinArrow => "thin arrow",
+            Self::Pipe => "pipe operator",
             Self::Root => "root",
             Self::Bang => "exclamation mark",
             Self::Not => "operator `not`",
@@ -480,6 +505,9 @@ impl SyntaxKind {
             Self::Break => "keyword `break`",
             Self::Continue => "keyword `continue`",
             Self::Return => "keyword `return`",
+            Self::Try => "keyword `try`",
+            Self::Catch => "keyword `catch`",
+            Self::Match => "keyword `match`",
             Self::Import => "keyword `import`",
             Self::Include => "keyword `include`",
             Self::As => "keyword `as`",
@@ -505,6 +533,7 @@ impl SyntaxKind {
             Self::Spread => "spread",
             Self::Closure => "closure",
             Self::Params => "closure parameters",
+            Self::TypedParam => "typed parameter",
             Self::LetBinding => "`let` expression",
             Self::SetRule => "`set` expression",
             Self::ShowRule => "`show` expression",
@@ -512,6 +541,10 @@ impl SyntaxKind {
             Self::Conditional => "`if` expression",
             Self::WhileLoop => "while-loop expression",
             Self::ForLoop => "for-loop expression",
+            Self::TryCatch => "`try` expression",
+            Self::MatchExpr => "`match` expression",
+            Self::MatchArm => "match arm",
+            Self::TypePattern => "type pattern",
             Self::ModuleImport => "`import` expression",
             Self::ImportItems => "import items",
             Self::ImportItemPath => "imported item path",
@@ -658,6 +691,8 @@ impl SyntaxKind {
             Self::SlashEq => Known(Code),
             Self::Dots => Parent, // code/math: Spread
             Self::Arrow => Known(Code),
+            Self::ThinArrow => Known(Code),
+            Self::Pipe => Known(Code),
             Self::Root => Known(Math),
             Self::Bang => Known(Math),
 
@@ -678,6 +713,9 @@ impl SyntaxKind {
             Self::Break => Known(Code),
             Self::Continue => Known(Code),
             Self::Return => Known(Code),
+            Self::Try => Known(Code),
+            Self::Catch => Known(Code),
+            Self::Match => Known(Code),
             Self::Import => Known(Code),
             Self::Include => Known(Code),
             Self::As => Known(Code),
@@ -704,6 +742,7 @@ impl SyntaxKind {
             Self::Spread => Parent, // code: part | math: MathArgs
             Self::Closure => Known(Code),
             Self::Params => Known(Code),
+            Self::TypedParam => Known(Code),
             Self::LetBinding => Known(Code),
             Self::SetRule => Known(Code),
             Self::ShowRule => Known(Code),
@@ -711,6 +750,10 @@ impl SyntaxKind {
             Self::Conditional => Known(Code),
             Self::WhileLoop => Known(Code),
             Self::ForLoop => Known(Code),
+            Self::TryCatch => Known(Code),
+            Self::MatchExpr => Known(Code),
+            Self::MatchArm => Known(Code),
+            Self::TypePattern => Known(Code),
             Self::ModuleImport => Known(Code),
             Self::ImportItems => Known(Code),
             Self::ImportItemPath => Known(Code),
@@ -604,6 +604,18 @@ impl Lexer<'_> {
             '|' if self.s.eat_if("->") => SyntaxKind::MathShorthand,
             '|' if self.s.eat_if("=>") => SyntaxKind::MathShorthand,
             '|' if self.s.eat_if('|') => SyntaxKind::MathShorthand,
+            // A spaced `|>` is most likely an attempt to pipe, while the
+            // unspaced `|x|>1` compares an absolute value.
+            '|' if self.s.scout(-2).is_some_and(char::is_whitespace)
+                && self.s.at('>')
+                && self.s.scout(1).is_none_or(char::is_whitespace) =>
+            {
+                self.s.eat();
+                let error = self.error("the pipe operator is not available in math");
+                self.hint("to pipe values, use an embedded expression: `#(x |> f())`");
+                self.hint("to write the symbols, escape the bar: `\\|>`");
+                error
+            }
             '~' if self.s.eat_if("~>") => SyntaxKind::MathShorthand,
             '~' if self.s.eat_if('>') => SyntaxKind::MathShorthand,
             '*' | '-' | '~' => SyntaxKind::MathShorthand,
@@ -770,6 +782,8 @@ impl Lexer<'_> {
             '/' if self.s.eat_if('=') => SyntaxKind::SlashEq,
             '.' if self.s.eat_if('.') => SyntaxKind::Dots,
             '=' if self.s.eat_if('>') => SyntaxKind::Arrow,
+            '-' if self.s.eat_if('>') => SyntaxKind::ThinArrow,
+            '|' if self.s.eat_if('>') => SyntaxKind::Pipe,
 
             '{' => SyntaxKind::LeftBrace,
             '}' => SyntaxKind::RightBrace,
@@ -995,6 +1009,8 @@ fn keyword(ident: &str) -> Option<SyntaxKind> {
         "break" => SyntaxKind::Break,
         "continue" => SyntaxKind::Continue,
         "return" => SyntaxKind::Return,
+        // `try`, `catch`, and `match` are contextual and converted by the
+        // parser, so that they remain usable as identifiers.
         "import" => SyntaxKind::Import,
         "include" => SyntaxKind::Include,
         "as" => SyntaxKind::As,
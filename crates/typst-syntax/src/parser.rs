@@ -674,8 +674,11 @@ fn code_primary(p: &mut Parser, atomic: bool) {
     let m = p.marker();
     match p.current() {
         SyntaxKind::Ident => {
+            if contextual_keyword_expr(p) {
+                return;
+            }
             p.eat();
-            if !atomic && p.at(SyntaxKind::Arrow) {
+            if !atomic && !p.in_guard && p.at(SyntaxKind::Arrow) {
                 p.wrap(m, SyntaxKind::Params);
                 p.assert(SyntaxKind::Arrow);
                 code_expr(p);
@@ -684,7 +687,7 @@ fn code_primary(p: &mut Parser, atomic: bool) {
         }
         SyntaxKind::Underscore if !atomic => {
             p.eat();
-            if p.at(SyntaxKind::Arrow) {
+            if !p.in_guard && p.at(SyntaxKind::Arrow) {
                 p.wrap(m, SyntaxKind::Params);
                 p.eat();
                 code_expr(p);
@@ -785,6 +788,7 @@ fn let_binding(p: &mut Parser) {
     if p.eat_if(SyntaxKind::Ident) {
         if p.directly_at(SyntaxKind::LeftParen) {
             params(p);
+            return_type(p);
             closure = true;
         }
     } else {
@@ -898,6 +902,152 @@ fn for_loop(p: &mut Parser) {
     p.wrap(m, SyntaxKind::ForLoop);
 }
 
+/// Parses a `try` or `match` expression if the current identifier starts one.
+///
+/// These keywords are contextual such that existing variables of the same name
+/// keep working: `try` must directly be followed by a block and `match` by a
+/// scrutinee and a block.
+fn contextual_keyword_expr(p: &mut Parser) -> bool {
+    match p.current_text() {
+        "try" => {
+            let checkpoint = p.checkpoint();
+            p.eat();
+            let block = p.at(SyntaxKind::LeftBrace)
+                || (p.at(SyntaxKind::LeftBracket) && p.had_trivia());
+            p.restore(checkpoint);
+            if block {
+                try_catch(p);
+            }
+            block
+        }
+        "match" => match_expr(p),
+        _ => false,
+    }
+}
+
+/// Parses a match expression: `match x { 1 => y, _ => z }`. Backtracks and
+/// returns `false` if no block follows the scrutinee.
+fn match_expr(p: &mut Parser) -> bool {
+    let start = p.current_start();
+    if p.memo.no_match.contains(&start) {
+        return false;
+    }
+
+    let m = p.marker();
+    let checkpoint = p.checkpoint();
+    p.convert_and_eat(SyntaxKind::Match);
+    if p.had_trivia() && p.at_set(set::CODE_EXPR) {
+        code_expr(p);
+    }
+
+    if !p.at(SyntaxKind::LeftBrace) {
+        // Remember the failure to not parse the scrutinee again when it
+        // contains further identifiers named `match`.
+        p.restore(checkpoint);
+        p.memo.no_match.insert(start);
+        return false;
+    }
+
+    let m2 = p.marker();
+    p.enter_modes(SyntaxMode::Code, AtNewline::Continue, |p| {
+        p.assert(SyntaxKind::LeftBrace);
+        while !p.current().is_terminator() {
+            if !p.at_set(set::PATTERN.union(set::LITERAL_PATTERN)) {
+                p.unexpected();
+                continue;
+            }
+
+            let m3 = p.marker();
+            match_arm(p);
+
+            // Arms whose body is a block need no comma.
+            let block = p[m3].children().last().is_some_and(|c| c.kind().is_block());
+            if !p.current().is_terminator() && !block {
+                p.expect(SyntaxKind::Comma);
+            } else {
+                p.eat_if(SyntaxKind::Comma);
+            }
+        }
+        p.expect_closing_delimiter(m2, SyntaxKind::RightBrace);
+    });
+
+    p.wrap(m, SyntaxKind::MatchExpr);
+    true
+}
+
+/// Parses an arm of a match expression: `(a, b) if a < b => a`.
+fn match_arm(p: &mut Parser) {
+    let m = p.marker();
+    match_pattern(p);
+
+    // In the guard, the arrow introduces the body instead of a closure.
+    if p.eat_if(SyntaxKind::If) {
+        let in_guard = mem::replace(&mut p.in_guard, true);
+        code_expr(p);
+        p.in_guard = in_guard;
+    }
+
+    if p.expect(SyntaxKind::Arrow) {
+        code_expr(p);
+    }
+
+    p.wrap(m, SyntaxKind::MatchArm);
+}
+
+/// Parses the pattern of a match arm: a literal, a type pattern, or a binding
+/// pattern.
+fn match_pattern(p: &mut Parser) {
+    let m = p.marker();
+
+    if p.at_set(set::LITERAL_PATTERN) {
+        if p.eat_if(SyntaxKind::Minus) {
+            if p.at_set(syntax_set!(Int, Float, Numeric)) {
+                p.eat();
+            } else {
+                p.expected("number");
+            }
+            p.wrap(m, SyntaxKind::Unary);
+        } else {
+            p.eat();
+        }
+        return;
+    }
+
+    if p.at(SyntaxKind::Ident) || p.at(SyntaxKind::Underscore) {
+        let checkpoint = p.checkpoint();
+        p.eat();
+        if p.eat_if(SyntaxKind::Colon) {
+            code_expr_prec(p, true, 0);
+            p.wrap(m, SyntaxKind::TypePattern);
+            return;
+        }
+        p.restore(checkpoint);
+    }
+
+    pattern(p, false, &mut FxHashSet::default(), None);
+}
+
+/// Parses a try-catch expression: `try { x } catch err { y }`.
+fn try_catch(p: &mut Parser) {
+    let m = p.marker();
+    p.convert_and_eat(SyntaxKind::Try);
+    block(p);
+
+    // Like `else`, the `catch` may continue the expression on the next line.
+    let node = &p.token.node;
+    if node.kind() == SyntaxKind::Ident
+        && node.text() == "catch"
+        && (p.at(SyntaxKind::Ident) || p.nl_mode == AtNewline::ContextualContinue)
+    {
+        p.convert_and_eat(SyntaxKind::Catch);
+        p.eat_if(SyntaxKind::Ident);
+        block(p);
+    } else {
+        p.expected(SyntaxKind::Catch.name());
+    }
+    p.wrap(m, SyntaxKind::TryCatch);
+}
+
 /// Parses a module import: `import "utils.typ": a, b, c`.
 fn module_import(p: &mut Parser) {
     let m = p.marker();
@@ -1029,10 +1179,11 @@ fn expr_with_paren(p: &mut Parser, atomic: bool) {
     // again, we can then just restore this result. In this way, no
     // parenthesized expression is parsed more than twice, leading to a worst
     // case running time of O(2n).
-    if p.at(SyntaxKind::Arrow) {
+    if p.at_set(syntax_set!(Arrow, ThinArrow)) && !p.in_guard {
         p.restore(checkpoint);
         let m = p.marker();
         params(p);
+        return_type(p);
         if !p.expect(SyntaxKind::Arrow) {
             return;
         }
@@ -1279,6 +1430,13 @@ fn params(p: &mut Parser) {
     p.wrap(m, SyntaxKind::Params);
 }
 
+/// Parses a closure's optional return type annotation: `-> content`.
+fn return_type(p: &mut Parser) {
+    if p.eat_if(SyntaxKind::ThinArrow) {
+        code_expr_prec(p, true, 0);
+    }
+}
+
 /// Parses a single parameter in a parameter list.
 fn param<'s>(p: &mut Parser<'s>, seen: &mut FxHashSet<&'s str>, sink: &mut bool) {
     let m = p.marker();
@@ -1299,6 +1457,12 @@ fn param<'s>(p: &mut Parser<'s>, seen: &mut FxHashSet<&'s str>, sink: &mut bool)
     let was_at_pat = p.at_set(set::PATTERN);
     pattern(p, false, seen, Some("parameter"));
 
+    // Parses a type annotation: `x as int`.
+    let typed = p.eat_if(SyntaxKind::As);
+    if typed {
+        code_expr_prec(p, true, 0);
+    }
+
     // Parses a named parameter: `thickness: 12pt`.
     if p.eat_if(SyntaxKind::Colon) {
         // Recover from bad parameter name.
@@ -1308,6 +1472,8 @@ fn param<'s>(p: &mut Parser<'s>, seen: &mut FxHashSet<&'s str>, sink: &mut bool)
 
         code_expr(p);
         p.wrap(m, SyntaxKind::Named);
+    } else if typed {
+        p.wrap(m, SyntaxKind::TypedParam);
     }
 }
 
@@ -1510,6 +1676,9 @@ struct Parser<'s> {
     memo: MemoArena,
     /// The current expression nesting depth.
     depth: u32,
+    /// Whether we are in the guard of a match arm, where an arrow ends the
+    /// guard instead of starting a closure. Delimited groups reset this.
+    in_guard: bool,
 }
 
 /// A single token returned from the lexer with a cached [`SyntaxKind`] and a
@@ -1548,7 +1717,8 @@ enum AtNewline {
     Continue,
     /// Stop at any newline.
     Stop,
-    /// Continue only if there is a continuation with `else` or `.` (Code only).
+    /// Continue only if there is a continuation with `else`, `catch`, `.`, or
+    /// `|>` (Code only).
     ContextualContinue,
     /// Stop only at a parbreak, not normal newlines (Markup only).
     StopParBreak,
@@ -1566,7 +1736,7 @@ impl AtNewline {
             AtNewline::Continue => false,
             AtNewline::Stop => true,
             AtNewline::ContextualContinue => match kind {
-                SyntaxKind::Else | SyntaxKind::Dot => false,
+                SyntaxKind::Else | SyntaxKind::Dot | SyntaxKind::Pipe => false,
                 _ => true,
             },
             AtNewline::StopParBreak => parbreak,
@@ -1620,6 +1790,7 @@ impl<'s> Parser<'s> {
             nodes,
             memo: Default::default(),
             depth: 0,
+            in_guard: false,
         }
     }
 
@@ -1817,9 +1988,12 @@ impl<'s> Parser<'s> {
     /// This function effectively repurposes the call stack as a stack of modes.
     fn with_nl_mode(&mut self, mode: AtNewline, func: impl FnOnce(&mut Parser<'s>)) {
         let previous = self.nl_mode;
+        let in_guard = self.in_guard && mode != AtNewline::Continue;
+        let in_guard = mem::replace(&mut self.in_guard, in_guard);
         self.nl_mode = mode;
         func(self);
         self.nl_mode = previous;
+        self.in_guard = in_guard;
         if let Some(newline) = self.token.newline
             && mode != previous
         {
@@ -1886,6 +2060,9 @@ struct MemoArena {
     /// nodes in the arena and a checkpoint of the parser's state. These allow
     /// us to reset the parser to avoid parsing the same location again.
     memo_map: FxHashMap<MemoKey, (Range<usize>, PartialState)>,
+    /// Positions of `match` identifiers that turned out not to start a match
+    /// expression.
+    no_match: FxHashSet<MemoKey>,
 }
 
 /// A type alias for the memo key so it doesn't get confused with other usizes.
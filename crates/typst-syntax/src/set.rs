@@ -131,7 +131,7 @@ pub const UNARY_OP: SyntaxSet = syntax_set!(Plus, Minus, Not);
 /// Syntax kinds that are binary operators.
 pub const BINARY_OP: SyntaxSet = syntax_set!(
     Plus, Minus, Star, Slash, And, Or, EqEq, ExclEq, Lt, LtEq, Gt, GtEq, Eq, In, PlusEq,
-    HyphEq, StarEq, SlashEq,
+    HyphEq, StarEq, SlashEq, Pipe,
 );
 
 /// Syntax kinds that can start an argument in a function call.
@@ -153,6 +153,10 @@ pub const PATTERN: SyntaxSet =
 /// Syntax kinds that can start a pattern leaf.
 pub const PATTERN_LEAF: SyntaxSet = ATOMIC_CODE_EXPR;
 
+/// Syntax kinds that can start a literal pattern in a match arm.
+pub const LITERAL_PATTERN: SyntaxSet =
+    syntax_set!(None, Auto, Bool, Int, Float, Numeric, Str, Minus);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -39,16 +39,17 @@ pub use typst_syntax as syntax;
 #[doc(inline)]
 pub use typst_utils as utils;
 
-use std::sync::LazyLock;
+use std::sync::{Arc, LazyLock};
 
 use arrayvec::ArrayVec;
 use comemo::{Track, Tracked};
 use ecow::{EcoString, EcoVec, eco_format, eco_vec};
 use rustc_hash::FxHashSet;
+use typst_library::debug::Debugger;
 use typst_library::diag::{
     FileError, SourceDiagnostic, SourceResult, Warned, bail, warning,
 };
-use typst_library::engine::{Engine, Route, Sink, Traced};
+use typst_library::engine::{Cancellation, Cancelled, Engine, Fuel, Route, Sink, Traced};
 use typst_library::foundations::{
     NativeRuleMap, Output, StyleChain, Styles, Target, TargetElem, Value,
 };
@@ -72,12 +73,79 @@ use typst_utils::Protected;
 /// - `Err(errors)` if there were fatal errors.
 #[typst_macros::time]
 pub fn compile<T>(world: &dyn World) -> Warned<SourceResult<T>>
+where
+    T: Output,
+{
+    compile_with(world, &Cancellation::new())
+}
+
+/// Compiles sources into an output like [`compile`], but stops as soon as
+/// possible once the given token is cancelled.
+///
+/// Returns `Err(Cancelled)` if the compilation was cancelled before it
+/// finished. If it finished nonetheless, its output is returned. Results
+/// computed by a cancelled compilation are never reused by later ones.
+#[typst_macros::time]
+pub fn compile_cancellable<T>(
+    world: &dyn World,
+    cancellation: &Cancellation,
+) -> Result<Warned<SourceResult<T>>, Cancelled>
+where
+    T: Output,
+{
+    let warned = compile_with(world, cancellation);
+    if warned.output.is_err() && cancellation.is_cancelled() {
+        return Err(Cancelled);
+    }
+    Ok(warned)
+}
+
+/// Compiles sources into an output like [`compile`], with a debugger that
+/// observes evaluation and may pause it.
+///
+/// Memoized results are not reused while the debugger is attached, such that
+/// it observes all of evaluation.
+#[typst_macros::time]
+pub fn compile_debugged<T>(
+    world: &dyn World,
+    debugger: Arc<dyn Debugger>,
+) -> Warned<SourceResult<T>>
 where
     T: Output,
 {
     let mut sink = Sink::new();
-    let output = compile_impl::<T>(world.track(), Traced::default().track(), &mut sink)
-        .map_err(deduplicate);
+    let traced = Traced::debugged(debugger);
+    let cancellation = Cancellation::new();
+    let fuel = Fuel::new(world.library().limits.fuel);
+    let output = compile_impl::<T>(
+        world.track(),
+        traced.track(),
+        cancellation.track(),
+        fuel.track(),
+        &mut sink,
+    )
+    .map_err(deduplicate);
+    Warned { output, warnings: sink.warnings() }
+}
+
+/// The shared implementation of `compile` and `compile_cancellable`.
+fn compile_with<T>(
+    world: &dyn World,
+    cancellation: &Cancellation,
+) -> Warned<SourceResult<T>>
+where
+    T: Output,
+{
+    let mut sink = Sink::new();
+    let fuel = Fuel::new(world.library().limits.fuel);
+    let output = compile_impl::<T>(
+        world.track(),
+        Traced::default().track(),
+        cancellation.track(),
+        fuel.track(),
+        &mut sink,
+    )
+    .map_err(deduplicate);
     Warned { output, warnings: sink.warnings() }
 }
 
@@ -90,7 +158,16 @@ where
 {
     let mut sink = Sink::new();
     let traced = Traced::new(span);
-    compile_impl::<T>(world.track(), traced.track(), &mut sink).ok();
+    let cancellation = Cancellation::new();
+    let fuel = Fuel::new(world.library().limits.fuel);
+    compile_impl::<T>(
+        world.track(),
+        traced.track(),
+        cancellation.track(),
+        fuel.track(),
+        &mut sink,
+    )
+    .ok();
     sink.values()
 }
 
@@ -99,6 +176,8 @@ where
 fn compile_impl<T: Output>(
     world: Tracked<dyn World + '_>,
     traced: Tracked<Traced>,
+    cancellation: Tracked<Cancellation>,
+    fuel: Tracked<Fuel>,
     sink: &mut Sink,
 ) -> SourceResult<T> {
     let library = world.library();
@@ -124,6 +203,8 @@ fn compile_impl<T: Output>(
         world,
         library,
         traced,
+        cancellation,
+        fuel,
         sink.track_mut(),
         Route::default().track(),
         &main,
@@ -149,10 +230,13 @@ fn compile_impl<T: Output>(
             world,
             introspector: Protected::new(introspector.track_with(&constraint)),
             traced,
+            cancellation,
+            fuel,
             sink: subsink.track_mut(),
             route: Route::default(),
         };
 
+        engine.check_cancelled()?;
         document = T::create(&mut engine, &content, styles)?;
 
         if timed!("check stabilized", constraint.validate(document.introspector())) {
@@ -170,6 +254,7 @@ fn compile_impl<T: Output>(
 
             let warnings = typst_library::introspection::analyze(
                 world,
+                cancellation,
                 introspectors,
                 subsink.introspections(),
             );
@@ -323,3 +408,266 @@ static ROUTINES: LazyLock<Routines> = LazyLock::new(|| Routines {
     html_mathml_body: typst_html::html_mathml_body,
     html_span_filled: typst_html::html_span_filled,
 });
+
+#[cfg(test)]
+mod tests {
+    use typst_layout::PagedDocument;
+    use typst_library::diag::FileResult;
+    use typst_library::foundations::{Bytes, Datetime, Duration};
+    use typst_library::text::{Font, FontBook};
+    use typst_library::{Library, Limits};
+    use typst_syntax::Source;
+    use typst_utils::LazyHash;
+
+    use super::*;
+
+    /// A world with a single source file and no fonts. Reading any other file
+    /// cancels the world's token.
+    struct TestWorld {
+        library: LazyHash<Library>,
+        book: LazyHash<FontBook>,
+        main: Source,
+        cancellation: Cancellation,
+    }
+
+    impl TestWorld {
+        fn new(limits: Limits, text: &str) -> Self {
+            Self {
+                library: LazyHash::new(Library::builder().with_limits(limits).build()),
+                book: LazyHash::new(FontBook::new()),
+                main: Source::detached(text),
+                cancellation: Cancellation::new(),
+            }
+        }
+    }
+
+    impl World for TestWorld {
+        fn library(&self) -> &LazyHash<Library> {
+            &self.library
+        }
+
+        fn book(&self) -> &LazyHash<FontBook> {
+            &self.book
+        }
+
+        fn main(&self) -> FileId {
+            self.main.id()
+        }
+
+        fn source(&self, id: FileId) -> FileResult<Source> {
+            if id == self.main.id() {
+                Ok(self.main.clone())
+            } else {
+                Err(FileError::AccessDenied)
+            }
+        }
+
+        fn file(&self, id: FileId) -> FileResult<Bytes> {
+            if id == self.main.id() {
+                Ok(Bytes::from_string(self.main.clone()))
+            } else {
+                self.cancellation.cancel();
+                Ok(Bytes::new(Vec::<u8>::new()))
+            }
+        }
+
+        fn font(&self, _: usize) -> Option<Font> {
+            None
+        }
+
+        fn today(&self, _: Option<Duration>) -> Option<Datetime> {
+            None
+        }
+    }
+
+    #[track_caller]
+    fn test(limits: Limits, text: &str, error: Option<&str>) {
+        let world = TestWorld::new(limits, text);
+        let result = compile::<PagedDocument>(&world).output;
+        match (result, error) {
+            (Ok(_), None) => {}
+            (Ok(_), Some(expected)) => panic!("expected error: {expected}"),
+            (Err(errors), None) => panic!("unexpected errors: {errors:?}"),
+            (Err(errors), Some(expected)) => {
+                assert!(
+                    errors.iter().any(|error| error.message == expected),
+                    "{errors:?}"
+                )
+            }
+        }
+    }
+
+    #[test]
+    fn test_fuel_limit() {
+        let limits = Limits { fuel: Some(1000), ..Limits::default() };
+        let text = "#for i in range(1000) { let x = i }";
+        let error = "maximum number of evaluation steps exceeded (limit is 1000)";
+        test(limits.clone(), text, Some(error));
+
+        // Running out of fuel can't be caught.
+        test(
+            limits.clone(),
+            &format!("#try {{ {} }} catch err {{}}", &text[1..]),
+            Some(error),
+        );
+
+        // Neither in a match arm whose pattern destructures the value.
+        test(
+            limits.clone(),
+            &format!("#match (1, 2) {{ (a, b) => {{ {} }}, _ => none }}", &text[1..]),
+            Some(error),
+        );
+
+        // Each compilation has its own fuel and memoized results of one that
+        // ran out of it are not reused by others.
+        test(limits.clone(), text, Some(error));
+        test(Limits { fuel: Some(100_000), ..limits }, text, None);
+        test(Limits::default(), text, None);
+    }
+
+    #[test]
+    fn test_call_depth_limit() {
+        let limits = Limits { call_depth: 10, ..Limits::default() };
+        let error = "maximum function call depth exceeded";
+        test(limits.clone(), "#let f(n) = if n > 0 { f(n - 1) }\n#f(5)", None);
+        test(limits, "#let f(n) = if n > 0 { f(n - 1) }\n#f(20)", Some(error));
+    }
+
+    #[test]
+    fn test_size_limit() {
+        let limits = Limits { size: Some(10), ..Limits::default() };
+        test(limits.clone(), "#let s = \"a\" * 10", None);
+        test(
+            limits.clone(),
+            "#let s = \"a\" * 1000000000000",
+            Some("string is too large (1000000000000 bytes, but the maximum is 10)"),
+        );
+        test(
+            limits.clone(),
+            "#let a = (1, 2) * 6",
+            Some("array is too large (12 items, but the maximum is 10)"),
+        );
+        test(
+            limits.clone(),
+            "#let a = range(6)\n#(a += a)",
+            Some("array is too large (12 items, but the maximum is 10)"),
+        );
+        test(
+            limits.clone(),
+            "#let a = range(1000000000000)",
+            Some("array is too large (1000000000000 items, but the maximum is 10)"),
+        );
+        test(
+            limits.clone(),
+            "#for i in range(3) { (1, 2, 3, 4) }",
+            Some("array is too large (12 items, but the maximum is 10)"),
+        );
+
+        // Builders are charged while they grow, not after they are done.
+        test(
+            Limits { size: Some(1000), ..Limits::default() },
+            "#let s = (\"a\" * 1000).replace(\"a\", \"a\" * 1000)",
+            Some("string is too large (2000 bytes, but the maximum is 1000)"),
+        );
+        test(
+            limits.clone(),
+            "#let a = (range(6), range(6)).flatten()",
+            Some("array is too large (11 items, but the maximum is 10)"),
+        );
+        test(
+            limits.clone(),
+            "#let a = range(6).intersperse(0)",
+            Some("array is too large (11 items, but the maximum is 10)"),
+        );
+        test(
+            limits.clone(),
+            "#let a = ((1, 2, 3, 4),) * 3\n#let b = a.join()",
+            Some("array is too large (12 items, but the maximum is 10)"),
+        );
+        test(
+            limits,
+            "#let a = range(10)\n#a.push(10)",
+            Some("array is too large (11 items, but the maximum is 10)"),
+        );
+    }
+
+    #[test]
+    fn test_page_limit() {
+        let limits = Limits { pages: Some(3), ..Limits::default() };
+        let error = "document has more than the maximum of 3 pages";
+        let page = "#set page(height: 10pt, margin: 0pt)\n";
+        test(
+            limits.clone(),
+            &format!("{page}#for i in range(3) {{ block(height: 8pt) }}"),
+            None,
+        );
+
+        // Pages from separate page runs add up.
+        test(limits.clone(), "#for i in range(3) { pagebreak() }", Some(error));
+
+        // Within a single page run, layout stops as soon as the budget is
+        // exhausted and points at the content that didn't fit.
+        let world = TestWorld::new(
+            limits,
+            &format!("{page}#for i in range(1000) {{ block(height: 8pt) }}"),
+        );
+        let errors = compile::<PagedDocument>(&world).output.unwrap_err();
+        assert!(
+            errors
+                .iter()
+                .any(|diag| diag.message == error && !diag.span.is_detached()),
+            "{errors:?}"
+        );
+    }
+
+    #[test]
+    fn test_plugin_fuel_limit() {
+        // A plugin with a function `spin` that never returns and a function
+        // `zero` that returns right away.
+        let plugin = "#let p = plugin(bytes((\
+            0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, \
+            0x01, 0x05, 0x01, 0x60, 0x00, 0x01, 0x7f, \
+            0x03, 0x03, 0x02, 0x00, 0x00, \
+            0x05, 0x03, 0x01, 0x00, 0x01, \
+            0x07, 0x18, 0x03, 0x04, 0x73, 0x70, 0x69, 0x6e, 0x00, 0x00, 0x04, 0x7a, \
+            0x65, 0x72, 0x6f, 0x00, 0x01, 0x06, 0x6d, 0x65, 0x6d, 0x6f, 0x72, 0x79, \
+            0x02, 0x00, \
+            0x0a, 0x10, 0x02, 0x09, 0x00, 0x03, 0x40, 0x0c, 0x00, 0x0b, 0x41, 0x00, \
+            0x0b, 0x04, 0x00, 0x41, 0x00, 0x0b,\
+        )))\n";
+        let limits = Limits { plugin_fuel: Some(1000), ..Limits::default() };
+        let error = "plugin function `spin` ran out of fuel (limit is 1000)";
+        test(limits.clone(), &format!("{plugin}#p.zero()"), None);
+        test(limits.clone(), &format!("{plugin}#p.spin()"), Some(error));
+
+        // Running out of fuel can't be caught.
+        test(limits, &format!("{plugin}#try {{ p.spin() }} catch err {{}}"), Some(error));
+    }
+
+    #[track_caller]
+    fn test_cancelled(text: &str) {
+        let world = TestWorld::new(Limits::default(), text);
+        let result = compile_cancellable::<PagedDocument>(&world, &world.cancellation);
+        assert!(matches!(result, Err(Cancelled)));
+
+        // The results of the cancelled compilation are not reused.
+        let fresh = TestWorld { cancellation: Cancellation::new(), ..world };
+        let result = compile_cancellable::<PagedDocument>(&fresh, &Cancellation::new());
+        assert!(matches!(result, Ok(Warned { output: Ok(_), .. })));
+    }
+
+    #[test]
+    fn test_cancel_during_eval() {
+        test_cancelled(
+            "#let data = read(\"cancel.txt\")\n#for i in range(100) { let x = i }",
+        );
+    }
+
+    #[test]
+    fn test_cancel_during_layout() {
+        test_cancelled(
+            "#block(context { let data = read(\"cancel.txt\") })\n\
+             #for i in range(100) { block(height: 1pt) }",
+        );
+    }
+}
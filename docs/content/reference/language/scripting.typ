@@ -175,6 +175,59 @@ The body of a loop can be a code or content block:
 - `{while condition {..}}`
 - `{while condition [..]}`
 
+= Matching <matching>
+A `{match}` expression compares a value against a list of _arms_ and evaluates the body of the first arm whose pattern fits. Each arm has the form `{pattern => body}` and arms are separated by commas. The comma can be omitted after an arm whose body is a block.
+
+```example
+#let describe(value) = match value {
+  0 => [zero],
+  -1 => [minus one],
+  n: int if n > 0 => [positive (#n)],
+  s: str => [text "#s"],
+  (x, y) => [a pair of #x and #y],
+  (name: n, ..) => [something named #n],
+  _ => [something else],
+}
+
+#describe(0) \
+#describe(-1) \
+#describe(5) \
+#describe("hi") \
+#describe((1, 2)) \
+#describe((name: "Typst", age: 12)) \
+#describe(1.5)
+```
+
+The following patterns are supported:
+
+- A literal like `{1}`, `{-2.5}`, `{"text"}`, `{true}`, `{none}`, or `{auto}` matches values that are equal to it.
+- A type pattern like `{x: int}` matches values of the given @type[type] and binds them to `x`. Instead of a type, an @function:element-functions[element function] can be given to match content of that element, for example `{it: heading => ..}`. Use `{_: int}` to match without binding.
+- A name like `{x}` matches any value and binds it.
+- The destructuring patterns described in @reference:scripting:bindings[Let binding] match arrays and dictionaries of the right shape and bind their parts.
+- The underscore `{_}` matches anything.
+
+An arm can have a _guard_ after its pattern: `{pattern if condition => body}`. The arm is only taken if the condition evaluates to `{true}`. Names bound by the pattern are available in the guard and in the body.
+
+If no arm matches, the `{match}` expression fails with an error. When all arms match on `{true}` and `{false}` or on `{none}` and `{auto}`, Typst also warns about cases that are not covered. End a `{match}` with a `{_ => ..}` arm to handle every remaining value.
+
+The word `match` only starts a `{match}` expression when a value and a block follow it. Elsewhere, it remains an ordinary identifier, so existing variables named `match` keep working. The same applies to `try` and `catch`, which are only keywords around the blocks of a `{try}` expression.
+
+= Error handling <error-handling>
+Errors raised while evaluating code, for example by a failing @read[`read`] or a call to @panic[`panic`], normally abort the compilation. With a `{try}` expression, you can recover from such an error instead. When the `{try}` body fails, the `{catch}` body is evaluated in its place. The error is bound to the name following `{catch}` as a dictionary with the keys `message` and `hints`.
+
+```example
+#let parse(text) = try {
+  int(text)
+} catch err {
+  [Not a number (#err.message)]
+}
+
+#parse("12") \
+#parse("twelve")
+```
+
+The name can also be omitted: `{try {..} catch {..}}`. Only errors that occur while the body is evaluated are caught. Errors that only arise when the resulting content is laid out, as well as errors that signal a compiler limit (like exceeding the maximum function call depth), cannot be caught.
+
 = Fields <fields>
 You can use _dot notation_ to access fields on a value. For values of type @content, you can also use the @content.fields[`fields`] function to list the fields.
 
@@ -204,7 +257,7 @@ A _method call_ is a convenient way to call a function that is scoped to a value
 #"abc".len()
 ```
 
-The structure of a method call is `{value.method(..args)}` and its equivalent full function call is `{type(value).method(value, ..args)}`. The documentation of each type lists its scoped functions. You cannot currently define your own methods.
+The structure of a method call is `{value.method(..args)}` and its equivalent full function call is `{type(value).method(value, ..args)}`. The documentation of each type lists its scoped functions. You can define your own methods on @type:custom-types[custom types].
 
 ```example
 #let values = (1, 2, 3, 4)
@@ -262,31 +315,36 @@ The following table lists all available unary and binary operators with effect,
   [`{-}`],
   [Negation],
   [Unary],
-  [7],
+  [8],
 
   [`{+}`],
   [No effect (exists for symmetry)],
   [Unary],
-  [7],
+  [8],
 
   [`{*}`],
   [Multiplication],
   [Binary],
-  [6],
+  [7],
 
   [`{/}`],
   [Division],
   [Binary],
-  [6],
+  [7],
 
   [`{+}`],
   [Addition],
   [Binary],
-  [5],
+  [6],
 
   [`{-}`],
   [Subtraction],
   [Binary],
+  [6],
+
+  [`{|>}`],
+  [Pipe into function call],
+  [Binary],
   [5],
 
   [`{==}`],
@@ -369,3 +427,19 @@ The following table lists all available unary and binary operators with effect,
   [Binary],
   [1],
 )
+
+The pipe operator `{|>}` passes its left-hand side as the first positional argument to the function call on its right-hand side. If the right-hand side is a plain function instead of a call, that function is called with just the piped value. This makes it possible to write nested calls as a sequence of steps, which may also be continued on the next line:
+
+```example
+#let name = " Typst "
+#(name |> str.trim() |> upper())
+
+#{
+  (3, 1, 2)
+    |> array.sorted()
+    |> array.map(str)
+    |> array.join(", ")
+}
+```
+
+Like the other operators, the pipe has a single precedence level, as listed above. Arithmetic on either of its sides is thus evaluated first: `{1 + 2 |> str()}` pipes `{3}` into `str` and `{x |> f() + 1}` pipes into the sum `{f() + 1}`. To continue with the result of a call, wrap the pipe in parentheses. In math mode, the pipe is only available within embedded expressions like `{#(x |> f())}`.
@@ -343,6 +343,14 @@ Within code blocks and expressions, new expressions can start without a leading
   [`{break, continue}`],
   [@reference:scripting:loops[Scripting]],
 
+  [Matching],
+  [`{match x { 1 => .., _ => .. }}`],
+  [@reference:scripting:matching[Scripting]],
+
+  [Error handling],
+  [`{try {..} catch err {..}}`],
+  [@reference:scripting:error-handling[Scripting]],
+
   [Return from function],
   [`{return x}`],
   [@function[Function]],
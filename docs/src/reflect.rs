@@ -95,7 +95,7 @@ fn describe_ty(ty: &Type) -> Dict {
         "long-name" => ty.long_name(),
         "title" => ty.title(),
         "docs" => ty.docs(),
-        "def-site" => describe_def_site(ty.def_site()),
+        "def-site" => ty.def_site().map(describe_def_site),
         "keywords" => ty.keywords(),
         "constructor" => ty.constructor().ok(),
         "scope" => Module::anonymous(ty.scope().clone()),
@@ -143,7 +143,7 @@ fn describe_cast_info(info: &CastInfo) -> Dict {
         },
         CastInfo::Type(ty) => dict! {
             "kind" => "type",
-            "ty" => *ty,
+            "ty" => ty.clone(),
         },
         CastInfo::Union(infos) => dict! {
             "kind" => "union",
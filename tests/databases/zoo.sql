@@ -0,0 +1,18 @@
+-- Creates `zoo.db`: sqlite3 zoo.db < zoo.sql
+PRAGMA page_size = 512;
+
+CREATE TABLE animals (
+  name TEXT PRIMARY KEY,
+  legs INTEGER NOT NULL,
+  weight REAL,
+  born TEXT,
+  photo BLOB
+);
+
+INSERT INTO animals VALUES
+  ('cat', 4, 4.5, '2019-03-14', x'CAFE'),
+  ('parrot', 2, 0.4, '2011-08-02', NULL),
+  ('snake', 0, 2.25, NULL, NULL),
+  ('spider', 8, NULL, '2024-06-30', x'');
+
+VACUUM;
@@ -0,0 +1,29 @@
+;; A plugin that reads files.
+;;
+;; Compile with `wat2wasm files.wat -o files.wasm`.
+(module
+  (import "typst_env" "wasm_minimal_protocol_write_args_to_buffer"
+    (func $write_args (param i32)))
+  (import "typst_env" "wasm_minimal_protocol_send_result_to_host"
+    (func $send_result (param i32 i32)))
+  (import "typst_env" "wasm_minimal_protocol_read_file"
+    (func $read_file (param i32 i32) (result i32)))
+  (import "typst_env" "wasm_minimal_protocol_write_file_to_buffer"
+    (func $write_file (param i32)))
+
+  (memory (export "memory") 1)
+
+  (data (i32.const 1024) "failed to read file")
+
+  ;; Takes a path and returns the contents of the file at that path.
+  (func (export "read") (param i32) (result i32)
+    (local i32)
+    (call $write_args (i32.const 4096))
+    (local.set 1 (call $read_file (i32.const 4096) (local.get 0)))
+    (if (i32.lt_s (local.get 1) (i32.const 0))
+      (then
+        (call $send_result (i32.const 1024) (i32.const 19))
+        (return (i32.const 1))))
+    (call $write_file (i32.const 8192))
+    (call $send_result (i32.const 8192) (local.get 1))
+    (i32.const 0)))
@@ -0,0 +1,57 @@
+;; A plugin that exchanges Typst values instead of bytes.
+;;
+;; Compile with `wat2wasm values.wat -o values.wasm`.
+(module
+  (import "typst_env" "wasm_minimal_protocol_write_args_to_buffer"
+    (func $write_args (param i32)))
+  (import "typst_env" "wasm_minimal_protocol_send_result_to_host"
+    (func $send_result (param i32 i32)))
+
+  (memory (export "memory") 1)
+
+  ;; The metadata, as CBOR:
+  ;; {
+  ;;   "abi": "values",
+  ;;   "functions": {
+  ;;     "echo": {
+  ;;       "docs": "Returns its argument.",
+  ;;       "params": [
+  ;;         {"name": "value", "docs": "The value to return."},
+  ;;         {"name": "extra", "named": true, "default": 0},
+  ;;       ],
+  ;;     },
+  ;;     "mismatch": {"params": [{"name": "value"}]},
+  ;;   },
+  ;; }
+  (data (i32.const 256)
+    "\a2cabifvaluesifunctions\a2decho\a2ddocsuReturns its argument.fparams\82"
+    "\a2dnameevalueddocstThe value to return.\a3dnameeextraenamed\f5gdefault\00"
+    "hmismatch\a1fparams\81\a1dnameevalue")
+
+  ;; The markup `*hi*`, as a CBOR string with tag 29817.
+  (data (i32.const 1024) "\d9tyd*hi*")
+
+  ;; The error {"message": "oops", "hints": ["try again"]}, as CBOR.
+  (data (i32.const 1280) "\a2gmessagedoopsehints\81itry again")
+
+  (func (export "typst_plugin_metadata") (result i32)
+    (call $send_result (i32.const 256) (i32.const 160))
+    (i32.const 0))
+
+  ;; Returns the first argument unchanged.
+  (func (export "echo") (param i32 i32) (result i32)
+    (call $write_args (i32.const 4096))
+    (call $send_result (i32.const 4096) (local.get 0))
+    (i32.const 0))
+
+  (func (export "markup") (result i32)
+    (call $send_result (i32.const 1024) (i32.const 8))
+    (i32.const 0))
+
+  (func (export "fail") (result i32)
+    (call $send_result (i32.const 1280) (i32.const 31))
+    (i32.const 1))
+
+  ;; Takes one more argument than its signature declares.
+  (func (export "mismatch") (param i32 i32) (result i32)
+    (i32.const 0)))
@@ -134,3 +134,133 @@
 --- content-try-to-access-internal-field eval ---
 // Error: 9-15 hide does not have field "hidden"
 #hide[].hidden
+
+--- content-define eval ---
+#let callout = content.define(
+  "callout",
+  it => it.body,
+  fields: (
+    body: (type: content, positional: true),
+    kind: (type: str, default: "note"),
+  ),
+)
+
+#let c = callout(kind: "warning")[Hi]
+#test(c.func(), callout)
+#test(c.body, [Hi])
+#test(c.kind, "warning")
+#test(c.has("kind"), true)
+#test(callout[Hi].has("kind"), false)
+#test(c.fields(), (body: [Hi], kind: "warning"))
+#test(repr(c), "callout(body: [Hi], kind: \"warning\")")
+#test(c, callout([Hi], kind: "warning"))
+#test(c == callout[Hi], false)
+
+--- content-define-identity eval ---
+#let make() = content.define("tag", it => none)
+#test(make(), make())
+#test(make() == content.define("tag", it => [other]), false)
+
+--- content-define-display paged empty ---
+#let note = content.define(
+  "note",
+  it => test((it.kind, it.location() != none), ("info", true)),
+  fields: (kind: (type: str, default: "info")),
+)
+#note()
+
+--- content-define-set-show paged empty ---
+#let callout = content.define(
+  "callout",
+  it => panic("should be overridden"),
+  fields: (
+    body: (type: content, positional: true),
+    kind: (type: str, default: "note"),
+  ),
+)
+#set callout(kind: "warning")
+#show callout: it => test((it.body, it.kind), ([Hi], "warning"))
+#callout[Hi]
+
+--- content-define-set-distinct paged empty ---
+#let a = content.define("note", it => none, fields: (kind: (default: "a")))
+#let b = content.define("note", it => [b], fields: (kind: (default: "b")))
+#set a(kind: "set")
+#context test((a.kind, b.kind), ("set", "b"))
+#show b: it => test(it.func(), b)
+#b()
+
+--- content-define-show-where paged empty ---
+#let callout = content.define(
+  "callout",
+  it => none,
+  fields: (kind: (type: str, default: "note")),
+)
+#show callout.where(kind: "warning"): it => test(it.kind, "warning")
+#callout(kind: "warning")
+#callout()
+
+--- content-define-context-field paged empty ---
+#let note = content.define(
+  "note",
+  it => none,
+  fields: (kind: (type: str, default: "info")),
+)
+#context test(note.kind, "info")
+#set note(kind: "tip")
+#context test(note.kind, "tip")
+
+--- content-define-query paged empty ---
+#let marker = content.define("marker", it => none, fields: (id: int))
+#marker(id: 1)
+#marker(id: 2)
+#context test(query(marker).map(it => it.id), (1, 2))
+#context test(query(marker.where(id: 2)).len(), 1)
+
+--- content-define-missing-field eval ---
+#let callout = content.define(
+  "callout",
+  it => it.body,
+  fields: (body: (type: content, positional: true)),
+)
+// Error: 2-11 missing argument: body
+#callout()
+
+--- content-define-wrong-type eval ---
+#let note = content.define("note", it => none, fields: (kind: str))
+// Error: 13-14 expected string for field `kind`, found integer
+#note(kind: 1)
+
+--- content-define-set-wrong-type eval ---
+#let note = content.define(
+  "note",
+  it => none,
+  fields: (kind: (type: str, default: "info")),
+)
+// Error: 17-18 expected string for field `kind`, found integer
+#set note(kind: 1)
+
+--- content-define-set-required eval ---
+#let callout = content.define(
+  "callout",
+  it => it.body,
+  fields: (body: (type: content, positional: true)),
+)
+// Error: 14-17 unexpected argument
+#set callout([A])
+
+--- content-define-empty-name eval ---
+// Error: 2-32 element name must not be empty
+#content.define("", it => none)
+
+--- content-define-invalid-field eval ---
+// Error: 2-49 expected type or dictionary for field `a`, found integer
+#content.define("x", it => none, fields: (a: 1))
+
+--- content-define-invalid-positional eval ---
+// Error: 2-63 expected boolean for `positional` of field `a`, found integer
+#content.define("x", it => none, fields: (a: (positional: 1)))
+
+--- content-define-reserved-field eval ---
+// Error: 2-55 field name `label` is reserved
+#content.define("x", it => none, fields: (label: str))
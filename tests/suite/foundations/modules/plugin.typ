@@ -0,0 +1,3 @@
+// SKIP
+// A module that loads a plugin with access to a file relative to itself.
+#let p = plugin("/tests/plugins/files.wasm", files: ("../../../plugins/files.wat",))
@@ -76,3 +76,88 @@
 
 // Error: 2-27 plugin tried to write out of bounds: pointer 0x40000000 is out of bounds for write of length 3
 #p.write_oob(bytes("xyz"))
+
+--- plugin-values eval ---
+#let p = plugin("/tests/plugins/values.wasm")
+#test(p.echo((a: 1, b: (2, "x"))), (a: 1, b: (2, "x")))
+#test(p.echo(5, extra: 3), 5)
+#test(p.echo(none), none)
+
+--- plugin-values-import eval ---
+#import plugin("/tests/plugins/values.wasm"): echo
+#test(echo("hi"), "hi")
+
+--- plugin-values-markup eval ---
+#let p = plugin("/tests/plugins/values.wasm")
+#test(p.markup(), strong[hi])
+
+--- plugin-values-missing-argument eval ---
+#let p = plugin("/tests/plugins/values.wasm")
+
+// Error: 2-10 missing argument: value
+#p.echo()
+
+--- plugin-values-unexpected-argument eval ---
+#let p = plugin("/tests/plugins/values.wasm")
+
+// Error: 12-13 unexpected argument
+#p.echo(1, 2)
+
+--- plugin-values-signature-mismatch eval ---
+#let p = plugin("/tests/plugins/values.wasm")
+
+// Error: 2-15 plugin function takes 2 arguments, but 1 was given
+#p.mismatch(1)
+
+--- plugin-values-error eval ---
+#let p = plugin("/tests/plugins/values.wasm")
+
+// Error: 2-10 plugin errored with: oops
+// Hint: 2-10 try again
+#p.fail()
+
+--- plugin-files eval ---
+#let p = plugin("/tests/plugins/files.wasm", files: ("/tests/plugins/files.wat",))
+#test(
+  p.read(bytes("/tests/plugins/files.wat")),
+  read("/tests/plugins/files.wat", encoding: none),
+)
+
+--- plugin-files-relative eval ---
+#let p = plugin("/tests/plugins/files.wasm", files: ("../../plugins/files.wat",))
+#test(
+  p.read(bytes("../../plugins/files.wat")),
+  read("/tests/plugins/files.wat", encoding: none),
+)
+
+--- plugin-files-module eval ---
+// Grants are resolved relative to the file that loads the plugin.
+#import "modules/plugin.typ": p
+#test(
+  p.read(bytes("../../../plugins/files.wat")),
+  read("/tests/plugins/files.wat", encoding: none),
+)
+
+--- plugin-files-not-granted eval ---
+#let p = plugin("/tests/plugins/files.wasm", files: ("/tests/plugins/files.wat",))
+
+// Error: 2-44 plugin tried to read `/tests/plugins/values.wat`, but was not granted access to it
+#p.read(bytes("/tests/plugins/values.wat"))
+
+--- plugin-files-none-granted eval ---
+#let p = plugin("/tests/plugins/files.wasm")
+
+// Error: 2-43 plugin tried to read `/tests/plugins/files.wat`, but was not granted access to it
+#p.read(bytes("/tests/plugins/files.wat"))
+
+--- plugin-files-outside-root eval ---
+// Error: 53-81 path `"../../../../outside.txt"` would escape the project root
+// Hint: 53-81 cannot access files outside of the project sandbox
+// Hint: 53-81 you can adjust the project root with the `--root` argument
+#let p = plugin("/tests/plugins/files.wasm", files: ("../../../../outside.txt",))
+
+--- plugin-files-missing eval ---
+#let p = plugin("/tests/plugins/files.wasm", files: ("missing.txt",))
+
+// Error: 2-30 plugin errored with: failed to read file
+#p.read(bytes("missing.txt"))
@@ -21,3 +21,130 @@
 #test(repr(auto), "auto")
 #test(repr(type(none)), "type(none)")
 #test(repr(type(auto)), "type(auto)")
+
+--- type-define eval ---
+#let point = type.define(
+  "point",
+  fields: (x: int, y: (type: int, default: 0)),
+  methods: (
+    sum: self => self.x + self.y,
+    shift: (self, d) => type(self)(x: self.x + d, y: self.y + d),
+  ),
+)
+#let p = point(x: 1, y: 2)
+#test(type(p), point)
+#test(type(p) == dictionary, false)
+#test(p.x, 1)
+#test(point(x: 5).y, 0)
+#test(p.sum(), 3)
+#test(point.sum(p), 3)
+#test(p.shift(1), point(x: 2, y: 3))
+#test(p == point(x: 1, y: 2), true)
+#test(p == point(x: 1, y: 3), false)
+#test(repr(p), "point(x: 1, y: 2)")
+#test(repr(point), "point")
+
+--- type-define-cast eval ---
+// Fields are converted like the arguments of built-in functions.
+#let point = type.define(
+  "point",
+  fields: (
+    x: float,
+    y: (type: float, default: 0),
+    body: (type: content, default: none),
+  ),
+)
+#let p = point(x: 3)
+#test(type(p.x), float)
+#test(type(p.y), float)
+#test(type(p.body), content)
+#test(point(x: 1, body: "a").body, [a])
+#{
+  let q = point(x: 1)
+  q.x = 2
+  test(type(q.x), float)
+}
+
+--- type-define-untyped-field eval ---
+#let holder = type.define("holder", fields: (value: (default: none)))
+#test(holder().value, none)
+#test(holder(value: "a").value, "a")
+#test(holder(value: 1).value, 1)
+
+--- type-define-identity eval ---
+#let make() = type.define("pair", fields: (a: int))
+#test(make(), make())
+#test(make()(a: 1), make()(a: 1))
+#let a = type.define("thing")
+#let b = type.define("thing")
+#test(a == b, false)
+#test(a() == b(), false)
+
+--- type-define-match eval ---
+#let point = type.define("point", fields: (x: int, y: int))
+#let describe(v) = match v {
+  p: point if p.x == 0 => "on axis",
+  _: point => "point",
+  _ => "other",
+}
+#test(describe(point(x: 0, y: 1)), "on axis")
+#test(describe(point(x: 1, y: 1)), "point")
+#test(describe((x: 1, y: 1)), "other")
+
+--- type-define-mutate eval ---
+#let tally = type.define("tally", fields: (n: int))
+#{
+  let s = tally(n: 1)
+  s.n += 1
+  test(s.n, 2)
+  s.n = 5
+  test(s.n, 5)
+}
+
+--- type-define-mutate-wrong-type eval ---
+#let tally = type.define("tally", fields: (n: int))
+#{
+  let s = tally(n: 1)
+  // Error: 3-12 expected integer for field `n`, found string
+  s.n = "a"
+}
+
+--- type-define-mutate-missing-field eval ---
+#let tally = type.define("tally", fields: (n: int))
+#{
+  let s = tally(n: 1)
+  // Error: 3-10 type tally does not have field `m`
+  s.m = 1
+}
+
+--- type-define-missing-field eval ---
+#let point = type.define("point", fields: (x: int))
+// Error: 2-9 missing argument: x
+#point()
+
+--- type-define-wrong-type eval ---
+#let point = type.define("point", fields: (x: int))
+// Error: 11-14 expected integer for field `x`, found string
+#point(x: "a")
+
+--- type-define-unexpected-field eval ---
+#let point = type.define("point", fields: (x: int))
+// Error: 14-18 unexpected argument: y
+#point(x: 1, y: 2)
+
+--- type-define-unknown-field eval ---
+#let point = type.define("point", fields: (x: int))
+// Error: 14-15 type point does not have field `y`
+#point(x: 1).y
+
+--- type-define-invalid-field eval ---
+// Error: 2-34 expected type or dictionary for field `a`, found integer
+#type.define("x", fields: (a: 1))
+
+--- type-define-invalid-default eval ---
+// Error: 2-59 expected integer for field `a`, found string
+#type.define("x", fields: (a: (type: int, default: "no")))
+
+--- type-define-invalid-method eval ---
+// Error: 2-35 method `m` must be a function, found integer
+#type.define("x", methods: (m: 1))
@@ -67,8 +67,8 @@ Lריווח #h(1cm) R
 
 --- bidi-vertical eval ---
 // Test setting a vertical direction.
-// Error: 16-19 text direction must be horizontal
-#set text(dir: ttb)
+// Error: 16-19 text direction must be horizontal or top-to-bottom
+#set text(dir: btt)
 
 --- issue-1373-bidi-tofus paged ---
 // Test that shaping missing characters in both left-to-right and
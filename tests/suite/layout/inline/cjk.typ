@@ -110,3 +110,37 @@ abc字\
 时间#footnote[有空白]
 
 时间#sub[123]#super[时间]B
+
+--- text-vertical-basic paged ---
+#set page(width: 120pt, height: 100pt)
+#set text(lang: "ja", font: "Noto Serif CJK SC", dir: ttb)
+吾輩は猫である。名前はまだ無い。どこで生れたかとんと見当がつかぬ。
+
+--- text-vertical-orientation paged ---
+#set page(width: 80pt, height: 100pt)
+#set text(lang: "ja", font: ("Libertinus Serif", "Noto Serif CJK SC"), dir: ttb)
+東京（Tokyo）
+
+#set text(orientation: "upright")
+東京（Tokyo）
+
+--- text-vertical-combine-digits paged ---
+#set page(width: 80pt, height: 100pt)
+#set text(lang: "ja", font: "Noto Serif CJK SC", dir: ttb)
+平成31年4月2019年
+
+#set text(combine-digits: 4)
+平成31年4月2019年
+
+--- text-vertical-terms-inset paged empty ---
+// Insets of lists in vertical text are applied at the left.
+#set text(dir: ttb)
+#context test(
+  measure(terms(
+    indent: 10pt,
+    hanging-indent: 0pt,
+    separator: none,
+    terms.item([], block(width: 5pt, height: 5pt)),
+  )),
+  (width: 15pt, height: 5pt),
+)
@@ -259,6 +259,30 @@ int main() {
 #set par(justify: true)
 #block(width: 1cm, fill: aqua, lorem(2))
 
+--- justify-kashida paged ---
+#set page(width: 160pt)
+#set text(lang: "ar", font: ("Noto Sans Arabic", "Libertinus Serif"))
+#set par(justify: "kashida")
+لا يمكن أن يكون هناك سلام دائم في العالم بدون عدالة اجتماعية
+
+--- justify-kashida-single-point paged ---
+// A line with a single joint is only elongated up to a limit and the rest is
+// distributed between the words.
+#set page(width: 160pt)
+#set text(lang: "ar", font: ("Noto Sans Arabic", "Libertinus Serif"))
+#set par(justify: "kashida")
+في #lorem(20)
+
+--- justify-kashida-latin paged ---
+// Lines without kashida points are justified with spacing.
+#set page(width: 120pt)
+#set par(justify: "kashida")
+#lorem(20)
+
+--- justify-kashida-invalid eval ---
+// Error: 19-28 expected boolean or "kashida"
+#set par(justify: "tatweel")
+
 --- issue-2419-justify-hanging-indent paged ---
 // Test that combination of justification and hanging indent doesn't result in
 // an underfull first line.
@@ -137,6 +137,26 @@
 // Error: 20-23 must be `left` or `right`
 #set page(binding: top)
 
+--- page-baseline-grid paged ---
+#set page(height: 120pt, columns: 2, baseline-grid: 10pt)
+#set par(leading: 4pt, spacing: 4pt)
+#set text(7pt)
+
+= Heading
+#lorem(6)
+
+#text(5pt, lorem(7))
+
+#colbreak()
+#lorem(12)
+
+--- page-baseline-grid-float paged ---
+#set page(height: 100pt, baseline-grid: 10pt)
+#set par(leading: 4pt)
+#set text(7pt)
+#place(top, float: true, rect(height: 13pt, width: 100%, fill: aqua))
+#lorem(16)
+
 --- page-marginals paged ---
 #set page(
   paper: "a8",
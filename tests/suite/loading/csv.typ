@@ -33,3 +33,86 @@
 --- csv-invalid-delimiter eval ---
 // Error: 41-51 delimiter must be an ASCII character
 #csv("/assets/data/zoo.csv", delimiter: "\u{2008}")
+
+--- csv-types-auto eval ---
+#let data = csv(bytes("a,1,2.5,true,\n-3,1e3,x,False,nan"), types: auto)
+#test(data, (("a", 1, 2.5, true, ""), (-3, 1000.0, "x", false, "nan")))
+
+--- csv-types-positional eval ---
+#let data = csv(bytes("1,2,3\n4,,6"), types: (int, float))
+#test(data, ((1, 2.0, "3"), (4, none, "6")))
+
+--- csv-types-named eval ---
+#let data = csv(
+  bytes("name,date,time,at\nAda,1815-12-10,09:30,2024-01-02T03:04:05"),
+  row-type: dictionary,
+  types: (date: datetime, time: datetime, at: datetime),
+)
+#let row = data.first()
+#test(row.name, "Ada")
+#test(row.date, datetime(year: 1815, month: 12, day: 10))
+#test(row.time, datetime(hour: 9, minute: 30, second: 0))
+#test(row.at, datetime(year: 2024, month: 1, day: 2, hour: 3, minute: 4, second: 5))
+
+--- csv-types-invalid-field eval ---
+// Error: 6-19 failed to parse CSV (expected integer, found "x" in column 1 at 2:1)
+#csv(bytes("1\nx"), types: (int,))
+
+--- csv-types-named-without-dict-rows eval ---
+// Error: 6-19 a dictionary of types requires dictionary rows
+// Hint: 6-19 try setting `row-type` to `dictionary`
+#csv(bytes("a\n1"), types: (a: int))
+
+--- csv-types-invalid-type eval ---
+// Error: 25-37 expected `str`, `int`, `float`, `bool`, `datetime`, or `auto`
+#csv(bytes("a"), types: (int, color))
+
+--- csv-comment eval ---
+#let data = csv(bytes("# header\na,b\n# skipped\nc,d"), comment: "#")
+#test(data, (("a", "b"), ("c", "d")))
+
+--- csv-comment-error-line eval ---
+// Error: 6-35 failed to parse CSV (found 1 instead of 2 fields in line 4 at 4:1)
+#csv(bytes("a,b\n# one\n# two\nc"), comment: "#")
+
+--- csv-comment-types-error-line eval ---
+// Error: 6-33 failed to parse CSV (expected integer, found "x" in column 1 at 4:1)
+#csv(bytes("# one\n1\n# two\nx"), types: (int,), comment: "#")
+
+--- csv-comment-header-error-line eval ---
+// Error: 6-28 failed to parse CSV (found 1 instead of 2 fields in line 3 at 3:1)
+#csv(bytes("# one\na,b\nc"), comment: "#", row-type: dictionary)
+
+--- csv-encode eval ---
+#test(csv.encode((("a", "b"), (1, 2.5), (true, none))), "a,b\n1,2.5\ntrue,\n")
+#test(csv.encode((("x,y", "say \"hi\""),)), "\"x,y\",\"say \"\"hi\"\"\"\n")
+#test(csv.encode((("a", "b"),), delimiter: ";", quoting: "always"), "\"a\";\"b\"\n")
+
+--- csv-encode-dict-rows eval ---
+#let rows = ((name: "Ada", year: 1815), (year: 1906, name: "Grace"), (name: "Alan"))
+#test(csv.encode(rows), "name,year\nAda,1815\nGrace,1906\nAlan,\n")
+#test(csv.encode(rows, header: false), "Ada,1815\nGrace,1906\nAlan,\n")
+
+// Test that encoding and decoding roundtrips.
+#test(csv(bytes(csv.encode(rows)), row-type: dictionary, types: auto).at(1), rows.at(1))
+
+--- csv-encode-unknown-key eval ---
+// Error: 13-35 row 2 has key `b`, which is not in the header
+#csv.encode(((a: 1), (a: 2, b: 3)))
+
+--- csv-encode-mixed-rows eval ---
+// Error: 13-27 row 2 is array, but the first row is dictionary
+#csv.encode(((a: 1), (2,)))
+
+--- csv-encode-invalid-field eval ---
+// Error: 13-22 cannot encode content as a CSV field
+#csv.encode((([a],),))
+
+--- csv-types-named-unknown-key eval ---
+// Error: 6-23 `types` has key `b`, which is not in the header
+// Hint: 6-23 the header has the keys `a`, `c`
+#csv(bytes("a,c\n1,2"), row-type: dictionary, types: (a: int, b: int))
+
+--- csv-types-auto-trimmed eval ---
+#let data = csv(bytes("a, 1 ,2.5 , true, x "), types: auto)
+#test(data, (("a", 1, 2.5, true, " x "),))
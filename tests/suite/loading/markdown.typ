@@ -0,0 +1,56 @@
+--- markdown-inline eval ---
+#test(markdown(bytes("*hi*")), emph[hi])
+#test(markdown(bytes("**hi**")), strong[hi])
+#test(markdown(bytes("~~hi~~")), strike[hi])
+#test(markdown(bytes("[Typst](https://typst.app)")), link("https://typst.app")[Typst])
+#test(markdown(bytes("<hi@typst.app>")), link("mailto:hi@typst.app")[hi\@typst.app])
+
+--- markdown-heading eval ---
+#test(markdown(bytes("## Hello")), heading(depth: 2)[Hello])
+
+--- markdown-list eval ---
+#test(markdown(bytes("- a\n- b")), list(tight: true, [a], [b]))
+#test(markdown(bytes("3. a\n4. b")), enum(start: 3, tight: true, [a], [b]))
+#test(markdown(bytes("- a\n\n- b")).tight, false)
+
+--- markdown-raw eval ---
+#let code = markdown(bytes("```rust\nfn main() {}\n```"))
+#test(code.func(), raw)
+#test(code.text, "fn main() {}")
+#test(code.lang, "rust")
+#test(code.block, true)
+#test(markdown(bytes("`x`")).block, false)
+
+--- markdown-paragraphs eval ---
+#let doc = markdown(bytes("A\nB\n\nC"))
+#test(doc.children.map(c => c.func()), (text, space, text, parbreak, text))
+
+--- markdown-table eval ---
+#let table = markdown(bytes("| a | b |\n|:-|-:|\n| 1 | 2 |"))
+#test(table.func(), std.table)
+#test(table.columns, (auto, auto))
+#test(table.children.len(), 3)
+#test(table.children.first().func(), std.table.header)
+#test(table.children.last().align, right)
+
+--- markdown-footnote eval ---
+#let doc = markdown(bytes("A[^1].\n\n[^1]: Note."))
+#test(doc.children.at(1), footnote[Note.])
+
+--- markdown-footnote-repeated eval ---
+#let doc = markdown(bytes("A[^1] B[^1].\n\n[^1]: Note."))
+#let (first, second) = doc.children.filter(c => c.func() == footnote)
+#test(first.body, [Note.])
+#test(first.has("label"), true)
+#test(second.body, first.label)
+
+--- markdown-image-url eval ---
+#test(markdown(bytes("![*Logo*](https://typst.app/logo.png)")), emph[Logo])
+
+--- markdown-image-not-found eval ---
+// Error: 11-39 file not found (searched at tests/suite/loading/missing.png)
+#markdown(bytes("![alt](missing.png)"))
+
+--- markdown-invalid-utf-8 eval ---
+// Error: 11-24 failed to convert to string (file is not valid UTF-8 at 1:1)
+#markdown(bytes((255,)))
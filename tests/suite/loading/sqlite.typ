@@ -0,0 +1,101 @@
+--- sqlite-type eval ---
+#let db = sqlite(bytes(()))
+#test(type(db), sqlite)
+
+--- sqlite-query-params-invalid eval ---
+// Error: 38-41 expected array or dictionary, found string
+#sqlite(bytes(())).query("SELECT 1", "a")
+
+--- sqlite-query eval ---
+#let db = sqlite("/tests/databases/zoo.db")
+#let rows = db.query("SELECT * FROM animals ORDER BY legs")
+#test(rows.len(), 4)
+#test(rows.at(0), (
+  name: "snake",
+  legs: 0,
+  weight: 2.25,
+  born: none,
+  photo: none,
+))
+#test(rows.at(1).weight, 0.4)
+#test(rows.at(2).photo, bytes((0xca, 0xfe)))
+#test(rows.at(3).weight, none)
+#test(rows.at(3).photo, bytes(()))
+
+--- sqlite-query-empty eval ---
+#let db = sqlite("/tests/databases/zoo.db")
+#test(db.query("SELECT name FROM animals WHERE legs > 100"), ())
+
+--- sqlite-query-params-positional eval ---
+#let db = sqlite("/tests/databases/zoo.db")
+#let rows = db.query(
+  "SELECT name FROM animals WHERE legs >= ? AND legs <= ?2 ORDER BY name",
+  (2, 4),
+)
+#test(rows.map(row => row.name), ("cat", "parrot"))
+#test(db.query("SELECT ? AS a, ? AS b", (true, none)), ((a: 1, b: none),))
+
+--- sqlite-query-params-named eval ---
+#let db = sqlite("/tests/databases/zoo.db")
+#let rows = db.query(
+  "SELECT name FROM animals WHERE legs = :legs OR weight < @weight OR name = $name",
+  (legs: 8, weight: 1.0, name: "snake"),
+)
+#test(rows.map(row => row.name).sorted(), ("parrot", "snake", "spider"))
+#test(db.query("SELECT :x + :x AS y", (x: 2)), ((y: 4),))
+
+--- sqlite-query-params-count eval ---
+// Error: 42-77 query has 2 parameters, but 1 value was given
+#sqlite("/tests/databases/zoo.db").query("SELECT * FROM animals WHERE ? < ?", (1,))
+
+--- sqlite-query-params-missing eval ---
+// Error: 42-105 missing value for query parameter `:weight`
+#sqlite("/tests/databases/zoo.db").query("SELECT * FROM animals WHERE legs = :legs AND weight < :weight", (legs: 4))
+
+--- sqlite-query-params-unknown eval ---
+// Error: 42-84 query has no parameter named `wings`
+#sqlite("/tests/databases/zoo.db").query("SELECT * FROM animals WHERE legs = :legs", (legs: 4, wings: 2))
+
+--- sqlite-query-params-positional-dict eval ---
+// Error: 42-80 query has positional parameters, but a dictionary was given
+#sqlite("/tests/databases/zoo.db").query("SELECT * FROM animals WHERE legs = ?", (legs: 4))
+
+--- sqlite-query-params-type eval ---
+// Error: 42-80 cannot use array as a query parameter
+#sqlite("/tests/databases/zoo.db").query("SELECT * FROM animals WHERE legs = ?", ((1, 2),))
+
+--- sqlite-query-read-only eval ---
+// Error: 42-63 query is not allowed (queries must be read-only and deterministic)
+#sqlite("/tests/databases/zoo.db").query("DELETE FROM animals")
+
+--- sqlite-query-attach eval ---
+// Error: 42-70 query is not allowed (queries must be read-only and deterministic)
+#sqlite("/tests/databases/zoo.db").query("ATTACH 'other.db' AS other")
+
+--- sqlite-query-now eval ---
+// Error: 42-62 failed to query SQLite database (the current time is not available in queries)
+#sqlite("/tests/databases/zoo.db").query("SELECT date('now')")
+
+--- sqlite-query-random eval ---
+// Error: 42-59 failed to query SQLite database (`random` is not available in queries)
+#sqlite("/tests/databases/zoo.db").query("SELECT random()")
+
+--- sqlite-query-dates eval ---
+#let db = sqlite("/tests/databases/zoo.db")
+#let rows = db.query(
+  "SELECT name, date(born, '+1 year') AS birthday FROM animals
+   WHERE born < ? ORDER BY born",
+  ("2020-01-01",),
+)
+#test(rows, (
+  (name: "parrot", birthday: "2012-08-02"),
+  (name: "cat", birthday: "2020-03-14"),
+))
+
+--- sqlite-query-syntax-error eval ---
+// Error: 42-51 failed to query SQLite database (near "SELEC": syntax error in SELEC 1 at offset 0)
+#sqlite("/tests/databases/zoo.db").query("SELEC 1")
+
+--- sqlite-invalid eval ---
+// Error: 34-44 failed to query SQLite database (file is not a database)
+#sqlite(bytes("not a db")).query("SELECT 1")
@@ -57,3 +57,103 @@
 --- xml-invalid eval ---
 // Error: "/assets/data/bad.xml" 3:1 failed to parse XML (found closing tag 'data' instead of 'hello')
 #xml("/assets/data/bad.xml")
+
+--- xml-query eval ---
+#let data = xml(bytes(
+  ```xml
+  <feed>
+    <entry id="a" class="post pinned"><title>A</title></entry>
+    <entry id="b" draft="true">
+      <title>B</title>
+      <meta><title>Meta</title></meta>
+    </entry>
+  </feed>
+  ```.text
+))
+
+#let texts(nodes) = nodes.map(n => n.children.first())
+#test(texts(xml.query(data, "title")), ("A", "B", "Meta"))
+#test(texts(xml.query(data, "entry > title")), ("A", "B"))
+#test(texts(xml.query(data, "entry title")), ("A", "B", "Meta"))
+#test(texts(xml.query(data, "entry[draft] > title")), ("B",))
+#test(texts(xml.query(data, "entry[draft=\"true\"] meta title")), ("Meta",))
+#test(texts(xml.query(data, "#a title, .pinned > title")), ("A",))
+#test(xml.query(data, "feed").len(), 1)
+#test(xml.query(data, "*").len(), 7)
+#test(xml.query(data, "item"), ())
+
+// A single element can be queried, too.
+#let entry = xml.query(data, "#b").first()
+#test(texts(xml.query(entry, "meta > title")), ("Meta",))
+
+--- xml-query-namespaces eval ---
+#let data = xml(bytes(
+  ```xml
+  <doc xmlns:m="urn:meta"><title>Body</title><m:title>Meta</m:title></doc>
+  ```.text
+))
+
+#test(xml.query(data, "title").len(), 2)
+#test(xml.query(data, "*|title").len(), 2)
+#test(xml.query(data, "|title").first().children, ("Body",))
+#test(xml.query(data, "m|title", namespaces: (m: "urn:meta")).first().children, ("Meta",))
+
+--- xml-query-unknown-prefix eval ---
+// Error: 17-26 unknown namespace prefix `m`
+#xml.query((:), "m|title")
+
+--- xml-query-invalid-selector eval ---
+// Error: 17-25 invalid selector (expected `]`, `=`, or `~=`)
+#xml.query((:), "a[b c]")
+
+--- xml-encode eval ---
+#let data = (
+  tag: "feed",
+  namespace: "urn:feed",
+  children: (
+    (tag: "title", children: ("Tom & Jerry",)),
+    (tag: "link", attrs: (href: "a\"b")),
+    (tag: "p", children: ("Some ", (tag: "em", children: ("text",)), ".")),
+  ),
+)
+
+#test(
+  xml.encode(data),
+  ```xml
+  <feed xmlns="urn:feed">
+    <title>Tom &amp; Jerry</title>
+    <link href="a&quot;b"/>
+    <p>Some <em>text</em>.</p>
+  </feed>
+  ```.text,
+)
+#test(
+  xml.encode(data, pretty: false),
+  "<feed xmlns=\"urn:feed\"><title>Tom &amp; Jerry</title><link href=\"a&quot;b\"/><p>Some <em>text</em>.</p></feed>",
+)
+
+--- xml-encode-roundtrip eval ---
+#let text = "<a xmlns=\"urn:a\"><b x=\"1\">c</b><d xmlns=\"\"/></a>"
+#test(xml.encode(xml(bytes(text)), pretty: false), text)
+
+--- xml-encode-invalid eval ---
+// Error: 13-39 failed to encode value as XML (expected string or dictionary, found integer)
+#xml.encode((tag: "a", children: (1,)))
+
+--- xml-encode-invalid-tag eval ---
+// Error: 13-43 failed to encode value as XML (tag "a b" is not a valid XML name)
+#xml.encode((tag: "a b", children: ("c",)))
+
+--- xml-encode-invalid-tag-start eval ---
+// Error: 13-24 failed to encode value as XML (tag "1a" is not a valid XML name)
+#xml.encode((tag: "1a"))
+
+--- xml-encode-invalid-attr eval ---
+// Error: 13-48 failed to encode value as XML (attribute "x=\"1\"" is not a valid XML name)
+#xml.encode((tag: "a", attrs: ("x=\"1\"": "2")))
+
+--- xml-encode-valid-names eval ---
+#test(
+  xml.encode((tag: "ns:élément-1.x", attrs: (_a: "1", "b·c": "2")), pretty: false),
+  "<ns:élément-1.x _a=\"1\" b·c=\"2\"/>",
+)
@@ -104,3 +104,12 @@ Ein Paragraph.
 // Hint: 4-38 avoid manually calling `figure.caption`
   #figure.caption[Additional caption]
 ]
+
+--- figure-tags-chart pdftags pdfstandard(ua-1) ---
+// The chart is tagged as a figure with a short description, followed by a
+// table with its data.
+#chart(
+  categories: ([Q1], [Q2]),
+  chart.series((3, 5), caption: [2024]),
+  chart.series((4, 6), caption: [2025]),
+)
@@ -230,3 +230,64 @@
 #let x = 1
 #let c = [#(x) => (1, 2)]
 #test(c.children.last(), [(1, 2)]))
+
+--- closure-typed-params eval ---
+#let f(x as int, suffix as str: "a") -> str = str(x) + suffix
+#test(f(1), "1a")
+#test(f(2, suffix: "b"), "2b")
+
+#let g = (body as content) -> content => strong(body)
+#test(g[A], strong[A])
+
+#let h((a, b) as array) = a + b
+#test(h((1, 2)), 3)
+
+// Annotations can refer to variables from the surrounding scope.
+#let make(t) = (x as t) => x
+#test(make(int)(1), 1)
+
+--- closure-typed-param-cast eval ---
+// Arguments are converted like for built-in functions.
+#let f(x as float, body as content: none) = (x, body)
+#test(type(f(1).first()), float)
+#test(f(1.5, body: "a"), (1.5, [a]))
+#test(type(f(1).last()), content)
+
+#let g(r as relative) -> float = 2
+#test(g(1pt), 2.0)
+#test(type(g(50%)), float)
+
+--- closure-typed-param-wrong-type eval ---
+#let f(x as int) = x
+// Error: 4-7 expected integer for parameter `x`, found string
+#f("a")
+
+--- closure-typed-param-named-wrong-type eval ---
+#let f(size as length: 1pt) = size
+// Error: 10-13 expected length for parameter `size`, found string
+#f(size: "a")
+
+--- closure-typed-param-pattern-wrong-type eval ---
+#let f((a, b) as array) = a + b
+// Error: 4-5 expected array for pattern parameter, found integer
+#f(1)
+
+--- closure-typed-param-default-wrong-type eval ---
+// Error: 24-27 expected length for parameter `size`, found string
+#let f(size as length: "a") = size
+
+--- closure-typed-param-not-a-type eval ---
+// Error: 13-14 expected type, found integer
+#let f(x as 1) = x
+
+--- closure-return-type-wrong-type eval ---
+// Error: 20-21 expected string as return value, found integer
+#let f(x) -> str = x
+#f(1)
+
+--- closure-return-type-explicit-return eval ---
+#let f(x) -> str = {
+  // Error: 3-11 expected string as return value, found integer
+  return x
+}
+#f(1)
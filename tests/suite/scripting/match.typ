@@ -0,0 +1,184 @@
+// Test match expressions.
+
+--- match-literal eval ---
+#let name(n) = match n {
+  0 => "zero",
+  1 => "one",
+  -1 => "minus one",
+  1.5 => "one and a half",
+  "two" => "two",
+  none => "nothing",
+  _ => "many",
+}
+#test(name(0), "zero")
+#test(name(1), "one")
+#test(name(-1), "minus one")
+#test(name(1.5), "one and a half")
+#test(name("two"), "two")
+#test(name(none), "nothing")
+#test(name(7), "many")
+
+--- match-type eval ---
+#let kind(v) = match v {
+  n: int => n + 1,
+  s: str => s + "!",
+  _: float => "float",
+  _ => "other",
+}
+#test(kind(1), 2)
+#test(kind("hi"), "hi!")
+#test(kind(1.5), "float")
+#test(kind(true), "other")
+
+--- match-element eval ---
+#let level(it) = match it {
+  h: heading => h.depth,
+  _: strong => "strong",
+  _ => none,
+}
+#test(level(heading(depth: 2)[A]), 2)
+#test(level(strong[B]), "strong")
+#test(level([C]), none)
+
+--- match-custom-element eval ---
+#let note = content.define("note", it => none, fields: (kind: str))
+#let other = content.define("note", it => none, fields: (kind: str))
+#let kind(it) = match it {
+  n: note => n.kind,
+  _ => none,
+}
+#test(kind(note(kind: "tip")), "tip")
+#test(kind(other(kind: "tip")), none)
+
+--- match-destructuring eval ---
+#let shape(v) = match v {
+  (name: n, ..) => "named " + n,
+  () => "empty",
+  (x,) => "one: " + str(x),
+  (x, y) => "pair: " + str(x + y),
+  (first, ..rest) => "many: " + str(rest.len()),
+}
+#test(shape(()), "empty")
+#test(shape((1,)), "one: 1")
+#test(shape((1, 2)), "pair: 3")
+#test(shape((1, 2, 3, 4)), "many: 3")
+#test(shape((name: "Typst", age: 12)), "named Typst")
+
+#let nested(v) = match v {
+  ((a, b), c) => a + b + c,
+  (x: (y,)) => y,
+  _ => 0,
+}
+#test(nested(((1, 2), 3)), 6)
+#test(nested(((1,), 3)), 0)
+#test(nested((x: (4,))), 4)
+#test(nested((x: 4)), 0)
+#test(nested((y: 4)), 0)
+
+--- match-guard eval ---
+#let sign(n) = match n {
+  0 => "zero",
+  x if x < 0 => "negative",
+  x: int if calc.even(x) => "even",
+  _ => "odd",
+}
+#test(sign(0), "zero")
+#test(sign(-3), "negative")
+#test(sign(4), "even")
+#test(sign(5), "odd")
+
+--- match-guard-closure eval ---
+#let f = match 3 {
+  x if x > 2 => y => x + y,
+  _ => none,
+}
+#test(f(1), 4)
+
+--- match-block eval ---
+#let out = match (1, 2) {
+  (a, b) if a > b => "never",
+  (a, b) => {
+    let sum = a + b
+    sum * 2
+  }
+  _ => 0
+}
+#test(out, 6)
+
+--- match-content eval ---
+#test(match true { true => [Yes], false => [No] }, [Yes])
+
+--- match-scope eval ---
+#let x = "outer"
+#test(match 1 { x => x + 1 }, 2)
+#test(x, "outer")
+
+--- match-return eval ---
+#let first-even(values) = {
+  for v in values {
+    match v {
+      x if calc.even(x) => return x,
+      _ => {}
+    }
+  }
+  none
+}
+#test(first-even((1, 3, 4, 5)), 4)
+#test(first-even((1, 3)), none)
+
+--- match-capture eval ---
+#let offset = 10
+#let f(v) = match v {
+  n: int => n + offset,
+  _ => offset,
+}
+#test(f(1), 11)
+#test(f("a"), 10)
+
+--- match-no-arm eval ---
+// Error: 8-11 no arm matched value of type string
+// Hint: 8-11 add a catch-all arm like `_ => none`
+#match "a" {
+  1 => "one",
+}
+
+--- match-not-a-type eval ---
+// Error: 15-16 expected type or element, found integer
+#match 1 { x: 1 => x }
+
+--- match-exhaustive-bool eval ---
+// Warning: 8-12 match does not cover `false`
+// Hint: 8-12 add an arm for the missing case or a catch-all arm like `_ => none`
+#match true {
+  true => 1,
+}
+
+--- match-exhaustive-none-auto eval ---
+// Warning: 8-12 match does not cover `auto`
+// Hint: 8-12 add an arm for the missing case or a catch-all arm like `_ => none`
+#match none {
+  none => 1,
+}
+
+--- match-exhaustive-complete eval ---
+#test(match false { true => 1, false => 0 }, 0)
+#test(match auto { none => 1, auto => 2 }, 2)
+
+--- match-incomplete eval ---
+// Error: 16 expected arrow
+#match true { _ }
+
+// Error: 15-16 unexpected plus
+#match true { + _ => 2 }
+
+--- match-contextual-keyword eval ---
+// The keyword is only reserved in front of a scrutinee and a block.
+#let match = (1, 2)
+#test(match.len(), 2)
+#test(match + (3,), (1, 2, 3))
+#test(match.map(match => match * 2), (2, 4))
+#test(match match { (a, b) => b }, 2)
+
+--- match-identifier eval ---
+// Error: 2-7 unknown variable: match
+#match true
@@ -351,6 +351,69 @@
   test(y, "ok")
 }
 
+--- ops-pipe-basic eval ---
+// The left-hand side is passed as the first positional argument.
+#test("a" |> upper(), "A")
+#test(" ab " |> str.trim() |> upper(), "AB")
+#test(2 |> calc.pow(3), 8)
+#test((1, 2, 3) |> array.map(x => x * 2), (2, 4, 6))
+
+// A bare function is called with just the piped value.
+#test("a" |> upper, "A")
+#test(4 |> (x => x + 1), 5)
+
+// Methods receive the piped value after their target.
+#test(", " |> ("a", "b").join(), "a, b")
+
+--- ops-pipe-precedence eval ---
+// Arithmetic binds stronger than the pipe, on both of its sides.
+#test(1 + 2 |> str(), "3")
+#test(2 |> x => str(x) + "a", "2a")
+#test((2 |> str()) + "a", "2a")
+
+// The pipe binds stronger than comparison.
+#test(-3 |> calc.abs() == 3, true)
+
+--- ops-pipe-precedence-sum eval ---
+// Error: 8-13 missing argument: value
+#(2 |> str() + "a")
+
+--- ops-pipe-chain eval ---
+// Pipes are left-associative and may continue on the next line.
+#{
+  let n = "hello"
+    |> upper()
+    |> str.len()
+  test(n, 5)
+}
+
+--- ops-pipe-mutating eval ---
+#{
+  let arr = (1, 2)
+  3 |> arr.push()
+  test(arr, (1, 2, 3))
+}
+
+--- ops-pipe-math eval ---
+// An unspaced `|>` in math is just text.
+#test($a|>b$.body.children.map(c => c.text), ("a", "|", ">", "b"))
+#test($a \|> b$.body.children.at(2).text, "|")
+
+--- ops-pipe-math-spaced eval ---
+// Error: 4-6 the pipe operator is not available in math
+// Hint: 4-6 to pipe values, use an embedded expression: `#(x |> f())`
+// Hint: 4-6 to write the symbols, escape the bar: `\|>`
+$a |> b$
+
+--- ops-pipe-not-a-function eval ---
+// Error: 8-9 expected function, found integer
+// Hint: 8-9 the right-hand side of `|>` must be a function or a function call
+#(1 |> 2)
+
+--- ops-pipe-missing-rhs eval ---
+// Error: 7 expected expression
+#(1 |>)
+
 --- ops-unary-minus-missing-expr eval ---
 // Error: 4 expected expression
 #(-)
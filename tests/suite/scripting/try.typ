@@ -0,0 +1,91 @@
+// Test try-catch expressions.
+
+--- try-catch-basic eval ---
+#test(try { 1 + 1 } catch { 0 }, 2)
+#test(try { panic("oh no") } catch { 0 }, 0)
+#test(try [#panic()] catch [caught], [caught])
+#test(try { "a" + 1 } catch err { err.message }, "cannot add string and integer")
+
+--- try-catch-error-value eval ---
+#let err = try { panic("oh no") } catch err { err }
+#test(type(err), dictionary)
+#test(err.message, "panicked with: oh no")
+#test(err.hints, ())
+
+--- try-catch-newline eval ---
+#{
+  let value = try {
+    int("twelve")
+  }
+  catch {
+    12
+  }
+  test(value, 12)
+}
+
+--- try-catch-scope eval ---
+#let err = "outer"
+#test(try { panic() } catch err { "inner" }, "inner")
+#test(err, "outer")
+
+--- try-catch-closure eval ---
+#let safe(f, default) = (..args) => try { f(..args) } catch { default }
+#let parse = safe(int, none)
+#test(parse("12"), 12)
+#test(parse("twelve"), none)
+
+--- try-catch-capture eval ---
+#let f = () => try { panic() } catch err { err.message }
+#test(f(), "panicked")
+
+--- try-catch-loop eval ---
+#let results = ()
+#for x in ("1", "a", "3", "stop", "5") {
+  try {
+    if x == "stop" { break }
+    results.push(int(x))
+  } catch {
+    continue
+  }
+}
+#test(results, (1, 3))
+
+--- try-catch-rethrow eval ---
+// Error: 33-47 panicked with: again
+#try { panic("first") } catch { panic("again") }
+
+--- try-catch-maximum-depth eval ---
+// Error: 15-21 maximum function call depth exceeded
+#let rec(n) = rec(n) + 1
+#try { rec(1) } catch { none }
+
+--- try-catch-incomplete eval ---
+// Error: 8 expected keyword `catch`
+#try {}
+
+// Error: 14 expected block
+#try {} catch
+
+--- try-catch-contextual-keywords eval ---
+// The keywords are only reserved in front of the blocks.
+#let try(catch) = catch + 1
+#let catch = 1
+#test(try(catch), 2)
+#test((try: 1, catch: 2).catch, 2)
+#test({
+  try { panic() }
+  catch { catch }
+}, 1)
+
+--- try-catch-identifier eval ---
+// Error: 2-5 unknown variable: try
+#try
+
+--- try-catch-scope-cleanup eval ---
+#let x = "outer"
+#try {
+  for x in (1, 2) {
+    panic()
+  }
+} catch {}
+#test(x, "outer")
@@ -0,0 +1,55 @@
+// Test ruby annotations.
+
+--- ruby-basic paged ---
+#set text(lang: "ja", font: "Noto Serif CJK SC")
+#ruby[東京][とうきょう]に#ruby[行][い]く。
+
+--- ruby-position paged ---
+#set text(lang: "zh", font: ("Libertinus Serif", "Noto Serif CJK SC"))
+#ruby[汉字][hànzì] #ruby(position: bottom)[汉字][hànzì]
+
+--- ruby-align paged ---
+#set text(lang: "ja", font: "Noto Serif CJK SC")
+#ruby(align: start, overhang: false)[東][ひがし] \
+#ruby(align: center, overhang: false)[東][ひがし] \
+#ruby(align: end, overhang: false)[東][ひがし]
+
+--- ruby-overhang paged ---
+#set text(lang: "ja", font: "Noto Serif CJK SC")
+あ#ruby[子][こども]い \
+あ#ruby(overhang: false)[子][こども]い
+
+--- ruby-overhang-neighbours paged empty ---
+// The annotation only overhangs adjacent Chinese or Japanese characters.
+#set text(lang: "ja", font: "Noto Serif CJK SC")
+#let r = ruby(box(width: 10pt), box(width: 20pt))
+#context {
+  let w(body) = measure(body).width
+  test(w[あ#r;い], w[あ] + w[い] + 10pt)
+  test(w[a#r;b], w[a] + w[b] + 20pt)
+  test(w[あ#r;b], w[あ] + w[b] + 15pt)
+}
+
+--- ruby-overhang-line-edges paged empty ---
+// The annotation doesn't overhang the start or end of a line.
+#set text(lang: "ja", font: "Noto Serif CJK SC")
+#let r = ruby(box(width: 10pt), box(width: 20pt))
+#context {
+  // Neither the ruby and the preceding nor the ruby and the following
+  // character fit into one line together when the annotation overhangs.
+  let a = measure[あ].width
+  test(measure(width: calc.max(a + 9pt, 20pt))[あ#r;い].width, calc.max(a, 20pt))
+}
+
+--- ruby-size paged ---
+#set text(lang: "ja", font: "Noto Serif CJK SC")
+#show ruby: set ruby(size: 0.3em)
+#ruby[漢字][かんじ]
+
+--- ruby-html html ---
+#ruby[東京][とうきょう]
+#ruby(position: bottom)[漢字][かんじ]
+
+--- ruby-tags pdftags ---
+#set text(lang: "ja", font: "Noto Serif CJK SC")
+#ruby[東京][とうきょう]
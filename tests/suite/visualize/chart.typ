@@ -0,0 +1,78 @@
+// Test charts.
+
+--- chart-range-invalid eval ---
+// Error: 17-23 range start must be smaller than range end
+#chart(y-range: (5, 1))
+
+--- chart-point-invalid eval ---
+// Error: 21-33 data point must contain exactly two numbers
+#chart(chart.series(((1, 2, 3),)))
+
+--- chart-kind-invalid eval ---
+// Error: 35-40 expected "line", "bar", "scatter", or "area"
+#chart(chart.series((1, 2), kind: "pie"))
+
+--- chart-too-small paged ---
+// Error: 2-56 chart is too small to fit its labels
+// Hint: 2-56 try increasing the chart's `width` or `height`
+#chart(width: 20pt, height: 20pt, chart.series((1, 2)))
+
+--- chart-line paged ---
+#chart(
+  width: 120pt,
+  height: 80pt,
+  chart.series((3, 5, 4, 7)),
+  chart.series(((0, 1), (1, 2), (3, 6)), thickness: 2pt),
+)
+
+--- chart-bar paged ---
+#chart(
+  width: 120pt,
+  height: 80pt,
+  categories: ([Q1], [Q2], [Q3]),
+  chart.series((3, 5, 4), kind: "bar"),
+  chart.series((4, 6, 6), kind: "bar"),
+)
+
+--- chart-scatter paged ---
+#chart(
+  width: 120pt,
+  height: 80pt,
+  chart.series(((0.5, 1), (1.5, 3), (2, 2.5), (3.2, 4)), kind: "scatter"),
+)
+
+--- chart-area paged ---
+#chart(
+  width: 120pt,
+  height: 80pt,
+  y-range: (0, 8),
+  chart.series((2, 6, 3, 5), kind: "area", paint: eastern),
+)
+
+--- chart-legend paged ---
+#chart(
+  width: 160pt,
+  height: 80pt,
+  chart.series((3, 5, 4, 7), caption: [Product A]),
+  chart.series((4, 6, 6, 9), kind: "bar", caption: [Product B]),
+  chart.series((1, 2, 2, 3)),
+)
+
+--- chart-html html ---
+#set text(font: "DejaVu Sans Mono")
+#chart(
+  categories: ([Q1], [Q2]),
+  x-label: [Quarter],
+  chart.series((3, 5), kind: "bar", caption: [2024]),
+  chart.series((4, 6), kind: "bar"),
+)
+
+--- chart-html-table html ---
+// Negative horizontal values are never categories and repeated horizontal
+// values get a row each.
+#set text(font: "DejaVu Sans Mono")
+#chart(
+  categories: ([A], [B]),
+  chart.series(((-1, 2), (0, 1), (0, 3), (1, 4))),
+  chart.series(((0, 5), (1, 6))),
+)
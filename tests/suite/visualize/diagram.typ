@@ -0,0 +1,112 @@
+// Test diagrams and arrows.
+
+--- diagram-layered paged empty ---
+// Nodes are placed in layers by their longest path from a source.
+#let node(name) = diagram.node(
+  name,
+  inset: 0pt,
+  stroke: none,
+  block(width: 20pt, height: 10pt),
+)
+#let edge = diagram.edge
+#set diagram(layer-spacing: 10pt, node-spacing: 10pt)
+#context {
+  let nodes = (node("a"), node("b"), node("c"))
+  let chain = diagram(..nodes, edge("a", "b"), edge("b", "c"))
+  test(measure(chain), (width: 20pt, height: 50pt))
+
+  // The long edge from `a` to `d` doesn't add a layer.
+  let diamond = diagram(
+    ..nodes,
+    node("d"),
+    edge("a", "b"),
+    edge("a", "c"),
+    edge("b", "d"),
+    edge("c", "d"),
+    edge("a", "d"),
+  )
+  test(measure(diamond).height, 50pt)
+
+  // The layers flow in the diagram's direction.
+  let horizontal = diagram(dir: rtl, ..nodes, edge("a", "b"), edge("b", "c"))
+  test(measure(horizontal), (width: 80pt, height: 10pt))
+
+  // Cycles still result in layers.
+  let cycle = diagram(..nodes, edge("a", "b"), edge("b", "c"), edge("c", "a"))
+  test(measure(cycle).height, 50pt)
+}
+
+--- diagram-parallel-edges paged empty ---
+// Parallel and antiparallel edges are bent apart instead of being drawn on top
+// of each other, so they stick out to the sides of the nodes.
+#let node(name) = diagram.node(
+  name,
+  inset: 0pt,
+  stroke: none,
+  block(width: 20pt, height: 10pt),
+)
+#let edge = diagram.edge
+#set diagram(layer-spacing: 20pt, node-spacing: 40pt)
+#context {
+  let nodes = (node("a"), node("b"))
+  let single = measure(diagram(..nodes, edge("a", "b")))
+  let parallel = measure(diagram(..nodes, edge("a", "b"), edge("a", "b")))
+  let antiparallel = measure(diagram(..nodes, edge("a", "b"), edge("b", "a")))
+  test(single, (width: 20pt, height: 40pt))
+  test(parallel.height, 40pt)
+  test(parallel.width > 40pt, true)
+  test(antiparallel, parallel)
+}
+
+--- diagram-self-loops paged empty ---
+// Loops are drawn on the right side of their node and multiple loops at the
+// same node are nested.
+#let node(name) = diagram.node(
+  name,
+  inset: 0pt,
+  stroke: none,
+  block(width: 20pt, height: 10pt),
+)
+#let edge = diagram.edge
+#set diagram(node-spacing: 10pt)
+#context {
+  let one = measure(diagram(node("a"), edge("a", "a")))
+  let two = measure(diagram(node("a"), edge("a", "a"), edge("a", "a")))
+  test(one.width > 20pt, true)
+  test(two.width > one.width, true)
+}
+
+--- line-marks paged empty ---
+// Marks don't change the size of a line or curve.
+#context {
+  let straight = line(
+    length: 20pt,
+    start-mark: "bar",
+    end-mark: "stealth",
+    stroke: 2pt,
+  )
+  test(measure(straight), (width: 20pt, height: 0pt))
+  let bent = curve(
+    end-mark: "arrow",
+    curve.move((0pt, 10pt)),
+    curve.cubic((10pt, 0pt), (20pt, 20pt), (30pt, 10pt)),
+  )
+  test(measure(bent).width, 30pt)
+}
+
+--- diagram-duplicate-node paged ---
+// Error: 32-52 duplicate node `a`
+#diagram(diagram.node("a")[A], diagram.node("a")[B])
+
+--- diagram-unknown-node paged ---
+// Error: 32-54 unknown node `b`
+// Hint: 32-54 edges can only connect nodes of the same diagram
+#diagram(diagram.node("a")[A], diagram.edge("a", "b"))
+
+--- diagram-invalid-child eval ---
+// Error: 32-38 expected a diagram node or edge
+#diagram(diagram.node("a")[A], [text])
+
+--- arrow-mark-invalid eval ---
+// Error: 43-49 expected "arrow", "triangle", "stealth", "circle", "bar", or none
+#arrow((0pt, 0pt), (10pt, 0pt), end-mark: "hook")
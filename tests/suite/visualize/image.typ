@@ -307,6 +307,43 @@ A #box(image("/assets/images/tiger.jpg", height: 1cm, width: 80%)) B
 // Error: 2-84 failed to decode image (Format error decoding Png: Invalid PNG signature.)
 #image(read("/assets/images/tiger.jpg", encoding: none), format: "png", width: 80%)
 
+--- image-bmp paged empty ---
+// Test decoding a BMP image with 3x2 pixels.
+#context {
+  let size = measure(image("/tests/images/rgb.bmp"))
+  test(size, (width: 3pt, height: 2pt))
+}
+
+--- image-tiff paged empty ---
+// Test decoding an uncompressed RGB TIFF image with 3x2 pixels.
+#context {
+  let size = measure(image("/tests/images/rgb.tiff"))
+  test(size, (width: 3pt, height: 2pt))
+}
+
+--- image-tiff-from-bytes paged empty ---
+// Test detecting the TIFF format from raw bytes.
+#context {
+  let data = read("/tests/images/rgb.tiff", encoding: none)
+  test(measure(image(data)), (width: 3pt, height: 2pt))
+}
+
+--- image-gif-animated html ---
+// Animated GIFs are embedded with their original data, so that they keep their
+// animation.
+#image("/tests/images/animated.gif")
+
+--- image-webp-animated html ---
+// The same holds for animated WebPs.
+#image("/tests/images/animated.webp")
+
+--- image-gif-animated-first-frame paged empty ---
+// In paged export, only the first frame of an animated image is shown.
+#context {
+  test(measure(image("/tests/images/animated.gif")), (width: 1pt, height: 1pt))
+  test(measure(image("/tests/images/animated.webp")), (width: 1pt, height: 1pt))
+}
+
 --- image-pixmap-empty paged ---
 // Error: 1:2-8:2 zero-sized images are not allowed
 #image(
@@ -355,7 +392,7 @@ A #box(image("/assets/images/tiger.jpg", height: 1cm, width: 80%)) B
 --- image-png-but-pixmap-format eval ---
 #image(
   read("/assets/images/tiger.jpg", encoding: none),
-  // Error: 11-18 expected "png", "jpg", "gif", "webp", dictionary, "svg", "pdf", or auto
+  // Error: 11-18 expected "png", "jpg", "gif", "webp", "avif", "jxl", "bmp", "tiff", dictionary, "svg", "pdf", or auto
   format: "rgba8",
 )
 
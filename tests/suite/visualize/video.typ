@@ -0,0 +1,34 @@
+--- video-html html ---
+// A video loaded from a file is embedded.
+#video(
+  "/tests/images/clip.mp4",
+  poster: "/tests/images/rgb.bmp",
+  alt: "A clip",
+  width: 50%,
+)
+
+--- video-html-bytes html ---
+// A video provided as bytes is embedded.
+#video(
+  bytes("\u{0}\u{0}\u{0}\u{18}ftypmp42"),
+  controls: false,
+  autoplay: true,
+  looping: true,
+  muted: true,
+)
+
+--- video-poster paged empty ---
+// In paged export, the poster is shown in place of the video.
+#context {
+  let clip = video("/tests/images/clip.mp4", poster: "/tests/images/rgb.bmp")
+  test(measure(clip), (width: 3pt, height: 2pt))
+}
+
+--- video-no-poster paged ---
+// Error: 2-47 cannot show a video without a poster in paged export
+// Hint: 2-47 provide an image to show instead with the `poster` parameter
+#video(bytes("\u{0}\u{0}\u{0}\u{18}ftypmp42"))
+
+--- video-format-invalid eval ---
+// Error: 31-36 expected "mp4", "webm", "ogg", or auto
+#video(bytes("OggS"), format: "avi")